@@ -1,6 +1,6 @@
 use crate::cell_memory::Memory;
-use log::{debug, warn};
-use std::collections::HashMap;
+use log::debug;
+use std::collections::BTreeMap;
 
 /// An extremely simple bump-allocator which never frees
 #[derive(Clone)]
@@ -9,7 +9,7 @@ pub struct Alloc {
     cursor: u64,
 
     /// Map from allocation address to its size in bits
-    sizes: HashMap<u64, u64>,
+    sizes: BTreeMap<u64, u64>,
 }
 
 impl Alloc {
@@ -18,28 +18,24 @@ impl Alloc {
     pub fn new() -> Self {
         Self {
             cursor: Self::ALLOC_START,
-            sizes: HashMap::new(),
+            sizes: BTreeMap::new(),
         }
     }
 
     /// Allocate the specified number of bits, returning a pointer to the allocated object.
+    ///
+    /// Even a 0-bit allocation gets its own address, distinct from all other
+    /// allocations.
     // Internal invariants:
     //   - for sizes <= cell size, allocation never crosses a cell boundary
     //   - for sizes > cell size, allocation always starts at a cell boundary
     pub fn alloc(&mut self, bits: impl Into<u64>) -> u64 {
         let bits: u64 = bits.into();
         if bits == 0 {
-            warn!("An allocation of 0 bits was requested");
+            debug!("An allocation of 0 bits was requested");
         }
-        let bits_in_byte: u64 = Memory::BITS_IN_BYTE.into();
         let cell_bytes: u64 = Memory::CELL_BYTES.into();
-        let bytes = {
-            let mut bytes = bits / bits_in_byte;
-            if bits % bits_in_byte != 0 {
-                bytes += 1; // round up to nearest byte
-            }
-            bytes
-        };
+        let bytes = Self::reserved_bytes(bits);
         let current_offset_bytes = self.cursor % cell_bytes;
         let bytes_remaining_in_cell = cell_bytes - current_offset_bytes;
         if bytes > bytes_remaining_in_cell {
@@ -58,4 +54,36 @@ impl Alloc {
     pub fn get_allocation_size(&self, addr: impl Into<u64>) -> Option<u64> {
         self.sizes.get(&addr.into()).copied()
     }
+
+    /// Get the address and size (in bits) of the allocation which contains the
+    /// given address, or `None` if that address is not within any allocation.
+    pub fn get_allocation_containing(&self, addr: impl Into<u64>) -> Option<(u64, u64)> {
+        let addr: u64 = addr.into();
+        let (&start, &bits) = self.sizes.range(0 ..= addr).next_back()?;
+        if addr < start + Self::reserved_bytes(bits) {
+            Some((start, bits))
+        } else {
+            None
+        }
+    }
+
+    /// Get the address at which the next allocation will start (or, if it
+    /// needs to be aligned, after which it will start). All existing
+    /// allocations are below this address.
+    pub fn next_addr(&self) -> u64 {
+        self.cursor
+    }
+
+    /// How many bytes we reserve for an allocation of the given number of bits
+    pub fn reserved_bytes(bits: u64) -> u64 {
+        let bits_in_byte: u64 = Memory::BITS_IN_BYTE.into();
+        let mut bytes = bits / bits_in_byte;
+        if bits % bits_in_byte != 0 {
+            bytes += 1; // round up to nearest byte
+        }
+        if bytes == 0 {
+            bytes = 1; // reserve a byte anyway, so that the address is distinct
+        }
+        bytes
+    }
 }
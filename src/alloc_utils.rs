@@ -2,14 +2,14 @@
 //! These may be useful in implementing hooks for various functions that
 //! perform memory allocation.
 
-use crate::backend::Backend;
+use crate::backend::{Backend, BV};
 use crate::error::*;
 use crate::state::State;
 use llvm_ir::*;
 use log::warn;
 
 /// Assume that allocations never exceed this size.
-const MAX_ALLOCATION_SIZE_BYTES: u64 = 1 << 20;
+pub(crate) const MAX_ALLOCATION_SIZE_BYTES: u64 = 1 << 20;
 
 /// Allocate a number of bytes given by the `Operand`.
 ///
@@ -39,7 +39,9 @@ pub fn zalloc<B: Backend>(state: &mut State<B>, num_bytes: &Operand) -> Result<B
     }
     let num_bits = num_bytes * 8;
     let addr = state.allocate(num_bits);
-    state.write(&addr, state.zero(num_bits as u32))?;
+    if num_bits > 0 {
+        state.write(&addr, state.zero(num_bits as u32))?;
+    }
     Ok(addr)
 }
 
@@ -59,7 +61,9 @@ pub fn calloc<B: Backend>(state: &mut State<B>, a: &Operand, b: &Operand) -> Res
     }
     let num_bits = num_bytes * 8;
     let addr = state.allocate(num_bits);
-    state.write(&addr, state.zero(num_bits as u32))?;
+    if num_bits > 0 {
+        state.write(&addr, state.zero(num_bits as u32))?;
+    }
     Ok(addr)
 }
 
@@ -67,36 +71,55 @@ pub fn calloc<B: Backend>(state: &mut State<B>, a: &Operand, b: &Operand) -> Res
 ///
 /// Returns the address of the allocation, which may or may not be the same
 /// address which was passed in.
+///
+/// As in C, if `addr` is `NULL`, this behaves like `malloc()`.
 pub fn realloc<B: Backend>(
     state: &mut State<B>,
     addr: &Operand,
     num_bytes: &Operand,
 ) -> Result<B::BV> {
     let addr = state.operand_to_bv(addr)?;
+    if state.bvs_must_be_equal(&addr, &state.zero(addr.get_width()))? {
+        return malloc(state, num_bytes);
+    }
     // As in `malloc()`, note that allocating too much doesn't hurt anything
     let new_size = try_as_u64(num_bytes).unwrap_or(MAX_ALLOCATION_SIZE_BYTES);
     if new_size > MAX_ALLOCATION_SIZE_BYTES {
         warn!("warning: encountered an allocation of {} bytes, greater than the assumed max of {}. \
             Since this allocation is constant-sized, it's fine in this case, but does draw into question the assumption.", new_size, MAX_ALLOCATION_SIZE_BYTES);
     }
-    let old_size = state.get_allocation_size(&addr)?.ok_or_else(|| {
+    let new_size_bits = new_size * 8;
+    let old_size_bits = state.get_allocation_size(&addr)?.ok_or_else(|| {
         Error::OtherError("realloc: failed to get old allocation size".to_owned())
     })?;
-    if new_size <= old_size {
+    if new_size_bits <= old_size_bits {
         // We treat this as a no-op. You get to keep the larger old_size region you already had.
         Ok(addr)
     } else {
         // Make a new allocation
-        let new_addr = state.allocate(new_size);
-        // Copy the contents of the old allocation
-        let contents = state.read(&addr, old_size as u32)?;
-        state.write(&new_addr, contents)?;
+        let new_addr = state.allocate(new_size_bits);
+        // Copy the contents of the old allocation (if it had any)
+        if old_size_bits > 0 {
+            let contents = state.read_maybe_uninitialized(&addr, old_size_bits as u32)?;
+            state.write(&new_addr, contents)?;
+        }
         // We don't free(), as our allocator won't ever reuse allocated addresses anyway.
-        // So, we can just return
+        // But we do record the old address as freed, as `free()` would
+        state.record_free(addr)?;
         Ok(new_addr)
     }
 }
 
+/// Free the address given by the `Operand`.
+///
+/// Our allocator won't ever reuse allocated addresses, so this just records
+/// the address as freed; see
+/// [`State::record_free()`](../struct.State.html#method.record_free).
+pub fn free<B: Backend>(state: &mut State<B>, addr: &Operand) -> Result<()> {
+    let addr = state.operand_to_bv(addr)?;
+    state.record_free(addr)
+}
+
 /// Try to interpret the `Operand` as a constant integer, and if so, return the value as a `u64`.
 /// (But don't try too hard - as of this writing, doesn't even try to evaluate constant expressions.)
 fn try_as_u64(op: &Operand) -> Option<u64> {
@@ -2,7 +2,11 @@
 //! etc) being used.
 
 use crate::error::Result;
-use boolector::{BVSolution, Btor};
+use crate::solver_utils::SolverStats;
+use boolector::option::{BtorOption, ModelGen};
+use boolector::{BVSolution, Btor, SolverResult};
+use std::borrow::Borrow;
+use std::cell::{Cell, RefCell};
 use std::fmt;
 use std::ops::Deref;
 use std::rc::Rc;
@@ -17,7 +21,7 @@ pub trait Backend: Clone {
 /// Trait for something which acts as a reference to a `boolector::Btor` (and
 /// possibly may carry other information as well).
 ///
-/// This module provides an implementation of `SolverRef` for `Rc<Btor>`.
+/// This module provides an implementation of `SolverRef`, `BtorRef`.
 pub trait SolverRef: Clone + Deref<Target = Btor> {
     type BV: BV<SolverRef = Self>;
     type Array;
@@ -49,11 +53,138 @@ pub trait SolverRef: Clone + Deref<Target = Btor> {
     /// It's also fine to call this with an `Array` created for this `SolverRef`
     /// itself, in which case you'll just get back `Some(array.clone())`.
     fn match_array(&self, array: &Self::Array) -> Option<Self::Array>;
+
+    /// Push `n` new solver scopes. Use this rather than the `Btor`'s own
+    /// `push()`, so that `assertions()` stays accurate.
+    fn push(&self, n: u32);
+
+    /// Pop `n` solver scopes, along with all of the constraints asserted in
+    /// them. Use this rather than the `Btor`'s own `pop()`, so that
+    /// `assertions()` stays accurate.
+    fn pop(&self, n: u32);
+
+    /// Get all of the constraints which have been asserted with
+    /// `BV::assert()` and are still in effect (that is, haven't been removed
+    /// with `pop()`), in the order they were asserted
+    fn assertions(&self) -> Vec<Self::BV>;
+
+    /// Check whether the current constraints are satisfiable. Use this (or
+    /// `solver_utils::sat()`) rather than the `Btor`'s own `sat()`, so that
+    /// `stats()` stays accurate.
+    fn sat(&self) -> SolverResult;
+
+    /// Enable or disable model generation. Use this rather than setting the
+    /// `Btor`'s `ModelGen` option directly, so that `stats()` knows which
+    /// queries were made with model generation enabled.
+    fn set_model_gen(&self, enabled: bool);
+
+    /// Get the counts of the queries made with `sat()` on this solver so far.
+    /// A `duplicate()` starts with the counts of the solver it was duplicated
+    /// from.
+    fn stats(&self) -> SolverStats;
 }
 
-impl SolverRef for Rc<Btor> {
-    type BV = boolector::BV<Rc<Btor>>;
-    type Array = boolector::Array<Rc<Btor>>;
+/// The `SolverRef` used by haybale's built-in backends: a reference to a
+/// `Btor`, which also keeps track of the constraints asserted on it (see
+/// `SolverRef::assertions()`).
+///
+/// Constraints are only tracked if they are asserted with the `BV` trait's
+/// `assert()` (as in code which is generic over the `Backend`), not with
+/// `boolector::BV`'s own `assert()` method.
+#[derive(Clone, Debug)]
+pub struct BtorRef {
+    btor: Rc<Btor>,
+    /// `None` only for the references held by the `BV`s recorded in the
+    /// `SolverInfo` itself, which would otherwise keep it alive forever
+    info: Option<Rc<SolverInfo>>,
+}
+
+/// The information which a `BtorRef` keeps about its solver instance
+#[derive(Debug, Default)]
+struct SolverInfo {
+    /// Constraints which have been asserted and are still in effect, in the
+    /// order they were asserted
+    assertions: RefCell<Vec<boolector::BV<BtorRef>>>,
+    /// For each currently pushed scope, the length of `assertions` when that
+    /// scope was pushed
+    scopes: RefCell<Vec<usize>>,
+    /// Whether model generation is currently enabled
+    model_gen: Cell<bool>,
+    /// Counts of the queries made on this solver
+    stats: Cell<SolverStats>,
+}
+
+impl BtorRef {
+    fn info(&self) -> &SolverInfo {
+        self.info
+            .as_ref()
+            .expect("BtorRef: the solver's recorded constraints should only be used internally")
+    }
+
+    /// A reference to the same `Btor` which doesn't keep the `SolverInfo` alive
+    fn detached(&self) -> Self {
+        Self {
+            btor: self.btor.clone(),
+            info: None,
+        }
+    }
+
+    /// Push `n` new solver scopes; see `SolverRef::push()`
+    pub fn push(&self, n: u32) {
+        let num_assertions = self.info().assertions.borrow().len();
+        let mut scopes = self.info().scopes.borrow_mut();
+        let new_len = scopes.len() + n as usize;
+        scopes.resize(new_len, num_assertions);
+        self.btor.push(n);
+    }
+
+    /// Pop `n` solver scopes; see `SolverRef::pop()`
+    pub fn pop(&self, n: u32) {
+        let mut scopes = self.info().scopes.borrow_mut();
+        let remaining = scopes
+            .len()
+            .checked_sub(n as usize)
+            .expect("BtorRef::pop: popping more scopes than have been pushed");
+        if let Some(&num_assertions) = scopes.get(remaining) {
+            self.info().assertions.borrow_mut().truncate(num_assertions);
+        }
+        scopes.truncate(remaining);
+        self.btor.pop(n);
+    }
+
+    /// Record that the given constraint has been asserted
+    fn record_assertion(&self, constraint: &boolector::BV<BtorRef>) {
+        let detached = Btor::get_matching_bv(self.detached(), constraint)
+            .expect("BtorRef: failed to find an asserted constraint in its own solver");
+        self.info().assertions.borrow_mut().push(detached);
+    }
+}
+
+impl PartialEq for BtorRef {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.btor, &other.btor)
+    }
+}
+
+impl Eq for BtorRef {}
+
+impl Deref for BtorRef {
+    type Target = Btor;
+
+    fn deref(&self) -> &Btor {
+        &self.btor
+    }
+}
+
+impl Borrow<Btor> for BtorRef {
+    fn borrow(&self) -> &Btor {
+        &self.btor
+    }
+}
+
+impl SolverRef for BtorRef {
+    type BV = boolector::BV<BtorRef>;
+    type Array = boolector::Array<BtorRef>;
 
     fn new() -> Self {
         // Note: We used to set model generation here, but now we toggle it so it's only
@@ -65,23 +196,87 @@ impl SolverRef for Rc<Btor> {
         btor.set_opt(BtorOption::Incremental(true));
         btor.set_opt(BtorOption::PrettyPrint(true));
         btor.set_opt(BtorOption::OutputNumberFormat(NumberFormat::Hexadecimal));
-        Rc::new(btor)
+        Self {
+            btor: Rc::new(btor),
+            info: Some(Rc::new(SolverInfo::default())),
+        }
     }
 
     fn duplicate(&self) -> Self {
-        Rc::new(self.as_ref().duplicate())
-    }
-
-    fn match_bv(&self, bv: &boolector::BV<Rc<Btor>>) -> Option<boolector::BV<Rc<Btor>>> {
+        let duplicated = Self {
+            btor: Rc::new(self.btor.as_ref().duplicate()),
+            info: Some(Rc::new(SolverInfo {
+                assertions: RefCell::new(Vec::new()),
+                scopes: self.info().scopes.clone(),
+                model_gen: self.info().model_gen.clone(),
+                stats: self.info().stats.clone(),
+            })),
+        };
+        let assertions = self
+            .info()
+            .assertions
+            .borrow()
+            .iter()
+            .map(|constraint| {
+                Btor::get_matching_bv(duplicated.detached(), constraint)
+                    .expect("BtorRef::duplicate: failed to find an asserted constraint in the duplicated solver")
+            })
+            .collect();
+        duplicated.info().assertions.replace(assertions);
+        duplicated
+    }
+
+    fn match_bv(&self, bv: &boolector::BV<BtorRef>) -> Option<boolector::BV<BtorRef>> {
         Btor::get_matching_bv(self.clone(), bv)
     }
 
-    fn match_array(
-        &self,
-        array: &boolector::Array<Rc<Btor>>,
-    ) -> Option<boolector::Array<Rc<Btor>>> {
+    fn match_array(&self, array: &boolector::Array<BtorRef>) -> Option<boolector::Array<BtorRef>> {
         Btor::get_matching_array(self.clone(), array)
     }
+
+    fn push(&self, n: u32) {
+        BtorRef::push(self, n)
+    }
+
+    fn pop(&self, n: u32) {
+        BtorRef::pop(self, n)
+    }
+
+    fn assertions(&self) -> Vec<boolector::BV<BtorRef>> {
+        self.info()
+            .assertions
+            .borrow()
+            .iter()
+            .map(|constraint| {
+                Btor::get_matching_bv(self.clone(), constraint)
+                    .expect("BtorRef: failed to find a recorded constraint in its own solver")
+            })
+            .collect()
+    }
+
+    fn sat(&self) -> SolverResult {
+        let info = self.info();
+        let mut stats = info.stats.get();
+        stats.sat_checks += 1;
+        if info.model_gen.get() {
+            stats.model_gens += 1;
+        }
+        info.stats.set(stats);
+        self.btor.sat()
+    }
+
+    fn set_model_gen(&self, enabled: bool) {
+        if enabled {
+            self.btor.set_opt(BtorOption::ModelGen(ModelGen::All));
+        } else {
+            self.btor.set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+        }
+        self.info().model_gen.set(enabled);
+    }
+
+    fn stats(&self) -> SolverStats {
+        self.info().stats.get()
+    }
 }
 
 /// Trait for things which can act like bitvectors.
@@ -347,6 +542,23 @@ pub trait Memory: Clone + PartialEq + Eq {
         addr_bits: u32,
     ) -> Self;
 
+    /// Like `new_uninitialized()`, but with the given `cell_bits` as the size,
+    /// in bits, of the "cells" in which the `Memory` stores data; see
+    /// [`Config.memory_cell_bits`](../config/struct.Config.html#structfield.memory_cell_bits).
+    ///
+    /// Implementations which don't store data in fixed-size cells may ignore
+    /// `cell_bits`. The default implementation does so, and just calls
+    /// `new_uninitialized()`.
+    fn new_uninitialized_with_cell_size(
+        solver: Self::SolverRef,
+        null_detection: bool,
+        name: Option<&str>,
+        addr_bits: u32,
+        _cell_bits: u32,
+    ) -> Self {
+        Self::new_uninitialized(solver, null_detection, name, addr_bits)
+    }
+
     /// Read any number (>0) of bits of memory, at any alignment.
     /// Returned `BV` will have size `bits`.
     fn read(&self, index: &Self::Index, bits: u32) -> Result<Self::Value>;
@@ -354,6 +566,17 @@ pub trait Memory: Clone + PartialEq + Eq {
     /// Write any number (>0) of bits of memory, at any alignment.
     fn write(&mut self, index: &Self::Index, value: Self::Value) -> Result<()>;
 
+    /// Have all of the `bits` bits at `index` been written since this `Memory`
+    /// was created? This is used for
+    /// [`Config.flag_uninitialized_reads`](../config/struct.Config.html#structfield.flag_uninitialized_reads).
+    ///
+    /// Implementations may be conservative, returning `true` when they can't
+    /// tell (e.g., for symbolic addresses). The default implementation doesn't
+    /// track writes at all, and always returns `true`.
+    fn is_initialized(&self, _index: &Self::Index, _bits: u32) -> bool {
+        true
+    }
+
     /// Get a reference to the solver instance this `Memory` belongs to
     fn get_solver(&self) -> Self::SolverRef;
 
@@ -368,45 +591,45 @@ pub trait Memory: Clone + PartialEq + Eq {
 }
 
 /// Some prototypical `BV` and `Memory` implementations:
-///   `boolector::BV<Rc<Btor>>`, `crate::simple_memory::Memory`, and `crate::cell_memory::Memory`
+///   `boolector::BV<BtorRef>`, `crate::simple_memory::Memory`, and `crate::cell_memory::Memory`
 
-impl BV for boolector::BV<Rc<Btor>> {
-    type SolverRef = Rc<Btor>;
+impl BV for boolector::BV<BtorRef> {
+    type SolverRef = BtorRef;
 
-    fn new(btor: Rc<Btor>, width: u32, name: Option<&str>) -> Self {
+    fn new(btor: BtorRef, width: u32, name: Option<&str>) -> Self {
         boolector::BV::new(btor, width, name)
     }
-    fn from_bool(btor: Rc<Btor>, b: bool) -> Self {
+    fn from_bool(btor: BtorRef, b: bool) -> Self {
         boolector::BV::from_bool(btor, b)
     }
-    fn from_i32(btor: Rc<Btor>, i: i32, width: u32) -> Self {
+    fn from_i32(btor: BtorRef, i: i32, width: u32) -> Self {
         boolector::BV::from_i32(btor, i, width)
     }
-    fn from_u32(btor: Rc<Btor>, u: u32, width: u32) -> Self {
+    fn from_u32(btor: BtorRef, u: u32, width: u32) -> Self {
         boolector::BV::from_u32(btor, u, width)
     }
-    fn from_i64(btor: Rc<Btor>, i: i64, width: u32) -> Self {
+    fn from_i64(btor: BtorRef, i: i64, width: u32) -> Self {
         boolector::BV::from_i64(btor, i, width)
     }
-    fn from_u64(btor: Rc<Btor>, u: u64, width: u32) -> Self {
+    fn from_u64(btor: BtorRef, u: u64, width: u32) -> Self {
         boolector::BV::from_u64(btor, u, width)
     }
-    fn zero(btor: Rc<Btor>, width: u32) -> Self {
+    fn zero(btor: BtorRef, width: u32) -> Self {
         boolector::BV::zero(btor, width)
     }
-    fn one(btor: Rc<Btor>, width: u32) -> Self {
+    fn one(btor: BtorRef, width: u32) -> Self {
         boolector::BV::one(btor, width)
     }
-    fn ones(btor: Rc<Btor>, width: u32) -> Self {
+    fn ones(btor: BtorRef, width: u32) -> Self {
         boolector::BV::ones(btor, width)
     }
-    fn from_binary_str(btor: Rc<Btor>, bits: &str) -> Self {
+    fn from_binary_str(btor: BtorRef, bits: &str) -> Self {
         boolector::BV::from_binary_str(btor, bits)
     }
-    fn from_dec_str(btor: Rc<Btor>, num: &str, width: u32) -> Self {
+    fn from_dec_str(btor: BtorRef, num: &str, width: u32) -> Self {
         boolector::BV::from_dec_str(btor, num, width)
     }
-    fn from_hex_str(btor: Rc<Btor>, num: &str, width: u32) -> Self {
+    fn from_hex_str(btor: BtorRef, num: &str, width: u32) -> Self {
         boolector::BV::from_hex_str(btor, num, width)
     }
     fn as_binary_str(&self) -> Option<String> {
@@ -444,6 +667,7 @@ impl BV for boolector::BV<Rc<Btor>> {
     }
     fn assert(&self) -> Result<()> {
         self.assert();
+        self.get_btor().record_assertion(self);
         Ok(())
     }
     fn is_failed_assumption(&self) -> bool {
@@ -605,12 +829,12 @@ impl BV for boolector::BV<Rc<Btor>> {
 }
 
 impl Memory for crate::cell_memory::Memory {
-    type SolverRef = Rc<Btor>;
-    type Index = boolector::BV<Rc<Btor>>;
-    type Value = boolector::BV<Rc<Btor>>;
+    type SolverRef = BtorRef;
+    type Index = boolector::BV<BtorRef>;
+    type Value = boolector::BV<BtorRef>;
 
     fn new_uninitialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
@@ -618,34 +842,52 @@ impl Memory for crate::cell_memory::Memory {
         crate::cell_memory::Memory::new_uninitialized(btor, null_detection, name, addr_bits)
     }
     fn new_zero_initialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
     ) -> Self {
         crate::cell_memory::Memory::new_zero_initialized(btor, null_detection, name, addr_bits)
     }
+    fn new_uninitialized_with_cell_size(
+        btor: BtorRef,
+        null_detection: bool,
+        name: Option<&str>,
+        addr_bits: u32,
+        cell_bits: u32,
+    ) -> Self {
+        crate::cell_memory::Memory::new_uninitialized_with_cell_size(
+            btor,
+            null_detection,
+            name,
+            addr_bits,
+            cell_bits,
+        )
+    }
     fn read(&self, index: &Self::Index, bits: u32) -> Result<Self::Value> {
         self.read(index, bits)
     }
     fn write(&mut self, index: &Self::Index, value: Self::Value) -> Result<()> {
         self.write(index, value)
     }
-    fn get_solver(&self) -> Rc<Btor> {
+    fn is_initialized(&self, index: &Self::Index, bits: u32) -> bool {
+        self.is_initialized(index, bits)
+    }
+    fn get_solver(&self) -> BtorRef {
         self.get_solver()
     }
-    fn change_solver(&mut self, new_btor: Rc<Btor>) {
+    fn change_solver(&mut self, new_btor: BtorRef) {
         self.change_solver(new_btor)
     }
 }
 
 impl Memory for crate::simple_memory::Memory {
-    type SolverRef = Rc<Btor>;
-    type Index = boolector::BV<Rc<Btor>>;
-    type Value = boolector::BV<Rc<Btor>>;
+    type SolverRef = BtorRef;
+    type Index = boolector::BV<BtorRef>;
+    type Value = boolector::BV<BtorRef>;
 
     fn new_uninitialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
@@ -653,7 +895,7 @@ impl Memory for crate::simple_memory::Memory {
         crate::simple_memory::Memory::new_uninitialized(btor, null_detection, name, addr_bits)
     }
     fn new_zero_initialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
@@ -666,10 +908,13 @@ impl Memory for crate::simple_memory::Memory {
     fn write(&mut self, index: &Self::Index, value: Self::Value) -> Result<()> {
         self.write(index, value)
     }
-    fn get_solver(&self) -> Rc<Btor> {
+    fn is_initialized(&self, index: &Self::Index, bits: u32) -> bool {
+        self.is_initialized(index, bits)
+    }
+    fn get_solver(&self) -> BtorRef {
         self.get_solver()
     }
-    fn change_solver(&mut self, new_btor: Rc<Btor>) {
+    fn change_solver(&mut self, new_btor: BtorRef) {
         self.change_solver(new_btor)
     }
 }
@@ -678,8 +923,8 @@ impl Memory for crate::simple_memory::Memory {
 pub struct CellMemoryBackend {}
 
 impl Backend for CellMemoryBackend {
-    type SolverRef = Rc<Btor>;
-    type BV = boolector::BV<Rc<Btor>>;
+    type SolverRef = BtorRef;
+    type BV = boolector::BV<BtorRef>;
     type Memory = crate::cell_memory::Memory;
 }
 
@@ -687,7 +932,7 @@ impl Backend for CellMemoryBackend {
 pub struct DefaultBackend {}
 
 impl Backend for DefaultBackend {
-    type SolverRef = Rc<Btor>;
-    type BV = boolector::BV<Rc<Btor>>;
+    type SolverRef = BtorRef;
+    type BV = boolector::BV<BtorRef>;
     type Memory = crate::simple_memory::Memory;
 }
@@ -1,8 +1,9 @@
-//! Functions and structures for defining and activating instruction callbacks
+//! Functions and structures for defining and activating instruction, call, return, and watchpoint callbacks
 
 use crate::backend::Backend;
 use crate::error::Result;
 use crate::state::State;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 #[derive(Clone)]
@@ -22,6 +23,30 @@ pub struct Callbacks<'p, B: Backend> {
     #[allow(clippy::type_complexity)]
     pub(crate) terminator_callbacks:
         Vec<Rc<dyn Fn(&'p llvm_ir::Terminator, &State<B>) -> Result<()> + 'p>>,
+
+    /// `haybale` will call each of these functions upon entering a function
+    /// via a `call` or `invoke` instruction, after the callee's parameters
+    /// have been assigned.
+    ///
+    /// If the callback returns an `Err`, `haybale` will propagate it accordingly.
+    #[allow(clippy::type_complexity)]
+    pub(crate) call_callbacks: Vec<Rc<dyn Fn(&'p llvm_ir::Function, &State<B>) -> Result<()> + 'p>>,
+
+    /// `haybale` will call each of these functions upon leaving a function
+    /// and returning (or unwinding) to its caller.
+    ///
+    /// If the callback returns an `Err`, `haybale` will propagate it accordingly.
+    #[allow(clippy::type_complexity)]
+    pub(crate) return_callbacks:
+        Vec<Rc<dyn Fn(&'p llvm_ir::Function, &State<B>) -> Result<()> + 'p>>,
+
+    /// Map from memory watchpoint name to the function `haybale` will call
+    /// when that watchpoint is triggered.
+    ///
+    /// If the callback returns an `Err`, `haybale` will propagate it accordingly.
+    #[allow(clippy::type_complexity)]
+    pub(crate) watchpoint_callbacks:
+        HashMap<String, Rc<dyn Fn(&State<B>, bool, &B::BV, &B::BV) -> Result<()> + 'p>>,
 }
 
 impl<'p, B: Backend> Callbacks<'p, B> {
@@ -54,6 +79,60 @@ impl<'p, B: Backend> Callbacks<'p, B> {
     ) {
         self.terminator_callbacks.push(Rc::new(cb))
     }
+
+    /// Add a call callback. `haybale` will call the provided function each
+    /// time symbolic execution enters a function via a `call` or `invoke`
+    /// instruction, passing the callee. The `State` will already be positioned
+    /// at the start of the callee, with its parameters assigned.
+    ///
+    /// Calls which are handled by hooks do not trigger call callbacks.
+    ///
+    /// If any callback returns an `Err`, `haybale` will propagate it accordingly.
+    pub fn add_call_callback(
+        &mut self,
+        cb: impl Fn(&'p llvm_ir::Function, &State<B>) -> Result<()> + 'p,
+    ) {
+        self.call_callbacks.push(Rc::new(cb))
+    }
+
+    /// Add a return callback. `haybale` will call the provided function each
+    /// time symbolic execution leaves a function and returns to its caller,
+    /// passing the function being left. This includes leaving a function
+    /// because an exception is propagating through it. It does not include
+    /// returning from the top-level function.
+    ///
+    /// If any callback returns an `Err`, `haybale` will propagate it accordingly.
+    pub fn add_return_callback(
+        &mut self,
+        cb: impl Fn(&'p llvm_ir::Function, &State<B>) -> Result<()> + 'p,
+    ) {
+        self.return_callbacks.push(Rc::new(cb))
+    }
+
+    /// Add a watchpoint callback for the memory watchpoint with the given
+    /// name. `haybale` will call the provided function each time that
+    /// watchpoint is triggered while it is enabled, passing whether the
+    /// access was a write (`true`) or a read (`false`), the address of the
+    /// access, and the value read or written. For writes, the write will
+    /// already have been performed.
+    ///
+    /// The watchpoint itself is added separately, with
+    /// `state.add_mem_watchpoint()` or `Config.initial_mem_watchpoints`.
+    /// Value-triggered watchpoints (`state.watch_for_value()`) do not run
+    /// callbacks.
+    ///
+    /// If a callback was previously added for the same watchpoint name, this
+    /// replaces it.
+    ///
+    /// If the callback returns an `Err`, `haybale` will propagate it accordingly.
+    pub fn add_watchpoint_callback(
+        &mut self,
+        watchpoint_name: impl Into<String>,
+        cb: impl Fn(&State<B>, bool, &B::BV, &B::BV) -> Result<()> + 'p,
+    ) {
+        self.watchpoint_callbacks
+            .insert(watchpoint_name.into(), Rc::new(cb));
+    }
 }
 
 impl<'p, B: Backend> Default for Callbacks<'p, B> {
@@ -61,6 +140,9 @@ impl<'p, B: Backend> Default for Callbacks<'p, B> {
         Self {
             instruction_callbacks: Vec::new(),
             terminator_callbacks: Vec::new(),
+            call_callbacks: Vec::new(),
+            return_callbacks: Vec::new(),
+            watchpoint_callbacks: HashMap::new(),
         }
     }
 }
@@ -1,14 +1,13 @@
-//! Implementation of a `Memory` based on a Boolector array and 64-bit cells.
+//! Implementation of a `Memory` based on a Boolector array and (by default) 64-bit cells.
 //! Handles fully general read and write operations: arbitrary addresses,
 //! sizes, and alignments.
 
-use crate::backend::SolverRef;
+use crate::backend::{BtorRef, SolverRef};
 use crate::error::*;
 use crate::solver_utils::bvs_can_be_equal;
-use boolector::Btor;
+use crate::written_bytes::WrittenBytes;
 use log::debug;
 use std::convert::TryInto;
-use std::rc::Rc;
 
 // Rust 1.51.0 introduced its own `.reduce()` on the main `Iterator` trait.
 // So, starting with 1.51.0, we don't need `reduce::Reduce`, and in fact it
@@ -16,15 +15,22 @@ use std::rc::Rc;
 #[rustversion::before(1.51)]
 use reduce::Reduce;
 
-type BV = boolector::BV<Rc<Btor>>;
-type Array = boolector::Array<Rc<Btor>>;
+type BV = boolector::BV<BtorRef>;
+type Array = boolector::Array<BtorRef>;
 
 #[derive(Clone, Debug)]
 pub struct Memory {
-    btor: Rc<Btor>,
+    btor: BtorRef,
     mem: Array,
     name: String,
     null_detection: bool,
+    /// Which bytes have been written, for `is_initialized()`
+    written: WrittenBytes,
+    /// Size of each memory "cell", in bits
+    cell_bits: u32,
+    /// log base 2 of the number of bytes in each cell. This many of the bottom
+    /// index bits determine cell offset.
+    log_cell_bytes: u32,
     cell_bytes_as_bv: BV,
     log_bits_in_byte_as_bv: BV,
     log_bits_in_byte_as_wide_bv: BV,
@@ -32,12 +38,12 @@ pub struct Memory {
 
 impl Memory {
     pub const INDEX_BITS: u32 = 64; // memory takes 64-bit indices
-    pub const CELL_BITS: u32 = 64; // memory "cells" are also 64-bit sized; we will mask if smaller operations are needed
+    pub const CELL_BITS: u32 = 64; // by default, memory "cells" are also 64-bit sized; we will mask if smaller operations are needed
     pub const BITS_IN_BYTE: u32 = 8;
     pub const LOG_BITS_IN_BYTE: u32 = 3; // log base 2 of BITS_IN_BYTE
-    pub const CELL_BYTES: u32 = Self::CELL_BITS / Self::BITS_IN_BYTE; // how many bytes in a cell
-    pub const LOG_CELL_BYTES: u32 = 3; // log base 2 of CELL_BYTES. This many of the bottom index bits determine cell offset.
-    pub const CELL_OFFSET_MASK: u32 = 0x7; // Applying this mask to the address gives the cell offset
+    pub const CELL_BYTES: u32 = Self::CELL_BITS / Self::BITS_IN_BYTE; // how many bytes in a default-sized cell
+    pub const LOG_CELL_BYTES: u32 = 3; // log base 2 of CELL_BYTES. For default-sized cells, this many of the bottom index bits determine cell offset.
+    pub const CELL_OFFSET_MASK: u32 = 0x7; // For default-sized cells, applying this mask to the address gives the cell offset
 
     /// A new `Memory`, whose contents at all addresses are completely uninitialized (unconstrained)
     ///
@@ -49,40 +55,18 @@ impl Memory {
     ///
     /// `addr_bits`: e.g. `64` for a `Memory` which uses 64-bit addresses
     pub fn new_uninitialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
     ) -> Self {
-        assert_eq!(addr_bits, Self::INDEX_BITS, "This `Memory` is only compatible with {}-bit pointers. Try `DefaultBackend` instead of `CellMemoryBackend` for a `Memory` which works with more pointer sizes.", Self::INDEX_BITS);
-        let log_num_cells = Self::INDEX_BITS - Self::LOG_CELL_BYTES; // 2 to this number gives the number of memory cells
-        let default_name = "mem";
-        Self {
-            mem: Array::new(
-                btor.clone(),
-                log_num_cells,
-                Self::CELL_BITS,
-                name.or(Some(default_name)),
-            ),
-            name: name.unwrap_or(default_name).into(),
+        Self::new_uninitialized_with_cell_size(
+            btor,
             null_detection,
-            cell_bytes_as_bv: BV::from_u64(
-                btor.clone(),
-                u64::from(Self::CELL_BYTES),
-                Self::INDEX_BITS,
-            ),
-            log_bits_in_byte_as_bv: BV::from_u64(
-                btor.clone(),
-                u64::from(Self::LOG_BITS_IN_BYTE),
-                Self::CELL_BITS,
-            ),
-            log_bits_in_byte_as_wide_bv: BV::from_u64(
-                btor.clone(),
-                u64::from(Self::LOG_BITS_IN_BYTE),
-                2 * Self::CELL_BITS,
-            ),
-            btor, // out of order so it can be used above but moved in here
-        }
+            name,
+            addr_bits,
+            Self::CELL_BITS,
+        )
     }
 
     /// A new `Memory`, whose contents at all addresses are initialized to be `0`
@@ -95,44 +79,152 @@ impl Memory {
     ///
     /// `addr_bits`: e.g. `64` for a `Memory` which uses 64-bit addresses
     pub fn new_zero_initialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
     ) -> Self {
-        assert_eq!(addr_bits, Self::INDEX_BITS, "This `Memory` is only compatible with {}-bit pointers. Try `DefaultBackend` instead of `CellMemoryBackend` for a `Memory` which works with more pointer sizes.", Self::INDEX_BITS);
-        let log_num_cells = Self::INDEX_BITS - Self::LOG_CELL_BYTES; // 2 to this number gives the number of memory cells
+        Self::new_zero_initialized_with_cell_size(
+            btor,
+            null_detection,
+            name,
+            addr_bits,
+            Self::CELL_BITS,
+        )
+    }
+
+    /// Like `new_uninitialized()`, but with the given cell size rather than
+    /// the default of `CELL_BITS`.
+    ///
+    /// `cell_bits`: size of each memory "cell", in bits. Accesses which are
+    /// cell-aligned and exactly one cell wide need the fewest solver
+    /// operations, so this can be tuned to match the most common access size.
+    /// Must be a power of two, and at least 16.
+    pub fn new_uninitialized_with_cell_size(
+        btor: BtorRef,
+        null_detection: bool,
+        name: Option<&str>,
+        addr_bits: u32,
+        cell_bits: u32,
+    ) -> Self {
+        Self::check_params(addr_bits, cell_bits);
+        let default_name = "mem";
+        let mem = Array::new(
+            btor.clone(),
+            Self::log_num_cells(cell_bits),
+            cell_bits,
+            name.or(Some(default_name)),
+        );
+        Self::from_array(
+            btor,
+            mem,
+            name.unwrap_or(default_name),
+            null_detection,
+            WrittenBytes::none(),
+            cell_bits,
+        )
+    }
+
+    /// Like `new_zero_initialized()`, but with the given cell size rather than
+    /// the default of `CELL_BITS`.
+    ///
+    /// `cell_bits`: size of each memory "cell", in bits; see notes on
+    /// `new_uninitialized_with_cell_size()`.
+    pub fn new_zero_initialized_with_cell_size(
+        btor: BtorRef,
+        null_detection: bool,
+        name: Option<&str>,
+        addr_bits: u32,
+        cell_bits: u32,
+    ) -> Self {
+        Self::check_params(addr_bits, cell_bits);
         let default_name = "mem_initialized";
+        let mem = Array::new_initialized(
+            btor.clone(),
+            Self::log_num_cells(cell_bits),
+            cell_bits,
+            &BV::zero(btor.clone(), cell_bits),
+        );
+        Self::from_array(
+            btor,
+            mem,
+            name.unwrap_or(default_name),
+            null_detection,
+            WrittenBytes::all(),
+            cell_bits,
+        )
+    }
+
+    fn check_params(addr_bits: u32, cell_bits: u32) {
+        assert_eq!(addr_bits, Self::INDEX_BITS, "This `Memory` is only compatible with {}-bit pointers. Try `DefaultBackend` instead of `CellMemoryBackend` for a `Memory` which works with more pointer sizes.", Self::INDEX_BITS);
+        assert!(
+            cell_bits.is_power_of_two() && cell_bits >= 16,
+            "Memory cell size must be a power of two, and at least 16 bits; got {}",
+            cell_bits
+        );
+    }
+
+    /// log base 2 of the number of bytes in a cell of the given size
+    fn log_cell_bytes(cell_bits: u32) -> u32 {
+        (cell_bits / Self::BITS_IN_BYTE).trailing_zeros()
+    }
+
+    /// 2 to this number gives the number of memory cells of the given size
+    fn log_num_cells(cell_bits: u32) -> u32 {
+        Self::INDEX_BITS - Self::log_cell_bytes(cell_bits)
+    }
+
+    fn from_array(
+        btor: BtorRef,
+        mem: Array,
+        name: &str,
+        null_detection: bool,
+        written: WrittenBytes,
+        cell_bits: u32,
+    ) -> Self {
         Self {
-            mem: Array::new_initialized(
-                btor.clone(),
-                log_num_cells,
-                Self::CELL_BITS,
-                &BV::zero(btor.clone(), Self::CELL_BITS),
-            ),
-            name: name.unwrap_or(default_name).into(),
+            mem,
+            name: name.into(),
             null_detection,
+            written,
+            cell_bits,
+            log_cell_bytes: Self::log_cell_bytes(cell_bits),
             cell_bytes_as_bv: BV::from_u64(
                 btor.clone(),
-                u64::from(Self::CELL_BYTES),
+                u64::from(cell_bits / Self::BITS_IN_BYTE),
                 Self::INDEX_BITS,
             ),
             log_bits_in_byte_as_bv: BV::from_u64(
                 btor.clone(),
                 u64::from(Self::LOG_BITS_IN_BYTE),
-                Self::CELL_BITS,
+                cell_bits,
             ),
             log_bits_in_byte_as_wide_bv: BV::from_u64(
                 btor.clone(),
                 u64::from(Self::LOG_BITS_IN_BYTE),
-                2 * Self::CELL_BITS,
+                2 * cell_bits,
             ),
             btor, // out of order so it can be used above but moved in here
         }
     }
 
+    /// Get the size of each memory "cell", in bits
+    pub fn cell_bits(&self) -> u32 {
+        self.cell_bits
+    }
+
+    /// How many bytes in a cell
+    fn cell_bytes(&self) -> u32 {
+        self.cell_bits / Self::BITS_IN_BYTE
+    }
+
+    /// Applying this mask to the address gives the cell offset
+    fn cell_offset_mask(&self) -> u64 {
+        u64::from(self.cell_bytes()) - 1
+    }
+
     /// Get a reference to the `Btor` instance this `Memory` belongs to
-    pub fn get_solver(&self) -> Rc<Btor> {
+    pub fn get_solver(&self) -> BtorRef {
         self.btor.clone()
     }
 
@@ -142,7 +234,7 @@ impl Memory {
     /// via `Btor::duplicate()` from the `Btor` this `Memory` was originally
     /// created with (or most recently changed to). Further, no new variables
     /// should have been added since the call to `Btor::duplicate()`.
-    pub fn change_solver(&mut self, new_btor: Rc<Btor>) {
+    pub fn change_solver(&mut self, new_btor: BtorRef) {
         self.mem = new_btor.match_array(&self.mem).unwrap();
         self.cell_bytes_as_bv = new_btor.match_bv(&self.cell_bytes_as_bv).unwrap();
         self.log_bits_in_byte_as_bv = new_btor.match_bv(&self.log_bits_in_byte_as_bv).unwrap();
@@ -156,7 +248,7 @@ impl Memory {
     /// If address is not cell-aligned, this will give the entire cell _containing_ that address.
     fn read_cell(&self, addr: &BV) -> BV {
         assert_eq!(addr.get_width(), Self::INDEX_BITS);
-        let cell_num = addr.slice(Self::INDEX_BITS - 1, Self::LOG_CELL_BYTES); // discard the cell offset
+        let cell_num = addr.slice(Self::INDEX_BITS - 1, self.log_cell_bytes); // discard the cell offset
         self.mem.read(&cell_num)
     }
 
@@ -165,8 +257,8 @@ impl Memory {
     // TODO: to enforce concretization, we could just take a u64 address here
     fn write_cell(&mut self, addr: &BV, val: BV) {
         assert_eq!(addr.get_width(), Self::INDEX_BITS);
-        assert_eq!(val.get_width(), Self::CELL_BITS);
-        let cell_num = addr.slice(Self::INDEX_BITS - 1, Self::LOG_CELL_BYTES); // discard the cell offset
+        assert_eq!(val.get_width(), self.cell_bits);
+        let cell_num = addr.slice(Self::INDEX_BITS - 1, self.log_cell_bytes); // discard the cell offset
         self.mem = self.mem.write(&cell_num, &val);
     }
 
@@ -174,14 +266,14 @@ impl Memory {
     /// Returned `BV` will have size `bits`.
     fn read_within_cell(&self, addr: &BV, bits: u32) -> BV {
         let cell_contents = self.read_cell(addr);
-        assert!(bits <= Self::CELL_BITS);
-        if bits == Self::CELL_BITS {
+        assert!(bits <= self.cell_bits);
+        if bits == self.cell_bits {
             cell_contents // shortcut to avoid more BV operations
                           // This assumes that `addr` was cell-aligned, but that must be the case if we're reading CELL_BITS bits and not crossing cell boundaries
         } else {
             let offset = addr
-                .slice(Self::LOG_CELL_BYTES - 1, 0) // the actual offset part of the address
-                .uext(Self::CELL_BITS - Self::LOG_CELL_BYTES) // zero-extend to CELL_BITS
+                .slice(self.log_cell_bytes - 1, 0) // the actual offset part of the address
+                .uext(self.cell_bits - self.log_cell_bytes) // zero-extend to CELL_BITS
                 .sll(&self.log_bits_in_byte_as_bv); // offset in bits rather than bytes
 
             // We can't `slice` at a non-const location, but we can shift by a non-const amount
@@ -195,25 +287,25 @@ impl Memory {
     // TODO: to enforce concretization, we could just take a `u64` address here
     fn write_within_cell(&mut self, addr: &BV, val: BV) {
         let write_size = val.get_width();
-        assert!(write_size <= Self::CELL_BITS);
-        let data_to_write = if write_size == Self::CELL_BITS {
+        assert!(write_size <= self.cell_bits);
+        let data_to_write = if write_size == self.cell_bits {
             val // shortcut to avoid more BV operations
                 // This assumes that `addr` was cell-aligned, but that must be the case if we're writing CELL_BITS bits and not crossing cell boundaries
         } else {
             let offset = addr
-                .slice(Self::LOG_CELL_BYTES - 1, 0) // the actual offset part of the address
-                .uext(Self::CELL_BITS - Self::LOG_CELL_BYTES) // zero-extend to CELL_BITS
+                .slice(self.log_cell_bytes - 1, 0) // the actual offset part of the address
+                .uext(self.cell_bits - self.log_cell_bytes) // zero-extend to CELL_BITS
                 .sll(&self.log_bits_in_byte_as_bv); // offset in bits rather than bytes
 
             // mask_clear is 0's in the bit positions that will be written, 1's elsewhere.
             // We construct the inverse of this mask, then bitwise negate it.
             let mask_clear = BV::ones(self.btor.clone(), write_size) // a bitvector of ones, of width equal to the width that will be written
-                .uext(Self::CELL_BITS - write_size) // zero-extend to CELL_BITS
+                .uext(self.cell_bits - write_size) // zero-extend to CELL_BITS
                 .sll(&offset) // now we have ones in the bit positions that will be written, zeroes elsewhere
                 .not(); // the final desired mask
 
             // mask_write is the write data in its appropriate bit positions, 0's elsewhere.
-            let mask_write = val.uext(Self::CELL_BITS - write_size).sll(&offset);
+            let mask_write = val.uext(self.cell_bits - write_size).sll(&offset);
 
             self.read_cell(addr)
                 .and(&mask_clear) // zero out the section we'll be writing
@@ -225,7 +317,7 @@ impl Memory {
     /// Read up to a cell size's worth of memory, at any alignment. May cross cell boundaries.
     /// Returned `BV` will have size `bits`.
     fn read_small(&self, addr: &BV, bits: u32) -> BV {
-        assert!(bits <= Self::CELL_BITS);
+        assert!(bits <= self.cell_bits);
         if bits <= 8 {
             // In this case we can't possibly cross cell boundaries
             self.read_within_cell(addr, bits)
@@ -236,8 +328,8 @@ impl Memory {
                 .read_cell(&next_cell_addr)
                 .concat(&self.read_cell(addr));
             let offset = addr
-                .slice(Self::LOG_CELL_BYTES - 1, 0) // the actual offset part of the address
-                .uext(2 * Self::CELL_BITS - Self::LOG_CELL_BYTES) // zero-extend to 2*CELL_BITS
+                .slice(self.log_cell_bytes - 1, 0) // the actual offset part of the address
+                .uext(2 * self.cell_bits - self.log_cell_bytes) // zero-extend to 2*CELL_BITS
                 .sll(&self.log_bits_in_byte_as_wide_bv); // offset in bits rather than bytes
 
             // We can't `slice` at a non-const location, but we can shift by a non-const amount
@@ -250,7 +342,7 @@ impl Memory {
     /// Write up to a cell size's worth of memory, at any alignment. May cross cell boundaries.
     fn write_small(&mut self, addr: &BV, val: BV) {
         let write_size = val.get_width();
-        assert!(write_size <= Self::CELL_BITS);
+        assert!(write_size <= self.cell_bits);
         if write_size <= 8 {
             // In this case we can't possibly cross cell boundaries
             self.write_within_cell(addr, val);
@@ -258,19 +350,19 @@ impl Memory {
             // We'll allow for the possibility that the write crosses into the next cell
             let next_cell_addr = addr.add(&self.cell_bytes_as_bv);
             let offset = addr
-                .slice(Self::LOG_CELL_BYTES - 1, 0) // the actual offset part of the address
-                .uext(2 * Self::CELL_BITS - Self::LOG_CELL_BYTES) // zero-extend to 2*CELL_BITS
+                .slice(self.log_cell_bytes - 1, 0) // the actual offset part of the address
+                .uext(2 * self.cell_bits - self.log_cell_bytes) // zero-extend to 2*CELL_BITS
                 .sll(&self.log_bits_in_byte_as_wide_bv); // offset in bits rather than bytes
 
             // mask_clear is 0's in the bit positions that will be written, 1's elsewhere.
             // We construct the inverse of this mask, then bitwise negate it.
             let mask_clear = BV::ones(self.btor.clone(), write_size) // a bitvector of ones, of width equal to the width that will be written
-                .uext(2 * Self::CELL_BITS - write_size) // zero-extend to 2*CELL_BITS
+                .uext(2 * self.cell_bits - write_size) // zero-extend to 2*CELL_BITS
                 .sll(&offset) // now we have ones in the bit positions that will be written, zeroes elsewhere
                 .not(); // the final desired mask
 
             // mask_write is the write data in its appropriate bit positions, 0's elsewhere.
-            let mask_write = val.uext(2 * Self::CELL_BITS - write_size).sll(&offset);
+            let mask_write = val.uext(2 * self.cell_bits - write_size).sll(&offset);
 
             let data_to_write = self
                 .read_cell(&next_cell_addr)
@@ -278,10 +370,10 @@ impl Memory {
                 .and(&mask_clear) // zero out the section we'll be writing
                 .or(&mask_write); // write the data
 
-            self.write_cell(addr, data_to_write.slice(Self::CELL_BITS - 1, 0)); // first cell gets the low bits
+            self.write_cell(addr, data_to_write.slice(self.cell_bits - 1, 0)); // first cell gets the low bits
             self.write_cell(
                 &next_cell_addr,
-                data_to_write.slice(2 * Self::CELL_BITS - 1, Self::CELL_BITS),
+                data_to_write.slice(2 * self.cell_bits - 1, self.cell_bits),
             ); // second cell gets the high bits
         }
     }
@@ -290,13 +382,13 @@ impl Memory {
     /// Returned `BV` will have size `bits`.
     fn read_large_aligned(&self, addr: &BV, bits: u32) -> BV {
         assert_ne!(bits, 0); // this function still technically works for small reads (just less efficient), so we only check for size 0 (which would break it)
-        let num_full_cells = (bits - 1) / Self::CELL_BITS; // this is bits / CELL_BITS, but if bits is a multiple of CELL_BITS, it undercounts by 1 (we treat this as N-1 full cells plus a "partial" cell of CELL_BITS bits)
-        let bits_in_last_cell = (bits - 1) % Self::CELL_BITS + 1; // this is bits % CELL_BITS, but if bits is a multiple of CELL_BITS, then we get CELL_BITS rather than 0
-        itertools::repeat_n(Self::CELL_BITS, num_full_cells.try_into().unwrap())
+        let num_full_cells = (bits - 1) / self.cell_bits; // this is bits / CELL_BITS, but if bits is a multiple of CELL_BITS, it undercounts by 1 (we treat this as N-1 full cells plus a "partial" cell of CELL_BITS bits)
+        let bits_in_last_cell = (bits - 1) % self.cell_bits + 1; // this is bits % CELL_BITS, but if bits is a multiple of CELL_BITS, then we get CELL_BITS rather than 0
+        itertools::repeat_n(self.cell_bits, num_full_cells.try_into().unwrap())
             .chain(std::iter::once(bits_in_last_cell)) // this forms the sequence of read sizes
             .enumerate()
             .map(|(i, sz)| {
-                let offset_bytes = i as u64 * u64::from(Self::CELL_BYTES);
+                let offset_bytes = i as u64 * u64::from(self.cell_bytes());
                 // note that all reads in the sequence must be within-cell, i.e., not cross cell boundaries, because of how we constructed the sequence
                 self.read_within_cell(
                     &addr.add(&BV::from_u64(
@@ -315,15 +407,15 @@ impl Memory {
     fn write_large_aligned(&mut self, addr: &BV, val: BV) {
         let write_size = val.get_width();
         assert_ne!(write_size, 0); // this function still technically works for small writes (just less efficient), so we only check for size 0 (which would break it)
-        let num_full_cells = (write_size - 1) / Self::CELL_BITS; // this is bits / CELL_BITS, but if bits is a multiple of CELL_BITS, it undercounts by 1 (we treat this as N-1 full cells plus a "partial" cell of CELL_BITS bits)
-        let bits_in_last_cell = (write_size - 1) % Self::CELL_BITS + 1; // this is bits % CELL_BITS, but if bits is a multiple of CELL_BITS, then we get CELL_BITS rather than 0
+        let num_full_cells = (write_size - 1) / self.cell_bits; // this is bits / CELL_BITS, but if bits is a multiple of CELL_BITS, it undercounts by 1 (we treat this as N-1 full cells plus a "partial" cell of CELL_BITS bits)
+        let bits_in_last_cell = (write_size - 1) % self.cell_bits + 1; // this is bits % CELL_BITS, but if bits is a multiple of CELL_BITS, then we get CELL_BITS rather than 0
         let write_size_sequence =
-            itertools::repeat_n(Self::CELL_BITS, num_full_cells.try_into().unwrap())
+            itertools::repeat_n(self.cell_bits, num_full_cells.try_into().unwrap())
                 .chain(std::iter::once(bits_in_last_cell)); // note that all writes in this sequence must be within-cell, i.e., not cross cell boundaries, because of how we constructed the sequence
         for (i, sz) in write_size_sequence.enumerate() {
             assert!(sz > 0);
-            let offset_bytes = i as u64 * u64::from(Self::CELL_BYTES);
-            let offset_bits = i as u32 * Self::CELL_BITS;
+            let offset_bytes = i as u64 * u64::from(self.cell_bytes());
+            let offset_bits = i as u32 * self.cell_bits;
             let write_data = val.slice(sz + offset_bits - 1, offset_bits);
             self.write_within_cell(
                 &addr.add(&BV::from_u64(
@@ -349,20 +441,20 @@ impl Memory {
             return Err(Error::NullPointerDereference);
         }
 
-        let rval = if bits <= Self::CELL_BITS {
+        let rval = if bits <= self.cell_bits {
             // special-case small reads because read_small() can handle them directly and efficiently
             self.read_small(addr, bits)
         } else {
             // Let's see if we can refactor this into a small read plus a large cell-aligned read
             if let Some(addr_u64) = addr.as_u64() {
                 // addr is constrained to a single concrete value, which we could find without a solve. Yay!
-                let cell_offset = addr_u64 & u64::from(Self::CELL_OFFSET_MASK);
+                let cell_offset = addr_u64 & self.cell_offset_mask();
                 if cell_offset == 0 {
                     // the address is cell-aligned, and we're free to do the large read
                     self.read_large_aligned(addr, bits)
                 } else {
-                    let bytes_till_cell_boundary = u64::from(Self::CELL_BYTES) - cell_offset;
-                    // first read the remainder of the cell to bring us to a cell boundary; this read must be <= Self::CELL_BITS
+                    let bytes_till_cell_boundary = u64::from(self.cell_bytes()) - cell_offset;
+                    // first read the remainder of the cell to bring us to a cell boundary; this read must be <= self.cell_bits
                     let first =
                         self.read_small(addr, bytes_till_cell_boundary as u32 * Self::BITS_IN_BYTE);
                     // now read the rest, which will be a cell-aligned read
@@ -400,6 +492,21 @@ impl Memory {
         Ok(rval)
     }
 
+    /// Have all of the `bits` bits at `addr` been written since this `Memory`
+    /// was created? Memory created with `new_zero_initialized()` counts as
+    /// written everywhere.
+    ///
+    /// Only writes and reads at concrete addresses are tracked precisely: a
+    /// read at a symbolic address is always considered initialized, and after
+    /// any write to a symbolic address, all memory is considered initialized.
+    pub fn is_initialized(&self, addr: &BV, bits: u32) -> bool {
+        let mut bytes = bits / Self::BITS_IN_BYTE;
+        if bytes * Self::BITS_IN_BYTE < bits {
+            bytes += 1; // round up to nearest byte
+        }
+        self.written.is_written(addr.as_u64(), u64::from(bytes))
+    }
+
     /// Write any number (>0) of bits of memory, at any alignment.
     pub fn write(&mut self, addr: &BV, val: BV) -> Result<()> {
         debug!("Writing {:?} to {} address {:?}", val, &self.name, addr);
@@ -417,22 +524,28 @@ impl Memory {
         }
 
         let write_size = val.get_width();
-        if write_size <= Self::CELL_BITS {
+        let mut bytes_written = write_size / Self::BITS_IN_BYTE;
+        if bytes_written * Self::BITS_IN_BYTE < write_size {
+            bytes_written += 1; // round up to nearest byte
+        }
+        self.written
+            .record_write(addr.as_u64(), u64::from(bytes_written));
+        if write_size <= self.cell_bits {
             // special-case small writes because write_small() can handle them directly and efficiently
             self.write_small(addr, val)
         } else {
             // Let's see if we can refactor this into a small write plus a large cell-aligned write
             if let Some(addr_u64) = addr.as_u64() {
                 // addr is constrained to a single concrete value, which we could find without a solve. Yay!
-                let cell_offset = addr_u64 & u64::from(Self::CELL_OFFSET_MASK);
+                let cell_offset = addr_u64 & self.cell_offset_mask();
                 if cell_offset == 0 {
                     // the address is cell-aligned, and we're free to do the large write
                     self.write_large_aligned(addr, val)
                 } else {
-                    let bytes_till_cell_boundary = u64::from(Self::CELL_BYTES) - cell_offset;
-                    // first write the remainder of the cell to bring us to a cell boundary; this write must be <= Self::CELL_BITS
+                    let bytes_till_cell_boundary = u64::from(self.cell_bytes()) - cell_offset;
+                    // first write the remainder of the cell to bring us to a cell boundary; this write must be <= self.cell_bits
                     let first =
-                        val.slice(bytes_till_cell_boundary as u32 * Self::BITS_IN_BYTE - 1, 0); // recall that the write is > Self::CELL_BITS, so this slice() must be valid
+                        val.slice(bytes_till_cell_boundary as u32 * Self::BITS_IN_BYTE - 1, 0); // recall that the write is > self.cell_bits, so this slice() must be valid
                     self.write_small(addr, first);
                     // now write the rest, which will be a cell-aligned write
                     let rest = val.slice(
@@ -470,7 +583,8 @@ impl Memory {
 
 impl PartialEq for Memory {
     fn eq(&self, other: &Self) -> bool {
-        self.btor == other.btor && self.mem == other.mem // we don't care about checking equality on `cell_bytes_as_bv`, `log_bits_in_byte_as_bv`, or `log_bits_in_byte_as_wide_bv`
+        self.btor == other.btor && self.mem == other.mem && self.written == other.written
+        // we don't care about checking equality on `cell_bytes_as_bv`, `log_bits_in_byte_as_bv`, or `log_bits_in_byte_as_wide_bv`
     }
 }
 
@@ -481,29 +595,27 @@ mod tests {
     use super::*;
     use crate::error::Result;
     use crate::solver_utils::{self, PossibleSolutions};
-    use boolector::option::{BtorOption, ModelGen};
     use boolector::{BVSolution, BV};
-    use std::rc::Rc;
 
     // Basically the `get_a_solution_for_bv()` method from `State`,
     // without requiring that we construct a `State` or depend on the
     // `State` module
-    fn get_a_solution(bv: &BV<Rc<Btor>>) -> Result<Option<BVSolution>> {
+    fn get_a_solution(bv: &BV<BtorRef>) -> Result<Option<BVSolution>> {
         let btor = bv.get_btor();
-        btor.set_opt(BtorOption::ModelGen(ModelGen::All));
+        btor.set_model_gen(true);
         let solution = if solver_utils::sat(&btor)? {
             Some(bv.get_a_solution())
         } else {
             None
         };
-        btor.set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+        btor.set_model_gen(false);
         Ok(solution)
     }
 
     #[test]
     fn uninitialized() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         let addr = BV::from_u64(btor.clone(), 0x10000, Memory::INDEX_BITS);
@@ -538,7 +650,7 @@ mod tests {
     #[test]
     fn zero_initialized() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mem = Memory::new_zero_initialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         let addr = BV::from_u64(btor.clone(), 0x10000, Memory::INDEX_BITS);
@@ -557,7 +669,7 @@ mod tests {
     #[test]
     fn read_and_write_to_cell_zero() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, Memory::INDEX_BITS);
 
         // Store a cell's worth of data to address 0
@@ -580,7 +692,7 @@ mod tests {
     #[test]
     fn read_and_write_cell_aligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store a cell's worth of data to a nonzero, but aligned, address
@@ -603,7 +715,7 @@ mod tests {
     #[test]
     fn read_and_write_small() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 8 bits of data to an aligned address
@@ -626,7 +738,7 @@ mod tests {
     #[test]
     fn read_single_bit() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 8 bits of data to an aligned address
@@ -649,7 +761,7 @@ mod tests {
     #[test]
     fn read_and_write_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 8 bits of data to offset 1 in a cell
@@ -672,7 +784,7 @@ mod tests {
     #[test]
     fn read_and_write_across_cell_boundaries() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 64 bits of data such that half is in one cell and half in the next
@@ -695,7 +807,7 @@ mod tests {
     #[test]
     fn read_and_write_symbolic_addr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, Memory::INDEX_BITS);
 
         // Store 64 bits of data to a symbolic address
@@ -718,7 +830,7 @@ mod tests {
     #[test]
     fn read_and_write_twocells() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store two cells' worth of data to an aligned address
@@ -757,7 +869,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 200 bits of data to an aligned address
@@ -803,7 +915,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 200 bits of data to an unaligned address
@@ -849,7 +961,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits_symbolic_addr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, Memory::INDEX_BITS);
 
         // Store 200 bits of data to a symbolic address
@@ -895,7 +1007,7 @@ mod tests {
     #[test]
     fn write_twice_read_once() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 8 bits of data
@@ -923,7 +1035,7 @@ mod tests {
     #[test]
     fn write_different_cells() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 32 bits of data to a cell
@@ -958,7 +1070,7 @@ mod tests {
     #[test]
     fn write_different_places_within_cell() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 32 bits of data to a cell
@@ -993,7 +1105,7 @@ mod tests {
     #[test]
     fn write_small_read_big() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_zero_initialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 8 bits of data to offset 1 in a cell
@@ -1041,7 +1153,7 @@ mod tests {
     #[test]
     fn write_big_read_small() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Store 32 bits of data to offset 2 in a cell
@@ -1084,7 +1196,7 @@ mod tests {
     #[test]
     fn partial_overwrite_aligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Write an entire cell
@@ -1119,7 +1231,7 @@ mod tests {
     #[test]
     fn partial_overwrite_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, Memory::INDEX_BITS);
 
         // Write an entire cell
@@ -1160,4 +1272,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn other_cell_sizes() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+        for &cell_bits in &[16, 32, 128] {
+            let btor = <BtorRef as SolverRef>::new();
+            let mut mem = Memory::new_uninitialized_with_cell_size(
+                btor.clone(),
+                true,
+                None,
+                Memory::INDEX_BITS,
+                cell_bits,
+            );
+            assert_eq!(mem.cell_bits(), cell_bits);
+
+            // Write 64 bits to an unaligned address, then overwrite one byte in the middle
+            let addr = BV::from_u64(btor.clone(), 0x10003, Memory::INDEX_BITS);
+            mem.write(&addr, BV::from_u64(btor.clone(), 0x12345678_9abcdef0, 64))?;
+            let overwrite_addr = BV::from_u64(btor.clone(), 0x10005, Memory::INDEX_BITS);
+            mem.write(&overwrite_addr, BV::from_u64(btor.clone(), 0x42, 8))?;
+
+            // Ensure that reading it back reflects both writes
+            let read_bv = mem.read(&addr, 64)?;
+            assert_eq!(solver_utils::sat(&btor), Ok(true));
+            let ps = solver_utils::get_possible_solutions_for_bv(btor.clone(), &read_bv, 1)?
+                .as_u64_solutions()
+                .unwrap();
+            assert_eq!(ps, PossibleSolutions::exactly_one(0x12345678_9a42def0));
+
+            // Zero-initialized memory reads as zero at any alignment
+            let mem = Memory::new_zero_initialized_with_cell_size(
+                btor.clone(),
+                true,
+                None,
+                Memory::INDEX_BITS,
+                cell_bits,
+            );
+            let read_bv = mem.read(&addr, 40)?;
+            let ps = solver_utils::get_possible_solutions_for_bv(btor.clone(), &read_bv, 1)?
+                .as_u64_solutions()
+                .unwrap();
+            assert_eq!(ps, PossibleSolutions::exactly_one(0));
+        }
+        Ok(())
+    }
 }
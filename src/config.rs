@@ -3,9 +3,19 @@
 use crate::backend::Backend;
 use crate::callbacks::Callbacks;
 pub use crate::demangling::Demangling;
+use crate::error::Result;
 use crate::function_hooks::FunctionHooks;
+use crate::global_allocations::Callable;
+use crate::hooks;
+use crate::return_value::ReturnValue;
+use crate::state::State;
 use crate::watchpoints::Watchpoint;
-use std::collections::HashMap;
+use llvm_ir::function::FunctionAttribute;
+use llvm_ir::Name;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Various settings which affect how the symbolic execution is performed.
@@ -29,6 +39,35 @@ pub struct Config<'p, B: Backend> {
     /// Default is `10`.
     pub loop_bound: usize,
 
+    /// Per-loop overrides of `loop_bound`, keyed by the name of the function
+    /// containing the loop and the name of the loop's header block (the block
+    /// which the loop's back edges jump to).
+    ///
+    /// While executing a loop which has an entry here, the bound given here is
+    /// used in place of `loop_bound`. This includes any code called from within
+    /// the loop. If loops with entries here are nested, the innermost one
+    /// applies. A bound of 0 is treated like a bound of 1.
+    ///
+    /// For example, to allow an initialization loop with header block `%5` in
+    /// function `init` to run 256 times while keeping everything else bounded
+    /// by `loop_bound`, insert `(("init".into(), Name::from(5)), 256)`.
+    ///
+    /// Default is the empty map - that is, `loop_bound` applies everywhere.
+    pub loop_bounds: HashMap<(String, Name), usize>,
+
+    /// Names of functions which are exempt from `loop_bound` (and
+    /// `loop_bounds`), e.g. helpers which are known to terminate.
+    ///
+    /// Unlike hooking these functions, they are still symbolically executed
+    /// as usual; but while executing the body of one of these functions, any
+    /// number of iterations of its loops are allowed. Functions they call are
+    /// not exempt unless they're also in this set. `max_instructions_per_path`
+    /// and `max_recursion_depth` still apply, and are the only protection
+    /// against a function in this set which doesn't actually terminate.
+    ///
+    /// Default is the empty set.
+    pub loop_bound_exempt_functions: HashSet<String>,
+
     /// Maximum callstack depth to allow when symbolically executing.
     /// If symbolic execution encounters a call which would result in a
     /// stack depth exceeding this number, and the call is not hooked (see
@@ -58,6 +97,156 @@ pub struct Config<'p, B: Backend> {
     /// Default is `None`.
     pub max_callstack_depth: Option<usize>,
 
+    /// Maximum recursion depth to allow when symbolically executing.
+    /// If symbolic execution encounters a call to a function which already
+    /// has more than this many activations on the callstack, the path will
+    /// end with `Error::RecursionBoundExceeded`.
+    ///
+    /// For example, `Some(0)` means that no recursive calls are allowed at
+    /// all, and `Some(2)` means that a function may be called recursively,
+    /// but may have at most three activations on the callstack at once.
+    ///
+    /// Unlike `max_callstack_depth`, this doesn't affect non-recursive calls
+    /// at all, no matter how deep the call chain is. Note that recursion is
+    /// also still bounded by `loop_bound`.
+    ///
+    /// Calls which are hooked (see
+    /// [`function_hooks`](struct.Config.html#structfield.function_hooks)) are
+    /// not affected by this setting.
+    ///
+    /// Default is `None`, meaning recursion is only bounded by `loop_bound`.
+    pub max_recursion_depth: Option<usize>,
+
+    /// If `true`, calls marked `tail` or `musttail` which are in tail position
+    /// (immediately followed by a `ret` of the call's result, or by a `ret
+    /// void` for a void call) reuse the current function's frame instead of
+    /// pushing a new callsite. This means deep tail-recursive code doesn't grow
+    /// the callstack, so it isn't limited by `max_callstack_depth` or
+    /// `max_recursion_depth`; it's still bounded by `loop_bound`, as with a
+    /// loop.
+    ///
+    /// This only affects the symbolic callstack. haybale itself still recurses
+    /// natively on each tail call, so extremely deep tail recursion (e.g., with
+    /// a large `loop_bound`) can still overflow haybale's own stack.
+    ///
+    /// When a tail call reuses the frame, the callee returns directly to the
+    /// caller of the function containing the tail call. Return callbacks (see
+    /// [`Callbacks`](../callbacks/struct.Callbacks.html)) only run when a
+    /// frame is actually left, and errors' callstacks won't include the
+    /// function containing the tail call.
+    ///
+    /// Note that the LLVM IR parser doesn't distinguish `musttail` from `tail`,
+    /// so both are treated the same. Tail calls to variadic functions always
+    /// push a new callsite.
+    ///
+    /// Default is `false`.
+    pub reuse_frames_for_tail_calls: bool,
+
+    /// Maximum number of LLVM instructions (including terminators) to execute
+    /// on any single path.
+    ///
+    /// If `Some`, any path which would execute more than this many
+    /// instructions will end with `Error::InstructionBudgetExceeded`. The count
+    /// covers the entire path, including instructions in called functions, but
+    /// not anything done inside function hooks. Since the count is per-path,
+    /// instructions executed before a branch are counted towards every path
+    /// through that branch. `State.instructions_executed()` gives the count so
+    /// far for the current path.
+    ///
+    /// This can be used to cut off paths which are long for reasons other than
+    /// loops, or to catch loops which `loop_bound` doesn't catch (e.g., loops
+    /// whose iterations assign no new variables).
+    ///
+    /// Default is `None`, meaning there is no limit on the length of a path.
+    pub max_instructions_per_path: Option<u64>,
+
+    /// Maximum number of paths to explore.
+    ///
+    /// If `Some`, once an `ExecutionManager` has produced this many paths
+    /// (counting both `Ok` and `Err` results), `next()` will return `None`,
+    /// even if there are more possible paths through the function.
+    /// `ExecutionManager.paths_explored()` can be used to tell whether this
+    /// limit was reached.
+    ///
+    /// Default is `None`, meaning there is no limit on the number of paths.
+    pub max_paths: Option<usize>,
+
+    /// In what order should paths be explored?
+    ///
+    /// With `ExplorationStrategy::DepthFirst`, whenever execution can go
+    /// multiple ways (e.g., at a conditional branch), we follow one of the
+    /// ways to the end of the path before exploring any of the others; the
+    /// next path explored resumes at the most recent such branch point. With
+    /// `ExplorationStrategy::BreadthFirst`, the next path explored instead
+    /// resumes at the oldest not-yet-explored branch point, so shallower
+    /// branches are explored before deeper ones.
+    ///
+    /// Note that in `BreadthFirst` mode, constraints added by hooks or
+    /// callbacks must be added with `State::assert()` rather than directly with
+    /// the `BV`'s own `assert()` method; otherwise, they may be lost when we
+    /// resume execution at a branch point.
+    ///
+    /// Default is `ExplorationStrategy::DepthFirst`.
+    pub exploration_strategy: ExplorationStrategy,
+
+    /// If `Some`, explore only a single path: whenever execution can go
+    /// multiple ways (e.g., at a conditional branch or a switch), follow just
+    /// one of the feasible ways, chosen according to the given policy, and
+    /// never save a backtracking point to explore the others. The
+    /// `ExecutionManager` will then produce exactly one path.
+    ///
+    /// This can be useful for quickly sanity-checking the setup of an analysis
+    /// before running a full exploration.
+    ///
+    /// Default is `None`, meaning all paths will be explored.
+    pub single_path: Option<SinglePathPolicy>,
+
+    /// Concrete seed values for some or all of the top-level function's
+    /// parameters, for concolic-style exploration. Maps parameter names, as
+    /// they appear in the LLVM IR but without the `%` (e.g., `"a"`, or `"0"`
+    /// for an unnamed parameter), to values.
+    ///
+    /// Whenever execution can go multiple ways (at a conditional branch or a
+    /// switch), it first follows the way which is consistent with the seed
+    /// values, if any, still saving backtracking points for the others. So,
+    /// with `ExplorationStrategy::DepthFirst`, the first path explored is
+    /// the one taken by the seed input. The seed values only affect the order
+    /// in which paths are explored; they don't constrain the parameters, and
+    /// all paths are still explored. They are ignored with
+    /// `Config.single_path` or `Config.dry_run`.
+    ///
+    /// Naming a parameter which the top-level function doesn't have will
+    /// result in a panic.
+    ///
+    /// Default is no seed values.
+    pub seed_values: HashMap<String, u64>,
+
+    /// If `true`, explore the function without checking whether paths are
+    /// feasible: every conditional branch takes both of its destinations, and
+    /// every switch takes all of its destinations (including the default),
+    /// without asking the solver which of them are possible. `loop_bound`,
+    /// `loop_bounds`, and the other path limits still apply.
+    ///
+    /// Since the solver is never asked to check satisfiability on the way, this
+    /// is much faster than a normal run. The number of paths produced by the
+    /// `ExecutionManager` is then an upper bound on the number of feasible paths
+    /// (for the given loop bounds), and every instruction handler on every path
+    /// is still exercised, so unsupported or malformed instructions are still
+    /// reported. This makes it useful as a quick check that a function is at
+    /// least structurally analyzable.
+    ///
+    /// In this mode, `NULL`-pointer checking, `check_arithmetic_overflow`,
+    /// `check_divide_by_zero`, and `check_memory_bounds` are disabled, since
+    /// they each require solver queries. Operations which
+    /// fundamentally need a concrete value (e.g., calls through a symbolic
+    /// function pointer, or `Concretize` policies other than
+    /// `Concretize::Symbolic`) still query the solver. Also note that paths may
+    /// be infeasible, so asking for solutions (e.g., with
+    /// `State::get_a_solution_for_bv()`) on them may fail.
+    ///
+    /// Default is `false`.
+    pub dry_run: bool,
+
     /// Maximum amount of time to allow for any single solver query.
     ///
     /// If `Some`, any solver query lasting longer than the given limit will
@@ -68,32 +257,253 @@ pub struct Config<'p, B: Backend> {
     /// Default is 300 seconds (5 minutes).
     pub solver_query_timeout: Option<Duration>,
 
+    /// Maximum amount of time to allow for the entire analysis of a function,
+    /// measured from when the `ExecutionManager` begins its first path.
+    ///
+    /// This is checked at the start of each path and at the start of each
+    /// basic block. Once it is exceeded, the current path will end with
+    /// `Error::TimeBudgetExceeded`, and the `ExecutionManager` will not produce
+    /// any more paths. Note that a single long-running solver query can still
+    /// overshoot the budget; see `solver_query_timeout` to bound those.
+    ///
+    /// Default is `None`, meaning there is no limit on the total time.
+    pub total_time_budget: Option<Duration>,
+
+    /// A flag which can be used to interrupt a running analysis, e.g., from
+    /// another thread.
+    ///
+    /// If `Some`, the flag is polled before each instruction and whenever the
+    /// `ExecutionManager` backtracks to begin another path. Once the flag is
+    /// set, the current path will end with `Error::Cancelled`, and the
+    /// `ExecutionManager` will not produce any more paths.
+    ///
+    /// Default is `None`, meaning the analysis cannot be cancelled this way.
+    pub cancellation: Option<Arc<AtomicBool>>,
+
     /// Should we check each memory access for possible `NULL` dereference,
     /// and if so, how should we report any errors?
     ///
     /// Default is `NullPointerChecking::Simple`.
     pub null_pointer_checking: NullPointerChecking,
 
-    /// When encountering a `memcpy`, `memset`, or `memmove` with multiple
-    /// possible lengths, how (if at all) should we concretize the length?
+    /// How should we handle memory accesses whose address has multiple
+    /// possible values?
+    ///
+    /// Concretizing addresses trades precision for speed: fully symbolic
+    /// addresses consider all possible values at once, but can make reads and
+    /// writes very slow, especially in pointer-heavy code.
+    ///
+    /// Default is `SymbolicAddressPolicy::FullySymbolic`.
+    pub symbolic_address_policy: SymbolicAddressPolicy,
+
+    /// Should `free()` check whether the pointer being freed may already have
+    /// been freed on the current path?
+    ///
+    /// If this is `true`, the built-in `free()` hook (see
+    /// [`add_libc_allocator_hooks()`](struct.Config.html#method.add_libc_allocator_hooks))
+    /// will end the path with `Error::DoubleFree` when the pointer being freed
+    /// may be equal to a pointer which was previously freed on the same path.
+    /// Freed pointers are tracked regardless of this setting; see
+    /// [`State::freed_pointers()`](../struct.State.html#method.freed_pointers).
+    ///
+    /// Default is `false`.
+    pub detect_double_free: bool,
+
+    /// Should we check each integer `add`, `sub`, and `mul` for possible
+    /// overflow?
+    ///
+    /// If this is `true`, then whenever one of these instructions may overflow,
+    /// the path ends with `Error::PossibleIntegerOverflow`, with the path
+    /// constraints including that the overflow happens, so that (e.g.)
+    /// `State::get_a_solution_for_bv()` gives an overflowing input. The case
+    /// where the instruction doesn't overflow is saved as a backtracking point,
+    /// so it will still be explored on a later path.
+    ///
+    /// Operands are interpreted as signed, as in C `int` arithmetic, where
+    /// overflow is undefined behavior. Unsigned wraparound is not reported: the
+    /// LLVM IR we get doesn't tell us which interpretation the source intended,
+    /// and e.g. `x - 1` appears as `add x, -1`, which wraps around (unsigned)
+    /// for every nonzero `x`.
+    ///
+    /// Default is `false`.
+    pub check_arithmetic_overflow: bool,
+
+    /// Should we check each integer `udiv`, `sdiv`, `urem`, and `srem` for a
+    /// possibly-zero divisor?
+    ///
+    /// If this is `true`, then whenever the divisor of one of these
+    /// instructions may be zero, the path ends with
+    /// `Error::PossibleDivideByZero`, which includes an example dividend and
+    /// divisor. As with `check_arithmetic_overflow`, the path constraints then
+    /// include that the divisor is zero, and the case where it isn't is saved as
+    /// a backtracking point, so it will still be explored on a later path.
+    ///
+    /// Default is `false`.
+    pub check_divide_by_zero: bool,
+
+    /// How to handle a `shl`, `lshr`, or `ashr` whose shift amount is at
+    /// least the bitwidth. LLVM says the result of such a shift is poison;
+    /// see [`ShiftOverflow`](enum.ShiftOverflow.html) for the options.
+    ///
+    /// Default is `ShiftOverflow::ShiftOutAllBits`.
+    pub shift_overflow: ShiftOverflow,
+
+    /// Should `freeze` produce an arbitrary value when its operand may be
+    /// poison?
+    ///
+    /// Our `BV`s are never poison themselves, so by default `freeze` simply
+    /// passes its operand through. If this is `true`, then when the operand of
+    /// a `freeze` is the result of a `udiv`, `sdiv`, `urem`, or `srem` whose
+    /// divisor may be zero, or of a `shl`, `lshr`, or `ashr` whose shift amount
+    /// may be at least the bitwidth, each element of the result for which that
+    /// is the case is instead a fresh unconstrained value. This only looks
+    /// through operands defined by such an instruction directly in the current
+    /// function.
+    ///
+    /// Default is `false`.
+    pub model_poison: bool,
+
+    /// Should we check each `load` and `store` for a possibly out-of-bounds
+    /// access?
+    ///
+    /// If this is `true`, then for each `load` or `store`, we find the
+    /// allocation (global variable, `alloca`, or heap allocation) which the
+    /// address was derived from, by following `getelementptr`s and `bitcast`s
+    /// back to their base pointer. If any part of the access may fall outside
+    /// that allocation, the path ends with `Error::PossibleOutOfBoundsAccess`,
+    /// which includes an example out-of-bounds address. As with
+    /// `check_arithmetic_overflow`, the path constraints then include that the
+    /// access is out of bounds, and the in-bounds case is saved as a
+    /// backtracking point, so it will still be explored on a later path.
+    ///
+    /// Accesses whose base pointer isn't within any allocation (e.g., through an
+    /// unconstrained pointer parameter) aren't checked. Neither is pointer
+    /// arithmetic which isn't done with `getelementptr` (e.g., through
+    /// `ptrtoint` and `inttoptr`), so such accesses are checked against
+    /// whichever allocation they land in.
+    ///
+    /// Default is `false`.
+    pub check_memory_bounds: bool,
+
+    /// Should reading memory which was never written be an error?
+    ///
+    /// By default, memory which hasn't been written (e.g., from `malloc()` or
+    /// an `alloca`, or pointed to by a function parameter) contains
+    /// unconstrained values, and reading it is not an error. If this is `true`,
+    /// such a read ends the path with `Error::UninitializedRead` instead.
+    /// Memory written by the global-variable initializers, by `calloc()`, or by
+    /// hooks counts as written.
+    ///
+    /// Only accesses at concrete addresses are tracked precisely: reads at
+    /// symbolic addresses are never flagged, and after any write to a symbolic
+    /// address, no further reads are flagged on that path. See
+    /// [`Memory::is_initialized()`](../backend/trait.Memory.html#method.is_initialized).
+    ///
+    /// Default is `false`.
+    pub flag_uninitialized_reads: bool,
+
+    /// Should every memory read and write be recorded?
+    ///
+    /// If this is `true`, each read and write on the current path is recorded
+    /// (with its location, address, and width) and available from
+    /// [`State::memory_trace()`](../struct.State.html#method.memory_trace).
+    /// This is useful for debugging, e.g. for understanding the order of
+    /// accesses on a short path; to watch only specific regions of memory,
+    /// use watchpoints instead (see `initial_mem_watchpoints`).
+    ///
+    /// The trace can get large on long paths, so this is off by default.
+    ///
+    /// Default is `false`.
+    pub trace_memory: bool,
+
+    /// Should every executed instruction be recorded?
+    ///
+    /// If this is `true`, each instruction executed on the current path is
+    /// recorded (along with its location) and available from
+    /// [`State::instruction_trace()`](../struct.State.html#method.instruction_trace).
+    /// This is useful for debugging, e.g. for seeing exactly which
+    /// instructions led to an error.
+    ///
+    /// This keeps an entry for every instruction on the path, so it can use a
+    /// lot of memory on long paths; it is off by default.
+    ///
+    /// Default is `false`.
+    pub record_instruction_trace: bool,
+
+    /// If `Some`, the size, in bits, of the "cells" in which memory contents
+    /// are stored. This only affects backends whose `Memory` stores data in
+    /// fixed-size cells, such as `CellMemoryBackend` (see
+    /// [`cell_memory::Memory`](../cell_memory/struct.Memory.html)); other
+    /// backends ignore it.
+    ///
+    /// Accesses which are aligned to a cell boundary and exactly one cell wide
+    /// need the fewest solver operations, so this can be tuned to match the
+    /// most common access size in the code being analyzed. Accesses of any
+    /// other size or alignment are still handled correctly, just with more
+    /// operations. For `cell_memory::Memory`, this must be a power of two, and
+    /// at least 16.
+    ///
+    /// Default is `None`, which uses the backend's default cell size (64 bits
+    /// for `cell_memory::Memory`).
+    pub memory_cell_bits: Option<u32>,
+
+    /// When encountering a `memcpy`, `memset`, `memmove`, or `memcmp` with
+    /// multiple possible lengths, how (if at all) should we concretize the
+    /// length?
     ///
     /// Default is `Concretize::Symbolic` - that is, no concretization.
     pub concretize_memcpy_lengths: Concretize,
 
-    /// Maximum supported length of a `memcpy`, `memset`, or `memmove` operation.
+    /// Maximum supported length of a `memcpy`, `memset`, `memmove`, or
+    /// `memcmp` operation.
     ///
     /// Setting this to `Some(x)` means that if we encounter a `memcpy`,
-    /// `memset`, or `memmove` with length which may be greater than `x` bytes,
-    /// we will constrain the length to be at most `x` bytes. (`haybale` will
-    /// also emit a warning when doing this.) If the only possible values for the
-    /// length are greater than `x` bytes, we will raise an error.
+    /// `memset`, `memmove`, or `memcmp` with length which may be greater than
+    /// `x` bytes, we will constrain the length to be at most `x` bytes.
+    /// (`haybale` will also emit a warning when doing this.) If the only
+    /// possible values for the length are greater than `x` bytes, we will raise
+    /// an error.
     ///
     /// Setting this to `None` means that there is no limit to the size of these
-    /// operations.
+    /// operations. However, operations with symbolic lengths (see
+    /// `concretize_memcpy_lengths`) are processed byte-by-byte, each byte
+    /// handled only if its index is less than the length; so without a limit
+    /// here, symbolic lengths which may be larger than 1 MiB will raise an
+    /// error.
     ///
     /// Default is `None` - that is, no limit.
     pub max_memcpy_length: Option<u64>,
 
+    /// Maximum supported number of elements for an `alloca` whose number of
+    /// elements isn't a constant (e.g., for a C99 variable-length array).
+    ///
+    /// Such an `alloca` allocates space for the largest possible number of
+    /// elements. Setting this to `Some(x)` means that if the number of elements
+    /// may be greater than `x`, we will constrain it to be at most `x`.
+    /// (`haybale` will also emit a warning when doing this.) If the only
+    /// possible values for the number of elements are greater than `x`, we
+    /// will raise an error.
+    ///
+    /// Setting this to `None` means that the number of elements must be
+    /// bounded by the existing constraints on the path, to a reasonable
+    /// allocation size; otherwise, we will raise an error.
+    ///
+    /// Default is `None`.
+    pub max_dynamic_alloc_elements: Option<u64>,
+
+    /// Maximum length of a string for the built-in string hooks, such as
+    /// `strlen()` and `strcmp()` (see
+    /// [`add_libc_string_hooks()`](struct.Config.html#method.add_libc_string_hooks)).
+    ///
+    /// These hooks compute their results symbolically, considering string
+    /// lengths up to this many bytes (not including the NUL terminator), and
+    /// constrain strings to have a NUL terminator within that bound (or, for
+    /// comparisons, to differ within that bound). Larger values allow longer
+    /// strings, but produce larger solver queries.
+    ///
+    /// Default is `256`.
+    pub max_string_len: u64,
+
     /// `Error::Unsat` is an error type which is used internally, but may not be
     /// useful for `ExecutionManager.next()` to return to consumers. In most
     /// cases, consumers probably don't care about paths which were partially
@@ -118,6 +528,23 @@ pub struct Config<'p, B: Backend> {
     /// Default is `true`.
     pub squash_unsats: bool,
 
+    /// If this setting is `true`, paths ending in `ReturnValue::Throw` (that
+    /// is, paths where an exception propagates uncaught out of the top-level
+    /// function) will be silently ignored by the `ExecutionManager`, and it
+    /// will move on to the next path, as if a filter were applied to the
+    /// iterator. This is useful if you only care about paths which return
+    /// normally (or end in errors).
+    ///
+    /// Skipped paths don't count towards `Config.max_paths` or
+    /// `ExecutionManager::paths_explored()`, and are not passed to the
+    /// `return_value_transform`.
+    ///
+    /// If this setting is `false`, such paths are returned as
+    /// `ReturnValue::Throw` like any other path.
+    ///
+    /// Default is `false`.
+    pub skip_throwing_paths: bool,
+
     /// When encountering the `llvm.assume()` intrinsic, should we only consider
     /// paths where the assumption holds (`true`), or should we also consider
     /// paths where the assumption does not hold, if that is possible (`false`)?
@@ -129,6 +556,32 @@ pub struct Config<'p, B: Backend> {
     /// Default is `true`.
     pub trust_llvm_assumes: bool,
 
+    /// Which LLVM function attributes should we trust as indicating that a
+    /// function is "pure", i.e., has no memory effects we need to track?
+    ///
+    /// If this set is nonempty, then any call to a function which carries
+    /// _all_ of the attributes in this set (either at the call site or on the
+    /// function's definition) will not be executed; instead, it will be
+    /// summarized as a pure function. That is, memory will be left unchanged,
+    /// and the function's return value (if any) will be a fresh unconstrained
+    /// value. This applies both to functions defined in the `Project` and to
+    /// functions which are only declared (e.g., many libc and math functions).
+    ///
+    /// For example, setting this to `{ReadNone, NoUnwind}` will summarize all
+    /// calls to functions marked both `readnone` and `nounwind`.
+    /// Note that `readnone` is considered to imply `readonly`.
+    ///
+    /// Calls which are hooked (see
+    /// [`function_hooks`](struct.Config.html#structfield.function_hooks)) are
+    /// unaffected by this setting; the hook is always used.
+    ///
+    /// This trades precision for speed: the analysis will no longer know
+    /// anything about the return values of such functions.
+    ///
+    /// Default is the empty set - that is, function attributes are not trusted
+    /// and all calls are executed normally.
+    pub pure_function_attributes: HashSet<PureFunctionAttribute>,
+
     /// The set of currently active function hooks; see
     /// [`FunctionHooks`](../function_hooks/struct.FunctionHooks.html) for more details.
     ///
@@ -137,12 +590,150 @@ pub struct Config<'p, B: Backend> {
     /// see docs there for more details.
     pub function_hooks: FunctionHooks<'p, B>,
 
+    /// Maximum number of distinct possible targets to enumerate when resolving
+    /// a call through a function pointer. These targets are passed to the
+    /// `function_ptr_resolution_hook` (if any), and if the call can't be
+    /// resolved, the first few of them are named in the resulting error
+    /// message. If the function pointer has more possible targets than this,
+    /// the error message says so.
+    ///
+    /// Enumerating more targets requires more solver queries, but only when a
+    /// function pointer has more than one possible target. Must be at least 1.
+    ///
+    /// Default is `32`.
+    pub max_fn_ptr_targets: usize,
+
+    /// If `Some`, `haybale` will call this function whenever it encounters a
+    /// call through a function pointer which has more than one possible
+    /// target. The function is given the current `State` and the possible
+    /// targets of the function pointer, and may return the target to call.
+    ///
+    /// If it returns `Some`, the function pointer is constrained to point to
+    /// that target for the rest of the path, and the call proceeds to that
+    /// target. Returning a `Callable` which isn't one of the possible targets
+    /// is an error. If it returns `None`, `haybale` uses its default behavior,
+    /// which is to raise an error for the call since the function pointer
+    /// has multiple possible targets.
+    ///
+    /// At most `max_fn_ptr_targets` possible targets are computed. If there
+    /// are more than that, the function is given only `max_fn_ptr_targets + 1`
+    /// of them, chosen arbitrarily.
+    ///
+    /// Default is `None`.
+    #[allow(clippy::type_complexity)]
+    pub function_ptr_resolution_hook:
+        Option<Rc<dyn Fn(&State<'p, B>, &[Callable<'p, B>]) -> Option<Callable<'p, B>> + 'p>>,
+
+    /// What to do when encountering a call to a function which isn't defined
+    /// in the `Project` and has no hook, when there is also no default hook
+    /// (see
+    /// [`FunctionHooks::add_default_hook()`](../function_hooks/struct.FunctionHooks.html#method.add_default_hook)).
+    /// See [`MissingFunctionBehavior`](enum.MissingFunctionBehavior.html).
+    ///
+    /// Default is `MissingFunctionBehavior::Error`.
+    pub on_missing_function: MissingFunctionBehavior,
+
+    /// If `Some`, `haybale` will use this function to map the RTTI (C++
+    /// `type_info`) of a thrown exception to a concrete type index (what the
+    /// [LLVM exception handling docs](https://releases.llvm.org/9.0.0/docs/ExceptionHandling.html#overview)
+    /// call a type info index, or selector). The function is given the
+    /// current `State` and the mangled name of the `type_info` global, e.g.
+    /// `_ZTIi` for `int` or `_ZTISt13runtime_error` for `std::runtime_error`,
+    /// and returns the type index.
+    ///
+    /// The same mapping is used both for exceptions thrown with
+    /// `__cxa_throw()` when they are caught by a `landingpad`, and for the
+    /// results of the `llvm.eh.typeid.for()` intrinsic. So, a catch block
+    /// which compares the selector against `llvm.eh.typeid.for()` of the
+    /// types it handles will only catch those exceptions whose types are
+    /// mapped to the same index; other exceptions will take the `resume`
+    /// path and continue propagating. To have a handler for a base class
+    /// catch exceptions of a derived class, map the derived class's
+    /// `type_info` to the base class's index.
+    ///
+    /// `haybale` can't inspect the clauses of a `landingpad` itself (see notes
+    /// in `llvm-ir` on `LandingPadClause`), so this is only as precise as the
+    /// mapping provided here.
+    ///
+    /// If `None`, or for exceptions which weren't thrown with `__cxa_throw()`
+    /// (or whose `type_info` isn't a reference to a global), the type index is
+    /// unconstrained, meaning that every `landingpad` may catch every
+    /// exception.
+    ///
+    /// Default is `None`.
+    #[allow(clippy::type_complexity)]
+    pub exception_type_index_hook: Option<Rc<dyn Fn(&State<'p, B>, &str) -> Result<u32> + 'p>>,
+
     /// The set of currently active callbacks; see
     /// [`Callbacks`](../callbacks/struct.Callbacks.html) for more details.
     ///
     /// Default is no callbacks.
     pub callbacks: Callbacks<'p, B>,
 
+    /// If `Some`, `haybale` will apply this function to each path's
+    /// `ReturnValue` before it is yielded by `ExecutionManager.next()`, and
+    /// yield the transformed `ReturnValue` instead.
+    ///
+    /// The function has mutable access to the `State` at the end of the path,
+    /// so it can, e.g., query the solver or add constraints. This can be used
+    /// to normalize or tag return values, or to turn certain `Return`s into
+    /// `Abort`s based on some predicate over the `State`.
+    ///
+    /// If the function returns an `Err`, that `Err` will be yielded for the
+    /// path instead, just as for any other error encountered on the path.
+    ///
+    /// The function is not applied to paths which end in an error.
+    ///
+    /// Default is `None`.
+    #[allow(clippy::type_complexity)]
+    pub return_value_transform: Option<
+        Rc<dyn Fn(&mut State<'p, B>, ReturnValue<B::BV>) -> Result<ReturnValue<B::BV>> + 'p>,
+    >,
+
+    /// If `Some`, `haybale` will call this function once on the initial
+    /// `State`, after all global variables and functions have been allocated
+    /// and the function parameters have been created, but before symbolic
+    /// execution begins.
+    ///
+    /// This can be used to encode program-wide invariants which should hold on
+    /// entry to the function, e.g., "global `g_count` is at most the length of
+    /// the array pointed to by global `g_buf`". Global variables are still
+    /// initialized lazily, on first reference; getting a global's address with
+    /// `state.const_to_bv()` on a `Constant::GlobalReference` will initialize
+    /// it if necessary, so that subsequent `state.read()`s see its initial
+    /// contents.
+    ///
+    /// Constraints should be added with `State::assert()` (rather than directly
+    /// with the `BV`'s own `assert()` method), so that they apply to every path
+    /// regardless of `exploration_strategy`.
+    ///
+    /// If the function returns an `Err`, that `Err` will be returned from
+    /// `symex_function()` (or similar).
+    ///
+    /// Default is `None`.
+    #[allow(clippy::type_complexity)]
+    pub initial_constraints: Option<Rc<dyn Fn(&mut State<'p, B>) -> Result<()> + 'p>>,
+
+    /// Map from function names to names of logical RNG streams.
+    ///
+    /// Calls to any function in this map (which isn't otherwise hooked; see
+    /// [`function_hooks`](struct.Config.html#structfield.function_hooks)) will
+    /// not be executed. Instead, the call will return a fresh unconstrained
+    /// value, which is recorded as the next value drawn from the given stream.
+    /// This applies whether or not the function is defined in the `Project`.
+    ///
+    /// For example, mapping `rand` to the stream `"rand"` allows you to refer
+    /// to "the value returned by the third call to `rand()` on this path" with
+    /// `state.nth_nondet_value("rand", 2)`, and constrain it like any other
+    /// `BV`. Several functions may share a stream, in which case their values
+    /// are numbered together, in the order the calls happen.
+    ///
+    /// Hooks may also draw from a stream directly with
+    /// `state.new_nondet_value()`.
+    ///
+    /// Default is the empty map - that is, no functions are modeled as RNGs.
+    pub rng_model: HashMap<String, String>,
+
     /// The initial memory watchpoints when a `State` is created (mapping from
     /// watchpoint name to the actual watchpoint).
     ///
@@ -153,13 +744,16 @@ pub struct Config<'p, B: Backend> {
     pub initial_mem_watchpoints: HashMap<String, Watchpoint>,
 
     /// Controls the (attempted) demangling of function names in error messages
-    /// and backtraces.
+    /// (e.g., `Error::FunctionNotFound`), backtraces, and `havoced_calls()`.
     ///
     /// If `None`, `haybale` will attempt to autodetect which mangling is
     /// appropriate, based on the LLVM metadata.
     ///
     /// `Some` can be used to force `haybale` to attempt to demangle with a
-    /// particular demangler.
+    /// particular demangler (or, with `Some(Demangling::NoDemangling)`, to not
+    /// demangle at all), in which case no autodetection is performed. This is
+    /// useful for `Project`s mixing code from several languages, where
+    /// autodetection may guess wrong.
     ///
     /// Any symbol that isn't valid for the chosen demangler will simply be left
     /// unchanged, regardless of this setting.
@@ -223,6 +817,103 @@ pub enum NullPointerChecking {
     None,
 }
 
+/// Enum used for the `symbolic_address_policy` option in `Config`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SymbolicAddressPolicy {
+    /// Memory accesses with symbolic addresses are performed symbolically,
+    /// considering all possible values of the address.
+    FullySymbolic,
+
+    /// Before a memory access with a symbolic address, choose one possible
+    /// value of the address, and constrain the address to be that value going
+    /// forward. Other possible values of the address are not considered.
+    ConcretizeToSingle,
+
+    /// Before a memory access with a symbolic address, choose up to this many
+    /// possible values of the address, and split into one path for each (via
+    /// backtracking points, each re-executing the current instruction), with
+    /// the address constrained to the corresponding value. If the address has
+    /// more possible values than this, the rest are not considered.
+    ConcretizeToN(usize),
+}
+
+/// Enum used for the `shift_overflow` option in `Config`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ShiftOverflow {
+    /// The shift is performed as if the operand were wide enough for the
+    /// shift amount, so every bit is shifted out: `shl` and `lshr` give 0, and
+    /// `ashr` gives all copies of the sign bit.
+    ShiftOutAllBits,
+
+    /// The shift amount is masked as x86 does. For 32- and 64-bit shifts, this
+    /// means it's taken modulo the bitwidth (for vectors, the bitwidth of each
+    /// element). For narrower shifts, it's taken modulo 32, as for a 32-bit
+    /// shift; an amount which is then still at least the bitwidth shifts out
+    /// every bit (so `shl` and `lshr` give 0, and `ashr` gives all copies of the
+    /// sign bit). For other widths, it's taken modulo the bitwidth. This
+    /// usually matches what the compiled program actually does when it runs
+    /// on x86, but not necessarily on other targets.
+    MaskLikeX86,
+
+    /// The result (or, for vectors, each element of the result whose shift
+    /// amount is too large) is a fresh unconstrained value, since LLVM says
+    /// it's poison.
+    Poison,
+
+    /// If the shift amount may be too large, the path ends with
+    /// `Error::PossibleOversizedShift`, with the path constraints including
+    /// that it is too large. As with `Config.check_divide_by_zero`, the case
+    /// where it isn't is saved as a backtracking point, so it will still be
+    /// explored on a later path.
+    Error,
+}
+
+/// Enum used for the `exploration_strategy` option in `Config`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExplorationStrategy {
+    /// Explore each path to its end before backtracking, always resuming at
+    /// the most recent point where execution could have gone another way.
+    DepthFirst,
+
+    /// Always resume at the oldest point where execution could have gone
+    /// another way, so that paths which diverge earlier are explored first.
+    BreadthFirst,
+}
+
+/// Enum used for the `on_missing_function` option in `Config`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MissingFunctionBehavior {
+    /// End the path with `Error::FunctionNotFound`.
+    Error,
+
+    /// Ignore the call's arguments and return an unconstrained value of the
+    /// appropriate size (or return void for void-typed functions), as
+    /// [`generic_stub_hook`](../function_hooks/fn.generic_stub_hook.html)
+    /// does. The call is recorded in
+    /// [`State::havoced_calls()`](../struct.State.html#method.havoced_calls).
+    ReturnUnconstrained,
+
+    /// Treat the call as aborting the program, ending the path with
+    /// `ReturnValue::Abort`, as
+    /// [`abort_hook`](../function_hooks/fn.abort_hook.html) does.
+    Abort,
+}
+
+/// Enum used for the `single_path` option in `Config`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SinglePathPolicy {
+    /// Always follow the first feasible way: for a conditional branch, the
+    /// true branch if it is feasible; for a switch, the first feasible case in
+    /// the order they're listed in the LLVM, or the default case if none of
+    /// the others are feasible. This is the path which would be explored first
+    /// without `single_path`.
+    FirstFeasible,
+
+    /// Choose uniformly (pseudo-)at random among the feasible ways. The choices
+    /// are deterministic for a given `seed`.
+    Random { seed: u64 },
+}
+
 /// Enum used for the `concretize_memcpy_lengths` option in `Config`.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Concretize {
@@ -262,6 +953,35 @@ pub enum Concretize {
     Minimum,
 }
 
+/// Enum used for the `pure_function_attributes` option in `Config`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum PureFunctionAttribute {
+    /// The LLVM `readnone` attribute
+    ReadNone,
+    /// The LLVM `readonly` attribute. This is also satisfied by `readnone`.
+    ReadOnly,
+    /// The LLVM `willreturn` attribute
+    WillReturn,
+    /// The LLVM `nounwind` attribute
+    NoUnwind,
+}
+
+impl PureFunctionAttribute {
+    /// Is this attribute satisfied by the given list of LLVM function attributes
+    pub(crate) fn is_satisfied_by(self, attrs: &[FunctionAttribute]) -> bool {
+        attrs.iter().any(|attr| {
+            matches!(
+                (self, attr),
+                (Self::ReadNone, FunctionAttribute::ReadNone)
+                    | (Self::ReadOnly, FunctionAttribute::ReadOnly)
+                    | (Self::ReadOnly, FunctionAttribute::ReadNone)
+                    | (Self::WillReturn, FunctionAttribute::WillReturn)
+                    | (Self::NoUnwind, FunctionAttribute::NoUnwind)
+            )
+        })
+    }
+}
+
 impl<'p, B: Backend> Config<'p, B> {
     /// Creates a new `Config` with defaults for all the options, except with
     /// no function hooks.
@@ -274,6 +994,50 @@ impl<'p, B: Backend> Config<'p, B> {
         config.function_hooks = FunctionHooks::new();
         config
     }
+
+    /// Adds the built-in hooks for the libc allocator functions `malloc()`,
+    /// `calloc()`, `realloc()`, and `free()` to `function_hooks`.
+    ///
+    /// These hooks allocate with
+    /// [`State::allocate()`](../struct.State.html#method.allocate), which
+    /// never reuses addresses. `malloc(0)` returns a valid pointer distinct
+    /// from all other allocations; `calloc()` zero-initializes the returned
+    /// region; `realloc()` copies the contents of the old allocation; and
+    /// `free()` doesn't actually free anything, but records the freed pointer
+    /// (see `detect_double_free`).
+    ///
+    /// `Config::default()` already includes these hooks, so this is mostly
+    /// useful with `Config::new()`.
+    pub fn add_libc_allocator_hooks(&mut self) {
+        self.function_hooks
+            .add("malloc", &hooks::allocation::malloc_hook);
+        self.function_hooks
+            .add("calloc", &hooks::allocation::calloc_hook);
+        self.function_hooks
+            .add("realloc", &hooks::allocation::realloc_hook);
+        self.function_hooks
+            .add("free", &hooks::allocation::free_hook);
+    }
+
+    /// Adds the built-in hooks for the libc string functions to
+    /// `function_hooks`. (At the time of this writing, this includes
+    /// `strlen()`, `memcmp()`, `strcmp()`, and `strncmp()`.)
+    ///
+    /// These hooks compute their results symbolically (see, e.g.,
+    /// [`hook_utils::strlen()`](../hook_utils/fn.strlen.html)) rather than
+    /// looping over the strings, so they don't split the path for each
+    /// possible length or count against the `loop_bound`. See
+    /// `max_string_len`.
+    pub fn add_libc_string_hooks(&mut self) {
+        self.function_hooks
+            .add("strlen", &hooks::strings::strlen_hook);
+        self.function_hooks
+            .add("memcmp", &hooks::strings::memcmp_hook);
+        self.function_hooks
+            .add("strcmp", &hooks::strings::strcmp_hook);
+        self.function_hooks
+            .add("strncmp", &hooks::strings::strncmp_hook);
+    }
 }
 
 impl<'p, B: Backend> Default for Config<'p, B> {
@@ -288,15 +1052,49 @@ impl<'p, B: Backend> Default for Config<'p, B> {
     fn default() -> Self {
         Self {
             loop_bound: 10,
+            loop_bounds: HashMap::new(),
+            loop_bound_exempt_functions: HashSet::new(),
             max_callstack_depth: None,
+            max_recursion_depth: None,
+            reuse_frames_for_tail_calls: false,
+            max_instructions_per_path: None,
+            max_paths: None,
+            exploration_strategy: ExplorationStrategy::DepthFirst,
+            single_path: None,
+            seed_values: HashMap::new(),
+            dry_run: false,
             solver_query_timeout: Some(Duration::from_secs(300)),
+            total_time_budget: None,
+            cancellation: None,
             null_pointer_checking: NullPointerChecking::Simple,
+            symbolic_address_policy: SymbolicAddressPolicy::FullySymbolic,
+            detect_double_free: false,
+            check_arithmetic_overflow: false,
+            check_divide_by_zero: false,
+            shift_overflow: ShiftOverflow::ShiftOutAllBits,
+            model_poison: false,
+            check_memory_bounds: false,
+            flag_uninitialized_reads: false,
+            trace_memory: false,
+            record_instruction_trace: false,
+            memory_cell_bits: None,
             concretize_memcpy_lengths: Concretize::Symbolic,
             max_memcpy_length: None,
+            max_dynamic_alloc_elements: None,
+            max_string_len: 256,
             squash_unsats: true,
+            skip_throwing_paths: false,
             trust_llvm_assumes: true,
+            pure_function_attributes: HashSet::new(),
             function_hooks: FunctionHooks::default(),
+            max_fn_ptr_targets: 32,
+            function_ptr_resolution_hook: None,
+            on_missing_function: MissingFunctionBehavior::Error,
+            exception_type_index_hook: None,
             callbacks: Callbacks::default(),
+            return_value_transform: None,
+            initial_constraints: None,
+            rng_model: HashMap::new(),
             initial_mem_watchpoints: HashMap::new(),
             demangling: None,
             print_source_info: true,
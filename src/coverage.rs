@@ -0,0 +1,108 @@
+//! Basic-block coverage statistics, collected across all explored paths
+
+use crate::project::Project;
+use crate::state::Location;
+use llvm_ir::Name;
+use std::collections::{HashMap, HashSet};
+
+/// Records which basic blocks have been visited, across all paths explored so
+/// far (i.e., this is not reset by backtracking), along with the total number
+/// of basic blocks in each function in the `Project`.
+///
+/// Functions are identified by (module name, function name), and basic blocks
+/// by (module name, function name, block name).
+#[derive(Clone, Debug)]
+pub struct CoverageReport<'p> {
+    /// Map from (module name, function name) to the names of the blocks in
+    /// that function which have been visited.
+    /// Functions with no visited blocks don't appear here.
+    visited: HashMap<(&'p str, &'p str), HashSet<&'p Name>>,
+    /// Map from (module name, function name) to the total number of blocks in
+    /// that function, for every function defined in the `Project`
+    total_blocks: HashMap<(&'p str, &'p str), usize>,
+}
+
+/// Coverage statistics for a single function; see
+/// [`CoverageReport::functions()`](struct.CoverageReport.html#method.functions).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FunctionCoverage<'p> {
+    pub modname: &'p str,
+    pub funcname: &'p str,
+    /// Number of distinct basic blocks in the function which have been visited
+    pub visited_blocks: usize,
+    /// Total number of basic blocks in the function
+    pub total_blocks: usize,
+}
+
+impl<'p> CoverageReport<'p> {
+    pub(crate) fn new(project: &'p Project) -> Self {
+        Self {
+            visited: HashMap::new(),
+            total_blocks: project
+                .all_functions()
+                .map(|(func, module)| {
+                    (
+                        (module.name.as_str(), func.name.as_str()),
+                        func.basic_blocks.len(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Record that the basic block containing the given `Location` has been visited
+    pub(crate) fn record_visit(&mut self, loc: &Location<'p>) {
+        self.visited
+            .entry((loc.module.name.as_str(), loc.func.name.as_str()))
+            .or_default()
+            .insert(&loc.bb.name);
+    }
+
+    /// Has the given basic block been visited on any path so far
+    pub fn is_visited(&self, modname: &str, funcname: &str, bbname: &Name) -> bool {
+        self.visited
+            .get(&(modname, funcname))
+            .map(|bbs| bbs.contains(bbname))
+            .unwrap_or(false)
+    }
+
+    /// Iterate over all the basic blocks which have been visited on any path
+    /// so far, as (module name, function name, block name), in no particular
+    /// order
+    pub fn visited_blocks(&self) -> impl Iterator<Item = (&'p str, &'p str, &'p Name)> + '_ {
+        self.visited.iter().flat_map(|(&(modname, funcname), bbs)| {
+            bbs.iter().map(move |&bbname| (modname, funcname, bbname))
+        })
+    }
+
+    /// Get the number of distinct basic blocks in the given function which have
+    /// been visited on any path so far
+    pub fn num_visited_blocks(&self, modname: &str, funcname: &str) -> usize {
+        self.visited
+            .get(&(modname, funcname))
+            .map(HashSet::len)
+            .unwrap_or(0)
+    }
+
+    /// Get the total number of basic blocks in the given function, or `None`
+    /// if the `Project` contains no definition of that function in that module
+    pub fn total_blocks(&self, modname: &str, funcname: &str) -> Option<usize> {
+        self.total_blocks.get(&(modname, funcname)).copied()
+    }
+
+    /// Iterate over the coverage statistics of every function defined in the
+    /// `Project`, including functions which haven't been visited at all, in no
+    /// particular order
+    pub fn functions(&self) -> impl Iterator<Item = FunctionCoverage<'p>> + '_ {
+        self.total_blocks
+            .iter()
+            .map(
+                move |(&(modname, funcname), &total_blocks)| FunctionCoverage {
+                    modname,
+                    funcname,
+                    visited_blocks: self.num_visited_blocks(modname, funcname),
+                    total_blocks,
+                },
+            )
+    }
+}
@@ -13,6 +13,20 @@ pub enum Demangling {
     Rust,
 }
 
+/// Which demangling scheme was actually applied to a particular name.
+/// See [`State::demangle_with_scheme_info()`](struct.State.html#method.demangle_with_scheme_info).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DemangleScheme {
+    /// The name was not demangled, and was left as-is. This may be because
+    /// demangling is disabled, or because the name failed to demangle with
+    /// the configured demangler.
+    NotDemangled,
+    /// The name was successfully demangled with the C++ (Itanium) demangler.
+    CPP,
+    /// The name was successfully demangled with the Rust demangler.
+    Rust,
+}
+
 impl Demangling {
     /// Attempts to demangle the given function name, as appropriate based on the
     /// `Demangling` setting.
@@ -26,6 +40,19 @@ impl Demangling {
         }
     }
 
+    /// Like `maybe_demangle()`, but also reports which demangling scheme (if
+    /// any) was successfully applied to the given function name.
+    //
+    // (takes `self` by value because `self` is `Copy`)
+    pub fn maybe_demangle_with_scheme_info(self, funcname: &str) -> (String, DemangleScheme) {
+        let demangled = match self {
+            Demangling::NoDemangling => None,
+            Demangling::CPP => try_cpp_demangle(funcname).map(|d| (d, DemangleScheme::CPP)),
+            Demangling::Rust => try_rust_demangle(funcname).map(|d| (d, DemangleScheme::Rust)),
+        };
+        demangled.unwrap_or_else(|| (funcname.to_owned(), DemangleScheme::NotDemangled))
+    }
+
     /// Guesses an appropriate `Demangling` for the given `Project`.
     pub fn autodetect(proj: &Project) -> Self {
         // our autodetection is pretty unsophisticated right now,
@@ -141,4 +168,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn scheme_info() {
+        let cpp_name = "_ZNK5Shape10sides_plusEi";
+        let rust_name = "_ZN4core9panicking5panic17h0123456789abcdefE";
+        let c_name = "simple_caller";
+
+        // with no demangling, everything is left as-is
+        assert_eq!(
+            Demangling::NoDemangling.maybe_demangle_with_scheme_info(cpp_name),
+            (cpp_name.to_owned(), DemangleScheme::NotDemangled),
+        );
+
+        // C++ demangling succeeds on C++ names, but not on C names
+        let (demangled, scheme) = Demangling::CPP.maybe_demangle_with_scheme_info(cpp_name);
+        assert_eq!(scheme, DemangleScheme::CPP);
+        assert!(demangled.starts_with("Shape::sides_plus"));
+        assert_eq!(
+            Demangling::CPP.maybe_demangle_with_scheme_info(c_name),
+            (c_name.to_owned(), DemangleScheme::NotDemangled),
+        );
+
+        // Rust demangling succeeds on Rust names, but not on C names
+        assert_eq!(
+            Demangling::Rust.maybe_demangle_with_scheme_info(rust_name),
+            ("core::panicking::panic".to_owned(), DemangleScheme::Rust),
+        );
+        assert_eq!(
+            Demangling::Rust.maybe_demangle_with_scheme_info(c_name),
+            (c_name.to_owned(), DemangleScheme::NotDemangled),
+        );
+    }
 }
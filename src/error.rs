@@ -1,3 +1,4 @@
+use llvm_ir::{DebugLoc, Name};
 use std::fmt;
 
 /// Error types used throughout this crate.
@@ -14,11 +15,88 @@ pub enum Error {
     /// see [`Config.squash_unsats`](config/struct.Config.html#structfield.squash_unsats).
     Unsat,
     /// The current path has exceeded the configured `loop_bound` (see [`Config`](config/struct.Config.html)).
-    /// (The `usize` here indicates the value of the configured `loop_bound`.)
-    LoopBoundExceeded(usize),
+    LoopBoundExceeded {
+        /// The value of the configured `loop_bound`
+        bound: usize,
+        /// The function containing the loop which exceeded the bound
+        funcname: String,
+        /// The basic block whose repeated execution exceeded the bound
+        bbname: Name,
+        /// The LLVM variable which was assigned too many times; this is
+        /// defined in the basic block `bbname`
+        varname: Name,
+    },
+    /// The current path has attempted a recursive call which would exceed the
+    /// configured `max_recursion_depth` (see [`Config`](config/struct.Config.html)).
+    /// (The `usize` here indicates the value of the configured `max_recursion_depth`.)
+    RecursionBoundExceeded(usize),
+    /// The current path has executed more instructions than the configured
+    /// `max_instructions_per_path` (see [`Config`](config/struct.Config.html)).
+    /// (The `u64` here indicates the value of the configured `max_instructions_per_path`.)
+    InstructionBudgetExceeded(u64),
+    /// The analysis has exceeded the configured `total_time_budget` (see [`Config`](config/struct.Config.html)).
+    /// After this error, the `ExecutionManager` will not produce any more paths.
+    TimeBudgetExceeded,
+    /// The analysis was cancelled via the `cancellation` flag (see [`Config`](config/struct.Config.html)).
+    /// After this error, the `ExecutionManager` will not produce any more paths.
+    Cancelled,
     /// The current path has attempted to dereference a null pointer (or
     /// more precisely, a pointer for which `NULL` is a possible value)
     NullPointerDereference,
+    /// The current path has attempted to `free()` a pointer which was already
+    /// freed (or more precisely, a pointer which may be equal to one which
+    /// was already freed). This is only checked if
+    /// [`Config.detect_double_free`](config/struct.Config.html#structfield.detect_double_free)
+    /// is enabled.
+    DoubleFree,
+    /// The current path has performed an integer `add`, `sub`, or `mul` which
+    /// may overflow (interpreting its operands as signed). This is only
+    /// checked if
+    /// [`Config.check_arithmetic_overflow`](config/struct.Config.html#structfield.check_arithmetic_overflow)
+    /// is enabled. The `DebugLoc` here is the source location of the
+    /// instruction, if available.
+    PossibleIntegerOverflow(Option<DebugLoc>),
+    /// The current path has performed an integer `udiv`, `sdiv`, `urem`, or
+    /// `srem` whose divisor may be zero. This is only checked if
+    /// [`Config.check_divide_by_zero`](config/struct.Config.html#structfield.check_divide_by_zero)
+    /// is enabled.
+    PossibleDivideByZero {
+        /// The source location of the instruction, if available
+        source_loc: Option<DebugLoc>,
+        /// A possible value of the dividend when the divisor is zero
+        dividend: String,
+        /// The corresponding value of the divisor (for vector operations, at
+        /// least one element of this will be zero)
+        divisor: String,
+    },
+    /// The current path has performed a `shl`, `lshr`, or `ashr` whose shift
+    /// amount may be at least the bitwidth. This is only checked if
+    /// [`Config.shift_overflow`](config/struct.Config.html#structfield.shift_overflow)
+    /// is `ShiftOverflow::Error`. The `DebugLoc` here is the source location
+    /// of the instruction, if available.
+    PossibleOversizedShift(Option<DebugLoc>),
+    /// The current path has performed a `load` or `store` which may access
+    /// memory outside of the allocation its address was derived from. This is
+    /// only checked if
+    /// [`Config.check_memory_bounds`](config/struct.Config.html#structfield.check_memory_bounds)
+    /// is enabled.
+    PossibleOutOfBoundsAccess {
+        /// The source location of the instruction, if available
+        source_loc: Option<DebugLoc>,
+        /// A possible out-of-bounds address accessed by the instruction
+        address: u64,
+        /// The address of the allocation the address was derived from
+        allocation_start: u64,
+        /// The size of that allocation, in bytes
+        allocation_size: u64,
+    },
+    /// The current path has read memory which it never wrote (or more
+    /// precisely, which was never written since the start of the analysis).
+    /// This is only checked if
+    /// [`Config.flag_uninitialized_reads`](config/struct.Config.html#structfield.flag_uninitialized_reads)
+    /// is enabled. The `DebugLoc` here is the source location of the
+    /// instruction performing the read, if available.
+    UninitializedRead(Option<DebugLoc>),
     /// Processing a call of a function with the given name, but failed to find an LLVM definition, a function hook, or a built-in handler for it
     FunctionNotFound(String),
     /// The solver returned this processing error while evaluating a query.
@@ -43,15 +121,82 @@ pub enum Error {
     OtherError(String),
 }
 
+impl Error {
+    /// Is this error local to the current path, so that it still makes sense
+    /// to keep exploring other paths (i.e., keep calling
+    /// `ExecutionManager::next()`)?
+    ///
+    /// Returns `false` for errors which end the analysis (like
+    /// `TimeBudgetExceeded`), or which usually mean the analysis is set up
+    /// incorrectly or can't handle the code being analyzed (like
+    /// `FunctionNotFound` or `HookReturnValueMismatch`), such that exploring
+    /// further paths is likely pointless.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Error::Unsat => true,
+            Error::LoopBoundExceeded { .. } => true,
+            Error::RecursionBoundExceeded(_) => true,
+            Error::InstructionBudgetExceeded(_) => true,
+            Error::TimeBudgetExceeded => false,
+            Error::Cancelled => false,
+            Error::NullPointerDereference => true,
+            Error::DoubleFree => true,
+            Error::PossibleIntegerOverflow(_) => true,
+            Error::PossibleDivideByZero { .. } => true,
+            Error::PossibleOversizedShift(_) => true,
+            Error::PossibleOutOfBoundsAccess { .. } => true,
+            Error::UninitializedRead(_) => true,
+            Error::FunctionNotFound(_) => false,
+            Error::SolverError(_) => true,
+            Error::UnsupportedInstruction(_) => false,
+            Error::MalformedInstruction(_) => false,
+            Error::UnreachableInstruction => true,
+            Error::FailedToResolveFunctionPointer(_) => true,
+            Error::HookReturnValueMismatch(_) => false,
+            Error::OtherError(_) => false,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::Unsat =>
                 write!(f, "`Unsat`: the current state or path is unsat"),
-            Error::LoopBoundExceeded(bound) =>
-                write!(f, "`LoopBoundExceeded`: the current path has exceeded the configured `loop_bound`, which was {}", bound),
+            Error::LoopBoundExceeded { bound, funcname, bbname, varname } =>
+                write!(f, "`LoopBoundExceeded`: the current path has exceeded the configured `loop_bound`, which was {}, in function {:?} at basic block {} (variable {})", bound, funcname, bbname, varname),
+            Error::RecursionBoundExceeded(bound) =>
+                write!(f, "`RecursionBoundExceeded`: the current path has exceeded the configured `max_recursion_depth`, which was {}", bound),
+            Error::InstructionBudgetExceeded(budget) =>
+                write!(f, "`InstructionBudgetExceeded`: the current path has exceeded the configured `max_instructions_per_path`, which was {}", budget),
+            Error::TimeBudgetExceeded =>
+                write!(f, "`TimeBudgetExceeded`: the analysis has exceeded the configured `total_time_budget`"),
+            Error::Cancelled =>
+                write!(f, "`Cancelled`: the analysis was cancelled via the configured `cancellation` flag"),
             Error::NullPointerDereference =>
                 write!(f, "`NullPointerDereference`: the current path has attempted to dereference a null pointer"),
+            Error::DoubleFree =>
+                write!(f, "`DoubleFree`: the current path has attempted to free a pointer which was already freed"),
+            Error::PossibleIntegerOverflow(Some(loc)) =>
+                write!(f, "`PossibleIntegerOverflow`: the current path has performed integer arithmetic which may overflow, at {}", loc),
+            Error::PossibleIntegerOverflow(None) =>
+                write!(f, "`PossibleIntegerOverflow`: the current path has performed integer arithmetic which may overflow"),
+            Error::PossibleDivideByZero { source_loc: Some(loc), dividend, divisor } =>
+                write!(f, "`PossibleDivideByZero`: the current path has performed an integer division whose divisor may be zero, at {} (e.g., dividend {} and divisor {})", loc, dividend, divisor),
+            Error::PossibleDivideByZero { source_loc: None, dividend, divisor } =>
+                write!(f, "`PossibleDivideByZero`: the current path has performed an integer division whose divisor may be zero (e.g., dividend {} and divisor {})", dividend, divisor),
+            Error::PossibleOversizedShift(Some(loc)) =>
+                write!(f, "`PossibleOversizedShift`: the current path has performed a shift whose shift amount may be at least the bitwidth, at {}", loc),
+            Error::PossibleOversizedShift(None) =>
+                write!(f, "`PossibleOversizedShift`: the current path has performed a shift whose shift amount may be at least the bitwidth"),
+            Error::PossibleOutOfBoundsAccess { source_loc: Some(loc), address, allocation_start, allocation_size } =>
+                write!(f, "`PossibleOutOfBoundsAccess`: the current path has accessed memory which may be outside the {}-byte allocation at 0x{:x}, at {} (e.g., address 0x{:x})", allocation_size, allocation_start, loc, address),
+            Error::PossibleOutOfBoundsAccess { source_loc: None, address, allocation_start, allocation_size } =>
+                write!(f, "`PossibleOutOfBoundsAccess`: the current path has accessed memory which may be outside the {}-byte allocation at 0x{:x} (e.g., address 0x{:x})", allocation_size, allocation_start, address),
+            Error::UninitializedRead(Some(loc)) =>
+                write!(f, "`UninitializedRead`: the current path has read memory which was never written, at {}", loc),
+            Error::UninitializedRead(None) =>
+                write!(f, "`UninitializedRead`: the current path has read memory which was never written"),
             Error::FunctionNotFound(funcname) =>
                 write!(f, "`FunctionNotFound`: encountered a call of a function named {:?}, but failed to find an LLVM definition, a function hook, or a built-in handler for it", funcname),
             Error::SolverError(details) =>
@@ -80,3 +225,59 @@ impl From<Error> for String {
 
 /// A type alias for convenience, similar to how `std::io::Result` is used for I/O operations
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error type returned by [`find_zero_of_func()`](fn.find_zero_of_func.html).
+///
+/// The `Display` impl for `FindZeroError` provides the same messages which
+/// `find_zero_of_func()` returned as `String`s in previous versions of
+/// `haybale`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FindZeroError {
+    /// Failed to begin symbolic execution of the function, for instance because
+    /// no function with the given name was found in the `Project`
+    Setup(Error),
+    /// The function has void return type, so it can never return zero
+    VoidReturnType,
+    /// The function's return type is an opaque struct type, so we can't
+    /// determine its width
+    OpaqueReturnType,
+    /// Exploring one of the function's paths resulted in an error.
+    ///
+    /// Paths which exceed the configured loop bound, recursion bound, or
+    /// per-path instruction budget are skipped rather than producing this
+    /// error.
+    Path {
+        /// The error itself (boxed, to keep `FindZeroError` small)
+        error: Box<Error>,
+        /// Full description of the error, including the program context in
+        /// which it occurred; see
+        /// [`State.full_error_message_with_context()`](struct.State.html#method.full_error_message_with_context)
+        message: String,
+    },
+    /// A solver query failed while checking for, or solving for, inputs
+    /// which make the function return zero
+    Solver(Error),
+}
+
+impl fmt::Display for FindZeroError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FindZeroError::Setup(e) => write!(f, "{}", e),
+            FindZeroError::VoidReturnType => {
+                write!(f, "find_zero_of_func: function has void type")
+            },
+            FindZeroError::OpaqueReturnType => write!(
+                f,
+                "find_zero_of_func: function return type is an opaque struct type"
+            ),
+            FindZeroError::Path { message, .. } => write!(f, "{}", message),
+            FindZeroError::Solver(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<FindZeroError> for String {
+    fn from(e: FindZeroError) -> String {
+        e.to_string() // use the Display impl
+    }
+}
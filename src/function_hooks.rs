@@ -1,6 +1,6 @@
 //! Functions and structures for defining and activating function hooks
 
-use crate::backend::Backend;
+use crate::backend::{Backend, BV};
 use crate::demangling;
 use crate::error::*;
 use crate::hooks;
@@ -8,7 +8,7 @@ use crate::return_value::*;
 use crate::state::State;
 use either::Either;
 use llvm_ir::function::{CallingConvention, FunctionAttribute, ParameterAttribute};
-use llvm_ir::types::Typed;
+use llvm_ir::types::{TypeRef, Typed, Types};
 use llvm_ir::{instruction::InlineAssembly, Name, Operand, Type};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
@@ -29,7 +29,8 @@ use std::rc::Rc;
 ///
 /// (2) Haybale provides default hooks for certain LLVM intrinsics like
 /// `memcpy`, which have specially reserved names; it will apply these hooks
-/// unless a different hook was defined for the intrinsic in (1).
+/// unless a different hook was defined for the intrinsic in (1). Likewise, it
+/// applies its own hook to functions named in `Config.rng_model`.
 ///
 /// (3) Else, if the function is not hooked but is defined in an available
 /// LLVM `Module`, the function will be symbolically executed (called).
@@ -71,6 +72,10 @@ pub struct FunctionHooks<'p, B: Backend + 'p> {
     /// string itself, although it can still inspect function parameters etc.
     /// For now, this is the best we can do.
     ///
+    /// This is stored as an ordinary `FunctionHook`, which wraps the
+    /// user-provided hook and converts its `AsmReturnValue` into a
+    /// `ReturnValue`; see `add_inline_asm_hook()`.
+    ///
     /// If no hook is provided here, then all calls to inline assembly will
     /// result in errors.
     inline_asm_hook: Option<FunctionHook<'p, B>>,
@@ -90,14 +95,23 @@ pub struct FunctionHooks<'p, B: Backend + 'p> {
 /// with zero or more attributes which apply to it
 pub type Argument = (Operand, Vec<ParameterAttribute>);
 
-/// `IsCall` exists to unify the commonalities between LLVM `Call` and `Invoke`
-/// instructions
+/// `IsCall` exists to unify the commonalities between LLVM `Call`, `Invoke`,
+/// and `CallBr` instructions
 pub trait IsCall: Typed {
     fn get_called_func(&self) -> &Either<InlineAssembly, Operand>;
     fn get_arguments(&self) -> &Vec<Argument>;
     fn get_return_attrs(&self) -> &Vec<ParameterAttribute>;
     fn get_fn_attrs(&self) -> &Vec<FunctionAttribute>;
     fn get_calling_convention(&self) -> CallingConvention;
+
+    /// Get the type of the call's return value. For `Call` and `Invoke`, this
+    /// is the same as `get_type()` (e.g., `state.type_of(call)`). Hooks which
+    /// may be called for a `CallBr` should use this instead, because
+    /// `llvm-ir`'s `get_type()` for a `CallBr` panics when the called function
+    /// has pointer-to-function type, as inline assembly does.
+    fn get_return_type(&self, types: &Types) -> Result<TypeRef> {
+        Ok(self.get_type(types))
+    }
 }
 
 impl IsCall for llvm_ir::instruction::Call {
@@ -136,6 +150,37 @@ impl IsCall for llvm_ir::terminator::Invoke {
     }
 }
 
+impl IsCall for llvm_ir::terminator::CallBr {
+    fn get_called_func(&self) -> &Either<InlineAssembly, Operand> {
+        &self.function
+    }
+    fn get_arguments(&self) -> &Vec<Argument> {
+        &self.arguments
+    }
+    fn get_return_attrs(&self) -> &Vec<ParameterAttribute> {
+        &self.return_attributes
+    }
+    fn get_fn_attrs(&self) -> &Vec<FunctionAttribute> {
+        &self.function_attributes
+    }
+    fn get_calling_convention(&self) -> CallingConvention {
+        self.calling_convention
+    }
+    fn get_return_type(&self, types: &Types) -> Result<TypeRef> {
+        let func_ty = match types.type_of(&self.function).as_ref() {
+            Type::PointerType { pointee_type, .. } => pointee_type.clone(),
+            _ => types.type_of(&self.function),
+        };
+        match func_ty.as_ref() {
+            Type::FuncType { result_type, .. } => Ok(result_type.clone()),
+            ty => Err(Error::MalformedInstruction(format!(
+                "Expected CallBr's function argument to be of function or pointer-to-function type, got {:?}",
+                ty
+            ))),
+        }
+    }
+}
+
 impl<'p, B: Backend + 'p> FunctionHooks<'p, B> {
     /// Create a blank `FunctionHooks` instance with no function hooks.
     ///
@@ -202,22 +247,28 @@ impl<'p, B: Backend + 'p> FunctionHooks<'p, B> {
     /// the hook will actually have no way of obtaining the contents of the asm
     /// string itself, although it can still inspect function parameters etc.
     /// For now, this is the best we can do.
+    ///
+    /// Rather than a `ReturnValue`, the hook returns an
+    /// [`AsmReturnValue`](../enum.AsmReturnValue.html) giving the values of the
+    /// asm's output operands, in the order of its output constraints. These
+    /// are checked against the type of the call and then assigned to the
+    /// call's result, so the hook needn't know how LLVM packs multiple outputs
+    /// into a struct. Asm which writes to memory (e.g., through a pointer
+    /// operand) can be modeled by having the hook perform the appropriate
+    /// `State::write()`s itself.
     pub fn add_inline_asm_hook<H>(&mut self, hook: &'p H) -> bool
     where
-        H: Fn(&mut State<'p, B>, &'p dyn IsCall) -> Result<ReturnValue<B::BV>>,
+        H: Fn(&mut State<'p, B>, &'p dyn IsCall) -> Result<AsmReturnValue<B::BV>>,
     {
-        match &mut self.inline_asm_hook {
-            h @ Some(_) => {
-                *h = Some(FunctionHook::new(self.cur_id, hook));
-                self.cur_id += 1;
-                true
+        let wrapped = FunctionHook::new_owned(
+            self.cur_id,
+            move |state: &mut State<'p, B>, call: &'p dyn IsCall| {
+                let asm_retval = hook(state, call)?;
+                asm_return_value_to_return_value(state, call, asm_retval)
             },
-            h @ None => {
-                *h = Some(FunctionHook::new(self.cur_id, hook));
-                self.cur_id += 1;
-                false
-            },
-        }
+        );
+        self.cur_id += 1;
+        self.inline_asm_hook.replace(wrapped).is_some()
     }
 
     /// Add a hook to be used if no other definition or hook is found for the
@@ -390,7 +441,11 @@ impl<'p, B: Backend + 'p> Default for FunctionHooks<'p, B> {
 /// They should return the [`ReturnValue`](enum.ReturnValue.html) representing
 /// the return value of the call, or an appropriate [`Error`](enum.Error.html) if
 /// they cannot.
-pub(crate) struct FunctionHook<'p, B: Backend> {
+///
+/// Outside of this crate, a `FunctionHook` is opaque: it can only be compared,
+/// hashed, or cloned, e.g. when it appears as a
+/// [`Callable::FunctionHook`](../enum.Callable.html#variant.FunctionHook).
+pub struct FunctionHook<'p, B: Backend> {
     /// The actual hook to be executed
     #[allow(clippy::type_complexity)]
     hook: Rc<dyn Fn(&mut State<'p, B>, &'p dyn IsCall) -> Result<ReturnValue<B::BV>> + 'p>,
@@ -426,7 +481,7 @@ impl<'p, B: Backend> Hash for FunctionHook<'p, B> {
 impl<'p, B: Backend> FunctionHook<'p, B> {
     /// `id`: A unique id, used for nothing except equality comparisons between `FunctionHook`s.
     /// This `id` should be globally unique across all created `FunctionHook`s.
-    pub fn new(
+    pub(crate) fn new(
         id: usize,
         f: &'p dyn Fn(&mut State<'p, B>, &'p dyn IsCall) -> Result<ReturnValue<B::BV>>,
     ) -> Self {
@@ -436,7 +491,19 @@ impl<'p, B: Backend> FunctionHook<'p, B> {
         }
     }
 
-    pub fn call_hook(
+    /// Like `new()`, but takes ownership of the hook rather than a reference to
+    /// it. This allows the hook to be a closure wrapping some other hook.
+    fn new_owned(
+        id: usize,
+        f: impl Fn(&mut State<'p, B>, &'p dyn IsCall) -> Result<ReturnValue<B::BV>> + 'p,
+    ) -> Self {
+        Self {
+            hook: Rc::new(f),
+            id,
+        }
+    }
+
+    pub(crate) fn call_hook(
         &self,
         state: &mut State<'p, B>,
         call: &'p dyn IsCall,
@@ -445,6 +512,73 @@ impl<'p, B: Backend> FunctionHook<'p, B> {
     }
 }
 
+/// Converts the `AsmReturnValue` returned by an inline assembly hook into the
+/// `ReturnValue` for the call, checking the hook's outputs against the call's
+/// type. Multiple outputs are packed into a single struct value, with the
+/// first output in the lowest bits as with any other struct.
+fn asm_return_value_to_return_value<'p, B: Backend>(
+    state: &State<'p, B>,
+    call: &dyn IsCall,
+    asm_retval: AsmReturnValue<B::BV>,
+) -> Result<ReturnValue<B::BV>> {
+    let outputs = match asm_retval {
+        AsmReturnValue::Outputs(outputs) => outputs,
+        AsmReturnValue::Abort => return Ok(ReturnValue::Abort),
+    };
+    match call.get_return_type(&state.cur_loc.module.types)?.as_ref() {
+        Type::VoidType => {
+            if outputs.is_empty() {
+                Ok(ReturnValue::ReturnVoid)
+            } else {
+                Err(Error::HookReturnValueMismatch(format!(
+                    "Inline assembly hook returned {} outputs but the asm has no outputs",
+                    outputs.len()
+                )))
+            }
+        },
+        Type::StructType { element_types, .. } => {
+            if outputs.len() != element_types.len() {
+                return Err(Error::HookReturnValueMismatch(format!(
+                    "Inline assembly hook returned {} outputs but the asm has {} outputs",
+                    outputs.len(),
+                    element_types.len()
+                )));
+            }
+            for (i, (output, ty)) in outputs.iter().zip(element_types).enumerate() {
+                let width = state.size_in_bits(ty).ok_or_else(|| {
+                    Error::MalformedInstruction(
+                        "Inline assembly output has an opaque struct type".into(),
+                    )
+                })?;
+                if output.get_width() != width {
+                    return Err(Error::HookReturnValueMismatch(format!("Inline assembly hook returned a {}-bit value for output {}, but that output requires a {}-bit value", output.get_width(), i, width)));
+                }
+            }
+            outputs
+                .into_iter()
+                .fold(None, |acc: Option<B::BV>, output| match acc {
+                    None => Some(output),
+                    Some(acc) => Some(output.concat(&acc)),
+                })
+                .map(ReturnValue::Return)
+                .ok_or_else(|| {
+                    Error::MalformedInstruction("Inline assembly returns an empty struct".into())
+                })
+        },
+        _ => {
+            // a single output. Its width is checked in `symex_hook()`, as for
+            // any other hook's return value
+            let mut outputs = outputs.into_iter();
+            match (outputs.next(), outputs.next()) {
+                (Some(output), None) => Ok(ReturnValue::Return(output)),
+                _ => Err(Error::HookReturnValueMismatch(
+                    "Inline assembly hook should have returned exactly 1 output".into(),
+                )),
+            }
+        },
+    }
+}
+
 /// This hook ignores the function arguments and returns an unconstrained value
 /// of the appropriate size for the function's return value (or void for
 /// void-typed functions).
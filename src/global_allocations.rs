@@ -103,8 +103,11 @@ impl<V> Definition<V> {
 
 /// Both LLVM `Function`s and `FunctionHook`s can be assigned addresses, and
 /// function pointers can point to either
-pub(crate) enum Callable<'p, B: Backend> {
+pub enum Callable<'p, B: Backend> {
+    /// A function defined in the `Project`
     LLVMFunction(&'p Function),
+    /// A `FunctionHook` whose address has been taken (see
+    /// [`State::get_pointer_to_function_hook()`](struct.State.html#method.get_pointer_to_function_hook))
     FunctionHook(FunctionHook<'p, B>),
 }
 
@@ -405,6 +408,29 @@ impl<'p, B: Backend> GlobalAllocations<'p, B> {
         })
     }
 
+    /// Given a `Callable`, get the address at which it was allocated; or `None`
+    /// if it was not allocated.
+    ///
+    /// `module`: The `Module` in which the address would appear. As in
+    /// `get_func_for_address()`, module-private functions are only found from
+    /// their own module.
+    pub fn get_address_of_callable(
+        &self,
+        callable: &Callable<'p, B>,
+        module: &Module,
+    ) -> Option<u64> {
+        self.addr_to_function
+            .iter()
+            .chain(
+                self.module_private_addr_to_function
+                    .get(&module.name)
+                    .into_iter()
+                    .flatten(),
+            )
+            .find(|(_, c)| *c == callable)
+            .map(|(addr, _)| *addr)
+    }
+
     /// Adapt the `GlobalAllocations` to a new solver instance.
     ///
     /// The new solver instance should have been created (possibly transitively)
@@ -426,4 +452,19 @@ impl<'p, B: Backend> GlobalAllocations<'p, B> {
             }
         }
     }
+
+    /// Mark all global variables as not-yet-initialized, so that each will be
+    /// initialized again on its next reference. Their addresses are unchanged.
+    pub fn mark_all_uninitialized(&self) {
+        let all_allocations = self.allocated_globals.values().map(Definition::get).chain(
+            self.module_private_allocated_globals
+                .values()
+                .flat_map(HashMap::values),
+        );
+        for ga in all_allocations {
+            if let GlobalAllocation::GlobalVariable { initialized, .. } = ga {
+                initialized.set(false);
+            }
+        }
+    }
 }
@@ -1,12 +1,15 @@
-//! Utility functions for performing memset or memcpy operations.
+//! Utility functions for performing memset, memcpy, or string operations
+//! (such as strlen or strcmp), for operating on `va_list`s, or for producing
+//! constrained symbolic return values.
 //! These may be useful in implementing hooks for other functions.
 
 use crate::backend::{Backend, BV};
 use crate::config::Concretize;
 use crate::error::*;
+use crate::return_value::ReturnValue;
 use crate::solver_utils::PossibleSolutions;
 use crate::state::State;
-use llvm_ir::Operand;
+use llvm_ir::{Name, Operand};
 use log::{debug, info, warn};
 use std::convert::{TryFrom, TryInto};
 
@@ -86,9 +89,7 @@ pub fn memset_bv<B: Backend>(
             state.write(addr, big_val)?;
         },
         MemcpyLength::Symbolic => {
-            let max_num_bytes = state
-                .max_possible_solution_for_bv_as_u64(num_bytes)?
-                .unwrap();
+            let max_num_bytes = max_symbolic_memcpy_length(state, num_bytes)?;
             if max_num_bytes > 0x4000 {
                 warn!(
                     "Encountered a memset with symbolic size, up to {} bytes. This may be slow.",
@@ -102,8 +103,8 @@ pub fn memset_bv<B: Backend>(
             }
             let mut addr = addr.clone();
             let mut bytes_written = state.zero(num_bytes.get_width());
-            for _ in 0 ..= max_num_bytes {
-                let old_val = state.read(&addr, 8)?;
+            for _ in 0 .. max_num_bytes {
+                let old_val = state.read_maybe_uninitialized(&addr, 8)?;
                 let should_write = num_bytes.ugt(&bytes_written);
                 state.write(&addr, should_write.cond_bv(&val, &old_val))?;
                 addr = addr.inc();
@@ -151,13 +152,11 @@ pub fn memcpy_bv<B: Backend>(
             );
             let length_bytes: u32 = length_bytes.try_into().unwrap();
             // Do the operation as just one large read and one large write; let the memory choose the most efficient way to implement these.
-            let val = state.read(&src, length_bytes * 8)?;
+            let val = state.read_maybe_uninitialized(&src, length_bytes * 8)?;
             state.write(&dest, val)?;
         },
         MemcpyLength::Symbolic => {
-            let max_num_bytes = state
-                .max_possible_solution_for_bv_as_u64(&num_bytes)?
-                .unwrap();
+            let max_num_bytes = max_symbolic_memcpy_length(state, num_bytes)?;
             if max_num_bytes > 0x4000 {
                 warn!("Encountered a memcpy or memmove with symbolic size, up to {} bytes. This may be slow.", max_num_bytes);
             } else {
@@ -169,9 +168,9 @@ pub fn memcpy_bv<B: Backend>(
             let mut src_addr = src.clone();
             let mut dest_addr = dest.clone();
             let mut bytes_written = state.zero(num_bytes.get_width());
-            for _ in 0 ..= max_num_bytes {
-                let src_val = state.read(&src_addr, 8)?;
-                let dst_val = state.read(&dest_addr, 8)?;
+            for _ in 0 .. max_num_bytes {
+                let src_val = state.read_maybe_uninitialized(&src_addr, 8)?;
+                let dst_val = state.read_maybe_uninitialized(&dest_addr, 8)?;
                 let should_write = num_bytes.ugt(&bytes_written);
                 state.write(&dest_addr, should_write.cond_bv(&src_val, &dst_val))?;
                 src_addr = src_addr.inc();
@@ -184,6 +183,29 @@ pub fn memcpy_bv<B: Backend>(
     Ok(dest.clone())
 }
 
+/// Symbolic lengths larger than this are rejected, unless they are bounded with
+/// `max_memcpy_length`, because processing them byte-by-byte would take
+/// (practically) forever.
+const MAX_UNBOUNDED_SYMBOLIC_MEMCPY_LENGTH: u64 = 1 << 20;
+
+/// For a `memcpy`, `memset`, `memmove`, or `memcmp` operation with the given
+/// symbolic `num_bytes` parameter, get the maximum possible length, in bytes.
+///
+/// `get_memcpy_length()` has already applied `max_memcpy_length`, if any; so
+/// this only fails if there is no such bound and the length could be huge.
+fn max_symbolic_memcpy_length<B: Backend>(state: &State<B>, num_bytes: &B::BV) -> Result<u64> {
+    let max_num_bytes = state
+        .max_possible_solution_for_bv_as_u64(num_bytes)?
+        .ok_or(Error::Unsat)?;
+    if max_num_bytes > MAX_UNBOUNDED_SYMBOLIC_MEMCPY_LENGTH
+        && state.config.max_memcpy_length.is_none()
+    {
+        Err(Error::OtherError(format!("Encountered a memcpy/memset/memmove/memcmp with symbolic length, which may be as large as {} bytes. Consider bounding it with `Config.max_memcpy_length`, or concretizing it with `Config.concretize_memcpy_lengths`", max_num_bytes)))
+    } else {
+        Ok(max_num_bytes)
+    }
+}
+
 enum MemcpyLength {
     /// Use this concrete value as the memcpy length, in bytes
     Concrete(u64),
@@ -191,9 +213,10 @@ enum MemcpyLength {
     Symbolic,
 }
 
-/// For a `memcpy`, `memset`, or `memmove` operation with the given `num_bytes`
-/// parameter, return a `MemcpyLength` describing the length of the operation
-/// that should be performed, considering the given `Concretize` option.
+/// For a `memcpy`, `memset`, `memmove`, or `memcmp` operation with the given
+/// `num_bytes` parameter, return a `MemcpyLength` describing the length of the
+/// operation that should be performed, considering the given `Concretize`
+/// option.
 ///
 /// Also accounts for the `max_memcpy_length` option in `state.config`.
 fn get_memcpy_length<B: Backend>(
@@ -206,7 +229,7 @@ fn get_memcpy_length<B: Backend>(
             let single_val = v.iter().next().ok_or(Error::Unsat)?.as_u64().unwrap();
             match state.config.max_memcpy_length {
                 Some(max_memcpy_length) if single_val > max_memcpy_length => {
-                    Err(Error::OtherError(format!("Encountered a memcpy/memset/memmove of length exactly {} bytes, larger than max_memcpy_length {} bytes", single_val, max_memcpy_length)))
+                    Err(Error::OtherError(format!("Encountered a memcpy/memset/memmove/memcmp of length exactly {} bytes, larger than max_memcpy_length {} bytes", single_val, max_memcpy_length)))
                 },
                 _ => Ok(MemcpyLength::Concrete(single_val)),
             }
@@ -219,13 +242,13 @@ fn get_memcpy_length<B: Backend>(
                     &num_bytes.ulte(&max_memcpy_length_bv),
                 ))? {
                     let arbitrary_val = v.iter().next().unwrap().as_u64().unwrap();
-                    return Err(Error::OtherError(format!("Encountered a memcpy/memset/memmove with multiple possible lengths, but all of them are larger than max_memcpy_length {} bytes. One possible length is {} bytes.", max_memcpy_length, arbitrary_val)));
+                    return Err(Error::OtherError(format!("Encountered a memcpy/memset/memmove/memcmp with multiple possible lengths, but all of them are larger than max_memcpy_length {} bytes. One possible length is {} bytes.", max_memcpy_length, arbitrary_val)));
                 }
                 if state.sat_with_extra_constraints(std::iter::once(
                     &num_bytes.ugt(&max_memcpy_length_bv),
                 ))? {
-                    warn!("Encountered a memcpy/memset/memmove with multiple possible lengths, some of which are larger than max_memcpy_length {} bytes. Constraining the length to be at most {} bytes.", max_memcpy_length, max_memcpy_length);
-                    num_bytes.ulte(&max_memcpy_length_bv).assert()?;
+                    warn!("Encountered a memcpy/memset/memmove/memcmp with multiple possible lengths, some of which are larger than max_memcpy_length {} bytes. Constraining the length to be at most {} bytes.", max_memcpy_length, max_memcpy_length);
+                    state.assert(&num_bytes.ulte(&max_memcpy_length_bv))?;
                 }
             }
             let num_bytes_concrete = match concretize {
@@ -264,12 +287,498 @@ fn get_memcpy_length<B: Backend>(
                 },
                 Concretize::Symbolic => return Ok(MemcpyLength::Symbolic),
             };
-            info!("Encountered a memcpy/memset/memmove with multiple possible lengths; according to the concretization policy {:?}, chose a length of {} bytes and will constrain the length argument to be {} going forward", concretize, num_bytes_concrete, num_bytes_concrete);
+            info!("Encountered a memcpy/memset/memmove/memcmp with multiple possible lengths; according to the concretization policy {:?}, chose a length of {} bytes and will constrain the length argument to be {} going forward", concretize, num_bytes_concrete, num_bytes_concrete);
             // actually constrain that `num_bytes` has to now be equal to our chosen concrete value
-            num_bytes
-                ._eq(&state.bv_from_u64(num_bytes_concrete, num_bytes.get_width()))
-                .assert()?;
+            state.assert(
+                &num_bytes._eq(&state.bv_from_u64(num_bytes_concrete, num_bytes.get_width())),
+            )?;
             Ok(MemcpyLength::Concrete(num_bytes_concrete))
         },
     }
 }
+
+/// Compute the length of the NUL-terminated string at address `s`, not
+/// including the NUL terminator.
+///
+/// Returns the length as a `BV` of the same width as `s` (i.e., pointer width).
+///
+/// Rather than looping byte-by-byte (which for a symbolic string would split
+/// the path once per byte), this reads up to `state.config.max_string_len + 1`
+/// bytes and builds the length as a single symbolic expression. It also
+/// constrains the string to have a NUL byte within that bound; if it can't,
+/// this returns an error.
+///
+/// Unless one of the bytes is definitely NUL, all of those bytes are read,
+/// even ones which may be past the end of the string. So `s` should point to
+/// at least `max_string_len + 1` readable bytes; otherwise, those reads may
+/// result in errors such as `Error::NullPointerDereference`.
+pub fn strlen<B: Backend>(state: &mut State<B>, s: &Operand) -> Result<B::BV> {
+    let s = state.operand_to_bv(s)?;
+    strlen_bv(state, &s)
+}
+
+/// Just like `strlen()` above, but takes a `BV` instead of an `Operand` for its argument.
+pub fn strlen_bv<B: Backend>(state: &mut State<B>, s: &B::BV) -> Result<B::BV> {
+    let max_string_len = state.config.max_string_len;
+    // `is_nul[i]` is a condition for the byte at index `i` being NUL. We stop
+    // early if we find a byte which is definitely NUL, as nothing past it can
+    // matter.
+    let mut is_nul: Vec<B::BV> = Vec::new();
+    let mut found_definite_nul = false;
+    let mut addr = s.clone();
+    for _ in 0 ..= max_string_len {
+        let byte = state.read(&addr, 8)?;
+        if byte.as_u64() == Some(0) {
+            is_nul.push(state.bv_from_bool(true));
+            found_definite_nul = true;
+            break;
+        }
+        is_nul.push(byte._eq(&state.zero(8)));
+        addr = addr.inc();
+    }
+
+    if !found_definite_nul {
+        let has_nul = is_nul
+            .iter()
+            .fold(state.bv_from_bool(false), |a, b| a.or(b));
+        if !state.sat_with_extra_constraints(std::iter::once(&has_nul))? {
+            return Err(Error::OtherError(format!(
+                "strlen: string can't have a NUL terminator within max_string_len {} bytes",
+                max_string_len
+            )));
+        }
+        if state.sat_with_extra_constraints(std::iter::once(&has_nul.not()))? {
+            debug!(
+                "strlen: constraining the string to have length at most max_string_len {} bytes",
+                max_string_len
+            );
+            state.assert(&has_nul)?;
+        }
+    }
+
+    // With the constraint above, if none of the bytes before the last one is
+    // NUL, the last one must be
+    let width = s.get_width();
+    let last = is_nul.len() - 1;
+    Ok(is_nul[.. last]
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(state.bv_from_u64(last as u64, width), |len, (i, nul)| {
+            nul.cond_bv(&state.bv_from_u64(i as u64, width), &len)
+        }))
+}
+
+/// Compare the first `num_bytes` bytes of memory at addresses `a` and `b`.
+///
+/// Returns an `i32` which is negative, zero, or positive as the first
+/// differing byte (compared as `unsigned char`) is less in `a`, not present
+/// (i.e., the buffers are equal), or greater in `a`. This is the behavior of
+/// `memcmp()`.
+///
+/// `num_bytes` may be symbolic, in which case the result is computed
+/// symbolically for all of its possible values.
+///
+/// Respects the `state.config.concretize_memcpy_lengths` and
+/// `state.config.max_memcpy_length` settings, as `memcpy()` does.
+pub fn memcmp<B: Backend>(
+    state: &mut State<B>,
+    a: &Operand,
+    b: &Operand,
+    num_bytes: &Operand,
+) -> Result<B::BV> {
+    let a = state.operand_to_bv(a)?;
+    let b = state.operand_to_bv(b)?;
+    let num_bytes = state.operand_to_bv(num_bytes)?;
+    memcmp_bv(state, &a, &b, &num_bytes)
+}
+
+/// Just like `memcmp()` above, but takes `BV`s instead of `Operand`s for its arguments.
+pub fn memcmp_bv<B: Backend>(
+    state: &mut State<B>,
+    a: &B::BV,
+    b: &B::BV,
+    num_bytes: &B::BV,
+) -> Result<B::BV> {
+    match get_memcpy_length(state, num_bytes, &state.config.concretize_memcpy_lengths)? {
+        MemcpyLength::Concrete(length_bytes) => {
+            compare_bytes(state, a, b, length_bytes, None, false, None)
+        },
+        MemcpyLength::Symbolic => {
+            let max_num_bytes = max_symbolic_memcpy_length(state, num_bytes)?;
+            if max_num_bytes > 0x4000 {
+                warn!(
+                    "Encountered a memcmp of symbolic size, up to {} bytes. This may be slow.",
+                    max_num_bytes
+                );
+            }
+            compare_bytes(state, a, b, max_num_bytes, Some(num_bytes), false, None)
+        },
+    }
+}
+
+/// Compare the NUL-terminated strings at addresses `a` and `b`.
+///
+/// Returns an `i32` which is negative, zero, or positive as `a` is less than,
+/// equal to, or greater than `b`, comparing bytes as `unsigned char`. This is
+/// the behavior of `strcmp()`.
+///
+/// Like `strlen()`, this considers strings of up to
+/// `state.config.max_string_len` bytes, and constrains the strings to either
+/// differ or end within that bound; if they can't, this returns an error.
+/// Also like `strlen()`, `a` and `b` should each point to at least
+/// `max_string_len + 1` readable bytes.
+pub fn strcmp<B: Backend>(state: &mut State<B>, a: &Operand, b: &Operand) -> Result<B::BV> {
+    let a = state.operand_to_bv(a)?;
+    let b = state.operand_to_bv(b)?;
+    strcmp_bv(state, &a, &b)
+}
+
+/// Just like `strcmp()` above, but takes `BV`s instead of `Operand`s for its arguments.
+pub fn strcmp_bv<B: Backend>(state: &mut State<B>, a: &B::BV, b: &B::BV) -> Result<B::BV> {
+    let max_string_len = state.config.max_string_len;
+    compare_bytes(state, a, b, max_string_len + 1, None, true, Some("strcmp"))
+}
+
+/// Like `strcmp()`, but compares at most `num_bytes` bytes. This is the
+/// behavior of `strncmp()`.
+///
+/// `num_bytes` may be symbolic, in which case the result is computed
+/// symbolically for all of its possible values. If `num_bytes` may be larger
+/// than `state.config.max_string_len`, then as in `strcmp()`, the strings are
+/// constrained to either differ or end within that bound.
+pub fn strncmp<B: Backend>(
+    state: &mut State<B>,
+    a: &Operand,
+    b: &Operand,
+    num_bytes: &Operand,
+) -> Result<B::BV> {
+    let a = state.operand_to_bv(a)?;
+    let b = state.operand_to_bv(b)?;
+    let num_bytes = state.operand_to_bv(num_bytes)?;
+    strncmp_bv(state, &a, &b, &num_bytes)
+}
+
+/// Just like `strncmp()` above, but takes `BV`s instead of `Operand`s for its arguments.
+pub fn strncmp_bv<B: Backend>(
+    state: &mut State<B>,
+    a: &B::BV,
+    b: &B::BV,
+    num_bytes: &B::BV,
+) -> Result<B::BV> {
+    let (max_num_bytes, limit) = max_length_and_limit(state, num_bytes)?;
+    let max_string_len = state.config.max_string_len;
+    if max_num_bytes <= max_string_len + 1 {
+        compare_bytes(state, a, b, max_num_bytes, limit, true, None)
+    } else {
+        compare_bytes(
+            state,
+            a,
+            b,
+            max_string_len + 1,
+            limit,
+            true,
+            Some("strncmp"),
+        )
+    }
+}
+
+/// For a comparison of length `num_bytes`, get the maximum number of bytes to
+/// compare, and (if `num_bytes` is symbolic) the limit to pass to
+/// `compare_bytes()`
+fn max_length_and_limit<'b, B: Backend>(
+    state: &State<B>,
+    num_bytes: &'b B::BV,
+) -> Result<(u64, Option<&'b B::BV>)> {
+    match num_bytes.as_u64() {
+        Some(num_bytes) => Ok((num_bytes, None)),
+        None => {
+            let max_num_bytes = state
+                .max_possible_solution_for_bv_as_u64(num_bytes)?
+                .ok_or(Error::Unsat)?;
+            Ok((max_num_bytes, Some(num_bytes)))
+        },
+    }
+}
+
+/// Compare up to `max_num_bytes` bytes at addresses `a` and `b`, returning an
+/// `i32` describing the first differing byte, as in `memcmp()`.
+///
+/// If `limit` is `Some`, bytes at or beyond that (symbolic) index are treated
+/// as equal. If `stop_at_nul` is `true`, the comparison also stops at the
+/// first NUL byte (in both buffers, as they must be equal to get that far).
+///
+/// If `bounded_by` is `Some`, then `max_num_bytes` is not the actual length
+/// of the comparison, just a bound we impose on it (named by the string, for
+/// error messages), so we constrain that the comparison stops within the
+/// bound.
+fn compare_bytes<B: Backend>(
+    state: &mut State<B>,
+    a: &B::BV,
+    b: &B::BV,
+    max_num_bytes: u64,
+    limit: Option<&B::BV>,
+    stop_at_nul: bool,
+    bounded_by: Option<&str>,
+) -> Result<B::BV> {
+    // For each byte index, a condition for the comparison stopping at that
+    // index, and the result if it does. We stop early if we find an index
+    // where the comparison definitely stops.
+    let mut stops: Vec<(B::BV, B::BV)> = Vec::new();
+    let mut found_definite_stop = false;
+    let mut a_addr = a.clone();
+    let mut b_addr = b.clone();
+    for i in 0 .. max_num_bytes {
+        let a_byte = state.read(&a_addr, 8)?;
+        let b_byte = state.read(&b_addr, 8)?;
+        let mut stop = a_byte._ne(&b_byte);
+        if stop_at_nul {
+            stop = stop.or(&a_byte._eq(&state.zero(8)));
+        }
+        let mut result = a_byte.zext(24).sub(&b_byte.zext(24));
+        if let Some(limit) = limit {
+            let in_bounds = state.bv_from_u64(i, limit.get_width()).ult(limit);
+            stop = in_bounds.not().or(&stop);
+            result = in_bounds.cond_bv(&result, &state.zero(32));
+        }
+        if stop.as_bool() == Some(true) {
+            stops.push((stop, result));
+            found_definite_stop = true;
+            break;
+        }
+        stops.push((stop, result));
+        a_addr = a_addr.inc();
+        b_addr = b_addr.inc();
+    }
+
+    if let (Some(funcname), false) = (bounded_by, found_definite_stop) {
+        let stops_in_bound = stops
+            .iter()
+            .fold(state.bv_from_bool(false), |acc, (stop, _)| acc.or(stop));
+        if !state.sat_with_extra_constraints(std::iter::once(&stops_in_bound))? {
+            return Err(Error::OtherError(format!(
+                "{}: strings can't differ or end within max_string_len {} bytes",
+                funcname, state.config.max_string_len
+            )));
+        }
+        if state.sat_with_extra_constraints(std::iter::once(&stops_in_bound.not()))? {
+            debug!(
+                "{}: constraining the strings to differ or end within max_string_len {} bytes",
+                funcname, state.config.max_string_len
+            );
+            state.assert(&stops_in_bound)?;
+        }
+    }
+
+    // If the comparison doesn't stop at any index, the buffers are equal
+    Ok(stops
+        .iter()
+        .rev()
+        .fold(state.zero(32), |acc, (stop, result)| {
+            stop.cond_bv(result, &acc)
+        }))
+}
+
+/// How a `va_list` is laid out in memory on the target platform
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum VaListLayout {
+    /// The x86-64 System V layout:
+    /// `{ i32 gp_offset, i32 fp_offset, i8* overflow_arg_area, i8* reg_save_area }`
+    X86_64SysV,
+    /// A `va_list` is simply a pointer to the next argument (e.g., on i386 or
+    /// on x86-64 Windows)
+    Pointer,
+}
+
+impl VaListLayout {
+    /// Determine the `va_list` layout for the module currently being executed
+    fn for_cur_module<B: Backend>(state: &State<B>) -> Self {
+        match &state.cur_loc.module.target_triple {
+            Some(triple) if triple.starts_with("x86_64") && !triple.contains("windows") => {
+                VaListLayout::X86_64SysV
+            },
+            _ => VaListLayout::Pointer,
+        }
+    }
+
+    /// Size of a `va_list` with this layout, in bits
+    fn size_in_bits(self, ptr_bits: u32) -> u32 {
+        match self {
+            VaListLayout::X86_64SysV => 32 + 32 + ptr_bits + ptr_bits,
+            VaListLayout::Pointer => ptr_bits,
+        }
+    }
+
+    /// Offset (in bytes) within the `va_list` of the pointer to the next
+    /// argument in memory
+    fn arg_area_offset_bytes(self) -> u32 {
+        match self {
+            VaListLayout::X86_64SysV => 8, // `overflow_arg_area`
+            VaListLayout::Pointer => 0,
+        }
+    }
+}
+
+/// Variadic arguments are each stored in one or more pointer-sized slots
+fn va_slot_bits(arg_bits: u32, ptr_bits: u32) -> u32 {
+    let mut slots = arg_bits / ptr_bits;
+    if slots * ptr_bits < arg_bits {
+        slots += 1; // round up to a whole slot
+    }
+    slots * ptr_bits
+}
+
+/// Initialize the `va_list` at address `va_list` so that it refers to the
+/// variadic arguments of the current function (see
+/// [`State::get_varargs()`](../struct.State.html#method.get_varargs)).
+/// This is the behavior of `va_start()`.
+///
+/// The variadic arguments are placed, in order, in a newly allocated buffer,
+/// each in its own pointer-sized (or larger) slot, and the `va_list` is made to
+/// point to that buffer. For the x86-64 System V `va_list` layout, we also mark
+/// all of the register save slots as used, so that all arguments are fetched
+/// from the buffer.
+pub fn va_start<B: Backend>(state: &mut State<B>, va_list: &B::BV) -> Result<()> {
+    let ptr_bits = state.proj.pointer_size_bits();
+    let slotted_args: Vec<B::BV> = state
+        .get_varargs()
+        .iter()
+        .map(|arg| arg.zero_extend_to_bits(va_slot_bits(arg.get_width(), ptr_bits)))
+        .collect();
+    let total_bits: u32 = slotted_args.iter().map(|arg| arg.get_width()).sum();
+    debug!(
+        "va_start: {} variadic arguments, totaling {} bits",
+        slotted_args.len(),
+        total_bits
+    );
+    let arg_area = state.allocate(std::cmp::max(total_bits, ptr_bits));
+    let mut offset_bytes = 0;
+    for arg in slotted_args {
+        let arg_bytes = arg.get_width() / 8;
+        let addr = arg_area.add(&state.bv_from_u32(offset_bytes, ptr_bits));
+        state.write(&addr, arg)?;
+        offset_bytes += arg_bytes;
+    }
+
+    let layout = VaListLayout::for_cur_module(state);
+    if layout == VaListLayout::X86_64SysV {
+        // `gp_offset` of 48 indicates all 6 general-purpose register slots are used
+        state.write(va_list, state.bv_from_u32(48, 32))?;
+        // `fp_offset` of 176 indicates all 8 floating-point register slots are used
+        let fp_offset_addr = va_list.add(&state.bv_from_u32(4, ptr_bits));
+        state.write(&fp_offset_addr, state.bv_from_u32(176, 32))?;
+        // `reg_save_area` will never be consulted, so we just leave it NULL
+        let reg_save_area_addr = va_list.add(&state.bv_from_u32(16, ptr_bits));
+        state.write(&reg_save_area_addr, state.zero(ptr_bits))?;
+    }
+    let arg_area_ptr_addr =
+        va_list.add(&state.bv_from_u32(layout.arg_area_offset_bytes(), ptr_bits));
+    state.write(&arg_area_ptr_addr, arg_area)?;
+    Ok(())
+}
+
+/// Copy the `va_list` at address `src` to address `dest`.
+/// This is the behavior of `va_copy()`.
+pub fn va_copy<B: Backend>(state: &mut State<B>, dest: &B::BV, src: &B::BV) -> Result<()> {
+    let layout = VaListLayout::for_cur_module(state);
+    let contents =
+        state.read_maybe_uninitialized(src, layout.size_in_bits(state.proj.pointer_size_bits()))?;
+    state.write(dest, contents)
+}
+
+/// Fetch the next variadic argument, which should have size `bits`, from the
+/// `va_list` at address `va_list`, and advance the `va_list` past it.
+/// This is the behavior of the LLVM `va_arg` instruction.
+///
+/// The `va_list` must have been initialized with `va_start()` (or copied from
+/// one which was).
+pub fn va_arg<B: Backend>(state: &mut State<B>, va_list: &B::BV, bits: u32) -> Result<B::BV> {
+    let ptr_bits = state.proj.pointer_size_bits();
+    let layout = VaListLayout::for_cur_module(state);
+    let arg_area_ptr_addr =
+        va_list.add(&state.bv_from_u32(layout.arg_area_offset_bytes(), ptr_bits));
+    let arg_addr = state.read(&arg_area_ptr_addr, ptr_bits)?;
+    let arg = state.read(&arg_addr, bits)?;
+    let next_arg_addr =
+        arg_addr.add(&state.bv_from_u32(va_slot_bits(bits, ptr_bits) / 8, ptr_bits));
+    state.write(&arg_area_ptr_addr, next_arg_addr)?;
+    Ok(arg)
+}
+
+/// Get a `ReturnValue` containing a fresh `width`-bit value which is
+/// constrained to lie in the range `low ..= high`, interpreting the value as
+/// unsigned. This is useful in hooks for functions which are only known to
+/// return a value in some range.
+///
+/// Returns an error if the range is empty, or if `high` doesn't fit in `width`
+/// bits.
+pub fn return_symbolic_in_range<B: Backend>(
+    state: &mut State<B>,
+    width: u32,
+    low: u64,
+    high: u64,
+) -> Result<ReturnValue<B::BV>> {
+    if low > high {
+        return Err(Error::OtherError(format!(
+            "return_symbolic_in_range: empty range {} ..= {}",
+            low, high
+        )));
+    }
+    if width < 64 && high >> width != 0 {
+        return Err(Error::OtherError(format!(
+            "return_symbolic_in_range: {} doesn't fit in {} bits",
+            high, width
+        )));
+    }
+    let bv = state.new_bv_with_name(Name::from("return_symbolic_in_range_retval"), width)?;
+    state.assert(&bv.ugte(&state.bv_from_u64(low, width)))?;
+    state.assert(&bv.ulte(&state.bv_from_u64(high, width)))?;
+    Ok(ReturnValue::Return(bv))
+}
+
+/// Just like `return_symbolic_in_range()` above, but interprets the value (and
+/// the bounds) as signed. E.g., for "a nonnegative `i32`", use a `width` of 32
+/// and the range `0 ..= i32::MAX`.
+///
+/// Returns an error if the range is empty, or if `low` or `high` doesn't fit
+/// in `width` bits.
+pub fn return_symbolic_in_signed_range<B: Backend>(
+    state: &mut State<B>,
+    width: u32,
+    low: i64,
+    high: i64,
+) -> Result<ReturnValue<B::BV>> {
+    if low > high {
+        return Err(Error::OtherError(format!(
+            "return_symbolic_in_signed_range: empty range {} ..= {}",
+            low, high
+        )));
+    }
+    if width < 64 {
+        let max = (1i64 << (width - 1)) - 1;
+        let min = -max - 1;
+        if low < min || high > max {
+            return Err(Error::OtherError(format!(
+                "return_symbolic_in_signed_range: range {} ..= {} doesn't fit in {} bits",
+                low, high, width
+            )));
+        }
+    }
+    let bv = state.new_bv_with_name(Name::from("return_symbolic_in_signed_range_retval"), width)?;
+    state.assert(&bv.sgte(&state.bv_from_i64(low, width)))?;
+    state.assert(&bv.slte(&state.bv_from_i64(high, width)))?;
+    Ok(ReturnValue::Return(bv))
+}
+
+/// Get a `ReturnValue` containing a fresh pointer-sized value which is
+/// constrained to be non-null, but is otherwise unconstrained.
+///
+/// Note that the pointer doesn't necessarily point to allocated memory; for a
+/// pointer to fresh memory, use
+/// [`State::allocate()`](../struct.State.html#method.allocate) instead.
+pub fn return_symbolic_nonnull_ptr<B: Backend>(state: &mut State<B>) -> Result<ReturnValue<B::BV>> {
+    let ptr_bits = state.proj.pointer_size_bits();
+    let bv = state.new_bv_with_name(Name::from("return_symbolic_nonnull_ptr_retval"), ptr_bits)?;
+    state.assert(&bv._ne(&state.zero(ptr_bits)))?;
+    Ok(ReturnValue::Return(bv))
+}
@@ -77,11 +77,24 @@ pub fn calloc_hook<'p, B: Backend + 'p>(
 }
 
 pub fn free_hook<'p, B: Backend + 'p>(
-    _state: &mut State<'p, B>,
-    _call: &'p dyn IsCall,
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    // The simplest implementation of free() is a no-op.
-    // Our allocator won't ever reuse allocated addresses anyway.
+    assert_eq!(call.get_arguments().len(), 1);
+    let addr = &call.get_arguments()[0].0;
+    match state.type_of(addr).as_ref() {
+        Type::PointerType { .. } => {},
+        ty => {
+            return Err(Error::OtherError(format!(
+                "free_hook: expected argument to be a pointer type, but got {:?}",
+                ty
+            )))
+        },
+    };
+
+    // Our allocator won't ever reuse allocated addresses, so this doesn't
+    // actually free anything; it just records the address as freed.
+    alloc_utils::free(state, addr)?;
     Ok(ReturnValue::ReturnVoid)
 }
 
@@ -68,6 +68,7 @@ pub fn cxa_throw<B: Backend>(
     }
 
     let thrown_ptr = state.operand_to_bv(thrown_ptr)?;
+    state.set_exception_type_info(type_info_name(type_info).map(str::to_owned));
     Ok(ReturnValue::Throw(thrown_ptr))
 }
 
@@ -86,11 +87,12 @@ pub fn cxa_begin_catch<B: Backend>(
 }
 
 pub fn cxa_end_catch<B: Backend>(
-    _state: &mut State<B>,
+    state: &mut State<B>,
     _call: &dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    // Since we don't free things anyway, we don't need to worry about freeing the exception, and
-    // __cxa_end_catch() can be a no-op
+    // Since we don't free things anyway, we don't need to worry about freeing the exception.
+    // We just forget its type, since the exception has now been handled
+    state.set_exception_type_info(None);
     Ok(ReturnValue::ReturnVoid)
 }
 
@@ -100,9 +102,38 @@ pub fn llvm_eh_typeid_for<B: Backend>(
 ) -> Result<ReturnValue<B::BV>> {
     assert_eq!(call.get_arguments().len(), 1);
 
-    // for now we ignore the argument and return an unconstrained value
+    // if we have a mapping from type_info globals to type indices, use it; see
+    // notes on `Config.exception_type_index_hook`
+    if let Some(hook) = state.config.exception_type_index_hook.clone() {
+        if let Some(type_info) = type_info_name(&call.get_arguments()[0].0) {
+            let type_index = hook(state, type_info)?;
+            return Ok(ReturnValue::Return(state.bv_from_u32(type_index, 32)));
+        }
+    }
+
+    // otherwise we ignore the argument and return an unconstrained value
     // (unconstrained except for the constraint that the value is positive, as specified in LLVM docs)
     let retval = state.new_bv_with_name(Name::from("llvm_eh_typeid_for_retval"), 32)?;
-    retval.sgte(&state.zero(32)).assert()?;
+    state.assert(&retval.sgte(&state.zero(32)))?;
     Ok(ReturnValue::Return(retval))
 }
+
+/// Get the name of the RTTI (`type_info`) global referred to by the given
+/// `Operand`, if it is a constant (possibly bitcasted) reference to a global.
+/// Returns `None` for other operands.
+fn type_info_name(operand: &Operand) -> Option<&str> {
+    fn constant_type_info_name(constant: &Constant) -> Option<&str> {
+        match constant {
+            Constant::GlobalReference {
+                name: Name::Name(name),
+                ..
+            } => Some(name),
+            Constant::BitCast(bitcast) => constant_type_info_name(bitcast.operand.as_ref()),
+            _ => None,
+        }
+    }
+    match operand {
+        Operand::ConstantOperand(cref) => constant_type_info_name(cref.as_ref()),
+        _ => None,
+    }
+}
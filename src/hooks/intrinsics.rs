@@ -6,7 +6,7 @@ use crate::function_hooks::IsCall;
 use crate::hook_utils;
 use crate::return_value::ReturnValue;
 use crate::state::State;
-use crate::symex::unary_on_vector;
+use crate::symex::{binary_on_vector, unary_on_vector};
 use llvm_ir::Type;
 use std::convert::TryInto;
 
@@ -19,15 +19,9 @@ pub fn symex_memset<'p, B: Backend>(
     let val = &call.get_arguments()[1].0;
     let num_bytes = &call.get_arguments()[2].0;
     match state.type_of(addr).as_ref() {
-        Type::PointerType { pointee_type, .. } => match pointee_type.as_ref() {
-            Type::IntegerType { bits: 8 } => (),
-            _ => {
-                return Err(Error::OtherError(format!(
-                    "memset: Expected address to be a pointer to i8, got pointer to {:?}",
-                    pointee_type
-                )))
-            },
-        },
+        // we don't inspect the pointee type: the intrinsic operates on bytes
+        // regardless, and opaque pointers don't have a pointee type anyway
+        Type::PointerType { .. } => {},
         ty => {
             return Err(Error::OtherError(format!(
                 "memset: Expected address to have pointer type, got {:?}",
@@ -57,15 +51,9 @@ pub fn symex_memcpy<'p, B: Backend>(
     let src = &call.get_arguments()[1].0;
     let num_bytes = &call.get_arguments()[2].0;
     match state.type_of(dest).as_ref() {
-        Type::PointerType { pointee_type, .. } => match pointee_type.as_ref() {
-            Type::IntegerType { bits: 8 } => (),
-            _ => {
-                return Err(Error::OtherError(format!(
-                    "memcpy: Expected dest to be a pointer to i8, got pointer to {:?}",
-                    pointee_type
-                )))
-            },
-        },
+        // we don't inspect the pointee type: the intrinsic operates on bytes
+        // regardless, and opaque pointers don't have a pointee type anyway
+        Type::PointerType { .. } => {},
         ty => {
             return Err(Error::OtherError(format!(
                 "memcpy: Expected dest to have pointer type, got {:?}",
@@ -74,18 +62,10 @@ pub fn symex_memcpy<'p, B: Backend>(
         },
     }
     match state.type_of(src).as_ref() {
-        Type::PointerType { pointee_type, .. } => match pointee_type.as_ref() {
-            Type::IntegerType { bits: 8 } => (),
-            _ => {
-                return Err(Error::OtherError(format!(
-                    "memcpy: Expected dest to be a pointer to i8, got pointer to {:?}",
-                    pointee_type
-                )))
-            },
-        },
+        Type::PointerType { .. } => {},
         ty => {
             return Err(Error::OtherError(format!(
-                "memcpy: Expected dest to have pointer type, got {:?}",
+                "memcpy: Expected src to have pointer type, got {:?}",
                 ty
             )))
         },
@@ -271,7 +251,8 @@ pub fn symex_assume<'p, B: Backend>(
     }
 
     if state.config.trust_llvm_assumes {
-        state.operand_to_bv(arg)?.assert()?;
+        let arg = state.operand_to_bv(arg)?;
+        state.assert(&arg)?;
     } else {
         // just ignore the assume
     }
@@ -279,122 +260,188 @@ pub fn symex_assume<'p, B: Backend>(
     Ok(ReturnValue::ReturnVoid)
 }
 
-pub fn symex_uadd_with_overflow<'p, B: Backend>(
+pub fn symex_va_start<'p, B: Backend>(
     state: &mut State<'p, B>,
-    call: &dyn IsCall,
+    call: &'p dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    assert_eq!(call.get_arguments().len(), 2);
-    let arg0 = &call.get_arguments()[0].0;
-    let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_uadd_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
+    assert_eq!(call.get_arguments().len(), 1);
+    let va_list = &call.get_arguments()[0].0;
+    match state.type_of(va_list).as_ref() {
+        Type::PointerType { .. } => {},
+        ty => {
+            return Err(Error::OtherError(format!(
+                "symex_va_start: expected arg to be of pointer type, got type {:?}",
+                ty
+            )))
+        },
     }
 
-    let arg0 = state.operand_to_bv(arg0)?;
-    let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.add(&arg1);
-    let overflow = arg0.uaddo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
-
-    Ok(ReturnValue::Return(overflow.concat(&result)))
+    let va_list = state.operand_to_bv(va_list)?;
+    hook_utils::va_start(state, &va_list)?;
+    Ok(ReturnValue::ReturnVoid)
 }
 
-pub fn symex_sadd_with_overflow<'p, B: Backend>(
+pub fn symex_va_copy<'p, B: Backend>(
     state: &mut State<'p, B>,
-    call: &dyn IsCall,
+    call: &'p dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
     assert_eq!(call.get_arguments().len(), 2);
-    let arg0 = &call.get_arguments()[0].0;
-    let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_sadd_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
+    let dest = &call.get_arguments()[0].0;
+    let src = &call.get_arguments()[1].0;
+    for arg in &[dest, src] {
+        match state.type_of(*arg).as_ref() {
+            Type::PointerType { .. } => {},
+            ty => {
+                return Err(Error::OtherError(format!(
+                    "symex_va_copy: expected args to be of pointer type, got type {:?}",
+                    ty
+                )))
+            },
+        }
     }
 
-    let arg0 = state.operand_to_bv(arg0)?;
-    let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.add(&arg1);
-    let overflow = arg0.saddo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
-
-    Ok(ReturnValue::Return(overflow.concat(&result)))
+    let dest = state.operand_to_bv(dest)?;
+    let src = state.operand_to_bv(src)?;
+    hook_utils::va_copy(state, &dest, &src)?;
+    Ok(ReturnValue::ReturnVoid)
 }
 
-pub fn symex_usub_with_overflow<'p, B: Backend>(
+pub fn symex_uadd_with_overflow<'p, B: Backend>(
     state: &mut State<'p, B>,
     call: &dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    assert_eq!(call.get_arguments().len(), 2);
-    let arg0 = &call.get_arguments()[0].0;
-    let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_usub_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
-    }
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_uadd_with_overflow",
+        |a, b| a.add(b),
+        |a, b| a.uaddo(b),
+    )
+}
 
-    let arg0 = state.operand_to_bv(arg0)?;
-    let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.sub(&arg1);
-    let overflow = arg0.usubo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
+pub fn symex_sadd_with_overflow<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_sadd_with_overflow",
+        |a, b| a.add(b),
+        |a, b| a.saddo(b),
+    )
+}
 
-    Ok(ReturnValue::Return(overflow.concat(&result)))
+pub fn symex_usub_with_overflow<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_usub_with_overflow",
+        |a, b| a.sub(b),
+        |a, b| a.usubo(b),
+    )
 }
 
 pub fn symex_ssub_with_overflow<'p, B: Backend>(
     state: &mut State<'p, B>,
     call: &dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    assert_eq!(call.get_arguments().len(), 2);
-    let arg0 = &call.get_arguments()[0].0;
-    let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_ssub_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
-    }
-
-    let arg0 = state.operand_to_bv(arg0)?;
-    let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.sub(&arg1);
-    let overflow = arg0.ssubo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
-
-    Ok(ReturnValue::Return(overflow.concat(&result)))
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_ssub_with_overflow",
+        |a, b| a.sub(b),
+        |a, b| a.ssubo(b),
+    )
 }
 
 pub fn symex_umul_with_overflow<'p, B: Backend>(
     state: &mut State<'p, B>,
     call: &dyn IsCall,
 ) -> Result<ReturnValue<B::BV>> {
-    assert_eq!(call.get_arguments().len(), 2);
-    let arg0 = &call.get_arguments()[0].0;
-    let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_umul_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
-    }
-
-    let arg0 = state.operand_to_bv(arg0)?;
-    let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.mul(&arg1);
-    let overflow = arg0.umulo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
-
-    Ok(ReturnValue::Return(overflow.concat(&result)))
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_umul_with_overflow",
+        |a, b| a.mul(b),
+        |a, b| a.umulo(b),
+    )
 }
 
 pub fn symex_smul_with_overflow<'p, B: Backend>(
     state: &mut State<'p, B>,
     call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_arith_with_overflow(
+        state,
+        call,
+        "symex_smul_with_overflow",
+        |a, b| a.mul(b),
+        |a, b| a.smulo(b),
+    )
+}
+
+/// Shared implementation of the `*.with.overflow` intrinsics, where `op`
+/// computes the result and `overflow_op` computes the (1-bit) overflow flag
+/// for scalar operands.
+///
+/// For vector operands, both are computed element-wise, and the returned
+/// `{result, overflow}` struct consists of the vector of results followed by
+/// the vector of (1-bit) overflow flags.
+fn symex_arith_with_overflow<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+    name: &str,
+    op: impl for<'a> Fn(&'a B::BV, &'a B::BV) -> B::BV,
+    overflow_op: impl for<'a> Fn(&'a B::BV, &'a B::BV) -> B::BV,
 ) -> Result<ReturnValue<B::BV>> {
     assert_eq!(call.get_arguments().len(), 2);
     let arg0 = &call.get_arguments()[0].0;
     let arg1 = &call.get_arguments()[1].0;
-    if state.type_of(arg0) != state.type_of(arg1) {
-        return Err(Error::OtherError(format!("symex_smul_with_overflow: expected arguments to be of the same type, but got types {:?} and {:?}", state.type_of(arg0), state.type_of(arg1))));
+    let argty = state.type_of(arg0);
+    if state.type_of(arg1) != argty {
+        return Err(Error::OtherError(format!(
+            "{}: expected arguments to be of the same type, but got types {:?} and {:?}",
+            name,
+            argty,
+            state.type_of(arg1)
+        )));
     }
 
     let arg0 = state.operand_to_bv(arg0)?;
     let arg1 = state.operand_to_bv(arg1)?;
-    let result = arg0.mul(&arg1);
-    let overflow = arg0.smulo(&arg1);
-    assert_eq!(overflow.get_width(), 1);
+    let (result, overflow) = match argty.as_ref() {
+        Type::IntegerType { .. } => {
+            let overflow = overflow_op(&arg0, &arg1);
+            assert_eq!(overflow.get_width(), 1);
+            (op(&arg0, &arg1), overflow)
+        },
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => {
+            return Err(Error::UnsupportedInstruction(format!(
+                "{} on a scalable vector",
+                name
+            )))
+        },
+        Type::VectorType { num_elements, .. } => {
+            // we must not let the operation carry across elements, so we
+            // compute the result element-wise too, not just the overflow flags
+            let num_elements: u32 = (*num_elements).try_into().unwrap();
+            let result = binary_on_vector(&arg0, &arg1, num_elements, op)?;
+            let overflow = binary_on_vector(&arg0, &arg1, num_elements, overflow_op)?;
+            assert_eq!(overflow.get_width(), num_elements);
+            (result, overflow)
+        },
+        _ => {
+            return Err(Error::UnsupportedInstruction(format!(
+                "{} with argument type {:?}",
+                name, argty
+            )))
+        },
+    };
 
     Ok(ReturnValue::Return(overflow.concat(&result)))
 }
@@ -685,6 +732,288 @@ pub fn symex_cttz<'p, B: Backend>(
     }
 }
 
+pub fn symex_ctpop<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 1);
+    let arg = &call.get_arguments()[0].0;
+    let argty = state.type_of(arg);
+
+    let arg = state.operand_to_bv(arg)?;
+    match argty.as_ref() {
+        Type::IntegerType { bits } => {
+            assert_eq!(arg.get_width(), *bits);
+            Ok(ReturnValue::Return(ctpop(&arg)))
+        },
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => Err(Error::UnsupportedInstruction(
+            "ctpop on a scalable vector".into(),
+        )),
+        Type::VectorType { num_elements, .. } => {
+            let final_bv = unary_on_vector(&arg, (*num_elements).try_into().unwrap(), |element| {
+                Ok(ctpop(element))
+            })?;
+            Ok(ReturnValue::Return(final_bv))
+        },
+        _ => Err(Error::UnsupportedInstruction(format!(
+            "llvm.ctpop with argument type {:?}",
+            argty
+        ))),
+    }
+}
+
+/// Count the number of 1 bits in `bv`. The result has the same width as `bv`.
+fn ctpop<V: BV>(bv: &V) -> V {
+    // Boolector doesn't provide a popcount operation, so we just sum up the
+    // individual bits, each zero-extended to the full width
+    let width = bv.get_width();
+    if width == 1 {
+        // the popcount of a single bit is just that bit
+        return bv.clone();
+    }
+    let bit = |i| bv.slice(i, i).zext(width - 1);
+    (1 .. width).fold(bit(0), |sum, i| sum.add(&bit(i)))
+}
+
+pub fn symex_bitreverse<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 1);
+    let arg = &call.get_arguments()[0].0;
+    let argty = state.type_of(arg);
+
+    let arg = state.operand_to_bv(arg)?;
+    match argty.as_ref() {
+        Type::IntegerType { bits } => {
+            assert_eq!(arg.get_width(), *bits);
+            Ok(ReturnValue::Return(bitreverse(&arg)))
+        },
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => Err(Error::UnsupportedInstruction(
+            "bitreverse on a scalable vector".into(),
+        )),
+        Type::VectorType { num_elements, .. } => {
+            // reverse the bits within each element, not across the whole vector
+            let final_bv = unary_on_vector(&arg, (*num_elements).try_into().unwrap(), |element| {
+                Ok(bitreverse(element))
+            })?;
+            Ok(ReturnValue::Return(final_bv))
+        },
+        _ => Err(Error::UnsupportedInstruction(format!(
+            "llvm.bitreverse with argument type {:?}",
+            argty
+        ))),
+    }
+}
+
+/// Reverse the order of the bits in `bv`
+fn bitreverse<V: BV>(bv: &V) -> V {
+    // bit 0 of the input becomes the most significant bit of the output, and so on
+    (1 .. bv.get_width()).fold(bv.slice(0, 0), |reversed, i| {
+        reversed.concat(&bv.slice(i, i))
+    })
+}
+
+pub fn symex_fshl<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_funnel_shift(state, call, ShiftDirection::Left)
+}
+
+pub fn symex_fshr<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_funnel_shift(state, call, ShiftDirection::Right)
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum ShiftDirection {
+    Left,
+    Right,
+}
+
+fn symex_funnel_shift<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+    direction: ShiftDirection,
+) -> Result<ReturnValue<B::BV>> {
+    let name = match direction {
+        ShiftDirection::Left => "llvm.fshl",
+        ShiftDirection::Right => "llvm.fshr",
+    };
+    assert_eq!(call.get_arguments().len(), 3);
+    let hi = &call.get_arguments()[0].0;
+    let lo = &call.get_arguments()[1].0;
+    let amount = &call.get_arguments()[2].0;
+    let argty = state.type_of(hi);
+    if state.type_of(lo) != argty || state.type_of(amount) != argty {
+        return Err(Error::OtherError(format!(
+            "Expected all three {} arguments to be the same type",
+            name
+        )));
+    }
+
+    let hi = state.operand_to_bv(hi)?;
+    let lo = state.operand_to_bv(lo)?;
+    let amount = state.operand_to_bv(amount)?;
+    match argty.as_ref() {
+        Type::IntegerType { bits } => {
+            assert_eq!(hi.get_width(), *bits);
+            let result = funnel_shift(&hi, &lo, &amount, direction);
+            Ok(ReturnValue::Return(result))
+        },
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => Err(Error::UnsupportedInstruction(format!(
+            "{} on a scalable vector",
+            name
+        ))),
+        Type::VectorType { num_elements, .. } => {
+            // perform the funnel shift separately on each element
+            let num_elements: u32 = (*num_elements).try_into().unwrap();
+            let el_size = hi.get_width() / num_elements;
+            let shift_element = |i: u32| {
+                let element = |v: &B::BV| v.slice((i + 1) * el_size - 1, i * el_size);
+                funnel_shift(&element(&hi), &element(&lo), &element(&amount), direction)
+            };
+            let final_bv =
+                (1 .. num_elements).fold(shift_element(0), |acc, i| shift_element(i).concat(&acc));
+            Ok(ReturnValue::Return(final_bv))
+        },
+        _ => Err(Error::UnsupportedInstruction(format!(
+            "{} with argument type {:?}",
+            name, argty
+        ))),
+    }
+}
+
+/// Concatenate `hi` and `lo`, shift the result in the given direction by
+/// `amount` modulo the width of `hi`, and then extract the most-significant
+/// (for left shifts) or least-significant (for right shifts) half.
+/// This is the behavior of LLVM's `fshl` and `fshr` intrinsics.
+fn funnel_shift<V: BV>(hi: &V, lo: &V, amount: &V, direction: ShiftDirection) -> V {
+    let width = hi.get_width();
+    let amount = amount
+        .urem(&V::from_u32(hi.get_solver(), width, width))
+        .zext(width); // we shift the double-width concatenation, so we need a double-width shift amount
+    let concatenated = hi.concat(lo);
+    match direction {
+        ShiftDirection::Left => concatenated.sll(&amount).slice(2 * width - 1, width),
+        ShiftDirection::Right => concatenated.srl(&amount).slice(width - 1, 0),
+    }
+}
+
+pub fn symex_abs<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 2);
+    // we ignore the second argument (`is_int_min_poison`): our BVs are never
+    // poison, so we always return INT_MIN for an INT_MIN input
+    let arg = &call.get_arguments()[0].0;
+    let argty = state.type_of(arg);
+
+    let arg = state.operand_to_bv(arg)?;
+    match argty.as_ref() {
+        Type::IntegerType { bits } => {
+            assert_eq!(arg.get_width(), *bits);
+            Ok(ReturnValue::Return(abs(&arg)))
+        },
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => Err(Error::UnsupportedInstruction(
+            "abs on a scalable vector".into(),
+        )),
+        Type::VectorType { num_elements, .. } => {
+            let final_bv = unary_on_vector(&arg, (*num_elements).try_into().unwrap(), |element| {
+                Ok(abs(element))
+            })?;
+            Ok(ReturnValue::Return(final_bv))
+        },
+        _ => Err(Error::UnsupportedInstruction(format!(
+            "llvm.abs with argument type {:?}",
+            argty
+        ))),
+    }
+}
+
+/// Two's-complement absolute value of `bv`
+fn abs<V: BV>(bv: &V) -> V {
+    let zero = V::zero(bv.get_solver(), bv.get_width());
+    bv.slt(&zero).cond_bv(&bv.neg(), bv)
+}
+
+pub fn symex_smax<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_minmax(state, call, "llvm.smax", |a, b| a.sgt(b).cond_bv(a, b))
+}
+
+pub fn symex_smin<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_minmax(state, call, "llvm.smin", |a, b| a.slt(b).cond_bv(a, b))
+}
+
+pub fn symex_umax<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_minmax(state, call, "llvm.umax", |a, b| a.ugt(b).cond_bv(a, b))
+}
+
+pub fn symex_umin<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    symex_minmax(state, call, "llvm.umin", |a, b| a.ult(b).cond_bv(a, b))
+}
+
+/// Shared implementation of the `smax`, `smin`, `umax`, and `umin`
+/// intrinsics, where `op` computes the result for scalar operands
+fn symex_minmax<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &dyn IsCall,
+    name: &str,
+    op: impl for<'a> Fn(&'a B::BV, &'a B::BV) -> B::BV,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 2);
+    let arg0 = &call.get_arguments()[0].0;
+    let arg1 = &call.get_arguments()[1].0;
+    let argty = state.type_of(arg0);
+    if state.type_of(arg1) != argty {
+        return Err(Error::OtherError(format!(
+            "{}: expected arguments to be of the same type, but got types {:?} and {:?}",
+            name,
+            argty,
+            state.type_of(arg1)
+        )));
+    }
+
+    let arg0 = state.operand_to_bv(arg0)?;
+    let arg1 = state.operand_to_bv(arg1)?;
+    match argty.as_ref() {
+        Type::IntegerType { .. } => Ok(ReturnValue::Return(op(&arg0, &arg1))),
+        #[cfg(feature = "llvm-11-or-greater")]
+        Type::VectorType { scalable: true, .. } => Err(Error::UnsupportedInstruction(format!(
+            "{} on a scalable vector",
+            name
+        ))),
+        Type::VectorType { num_elements, .. } => {
+            let final_bv = binary_on_vector(&arg0, &arg1, (*num_elements).try_into().unwrap(), op)?;
+            Ok(ReturnValue::Return(final_bv))
+        },
+        _ => Err(Error::UnsupportedInstruction(format!(
+            "{} with argument type {:?}",
+            name, argty
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -705,11 +1034,23 @@ mod tests {
     }
 
     impl DummyCall {
+        fn new_onearg_call(arg0: Operand) -> Self {
+            Self {
+                args: vec![(arg0, vec![])],
+            }
+        }
+
         fn new_twoarg_call(arg0: Operand, arg1: Operand) -> Self {
             Self {
                 args: vec![(arg0, vec![]), (arg1, vec![])],
             }
         }
+
+        fn new_threearg_call(arg0: Operand, arg1: Operand, arg2: Operand) -> Self {
+            Self {
+                args: vec![(arg0, vec![]), (arg1, vec![]), (arg2, vec![])],
+            }
+        }
     }
 
     impl Typed for DummyCall {
@@ -823,6 +1164,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn vector_sadd_with_overflow() {
+        let project = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&project, "test_func");
+
+        let int = |value: u64| ConstantRef::new(Constant::Int { bits: 8, value });
+        let vec0 = constant_operand(Constant::Vector(vec![int(4), int(64), int(0x80), int(1)]));
+        let vec1 = constant_operand(Constant::Vector(vec![
+            int(100),
+            int(100),
+            int(0xFF),
+            int(1),
+        ]));
+
+        // <4 x i8> results followed by <4 x i1> overflow flags; only the
+        // middle two lanes overflow (64 + 100, and -128 + -1)
+        let call = DummyCall::new_twoarg_call(vec0, vec1);
+        match symex_sadd_with_overflow(&mut state, &call).unwrap() {
+            ReturnValue::Return(bv) => {
+                assert_eq!(bv.get_width(), 36);
+                let result = bv.slice(31, 0).as_u64().unwrap();
+                let overflow = bv.slice(35, 32).as_u64().unwrap();
+                assert_eq!(result, 0x02_7F_A4_68);
+                assert_eq!(overflow, 0b0110);
+            },
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+    }
+
     #[test]
     fn usubs() {
         let project = blank_project(
@@ -1114,4 +1487,277 @@ mod tests {
         // 8-bit cttz(0xF1) = 0
         test_cttz(&mut state, 8, 0xF1, 0);
     }
+
+    fn test_ctpop<'p>(state: &mut State<'p, DefaultBackend>, width: u32, input: u32, output: u32) {
+        let call = DummyCall::new_onearg_call(constant_operand(Constant::Int {
+            bits: width,
+            value: input.into(),
+        }));
+        match symex_ctpop(state, &call).unwrap() {
+            ReturnValue::Return(bv) => {
+                assert_eq!(bv.get_width(), width);
+                let outval = bv.as_u64().unwrap();
+                assert_eq!(
+                    outval,
+                    output.into(),
+                    "Expected {}-bit ctpop({:#x}) = {}, got {}",
+                    width,
+                    input,
+                    output,
+                    outval
+                );
+            },
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+    }
+
+    #[test]
+    fn ctpop() {
+        let proj = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&proj, "test_func");
+
+        // 1-bit ctpop
+        test_ctpop(&mut state, 1, 0, 0);
+        test_ctpop(&mut state, 1, 1, 1);
+
+        // ctpop(0) = 0
+        test_ctpop(&mut state, 32, 0, 0);
+        test_ctpop(&mut state, 16, 0, 0);
+        test_ctpop(&mut state, 8, 0, 0);
+
+        // ctpop(0x0000_000F) = 4
+        test_ctpop(&mut state, 32, 0x0000_000F, 4);
+        test_ctpop(&mut state, 16, 0x000F, 4);
+        test_ctpop(&mut state, 8, 0x0F, 4);
+
+        // 32-bit ctpop(0x5555_AAAA) = 16
+        test_ctpop(&mut state, 32, 0x5555_AAAA, 16);
+        // 16-bit ctpop(0x8001) = 2
+        test_ctpop(&mut state, 16, 0x8001, 2);
+        // 8-bit ctpop(0x37) = 5
+        test_ctpop(&mut state, 8, 0x37, 5);
+
+        // all ones
+        test_ctpop(&mut state, 32, 0xFFFF_FFFF, 32);
+        test_ctpop(&mut state, 16, 0xFFFF, 16);
+        test_ctpop(&mut state, 8, 0xFF, 8);
+    }
+
+    #[test]
+    fn bitreverse() -> Result<()> {
+        let proj = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&proj, "test_func");
+
+        // 8-bit bitreverse(0x01) = 0x80
+        let call = DummyCall::new_onearg_call(constant_operand(Constant::Int {
+            bits: 8,
+            value: 0x01,
+        }));
+        match symex_bitreverse(&mut state, &call)? {
+            ReturnValue::Return(bv) => assert_eq!(bv.as_u64().unwrap(), 0x80),
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+
+        // 16-bit bitreverse(0x1234) = 0x2C48
+        let call = DummyCall::new_onearg_call(constant_operand(Constant::Int {
+            bits: 16,
+            value: 0x1234,
+        }));
+        match symex_bitreverse(&mut state, &call)? {
+            ReturnValue::Return(bv) => assert_eq!(bv.as_u64().unwrap(), 0x2C48),
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+
+        // the vector form reverses bits within each element:
+        // bitreverse(<i8 0x01, i8 0x0F>) = <i8 0x80, i8 0xF0>
+        let vector = constant_operand(Constant::Vector(vec![
+            ConstantRef::new(Constant::Int {
+                bits: 8,
+                value: 0x01,
+            }),
+            ConstantRef::new(Constant::Int {
+                bits: 8,
+                value: 0x0F,
+            }),
+        ]));
+        let call = DummyCall::new_onearg_call(vector);
+        match symex_bitreverse(&mut state, &call)? {
+            ReturnValue::Return(bv) => {
+                assert_eq!(bv.get_width(), 16);
+                assert_eq!(bv.slice(7, 0).as_u64().unwrap(), 0x80);
+                assert_eq!(bv.slice(15, 8).as_u64().unwrap(), 0xF0);
+            },
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+
+        // bitreverse(bitreverse(x)) == x for all x
+        let x = state.new_bv_with_name(Name::from("x"), 32)?;
+        let roundtrip = super::bitreverse(&super::bitreverse(&x));
+        assert!(state.bvs_must_be_equal(&roundtrip, &x)?);
+        // but bitreverse(x) isn't always x
+        assert!(!state.bvs_must_be_equal(&super::bitreverse(&x), &x)?);
+
+        Ok(())
+    }
+
+    fn test_funnel_shift<'p>(
+        state: &mut State<'p, DefaultBackend>,
+        direction: ShiftDirection,
+        hi: u64,
+        lo: u64,
+        amount: u64,
+        output: u64,
+    ) {
+        let int8 = |value| constant_operand(Constant::Int { bits: 8, value });
+        let call = DummyCall::new_threearg_call(int8(hi), int8(lo), int8(amount));
+        let retval = match direction {
+            ShiftDirection::Left => symex_fshl(state, &call),
+            ShiftDirection::Right => symex_fshr(state, &call),
+        };
+        match retval.unwrap() {
+            ReturnValue::Return(bv) => {
+                let outval = bv.as_u64().unwrap();
+                assert_eq!(
+                    outval, output,
+                    "Expected {:?} funnel shift of ({:#x}, {:#x}) by {} = {:#x}, got {:#x}",
+                    direction, hi, lo, amount, output, outval
+                );
+            },
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+    }
+
+    #[test]
+    fn funnel_shifts() {
+        let proj = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&proj, "test_func");
+
+        // fshl(0x12, 0x34, 4) = 0x23
+        test_funnel_shift(&mut state, ShiftDirection::Left, 0x12, 0x34, 4, 0x23);
+        // fshr(0x12, 0x34, 4) = 0x23
+        test_funnel_shift(&mut state, ShiftDirection::Right, 0x12, 0x34, 4, 0x23);
+        // shift amounts are taken modulo the bitwidth
+        test_funnel_shift(&mut state, ShiftDirection::Left, 0x12, 0x34, 12, 0x23);
+        test_funnel_shift(&mut state, ShiftDirection::Right, 0x12, 0x34, 12, 0x23);
+        // shifting by 0 returns the first argument for fshl, or the second for fshr
+        test_funnel_shift(&mut state, ShiftDirection::Left, 0x12, 0x34, 8, 0x12);
+        test_funnel_shift(&mut state, ShiftDirection::Right, 0x12, 0x34, 0, 0x34);
+        // with both arguments the same, these are rotates
+        test_funnel_shift(&mut state, ShiftDirection::Left, 0x81, 0x81, 1, 0x03);
+        test_funnel_shift(&mut state, ShiftDirection::Right, 0x81, 0x81, 1, 0xC0);
+    }
+
+    #[test]
+    fn abs() {
+        let proj = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&proj, "test_func");
+        let int8 = |value: i8| {
+            constant_operand(Constant::Int {
+                bits: 8,
+                value: value as u8 as u64,
+            })
+        };
+        let poison = constant_operand(Constant::Int { bits: 1, value: 0 });
+
+        let cases: [(i8, i8); 6] = [
+            (5, 5),
+            (-5, 5),
+            (0, 0),
+            (127, 127),
+            (-127, 127),
+            (-128, -128), // abs(INT_MIN) is INT_MIN
+        ];
+        for (input, output) in &cases {
+            let call = DummyCall::new_twoarg_call(int8(*input), poison.clone());
+            match symex_abs(&mut state, &call).unwrap() {
+                ReturnValue::Return(bv) => {
+                    let outval = bv.as_u64().unwrap() as u8 as i8;
+                    assert_eq!(
+                        outval, *output,
+                        "Expected abs({}) = {}, got {}",
+                        input, output, outval
+                    );
+                },
+                ret => panic!("Unexpected return value: {:?}", ret),
+            }
+        }
+
+        // the vector form operates element-wise: abs(<i8 -3, i8 4>) = <i8 3, i8 4>
+        let vector = constant_operand(Constant::Vector(vec![
+            ConstantRef::new(Constant::Int {
+                bits: 8,
+                value: -3_i8 as u8 as u64,
+            }),
+            ConstantRef::new(Constant::Int { bits: 8, value: 4 }),
+        ]));
+        let call = DummyCall::new_twoarg_call(vector, poison);
+        match symex_abs(&mut state, &call).unwrap() {
+            ReturnValue::Return(bv) => {
+                assert_eq!(bv.slice(7, 0).as_u64().unwrap(), 3);
+                assert_eq!(bv.slice(15, 8).as_u64().unwrap(), 4);
+            },
+            ret => panic!("Unexpected return value: {:?}", ret),
+        }
+    }
+
+    #[test]
+    fn minmax() {
+        let proj = blank_project(
+            "test_mod",
+            blank_function("test_func", vec![Name::from("test_bb")]),
+        );
+        let mut state = blank_state(&proj, "test_func");
+
+        // 0xFF is -1 when signed, or 255 when unsigned
+        let one = constant_operand(Constant::Int { bits: 8, value: 1 });
+        let ff = constant_operand(Constant::Int {
+            bits: 8,
+            value: 0xFF,
+        });
+
+        let call = DummyCall::new_twoarg_call(one.clone(), ff.clone());
+        let result =
+            |retval: Result<ReturnValue<<DefaultBackend as Backend>::BV>>| match retval.unwrap() {
+                ReturnValue::Return(bv) => bv.as_u64().unwrap(),
+                ret => panic!("Unexpected return value: {:?}", ret),
+            };
+        assert_eq!(result(symex_smax(&mut state, &call)), 1);
+        assert_eq!(result(symex_smin(&mut state, &call)), 0xFF);
+        assert_eq!(result(symex_umax(&mut state, &call)), 0xFF);
+        assert_eq!(result(symex_umin(&mut state, &call)), 1);
+
+        // vectors are handled element-wise:
+        // smax(<i8 1, i8 -1>, <i8 -1, i8 1>) = <i8 1, i8 1>
+        let vec0 = constant_operand(Constant::Vector(vec![
+            ConstantRef::new(Constant::Int { bits: 8, value: 1 }),
+            ConstantRef::new(Constant::Int {
+                bits: 8,
+                value: 0xFF,
+            }),
+        ]));
+        let vec1 = constant_operand(Constant::Vector(vec![
+            ConstantRef::new(Constant::Int {
+                bits: 8,
+                value: 0xFF,
+            }),
+            ConstantRef::new(Constant::Int { bits: 8, value: 1 }),
+        ]));
+        let call = DummyCall::new_twoarg_call(vec0, vec1);
+        assert_eq!(result(symex_smax(&mut state, &call)), 0x0101);
+        assert_eq!(result(symex_umin(&mut state, &call)), 0x0101);
+        assert_eq!(result(symex_smin(&mut state, &call)), 0xFFFF);
+        assert_eq!(result(symex_umax(&mut state, &call)), 0xFFFF);
+    }
 }
@@ -0,0 +1,80 @@
+//! Default hook for functions modeled as RNGs (see `Config.rng_model`)
+
+use crate::backend::Backend;
+use crate::error::*;
+use crate::function_hooks::IsCall;
+use crate::global_allocations::Callable;
+use crate::return_value::*;
+use crate::solver_utils::PossibleSolutions;
+use crate::state::State;
+use either::Either;
+use llvm_ir::*;
+
+/// Returns a fresh unconstrained value, recorded as the next value drawn from
+/// the RNG stream which `Config.rng_model` associates with the called function.
+pub fn rng_draw_hook<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    let funcname: String = match call.get_called_func() {
+        Either::Right(Operand::ConstantOperand(cref)) => match cref.as_ref() {
+            Constant::GlobalReference {
+                name: Name::Name(name),
+                ..
+            } => name.to_string(),
+            c => {
+                return Err(Error::OtherError(format!(
+                    "rng_draw_hook: unexpected called function {:?}",
+                    c
+                )))
+            },
+        },
+        Either::Right(operand) => {
+            let bv = state.operand_to_bv(operand)?;
+            match state.interpret_as_function_ptr(bv, 1)? {
+                PossibleSolutions::Exactly(v) => match v.into_iter().next() {
+                    Some(Callable::LLVMFunction(f)) => f.name.clone(),
+                    _ => {
+                        return Err(Error::OtherError(
+                            "rng_draw_hook: failed to resolve the called function pointer".into(),
+                        ))
+                    },
+                },
+                PossibleSolutions::AtLeast(_) => {
+                    return Err(Error::OtherError(
+                        "rng_draw_hook: called function pointer has multiple possible targets"
+                            .into(),
+                    ))
+                },
+            }
+        },
+        Either::Left(_) => {
+            return Err(Error::OtherError(
+                "rng_draw_hook: can't be used for inline assembly".into(),
+            ))
+        },
+    };
+    let stream = state
+        .config
+        .rng_model
+        .get(&funcname)
+        .cloned()
+        .ok_or_else(|| {
+            Error::OtherError(format!(
+                "rng_draw_hook: function {:?} has no RNG stream in the Config",
+                funcname
+            ))
+        })?;
+    let width = match state.type_of(call).as_ref() {
+        Type::VoidType => {
+            return Err(Error::OtherError(format!(
+                "rng_draw_hook: function {:?} is void-typed, so it can't return a nondeterministic value",
+                funcname
+            )))
+        },
+        ty => state.size_in_bits(ty).ok_or_else(|| {
+            Error::OtherError("Call return type is an opaque named struct".into())
+        })?,
+    };
+    Ok(ReturnValue::Return(state.new_nondet_value(&stream, width)))
+}
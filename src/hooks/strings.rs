@@ -0,0 +1,121 @@
+//! Hooks for libc string functions (see `Config::add_libc_string_hooks()`)
+
+use crate::backend::Backend;
+use crate::error::*;
+use crate::function_hooks::IsCall;
+use crate::hook_utils;
+use crate::return_value::*;
+use crate::state::State;
+use llvm_ir::*;
+
+pub fn strlen_hook<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 1);
+    let s = &call.get_arguments()[0].0;
+    match state.type_of(s).as_ref() {
+        Type::PointerType { .. } => {},
+        ty => {
+            return Err(Error::OtherError(format!(
+                "strlen_hook: expected argument to be a pointer type, but got {:?}",
+                ty
+            )))
+        },
+    };
+    match state.type_of(call).as_ref() {
+        Type::IntegerType { .. } => {},
+        ty => {
+            return Err(Error::OtherError(format!(
+                "strlen_hook: expected return type to be an integer type, but got {:?}",
+                ty
+            )))
+        },
+    };
+
+    let len = hook_utils::strlen(state, s)?;
+    Ok(ReturnValue::Return(len))
+}
+
+pub fn memcmp_hook<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 3);
+    let a = &call.get_arguments()[0].0;
+    let b = &call.get_arguments()[1].0;
+    let num_bytes = &call.get_arguments()[2].0;
+    check_comparison_types(state, call, "memcmp_hook", a, b, Some(num_bytes))?;
+
+    let result = hook_utils::memcmp(state, a, b, num_bytes)?;
+    Ok(ReturnValue::Return(result))
+}
+
+pub fn strcmp_hook<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 2);
+    let a = &call.get_arguments()[0].0;
+    let b = &call.get_arguments()[1].0;
+    check_comparison_types(state, call, "strcmp_hook", a, b, None)?;
+
+    let result = hook_utils::strcmp(state, a, b)?;
+    Ok(ReturnValue::Return(result))
+}
+
+pub fn strncmp_hook<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 3);
+    let a = &call.get_arguments()[0].0;
+    let b = &call.get_arguments()[1].0;
+    let num_bytes = &call.get_arguments()[2].0;
+    check_comparison_types(state, call, "strncmp_hook", a, b, Some(num_bytes))?;
+
+    let result = hook_utils::strncmp(state, a, b, num_bytes)?;
+    Ok(ReturnValue::Return(result))
+}
+
+/// Check that the arguments of a call to `memcmp()`, `strcmp()`, or
+/// `strncmp()` are two pointers and (optionally) an integer, and that the
+/// return type is `i32`
+fn check_comparison_types<'p, B: Backend + 'p>(
+    state: &State<'p, B>,
+    call: &'p dyn IsCall,
+    hookname: &str,
+    a: &Operand,
+    b: &Operand,
+    num_bytes: Option<&Operand>,
+) -> Result<()> {
+    for op in &[a, b] {
+        match state.type_of(*op).as_ref() {
+            Type::PointerType { .. } => {},
+            ty => {
+                return Err(Error::OtherError(format!(
+                    "{}: expected buffer arguments to be pointer types, but got {:?}",
+                    hookname, ty
+                )))
+            },
+        };
+    }
+    if let Some(num_bytes) = num_bytes {
+        match state.type_of(num_bytes).as_ref() {
+            Type::IntegerType { .. } => {},
+            ty => {
+                return Err(Error::OtherError(format!(
+                    "{}: expected length argument to be an integer type, but got {:?}",
+                    hookname, ty
+                )))
+            },
+        };
+    }
+    match state.type_of(call).as_ref() {
+        Type::IntegerType { bits: 32 } => Ok(()),
+        ty => Err(Error::OtherError(format!(
+            "{}: expected return type to be i32, but got {:?}",
+            hookname, ty
+        ))),
+    }
+}
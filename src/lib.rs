@@ -9,10 +9,9 @@
 #![doc(html_root_url = "https://docs.rs/haybale/")]
 
 use llvm_ir::Type;
-use std::collections::HashSet;
 
 mod project;
-pub use project::Project;
+pub use project::{FieldLayout, FunctionParameter, FunctionSignature, Project, StructLayout};
 
 mod symex;
 pub use symex::*;
@@ -27,17 +26,23 @@ mod parameter_val;
 pub use parameter_val::ParameterVal;
 
 mod return_value;
-pub use return_value::ReturnValue;
+pub use return_value::{AsmReturnValue, PathOutcome, ReturnValue};
+
+mod demangling;
+pub use demangling::DemangleScheme;
+
+mod coverage;
+pub use coverage::{CoverageReport, FunctionCoverage};
 
 mod alloc;
 pub mod alloc_utils;
 pub mod backend;
 pub mod callbacks;
 pub mod cell_memory;
-mod demangling;
 mod double_keyed_map;
 pub mod function_hooks;
 mod global_allocations;
+pub use global_allocations::Callable;
 pub mod hook_utils;
 mod hooks;
 pub mod simple_memory;
@@ -46,10 +51,11 @@ mod state;
 pub use state::get_path_length;
 mod varmap;
 pub mod watchpoints;
+mod written_bytes;
 
 use backend::*;
 use itertools::Itertools;
-use solver_utils::PossibleSolutions;
+use solver_utils::{IndexSet, PossibleSolutions};
 
 #[cfg(test)]
 mod test_utils;
@@ -57,14 +63,29 @@ mod test_utils;
 /// A simple enum describing either an integer value or a pointer
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum SolutionValue {
+    /// A 1-bit integer (`i1`)
+    Bool(bool),
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    /// An integer of any other width, up to 128 bits, e.g. `i24` or `i128`.
+    /// The `value` is zero-extended to 128 bits.
+    Int {
+        bits: u32,
+        value: u128,
+    },
     Ptr(u64),
 }
 
 impl SolutionValue {
+    pub fn unwrap_to_bool(self) -> bool {
+        match self {
+            SolutionValue::Bool(b) => b,
+            _ => panic!("unwrap_to_bool on {:?}", self),
+        }
+    }
+
     pub fn unwrap_to_i8(self) -> i8 {
         match self {
             SolutionValue::I8(i) => i,
@@ -93,6 +114,14 @@ impl SolutionValue {
         }
     }
 
+    /// Get the `value` of a `SolutionValue::Int`
+    pub fn unwrap_to_int(self) -> u128 {
+        match self {
+            SolutionValue::Int { value, .. } => value,
+            _ => panic!("unwrap_to_int on {:?}", self),
+        }
+    }
+
     pub fn unwrap_to_ptr(self) -> u64 {
         match self {
             SolutionValue::Ptr(u) => u,
@@ -120,7 +149,8 @@ impl SolutionValue {
 /// `ParameterVal::Unconstrained` entries.
 ///
 /// Returns `Ok(None)` if there are no values of the inputs such that the
-/// function returns zero.
+/// function returns zero. See [`FindZeroError`](enum.FindZeroError.html) for
+/// the possible errors.
 ///
 /// Note: `find_zero_of_func()` may be of some use itself, but also serves as an
 /// example of how you can use the other public functions in the crate.
@@ -129,18 +159,18 @@ pub fn find_zero_of_func<'p>(
     project: &'p Project,
     config: Config<'p, DefaultBackend>,
     params: Option<Vec<ParameterVal>>,
-) -> std::result::Result<Option<Vec<SolutionValue>>, String> {
+) -> std::result::Result<Option<Vec<SolutionValue>>, FindZeroError> {
     let mut em: ExecutionManager<DefaultBackend> =
-        symex_function(funcname, project, config, params).unwrap();
+        symex_function(funcname, project, config, params).map_err(FindZeroError::Setup)?;
 
     let returnwidth = match em.func().return_type.as_ref() {
         Type::VoidType => {
-            return Err("find_zero_of_func: function has void type".into());
+            return Err(FindZeroError::VoidReturnType);
         },
         ty => {
             let width = project
                 .size_in_bits(&ty)
-                .expect("Function return type shouldn't be an opaque struct type");
+                .ok_or(FindZeroError::OpaqueReturnType)?;
             assert_ne!(width, 0, "Function return type has width 0 bits but isn't void type"); // void type was handled above
             width
         },
@@ -155,13 +185,21 @@ pub fn find_zero_of_func<'p>(
             Ok(ReturnValue::Return(bvretval)) => {
                 let state = em.mut_state();
                 bvretval._eq(&zero).assert();
-                if state.sat()? {
+                if state.sat().map_err(FindZeroError::Solver)? {
                     found = true;
                     break;
                 }
             },
-            Err(Error::LoopBoundExceeded(_)) => continue, // ignore paths that exceed the loop bound, keep looking
-            Err(e) => return Err(em.state().full_error_message_with_context(e)),
+            Err(Error::LoopBoundExceeded { .. }) => continue, // ignore paths that exceed the loop bound, keep looking
+            Err(Error::RecursionBoundExceeded(_)) => continue, // likewise for the recursion bound
+            Err(Error::InstructionBudgetExceeded(_)) => continue, // and the per-path instruction budget
+            Err(error) => {
+                let message = em.state().full_error_message_with_context(error.clone());
+                return Err(FindZeroError::Path {
+                    error: Box::new(error),
+                    message,
+                });
+            },
         }
     }
 
@@ -169,27 +207,50 @@ pub fn find_zero_of_func<'p>(
     let func = em.func();
     let state = em.mut_state();
     if found {
-        // in this case state.sat() must have passed
+        // in this case state.sat() must have passed.
+        // we solve for all the parameters at once, so that their values are
+        // consistent with each other
+        let solutions = state
+            .get_a_solution_for_bvs(&param_bvs.iter().collect::<Vec<_>>())
+            .map_err(FindZeroError::Solver)?
+            .expect("since state.sat() passed, expected a solution for the params");
         Ok(Some(
             func.parameters
                 .iter()
-                .zip_eq(param_bvs.iter())
-                .map(|(p, bv)| {
-                    let param_as_u64 = state
-                        .get_a_solution_for_bv(bv)?
-                        .expect("since state.sat() passed, expected a solution for each var")
-                        .as_u64()
-                        .expect("parameter more than 64 bits wide");
-                    Ok(match p.ty.as_ref() {
-                        Type::IntegerType { bits: 8 } => SolutionValue::I8(param_as_u64 as i8),
-                        Type::IntegerType { bits: 16 } => SolutionValue::I16(param_as_u64 as i16),
-                        Type::IntegerType { bits: 32 } => SolutionValue::I32(param_as_u64 as i32),
-                        Type::IntegerType { bits: 64 } => SolutionValue::I64(param_as_u64 as i64),
-                        Type::PointerType { .. } => SolutionValue::Ptr(param_as_u64),
+                .zip_eq(solutions.iter())
+                .map(|(p, solution)| {
+                    let bits = match p.ty.as_ref() {
+                        Type::IntegerType { bits } => *bits,
+                        Type::PointerType { .. } => {
+                            let ptr = solution.as_u64().expect("pointer more than 64 bits wide");
+                            return SolutionValue::Ptr(ptr);
+                        },
                         ty => unimplemented!("Function parameter with type {:?}", ty),
-                    })
+                    };
+                    if bits > 128 {
+                        unimplemented!("Function parameter with type {:?}", p.ty)
+                    } else if bits > 64 {
+                        // `as_u64()` won't work, so parse the binary string instead,
+                        // treating any don't-care bits ('x') as 0
+                        let binary_str = solution.as_01x_str().replace('x', "0");
+                        let value = u128::from_str_radix(&binary_str, 2)
+                            .expect("solution should be a binary string");
+                        return SolutionValue::Int { bits, value };
+                    }
+                    let param_as_u64 = solution.as_u64().unwrap();
+                    match bits {
+                        1 => SolutionValue::Bool(param_as_u64 != 0),
+                        8 => SolutionValue::I8(param_as_u64 as i8),
+                        16 => SolutionValue::I16(param_as_u64 as i16),
+                        32 => SolutionValue::I32(param_as_u64 as i32),
+                        64 => SolutionValue::I64(param_as_u64 as i64),
+                        _ => SolutionValue::Int {
+                            bits,
+                            value: u128::from(param_as_u64),
+                        },
+                    }
                 })
-                .collect::<Result<_>>()?,
+                .collect(),
         ))
     } else {
         Ok(None)
@@ -229,9 +290,9 @@ pub fn find_zero_of_func<'p>(
 /// If there are more than `n` possible solutions, this returns a
 /// `PossibleSolutions::AtLeast` containing at least `n+1` solutions.
 ///
-/// Note: `get_possible_return_values_of_func()` may be of some use itself, but
-/// also serves as an example of how you can use the other public functions in
-/// the crate.
+/// To do this with an existing `ExecutionManager` (e.g., one whose initial
+/// state has been customized with `mut_state()`), see
+/// [`ExecutionManager::possible_return_values()`](struct.ExecutionManager.html#method.possible_return_values).
 pub fn get_possible_return_values_of_func<'p>(
     funcname: &str,
     project: &'p Project,
@@ -242,108 +303,90 @@ pub fn get_possible_return_values_of_func<'p>(
 ) -> PossibleSolutions<ReturnValue<u64>> {
     let mut em: ExecutionManager<DefaultBackend> =
         symex_function(funcname, project, config, params).unwrap();
+    em.possible_return_values(n, thrown_size)
+}
 
-    let return_width = project
-        .size_in_bits(&em.func().return_type)
-        .expect("Function return type shouldn't be opaque struct type");
-    let mut candidate_values = HashSet::<ReturnValue<u64>>::new();
-    let mut have_throw = false; // is there at least one `ReturnValue::Throw` in the `candidate_values`
-    while let Some(bvretval) = em.next() {
-        match bvretval {
-            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
-            Ok(ReturnValue::ReturnVoid) => {
-                candidate_values.insert(ReturnValue::ReturnVoid);
-                if candidate_values.len() > n {
-                    break;
-                }
-            },
-            Ok(ReturnValue::Abort) => {
-                candidate_values.insert(ReturnValue::Abort);
-                if candidate_values.len() > n {
-                    break;
-                }
-            },
-            Ok(ReturnValue::Return(bvretval)) => {
-                assert_eq!(bvretval.get_width(), return_width);
-                let state = em.mut_state();
-                // rule out all the returned values we already have - we're interested in new values
-                for candidate in candidate_values.iter() {
-                    if let ReturnValue::Return(candidate) = candidate {
-                        bvretval
-                            ._ne(&state.bv_from_u64(*candidate, return_width))
-                            .assert();
-                    }
-                }
-                match state.get_possible_solutions_for_bv(&bvretval, n).unwrap() {
-                    PossibleSolutions::Exactly(v) => {
-                        candidate_values.extend(
-                            v.iter()
-                                .map(|bvsol| ReturnValue::Return(bvsol.as_u64().unwrap())),
-                        );
-                        if candidate_values.len() > n {
-                            break;
-                        }
-                    },
-                    PossibleSolutions::AtLeast(v) => {
-                        candidate_values.extend(
-                            v.iter()
-                                .map(|bvsol| ReturnValue::Return(bvsol.as_u64().unwrap())),
-                        );
-                        break; // the total must be over n at this point
-                    },
-                };
-            },
-            Ok(ReturnValue::Throw(bvptr)) => {
-                let state = em.mut_state();
-                match thrown_size {
-                    None => {
-                        if !have_throw {
-                            candidate_values.insert(ReturnValue::Throw(bvptr.as_u64().unwrap()));
-                            have_throw = true;
-                            if candidate_values.len() > n {
-                                break;
-                            }
-                        }
-                    },
-                    Some(thrown_size) => {
-                        let thrown_value = state.read(&bvptr, thrown_size).unwrap();
-                        // rule out all the thrown values we already have - we're interested in new values
-                        for candidate in candidate_values.iter() {
-                            if let ReturnValue::Throw(candidate) = candidate {
-                                thrown_value
-                                    ._ne(&state.bv_from_u64(*candidate, thrown_size))
-                                    .assert();
-                            }
-                        }
-                        match state
-                            .get_possible_solutions_for_bv(&thrown_value, n)
-                            .unwrap()
-                        {
-                            PossibleSolutions::Exactly(v) => {
-                                candidate_values.extend(
-                                    v.iter()
-                                        .map(|bvsol| ReturnValue::Throw(bvsol.as_u64().unwrap())),
-                                );
-                                if candidate_values.len() > n {
-                                    break;
-                                }
-                            },
-                            PossibleSolutions::AtLeast(v) => {
-                                candidate_values.extend(
-                                    v.iter()
-                                        .map(|bvsol| ReturnValue::Throw(bvsol.as_u64().unwrap())),
-                                );
-                                break; // the total must be over n at this point
-                            },
-                        }
-                    },
-                }
-            },
-        }
-    }
-    if candidate_values.len() > n {
-        PossibleSolutions::AtLeast(candidate_values)
-    } else {
-        PossibleSolutions::Exactly(candidate_values)
+/// Like [`get_possible_return_values_of_func()`](fn.get_possible_return_values_of_func.html),
+/// but with separate budgets for distinct returned values and distinct thrown
+/// values, so that a function with many possible return values doesn't crowd
+/// out the discovery of thrown values (or vice versa).
+///
+/// `max_returns`: Maximum number of distinct non-throw results
+/// (`ReturnValue::Return`, `ReturnValue::ReturnVoid`, and `ReturnValue::Abort`)
+/// to check for.
+///
+/// `max_throws`: Maximum number of distinct `ReturnValue::Throw`s to check for.
+/// As for `get_possible_return_values_of_func()`, if `thrown_size` is `None`,
+/// at most one `ReturnValue::Throw` is returned.
+///
+/// If there are more than `max_returns` non-throw results, or more than
+/// `max_throws` thrown values, this returns a `PossibleSolutions::AtLeast`.
+/// Exploration continues until both budgets are exceeded (or all paths have
+/// been explored), so exceeding one budget doesn't prevent finding values of
+/// the other kind.
+///
+/// To do this with an existing `ExecutionManager`, see
+/// [`ExecutionManager::possible_returns_and_throws()`](struct.ExecutionManager.html#method.possible_returns_and_throws).
+pub fn get_possible_returns_and_throws_of_func<'p>(
+    funcname: &str,
+    project: &'p Project,
+    config: Config<'p, DefaultBackend>,
+    params: Option<Vec<ParameterVal>>,
+    thrown_size: Option<u32>,
+    max_returns: usize,
+    max_throws: usize,
+) -> PossibleSolutions<ReturnValue<u64>> {
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, project, config, params).unwrap();
+    em.possible_returns_and_throws(thrown_size, max_returns, max_throws)
+}
+
+/// Assert that the set of possible return values of a function, for the given
+/// argument values, is exactly `expected`. Panics with a message describing
+/// the missing and unexpected values if it is not.
+///
+/// This is intended for use in tests (e.g., downstream users testing their
+/// own bitcode). Arguments are as for
+/// [`get_possible_return_values_of_func()`](fn.get_possible_return_values_of_func.html);
+/// in particular, `thrown_size` must be provided if `expected` contains any
+/// `ReturnValue::Throw`s of specific values.
+///
+/// The order of `expected` doesn't matter, and duplicates are ignored.
+pub fn assert_possible_returns<'p>(
+    funcname: &str,
+    project: &'p Project,
+    config: Config<'p, DefaultBackend>,
+    params: Option<Vec<ParameterVal>>,
+    thrown_size: Option<u32>,
+    expected: &[ReturnValue<u64>],
+) {
+    let expected: IndexSet<ReturnValue<u64>> = expected.iter().copied().collect();
+    match get_possible_return_values_of_func(
+        funcname,
+        project,
+        config,
+        params,
+        thrown_size,
+        expected.len(),
+    ) {
+        PossibleSolutions::Exactly(actual) => {
+            if actual != expected {
+                panic!(
+                    "Possible return values of {:?} didn't match the expected values.\n  Expected but not possible: {:?}\n  Possible but not expected: {:?}",
+                    funcname,
+                    expected.difference(&actual).collect::<Vec<_>>(),
+                    actual.difference(&expected).collect::<Vec<_>>(),
+                );
+            }
+        },
+        PossibleSolutions::AtLeast(actual) => {
+            panic!(
+                "{:?} has more possible return values than the {} expected.\n  Expected but not possible: {:?}\n  Possible but not expected (possibly not all of them): {:?}",
+                funcname,
+                expected.len(),
+                expected.difference(&actual).collect::<Vec<_>>(),
+                actual.difference(&expected).collect::<Vec<_>>(),
+            );
+        },
     }
 }
@@ -16,6 +16,70 @@ pub enum ParameterVal {
     /// memory.
     /// This can only be used for pointer-type parameters.
     PointerToAllocated(u64),
+    /// The parameter will point to a newly allocated C++ polymorphic object
+    /// (e.g., a `this` pointer), whose vtable pointer points to the vtable in
+    /// the global variable with the given name (e.g., `_ZTV8Triangle`). This
+    /// allows virtual calls on the object to resolve to that vtable's methods.
+    /// The rest of the object will be unconstrained.
+    ///
+    /// Following the Itanium C++ ABI, the vtable pointer is placed at offset 0
+    /// in the object, and points to the vtable's address point, i.e., just past
+    /// the offset-to-top and RTTI entries.
+    ///
+    /// The object's size is taken from the parameter's pointee type.
+    /// This can only be used for pointer-type parameters.
+    PolymorphicObject { vtable_global: String },
+    /// The parameter will point to a newly allocated buffer with room for
+    /// `max_len` elements, where the element type is the parameter's pointee
+    /// type. It will not be NULL and will not alias any other allocated
+    /// memory. The buffer's contents will be unconstrained.
+    ///
+    /// If `len_param_index` is given, it is the (0-indexed) position of an
+    /// integer-type parameter holding the buffer's length, in elements. That
+    /// parameter will be constrained to be at most `max_len` (unsigned), and
+    /// the parameter's own `ParameterVal` may constrain it further. In that
+    /// case, if `Config.check_memory_bounds` is enabled, accesses to the buffer
+    /// beyond the (symbolic) length are reported as out-of-bounds, even if they
+    /// are within the `max_len` elements allocated.
+    ///
+    /// This can only be used for pointer-type parameters.
+    Buffer {
+        max_len: usize,
+        len_param_index: Option<usize>,
+    },
+    /// The parameter will point to a newly allocated array of `num_elements`
+    /// elements, each `element_bits` bits wide. It will not be NULL and will
+    /// not alias any other allocated memory.
+    ///
+    /// Each element is initialized with a fresh unconstrained `BV`. These can
+    /// be retrieved with
+    /// [`ExecutionManager::param_array_elements()`](struct.ExecutionManager.html#method.param_array_elements),
+    /// e.g. to constrain them, or to solve for them at the end of a path.
+    ///
+    /// `element_bits` must be a multiple of 8.
+    /// This can only be used for pointer-type parameters.
+    PointerToUnconstrainedArray {
+        element_bits: u32,
+        num_elements: usize,
+    },
+    /// The parameter will be a multiple of the given alignment (in bytes), but
+    /// otherwise be completely unconstrained (could be NULL, point anywhere, or
+    /// alias anything).
+    ///
+    /// `alignment` must be a power of two.
+    /// This can only be used for pointer-type parameters.
+    AlignedPointer { alignment: u64 },
+    /// The parameter will point to a newly allocated C string of at most
+    /// `max_len` characters (not counting the NUL terminator). It will not be
+    /// NULL and will not alias any other allocated memory.
+    ///
+    /// `max_len + 1` bytes are allocated. The string's length is symbolic:
+    /// some byte at index `max_len` or less is NUL, all bytes before it are
+    /// nonzero but otherwise unconstrained, and any bytes after it are
+    /// unconstrained.
+    ///
+    /// This can only be used for pointer-type parameters.
+    PointerToNulTerminatedString { max_len: usize },
 }
 
 impl Default for ParameterVal {
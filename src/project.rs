@@ -1,14 +1,28 @@
 use crate::demangling::try_cpp_demangle;
 use crate::error::Error;
+use either::Either;
+use itertools::Itertools;
 use llvm_ir::module::{GlobalAlias, GlobalVariable};
-use llvm_ir::types::{FPType, NamedStructDef, Type};
-use llvm_ir::{Function, Module};
+use llvm_ir::types::{FPType, NamedStructDef, Type, TypeRef};
+use llvm_ir::{
+    Constant,
+    DebugLoc,
+    Function,
+    HasDebugLoc,
+    Instruction,
+    Module,
+    Name,
+    Operand,
+    Terminator,
+};
 use log::{info, warn};
 use rustc_demangle::demangle;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::fs::DirEntry;
-use std::io;
+use std::io::{self, Write};
 use std::path::Path;
+use std::process::Command;
 
 /// A `Project` is a collection of LLVM code to be explored,
 /// consisting of one or more LLVM modules.
@@ -60,6 +74,59 @@ impl Project {
         })
     }
 
+    /// Construct a new `Project` from a path to a text-format LLVM IR (`.ll`)
+    /// file.
+    ///
+    /// The resulting `Project` is the same as if the file had been assembled
+    /// to bitcode and loaded with `from_bc_path()`, except that the module's
+    /// name is the path to the `.ll` file.
+    ///
+    /// `llvm-ir` can only parse bitcode, so this assembles the file with
+    /// `llvm-as` into a private temporary directory, which is removed after
+    /// parsing. `llvm-as` must be on the `PATH`, and should be from the same
+    /// LLVM version that `haybale` was built for.
+    pub fn from_ll_path(path: impl AsRef<Path>) -> Result<Self, String> {
+        info!("Parsing LLVM IR in file {}", path.as_ref().display());
+        let module = module_from_ll_path(path.as_ref())?;
+        Ok(Self {
+            pointer_size_bits: get_ptr_size(&module),
+            modules: vec![module],
+        })
+    }
+
+    /// Construct a new `Project` from multiple text-format LLVM IR (`.ll`)
+    /// files; see [`Project::from_ll_path()`](struct.Project.html#method.from_ll_path).
+    pub fn from_ll_paths<P>(paths: impl IntoIterator<Item = P>) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        info!("Parsing LLVM IR from specified files");
+        let (modules, ptr_sizes): (Vec<Module>, Vec<u32>) = paths
+            .into_iter()
+            .map(|p| module_from_ll_path(p.as_ref()))
+            .map(|r| {
+                r.map(|m| {
+                    let ptr_size = get_ptr_size(&m);
+                    (m, ptr_size)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        let mut ptr_sizes = ptr_sizes.into_iter();
+        let pointer_size_bits = ptr_sizes
+            .next()
+            .expect("Project::from_ll_paths: at least one path is required");
+        assert!(
+            ptr_sizes.all(|size| size == pointer_size_bits),
+            "Project::from_ll_paths: modules have conflicting pointer sizes"
+        );
+        Ok(Self {
+            modules,
+            pointer_size_bits,
+        })
+    }
+
     /// Construct a new `Project` from a path to a directory containing
     /// LLVM bitcode files.
     ///
@@ -101,6 +168,38 @@ impl Project {
         })
     }
 
+    /// Construct a new `Project` from LLVM bitcode in memory, e.g. bitcode
+    /// produced by a JIT pipeline.
+    ///
+    /// Each module is given as a pair of a name and the bitcode bytes. The name
+    /// becomes the module's name (as the path does for `from_bc_path()`), e.g.
+    /// in `Location`s and error messages.
+    ///
+    /// `llvm-ir` can currently only parse bitcode from a file, so each buffer
+    /// is briefly written to a newly created temporary file (which only the
+    /// current user can access), which is removed after parsing.
+    ///
+    /// Returns an `Err` if `modules` is empty, or if the modules have
+    /// conflicting pointer sizes.
+    pub fn from_bc_bytes(modules: &[(&str, &[u8])]) -> Result<Self, String> {
+        info!("Parsing bitcode from {} in-memory buffer(s)", modules.len());
+        let modules = modules
+            .iter()
+            .map(|(name, bytes)| module_from_bc_bytes(name, bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut ptr_sizes = modules.iter().map(get_ptr_size);
+        let pointer_size_bits = ptr_sizes
+            .next()
+            .ok_or_else(|| "Project::from_bc_bytes: at least one module is required".to_owned())?;
+        if !ptr_sizes.all(|size| size == pointer_size_bits) {
+            return Err("Project::from_bc_bytes: modules have conflicting pointer sizes".into());
+        }
+        Ok(Self {
+            modules,
+            pointer_size_bits,
+        })
+    }
+
     /// Add the code in the given LLVM bitcode file to the `Project`
     pub fn add_bc_path(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
         info!("Parsing bitcode in file {}", path.as_ref().display());
@@ -197,6 +296,55 @@ impl Project {
             .flatten()
     }
 
+    /// Iterate over all named struct types in the `Project` which have a
+    /// (non-opaque) definition, giving the name and layout of each.
+    ///
+    /// Each struct name appears only once, even if the struct is defined in
+    /// multiple modules. See
+    /// [`get_named_struct_def()`](struct.Project.html#method.get_named_struct_def)
+    /// for which definition is used in that case.
+    pub fn struct_types(&self) -> impl Iterator<Item = (String, StructLayout)> + '_ {
+        self.all_named_struct_types()
+            .map(|(name, _, _)| name)
+            .unique()
+            .filter_map(move |name| {
+                let layout = self.struct_layout(&Type::NamedStructType { name: name.clone() })?;
+                Some((name.clone(), layout))
+            })
+    }
+
+    /// Get the layout of the given struct type (either a `StructType` or a
+    /// `NamedStructType`): the offset and size of each of its fields.
+    ///
+    /// Returns `None` if `ty` isn't a struct type, or if it (or one of its
+    /// fields) is a struct with no definition in the entire `Project`.
+    pub fn struct_layout(&self, ty: &Type) -> Option<StructLayout> {
+        match ty {
+            Type::StructType { element_types, .. } => {
+                let mut offset_bits = 0;
+                let mut fields = Vec::with_capacity(element_types.len());
+                for ty in element_types {
+                    let size_bits = self.size_in_bits(ty)?;
+                    fields.push(FieldLayout {
+                        offset_bits,
+                        size_bits,
+                        ty: ty.clone(),
+                    });
+                    offset_bits += size_bits;
+                }
+                Some(StructLayout {
+                    size_bits: offset_bits,
+                    fields,
+                })
+            },
+            Type::NamedStructType { name } => match self.get_named_struct_def(name).ok()? {
+                (NamedStructDef::Opaque, _) => None,
+                (NamedStructDef::Defined(ty), _) => self.struct_layout(ty),
+            },
+            _ => None,
+        }
+    }
+
     /// Get the names of the LLVM modules which have been parsed and loaded into
     /// the `Project`
     pub fn active_module_names(&self) -> impl Iterator<Item = &String> {
@@ -281,6 +429,138 @@ impl Project {
         retval
     }
 
+    /// Get the signature of the function with the given name: its parameter
+    /// names and types, and its return type. This is useful for deciding how
+    /// to set up the `ParameterVal`s for a run before starting symbolic
+    /// execution.
+    ///
+    /// The function name is resolved as in
+    /// [`get_func_by_name()`](struct.Project.html#method.get_func_by_name).
+    /// Returns `None` if no function with the given name is found.
+    pub fn function_signature(&self, funcname: &str) -> Option<FunctionSignature> {
+        let (func, _) = self.get_func_by_name(funcname)?;
+        Some(FunctionSignature {
+            name: func.name.clone(),
+            parameters: func
+                .parameters
+                .iter()
+                .map(|param| FunctionParameter {
+                    name: param.name.clone(),
+                    ty: param.ty.clone(),
+                    size_bits: self.size_in_bits(&param.ty),
+                })
+                .collect(),
+            return_type: func.return_type.clone(),
+            is_var_arg: func.is_var_arg,
+        })
+    }
+
+    /// Get the names of all functions which are reachable from the function
+    /// with the given name, by following `Call` and `Invoke` instructions
+    /// transitively. The result includes the entry function itself, and also
+    /// functions which are called but not defined anywhere in the `Project`
+    /// (e.g., `malloc()` or LLVM intrinsics). All names are mangled names, as
+    /// they appear in the LLVM.
+    ///
+    /// Calls through function pointers are followed if the function pointer is
+    /// a constant (possibly bitcasted) reference to a function. Otherwise, the
+    /// call is treated conservatively as possibly calling any function defined
+    /// in the `Project`. Calls to inline assembly are ignored.
+    ///
+    /// The entry function name is resolved as in
+    /// [`get_func_by_name()`](struct.Project.html#method.get_func_by_name).
+    /// Returns the empty set if no function with the given name is found.
+    pub fn reachable_functions(&self, entry: &str) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let (entry_func, entry_module) = match self.get_func_by_name(entry) {
+            Some(pair) => pair,
+            None => return reachable,
+        };
+        // (module name, function name) of the defined functions we've visited
+        let mut visited: HashSet<(&str, &str)> = HashSet::new();
+        let mut worklist = vec![(entry_func, entry_module)];
+        let mut added_all_functions = false;
+        while let Some((func, module)) = worklist.pop() {
+            if !visited.insert((&module.name, &func.name)) {
+                continue;
+            }
+            reachable.insert(func.name.clone());
+            let callees = func.basic_blocks.iter().flat_map(|bb| {
+                bb.instrs
+                    .iter()
+                    .filter_map(|instr| match instr {
+                        Instruction::Call(call) => Some(&call.function),
+                        _ => None,
+                    })
+                    .chain(match &bb.term {
+                        Terminator::Invoke(invoke) => Some(&invoke.function),
+                        _ => None,
+                    })
+            });
+            for callee in callees {
+                match callee {
+                    Either::Left(_) => {}, // inline assembly
+                    Either::Right(operand) => match direct_callee_name(operand) {
+                        Some(name) => {
+                            reachable.insert(name.to_owned());
+                            // prefer a (potentially module-private) definition in the same module
+                            let def = match module.get_func_by_name(name) {
+                                Some(f) => Some((f, module)),
+                                None => self.all_functions().find(|(f, _)| f.name == name),
+                            };
+                            worklist.extend(def);
+                        },
+                        None if !added_all_functions => {
+                            // conservatively, the call could target any function in the `Project`
+                            worklist.extend(self.all_functions());
+                            added_all_functions = true;
+                        },
+                        None => {},
+                    },
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Iterate over the instructions (not including terminators) of the
+    /// function with the given name, in order, each paired with its debug
+    /// location (source location), if it has one.
+    ///
+    /// Instructions only have debug locations if the LLVM was compiled with
+    /// debuginfo (e.g., `-g`).
+    ///
+    /// The function name is resolved as in
+    /// [`get_func_by_name()`](struct.Project.html#method.get_func_by_name).
+    /// Returns `None` if no function with the given name is found.
+    pub fn instructions_with_debug_locs(
+        &self,
+        funcname: &str,
+    ) -> Option<impl Iterator<Item = (&Instruction, Option<&DebugLoc>)>> {
+        let (func, _) = self.get_func_by_name(funcname)?;
+        Some(
+            func.basic_blocks
+                .iter()
+                .flat_map(|bb| bb.instrs.iter())
+                .map(|instr| (instr, instr.get_debug_loc().as_ref())),
+        )
+    }
+
+    /// Like
+    /// [`instructions_with_debug_locs()`](struct.Project.html#method.instructions_with_debug_locs),
+    /// but iterates over the terminators of the function's basic blocks instead.
+    pub fn terminators_with_debug_locs(
+        &self,
+        funcname: &str,
+    ) -> Option<impl Iterator<Item = (&Terminator, Option<&DebugLoc>)>> {
+        let (func, _) = self.get_func_by_name(funcname)?;
+        Some(
+            func.basic_blocks
+                .iter()
+                .map(|bb| (&bb.term, bb.term.get_debug_loc().as_ref())),
+        )
+    }
+
     /// Get the definition of the named struct with the given name.
     /// Returns both the definition, and the module that definition was found in.
     ///
@@ -438,6 +718,63 @@ impl Project {
     }
 }
 
+/// Describes how a struct type is laid out in memory.
+///
+/// This is the layout `haybale` itself uses, e.g., when executing a
+/// `GetElementPtr`: fields are placed consecutively, with no padding between
+/// them. So these offsets are the ones to use when reading or writing the
+/// struct's fields in `haybale`'s memory.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StructLayout {
+    /// Total size of the struct, in bits
+    pub size_bits: u32,
+    /// Layout of each of the struct's fields, in order
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Describes the location of a single field within a struct; see
+/// [`StructLayout`](struct.StructLayout.html).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FieldLayout {
+    /// Offset of the field from the start of the struct, in bits
+    pub offset_bits: u32,
+    /// Size of the field, in bits
+    pub size_bits: u32,
+    /// Type of the field. If the field is itself a struct, its layout can be
+    /// obtained with
+    /// [`Project::struct_layout()`](struct.Project.html#method.struct_layout).
+    pub ty: TypeRef,
+}
+
+/// Describes the signature of a function; see
+/// [`Project::function_signature()`](struct.Project.html#method.function_signature).
+#[derive(PartialEq, Clone, Debug)]
+pub struct FunctionSignature {
+    /// The (mangled) name of the function
+    pub name: String,
+    /// The function's parameters, in order
+    pub parameters: Vec<FunctionParameter>,
+    /// The function's return type
+    pub return_type: TypeRef,
+    /// Whether the function is variadic, i.e., accepts additional arguments
+    /// beyond its declared `parameters`
+    pub is_var_arg: bool,
+}
+
+/// Describes a single parameter of a function; see
+/// [`FunctionSignature`](struct.FunctionSignature.html).
+#[derive(PartialEq, Clone, Debug)]
+pub struct FunctionParameter {
+    /// Name of the parameter. This is the LLVM name, which may just be a
+    /// number if the module was compiled without parameter names.
+    pub name: Name,
+    /// Type of the parameter
+    pub ty: TypeRef,
+    /// Size of the parameter, in bits, or `None` if the size could not be
+    /// determined (e.g., it is an opaque struct)
+    pub size_bits: Option<u32>,
+}
+
 /// Returns `Some(true)` if the entry is a directory, `Some(false)` if the entry
 /// is not a directory, and `None` if there was an I/O error in trying to make
 /// the determination, or if the original `entry` was an `Err`.
@@ -450,6 +787,96 @@ fn entry_is_dir(entry: &io::Result<DirEntry>) -> Option<bool> {
     // entry.as_ref().ok().and_then(|entry| entry.file_type().map(|ft| ft.is_dir()).ok())
 }
 
+/// Get the name of the function called by a `Call` or `Invoke` whose
+/// `function` is the given `Operand`, if it is a constant (possibly bitcasted)
+/// reference to a function.
+/// Returns `None` for other function pointers.
+fn direct_callee_name(operand: &Operand) -> Option<&str> {
+    fn constant_callee_name(constant: &Constant) -> Option<&str> {
+        match constant {
+            Constant::GlobalReference {
+                name: Name::Name(name),
+                ..
+            } => Some(name),
+            Constant::BitCast(bitcast) => constant_callee_name(bitcast.operand.as_ref()),
+            _ => None,
+        }
+    }
+    match operand {
+        Operand::ConstantOperand(cref) => constant_callee_name(cref.as_ref()),
+        _ => None,
+    }
+}
+
+/// Parse the given in-memory bitcode into a `Module` with the given name.
+///
+/// `llvm-ir` only parses bitcode from a path (its in-memory parsing isn't
+/// public), so this goes through a temporary file.
+fn module_from_bc_bytes(name: &str, bytes: &[u8]) -> Result<Module, String> {
+    // LLVM exits the process (rather than returning an error) if asked to parse
+    // something without a bitcode signature, so check for one ourselves.
+    // This is either the raw bitcode magic, or the magic for the wrapper
+    // format used on Darwin.
+    const BC_MAGIC: [u8; 4] = [b'B', b'C', 0xC0, 0xDE];
+    const WRAPPER_MAGIC: [u8; 4] = [0xDE, 0xC0, 0x17, 0x0B];
+    if !bytes.starts_with(&BC_MAGIC) && !bytes.starts_with(&WRAPPER_MAGIC) {
+        return Err(format!(
+            "Failed to parse module {:?}: not LLVM bitcode (invalid bitcode signature)",
+            name
+        ));
+    }
+    // created exclusively (so nothing else can have planted or be sharing
+    // it), readable only by us, and removed when dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("haybale-")
+        .suffix(".bc")
+        .tempfile()
+        .map_err(|e| format!("Failed to create a temporary file: {}", e))?;
+    file.write_all(bytes)
+        .and_then(|()| file.flush())
+        .map_err(|e| {
+            format!(
+                "Failed to write bitcode for module {:?} to temporary file {}: {}",
+                name,
+                file.path().display(),
+                e
+            )
+        })?;
+    let module = Module::from_bc_path(file.path());
+    let mut module = module.map_err(|e| format!("Failed to parse module {:?}: {}", name, e))?;
+    module.name = name.to_owned();
+    Ok(module)
+}
+
+/// Parse the text-format LLVM IR file at the given path, by assembling it to
+/// bitcode with `llvm-as`. The module is named by the path, as if it were
+/// parsed from bitcode with `Module::from_bc_path()`.
+fn module_from_ll_path(path: &Path) -> Result<Module, String> {
+    // a fresh directory, which only we can access, so nothing else can
+    // interfere with the bitcode file we have `llvm-as` create in it
+    let dir = tempfile::Builder::new()
+        .prefix("haybale-")
+        .tempdir()
+        .map_err(|e| format!("Failed to create a temporary directory: {}", e))?;
+    let bc_path = dir.path().join("module.bc");
+    let output = Command::new("llvm-as")
+        .arg(path)
+        .arg("-o")
+        .arg(&bc_path)
+        .output()
+        .map_err(|e| format!("Failed to run llvm-as on {}: {}", path.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "llvm-as failed to assemble {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let mut module = Module::from_bc_path(&bc_path)?;
+    module.name = path.display().to_string();
+    Ok(module)
+}
+
 /// Extracts the pointer size from an LLVM module
 fn get_ptr_size(module: &Module) -> u32 {
     module.data_layout.alignments.ptr_alignment(0).size
@@ -458,6 +885,7 @@ fn get_ptr_size(module: &Module) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn single_file_project() {
@@ -471,6 +899,70 @@ mod tests {
         assert_eq!(&module.name, "tests/bcfiles/basic.bc");
     }
 
+    #[test]
+    fn function_signature() {
+        let proj = Project::from_bc_path("tests/bcfiles/basic.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let sig = proj
+            .function_signature("two_args")
+            .expect("Failed to find function");
+        assert_eq!(&sig.name, "two_args");
+        assert_eq!(sig.parameters.len(), 2);
+        for param in &sig.parameters {
+            assert_eq!(*param.ty, Type::IntegerType { bits: 32 });
+            assert_eq!(param.size_bits, Some(32));
+        }
+        assert_eq!(*sig.return_type, Type::IntegerType { bits: 32 });
+        assert!(!sig.is_var_arg);
+        assert!(proj.function_signature("nonexistent_function").is_none());
+    }
+
+    #[test]
+    fn reachable_functions() {
+        let proj = Project::from_bc_path("tests/bcfiles/call.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let reachable = proj.reachable_functions("nested_caller");
+        let expected: HashSet<String> = vec!["nested_caller", "simple_caller", "simple_callee"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(reachable, expected);
+        assert!(proj.reachable_functions("nonexistent_function").is_empty());
+
+        // calling through a non-constant function pointer could reach anything
+        let proj = Project::from_bc_path("tests/bcfiles/functionptr.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let reachable = proj.reachable_functions("calls_fptr");
+        for (func, _) in proj.all_functions() {
+            assert!(
+                reachable.contains(&func.name),
+                "{:?} should be reachable",
+                func.name
+            );
+        }
+    }
+
+    #[test]
+    fn debug_locs() {
+        let proj = Project::from_bc_path("tests/bcfiles/debuginfo.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let lines: Vec<Option<u32>> = proj
+            .instructions_with_debug_locs("debuginfo")
+            .expect("Failed to find function")
+            .map(|(_, debugloc)| debugloc.map(|debugloc| debugloc.line))
+            .collect();
+        assert_eq!(lines, vec![Some(2), Some(3)]);
+        let lines: Vec<Option<u32>> = proj
+            .terminators_with_debug_locs("debuginfo")
+            .expect("Failed to find function")
+            .map(|(_, debugloc)| debugloc.map(|debugloc| debugloc.line))
+            .collect();
+        assert_eq!(lines, vec![Some(2), Some(3), None]);
+        assert!(proj
+            .instructions_with_debug_locs("nonexistent_function")
+            .is_none());
+    }
+
     #[test]
     fn double_file_project() {
         let proj = Project::from_bc_paths(&["tests/bcfiles/basic.bc", "tests/bcfiles/loop.bc"])
@@ -488,6 +980,74 @@ mod tests {
         assert_eq!(&module.name, "tests/bcfiles/loop.bc");
     }
 
+    #[test]
+    fn ll_file_project() {
+        let ll_proj = Project::from_ll_path("tests/bcfiles/odd_widths.ll")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let bc_proj = Project::from_bc_path("tests/bcfiles/odd_widths.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        assert_eq!(ll_proj.pointer_size_bits(), bc_proj.pointer_size_bits());
+        let (func, module) = ll_proj
+            .get_func_by_name("i24_param")
+            .expect("Failed to find function");
+        assert_eq!(&func.name, "i24_param");
+        assert_eq!(&module.name, "tests/bcfiles/odd_widths.ll");
+        let (bc_func, _) = bc_proj
+            .get_func_by_name("i24_param")
+            .expect("Failed to find function");
+        assert_eq!(func, bc_func);
+        let ll_funcs: HashSet<&str> = ll_proj
+            .all_functions()
+            .map(|(func, _)| func.name.as_str())
+            .collect();
+        let bc_funcs: HashSet<&str> = bc_proj
+            .all_functions()
+            .map(|(func, _)| func.name.as_str())
+            .collect();
+        assert_eq!(ll_funcs, bc_funcs);
+
+        let proj = Project::from_ll_paths(
+            ["tests/bcfiles/odd_widths.ll", "tests/bcfiles/shift.ll"].iter(),
+        )
+        .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let (_, module) = proj
+            .get_func_by_name("shl_by")
+            .expect("Failed to find function");
+        assert_eq!(&module.name, "tests/bcfiles/shift.ll");
+
+        assert!(Project::from_ll_path("tests/bcfiles/nonexistent.ll").is_err());
+    }
+
+    #[test]
+    fn bytes_project() {
+        let basic = std::fs::read("tests/bcfiles/basic.bc").expect("Failed to read basic.bc");
+        let looop = std::fs::read("tests/bcfiles/loop.bc").expect("Failed to read loop.bc");
+        let proj = Project::from_bc_bytes(&[("basic", &basic[..]), ("loop", &looop[..])])
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        assert_eq!(proj.pointer_size_bits(), 64);
+        let (func, module) = proj
+            .get_func_by_name("no_args_zero")
+            .expect("Failed to find function");
+        assert_eq!(&func.name, "no_args_zero");
+        assert_eq!(&module.name, "basic");
+        let (func, module) = proj
+            .get_func_by_name("while_loop")
+            .expect("Failed to find function");
+        assert_eq!(&func.name, "while_loop");
+        assert_eq!(&module.name, "loop");
+
+        // malformed bitcode is an error, not a panic, and so is no bitcode at all
+        assert!(Project::from_bc_bytes(&[("bogus", &b"not bitcode"[..])]).is_err());
+        assert!(Project::from_bc_bytes(&[]).is_err());
+
+        // as is mixing modules with different pointer sizes
+        let issue_4 =
+            std::fs::read("tests/bcfiles/32bit/issue_4.bc").expect("Failed to read issue_4.bc");
+        assert!(
+            Project::from_bc_bytes(&[("basic", &basic[..]), ("issue_4", &issue_4[..])]).is_err()
+        );
+    }
+
     #[test]
     fn whole_directory_project() {
         let proj = Project::from_bc_dir("tests/bcfiles", "bc")
@@ -527,4 +1087,33 @@ mod tests {
             .expect("Failed to find function");
         assert_eq!(&module.name, "tests/bcfiles/32bit/issue_4.bc");
     }
+
+    #[test]
+    fn struct_layouts() {
+        let proj = Project::from_bc_path("tests/bcfiles/struct.bc")
+            .unwrap_or_else(|e| panic!("Failed to create project: {}", e));
+        let layouts: HashMap<String, StructLayout> = proj.struct_types().collect();
+        assert_eq!(layouts.len(), proj.all_named_struct_types().count());
+
+        let mismatched = &layouts["struct.Mismatched"];
+        assert_eq!(mismatched.size_bits, 48);
+        let offsets: Vec<u32> = mismatched.fields.iter().map(|f| f.offset_bits).collect();
+        let sizes: Vec<u32> = mismatched.fields.iter().map(|f| f.size_bits).collect();
+        assert_eq!(offsets, vec![0, 8, 40]);
+        assert_eq!(sizes, vec![8, 32, 8]);
+
+        let nested = &layouts["struct.Nested"];
+        assert_eq!(nested.size_bits, 112);
+        assert_eq!(nested.fields[1].offset_bits, 64);
+        assert_eq!(
+            proj.struct_layout(&nested.fields[1].ty),
+            Some(mismatched.clone())
+        );
+
+        let with_array = &layouts["struct.WithArray"];
+        assert_eq!(with_array.size_bits, 48 + 320 + 48);
+        assert_eq!(with_array.fields[2].offset_bits, 48 + 320);
+
+        assert_eq!(proj.struct_layout(&Type::IntegerType { bits: 32 }), None);
+    }
 }
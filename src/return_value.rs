@@ -1,3 +1,5 @@
+use crate::error::Error;
+
 /// A simple enum describing the value returned from a function
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum ReturnValue<V> {
@@ -15,3 +17,54 @@ pub enum ReturnValue<V> {
     /// panic, or by calling the C `exit()` function)
     Abort,
 }
+
+/// Describes how a path explored by an `ExecutionManager` ended.
+/// See
+/// [`ExecutionManager::next_with_outcome()`](struct.ExecutionManager.html#method.next_with_outcome).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PathOutcome<V> {
+    /// The top-level function returned this value
+    Return(V),
+    /// The top-level function returned void
+    ReturnVoid,
+    /// The top-level function threw this value; see notes on
+    /// `ReturnValue::Throw`
+    Throw(V),
+    /// The path aborted without ever returning; see notes on
+    /// `ReturnValue::Abort`
+    Abort,
+    /// The path ended with this error
+    Error(Error),
+    /// The path was pruned without reaching a return or an error: for
+    /// instance, because it turned out to be infeasible (with
+    /// `Config.squash_unsats`), or because neither direction of a branch was
+    /// feasible
+    Dead,
+}
+
+impl<V> From<ReturnValue<V>> for PathOutcome<V> {
+    fn from(retval: ReturnValue<V>) -> Self {
+        match retval {
+            ReturnValue::Return(v) => PathOutcome::Return(v),
+            ReturnValue::ReturnVoid => PathOutcome::ReturnVoid,
+            ReturnValue::Throw(v) => PathOutcome::Throw(v),
+            ReturnValue::Abort => PathOutcome::Abort,
+        }
+    }
+}
+
+/// Describes the effect of a call to inline assembly, as modeled by an inline
+/// assembly hook (see
+/// [`FunctionHooks::add_inline_asm_hook()`](function_hooks/struct.FunctionHooks.html#method.add_inline_asm_hook))
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub enum AsmReturnValue<V> {
+    /// The asm completes normally, assigning these values to its output
+    /// operands. There should be one value for each output constraint, in the
+    /// order the output constraints appear: none if the asm has no outputs
+    /// (i.e., the call is void-typed), one if the call returns a single
+    /// non-struct value, or one per struct element if the call returns a
+    /// struct (which is how LLVM represents multiple outputs).
+    Outputs(Vec<V>),
+    /// The asm aborts without ever returning
+    Abort,
+}
@@ -5,12 +5,11 @@
 //! Despite being simpler, it seems to outperform the `Memory` in `cell_memory.rs`
 //! in many situations.
 
-use crate::backend::SolverRef;
+use crate::backend::{BtorRef, SolverRef};
 use crate::error::*;
 use crate::solver_utils::bvs_can_be_equal;
-use boolector::Btor;
+use crate::written_bytes::WrittenBytes;
 use log::debug;
-use std::rc::Rc;
 
 // Rust 1.51.0 introduced its own `.reduce()` on the main `Iterator` trait.
 // So, starting with 1.51.0, we don't need `reduce::Reduce`, and in fact it
@@ -18,17 +17,19 @@ use std::rc::Rc;
 #[rustversion::before(1.51)]
 use reduce::Reduce;
 
-type BV = boolector::BV<Rc<Btor>>;
-type Array = boolector::Array<Rc<Btor>>;
+type BV = boolector::BV<BtorRef>;
+type Array = boolector::Array<BtorRef>;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Memory {
-    btor: Rc<Btor>,
+    btor: BtorRef,
     mem: Array,
     /// e.g. `64` for a `Memory` which uses 64-bit addresses
     addr_bits: u32,
     name: String,
     null_detection: bool,
+    /// Which bytes have been written, for `is_initialized()`
+    written: WrittenBytes,
 }
 
 impl Memory {
@@ -47,7 +48,7 @@ impl Memory {
     ///
     /// `addr_bits`: e.g. `64` for a `Memory` which uses 64-bit addresses
     pub fn new_uninitialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
@@ -62,6 +63,7 @@ impl Memory {
             ),
             name: name.unwrap_or(default_name).into(),
             null_detection,
+            written: WrittenBytes::none(),
             addr_bits,
             btor, // out of order so it can be used above but moved in here
         }
@@ -77,7 +79,7 @@ impl Memory {
     ///
     /// `addr_bits`: e.g. `64` for a `Memory` which uses 64-bit addresses
     pub fn new_zero_initialized(
-        btor: Rc<Btor>,
+        btor: BtorRef,
         null_detection: bool,
         name: Option<&str>,
         addr_bits: u32,
@@ -92,13 +94,14 @@ impl Memory {
             ),
             name: name.unwrap_or(default_name).into(),
             null_detection,
+            written: WrittenBytes::all(),
             addr_bits,
             btor, // out of order so it can be used above but moved in here
         }
     }
 
     /// Get a reference to the `Btor` instance this `Memory` belongs to
-    pub fn get_solver(&self) -> Rc<Btor> {
+    pub fn get_solver(&self) -> BtorRef {
         self.btor.clone()
     }
 
@@ -108,7 +111,7 @@ impl Memory {
     /// via `Btor::duplicate()` from the `Btor` this `Memory` was originally
     /// created with (or most recently changed to). Further, no new variables
     /// should have been added since the call to `Btor::duplicate()`.
-    pub fn change_solver(&mut self, new_btor: Rc<Btor>) {
+    pub fn change_solver(&mut self, new_btor: BtorRef) {
         self.mem = new_btor.match_array(&self.mem).unwrap();
         self.btor = new_btor;
     }
@@ -183,6 +186,21 @@ impl Memory {
         Ok(rval)
     }
 
+    /// Have all of the `bits` bits at `addr` been written since this `Memory`
+    /// was created? Memory created with `new_zero_initialized()` counts as
+    /// written everywhere.
+    ///
+    /// Only writes and reads at concrete addresses are tracked precisely: a
+    /// read at a symbolic address is always considered initialized, and after
+    /// any write to a symbolic address, all memory is considered initialized.
+    pub fn is_initialized(&self, addr: &BV, bits: u32) -> bool {
+        let mut bytes = bits / Self::BITS_IN_BYTE;
+        if bytes * Self::BITS_IN_BYTE < bits {
+            bytes += 1; // round up to nearest byte
+        }
+        self.written.is_written(addr.as_u64(), u64::from(bytes))
+    }
+
     /// Write any number (>0) of bits of memory, at any alignment.
     pub fn write(&mut self, addr: &BV, val: BV) -> Result<()> {
         debug!("Writing {:?} to {} address {:?}", val, &self.name, addr);
@@ -226,6 +244,8 @@ impl Memory {
             ));
             self.write_byte(&offset_addr, &data_byte);
         }
+        self.written
+            .record_write(addr.as_u64(), u64::from(write_size_bytes));
         Ok(())
     }
 }
@@ -238,29 +258,27 @@ mod tests {
     use super::*;
     use crate::error::Result;
     use crate::solver_utils::{self, PossibleSolutions};
-    use boolector::option::{BtorOption, ModelGen};
     use boolector::{BVSolution, BV};
-    use std::rc::Rc;
 
     // Basically the `get_a_solution_for_bv()` method from `State`,
     // without requiring that we construct a `State` or depend on the
     // `State` module
-    fn get_a_solution(bv: &BV<Rc<Btor>>) -> Result<Option<BVSolution>> {
+    fn get_a_solution(bv: &BV<BtorRef>) -> Result<Option<BVSolution>> {
         let btor = bv.get_btor();
-        btor.set_opt(BtorOption::ModelGen(ModelGen::All));
+        btor.set_model_gen(true);
         let solution = if solver_utils::sat(&btor)? {
             Some(bv.get_a_solution())
         } else {
             None
         };
-        btor.set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+        btor.set_model_gen(false);
         Ok(solution)
     }
 
     #[test]
     fn uninitialized() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         let addr = BV::from_u64(btor.clone(), 0x10000, 64);
@@ -295,7 +313,7 @@ mod tests {
     #[test]
     fn zero_initialized() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mem = Memory::new_zero_initialized(btor.clone(), true, None, 64);
 
         let addr = BV::from_u64(btor.clone(), 0x10000, 64);
@@ -314,7 +332,7 @@ mod tests {
     #[test]
     fn read_and_write_to_cell_zero() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, 64);
 
         // Store a byte of data to address 0
@@ -337,7 +355,7 @@ mod tests {
     #[test]
     fn read_and_write_cell_aligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store a byte of data to a nonzero, but aligned, address
@@ -360,7 +378,7 @@ mod tests {
     #[test]
     fn read_and_write_small() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 8 bits of data to an aligned address
@@ -384,7 +402,7 @@ mod tests {
     #[test]
     fn read_and_write_small_32bitaddr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 32);
 
         // Store 8 bits of data to an aligned address
@@ -407,7 +425,7 @@ mod tests {
     #[test]
     fn read_single_bit() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 8 bits of data to an aligned address
@@ -430,7 +448,7 @@ mod tests {
     #[test]
     fn read_and_write_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 8 bits of data to offset 1 in a cell
@@ -453,7 +471,7 @@ mod tests {
     #[test]
     fn read_and_write_64_bits() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 64 bits of data
@@ -476,7 +494,7 @@ mod tests {
     #[test]
     fn read_and_write_symbolic_addr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, 64);
 
         // Store 64 bits of data to a symbolic address
@@ -499,7 +517,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 200 bits of data to an aligned address
@@ -545,7 +563,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 200 bits of data to an unaligned address
@@ -591,7 +609,7 @@ mod tests {
     #[test]
     fn read_and_write_200bits_symbolic_addr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), false, None, 64);
 
         // Store 200 bits of data to a symbolic address
@@ -637,7 +655,7 @@ mod tests {
     #[test]
     fn write_twice_read_once() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 8 bits of data
@@ -665,7 +683,7 @@ mod tests {
     #[test]
     fn write_different_locations() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 32 bits of data
@@ -700,7 +718,7 @@ mod tests {
     #[test]
     fn write_adjacent_locations() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 32 bits of data
@@ -735,7 +753,7 @@ mod tests {
     #[test]
     fn write_small_read_big() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_zero_initialized(btor.clone(), true, None, 64);
 
         // Store 8 bits of data
@@ -783,7 +801,7 @@ mod tests {
     #[test]
     fn write_big_read_small() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Store 32 bits of data
@@ -827,7 +845,7 @@ mod tests {
     #[test]
     fn write_big_read_small_32bitaddr() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 32);
 
         // Store 32 bits of data
@@ -870,7 +888,7 @@ mod tests {
     #[test]
     fn partial_overwrite_aligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Write 64 bits
@@ -905,7 +923,7 @@ mod tests {
     #[test]
     fn partial_overwrite_unaligned() -> Result<()> {
         let _ = env_logger::builder().is_test(true).try_init();
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut mem = Memory::new_uninitialized(btor.clone(), true, None, 64);
 
         // Write 64 bits
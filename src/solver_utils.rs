@@ -1,20 +1,44 @@
 //! Simple utilities for interacting with the solver
 
-use crate::backend::BV;
+use crate::backend::{SolverRef, BV};
 use crate::error::*;
-use boolector::option::{BtorOption, ModelGen};
-use boolector::{BVSolution, Btor, SolverResult};
+use boolector::{BVSolution, SolverResult};
+pub use indexmap::IndexSet;
 use log::warn;
-use std::collections::HashSet;
 use std::hash::Hash;
 use std::iter::FromIterator;
 use std::ops::Deref;
 
+/// Counts of the solver queries which have been performed.
+///
+/// See [`State::solver_stats()`](../struct.State.html#method.solver_stats) and
+/// [`ExecutionManager::solver_stats()`](../struct.ExecutionManager.html#method.solver_stats).
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct SolverStats {
+    /// Total number of calls to `sat()`, including those made with model
+    /// generation enabled
+    pub sat_checks: u64,
+    /// Number of calls to `sat()` made with model generation enabled, i.e., in
+    /// order to get concrete solutions. Experimentally, these are much slower
+    /// than the other calls to `sat()`.
+    pub model_gens: u64,
+}
+
+impl SolverStats {
+    /// Get the number of queries performed between `earlier` and `self`
+    pub(crate) fn since(&self, earlier: &SolverStats) -> SolverStats {
+        SolverStats {
+            sat_checks: self.sat_checks - earlier.sat_checks,
+            model_gens: self.model_gens - earlier.model_gens,
+        }
+    }
+}
+
 /// Returns `true` if current constraints are satisfiable, `false` if not.
 ///
 /// Returns `Error::SolverError` if the query failed (e.g., was interrupted or timed out).
-pub fn sat(btor: &Btor) -> Result<bool> {
-    match btor.sat() {
+pub fn sat(solver: &impl SolverRef) -> Result<bool> {
+    match solver.sat() {
         SolverResult::Sat => Ok(true),
         SolverResult::Unsat => Ok(false),
         SolverResult::Unknown => Err(Error::SolverError(
@@ -30,19 +54,19 @@ pub fn sat(btor: &Btor) -> Result<bool> {
 ///
 /// Does not permanently add the constraints in `conds` to the solver.
 pub fn sat_with_extra_constraints<I, B>(
-    btor: &Btor,
+    solver: &B::SolverRef,
     constraints: impl IntoIterator<Item = I>,
 ) -> Result<bool>
 where
     I: Deref<Target = B>,
     B: BV,
 {
-    btor.push(1);
+    solver.push(1);
     for constraint in constraints {
         constraint.assert()?;
     }
-    let retval = sat(btor);
-    btor.pop(1);
+    let retval = sat(solver);
+    solver.pop(1);
     retval
 }
 
@@ -53,14 +77,14 @@ where
 ///
 /// A common use case for this function is to test whether some `BV` must be
 /// equal to a given concrete value. You can do this with something like
-/// `bvs_must_be_equal(btor, bv, BV::from_u64(...))`.
+/// `bvs_must_be_equal(solver, bv, BV::from_u64(...))`.
 ///
 /// This function and `bvs_can_be_equal()` are both more efficient than
 /// `get_a_solution()` or `get_possible_solutions()`-type functions, as they do
 /// not require full model generation. You should prefer this function or
 /// `bvs_can_be_equal()` if they are sufficient for your needs.
-pub fn bvs_must_be_equal<V: BV>(btor: &Btor, a: &V, b: &V) -> Result<bool> {
-    if sat_with_extra_constraints(btor, &[a._ne(&b)])? {
+pub fn bvs_must_be_equal<V: BV>(solver: &V::SolverRef, a: &V, b: &V) -> Result<bool> {
+    if sat_with_extra_constraints(solver, &[a._ne(&b)])? {
         Ok(false)
     } else {
         Ok(true)
@@ -74,34 +98,41 @@ pub fn bvs_must_be_equal<V: BV>(btor: &Btor, a: &V, b: &V) -> Result<bool> {
 ///
 /// A common use case for this function is to test whether some `BV` can be
 /// equal to a given concrete value. You can do this with something like
-/// `bvs_can_be_equal(btor, bv, BV::from_u64(...))`.
+/// `bvs_can_be_equal(solver, bv, BV::from_u64(...))`.
 ///
 /// This function and `bvs_must_be_equal()` are both more efficient than
 /// `get_a_solution()` or `get_possible_solutions()`-type functions, as they do
 /// not require full model generation. You should prefer this function or
 /// `bvs_must_be_equal()` if they are sufficient for your needs.
-pub fn bvs_can_be_equal<V: BV>(btor: &Btor, a: &V, b: &V) -> Result<bool> {
-    if sat_with_extra_constraints(btor, &[a._eq(&b)])? {
+pub fn bvs_can_be_equal<V: BV>(solver: &V::SolverRef, a: &V, b: &V) -> Result<bool> {
+    if sat_with_extra_constraints(solver, &[a._eq(&b)])? {
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// A description of the possible solutions for something (e.g., a `BV`).
+///
+/// Solutions are kept in the order in which they were found. For a given
+/// `Project`, `Config`, and inputs, that order is the same on every run (it
+/// doesn't depend on hash iteration order), so iterating over the solutions is
+/// deterministic. Comparing two `PossibleSolutions` with `==` ignores the
+/// order.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum PossibleSolutions<V: Eq + Hash> {
     /// This is exactly the set of possible solutions; there are no others.
     /// Note that an empty set here indicates there are no possible solutions.
-    Exactly(HashSet<V>),
+    Exactly(IndexSet<V>),
     /// All of the solutions in this set are possible solutions, but there
     /// may be others.  That is, there are at least this many solutions.
-    AtLeast(HashSet<V>),
+    AtLeast(IndexSet<V>),
 }
 
 impl<V: Eq + Hash> PossibleSolutions<V> {
     /// Create a new, empty, `PossibleSolutions` (representing no possible solution)
     pub fn empty() -> Self {
-        Self::Exactly(HashSet::new())
+        Self::Exactly(IndexSet::new())
     }
 
     /// Create a new `PossibleSolutions` representing exactly one possible solution
@@ -129,14 +160,14 @@ impl PossibleSolutions<BVSolution> {
                 let opt = v
                     .iter()
                     .map(|bvs| bvs.as_u64())
-                    .collect::<Option<HashSet<u64>>>();
+                    .collect::<Option<IndexSet<u64>>>();
                 opt.map(PossibleSolutions::Exactly)
             },
             PossibleSolutions::AtLeast(v) => {
                 let opt = v
                     .iter()
                     .map(|bvs| bvs.as_u64())
-                    .collect::<Option<HashSet<u64>>>();
+                    .collect::<Option<IndexSet<u64>>>();
                 opt.map(PossibleSolutions::AtLeast)
             },
         }
@@ -146,7 +177,7 @@ impl PossibleSolutions<BVSolution> {
 impl<V: Eq + Hash> FromIterator<V> for PossibleSolutions<V> {
     /// Create a `PossibleSolutions::Exactly` from the contents of an iterator
     fn from_iter<T: IntoIterator<Item = V>>(iter: T) -> Self {
-        Self::Exactly(HashSet::from_iter(iter))
+        Self::Exactly(IndexSet::from_iter(iter))
     }
 }
 
@@ -187,7 +218,7 @@ pub fn get_possible_solutions_for_bv<V: BV>(
 ) -> Result<PossibleSolutions<BVSolution>> {
     let ps = if n == 0 {
         warn!("A call to get_possible_solutions_for_bv() is resulting in a call to sat() with model generation enabled. Experimentally, these types of calls can be very slow. The BV is {:?}", bv);
-        solver.set_opt(BtorOption::ModelGen(ModelGen::All));
+        solver.set_model_gen(true);
         if sat(&solver)? {
             PossibleSolutions::AtLeast(
                 std::iter::once(
@@ -202,7 +233,7 @@ pub fn get_possible_solutions_for_bv<V: BV>(
         match bv.as_binary_str() {
             Some(bstr) => PossibleSolutions::exactly_one(BVSolution::from_01x_str(bstr)),
             None => {
-                let mut solutions = HashSet::new();
+                let mut solutions = IndexSet::new();
                 check_for_common_solutions(solver.clone(), bv, n, &mut solutions)?;
                 if solutions.len() > n {
                     PossibleSolutions::AtLeast(solutions)
@@ -214,7 +245,7 @@ pub fn get_possible_solutions_for_bv<V: BV>(
                             .assert()?;
                     }
                     warn!("A call to get_possible_solutions_for_bv() is resulting in a call to sat() with model generation enabled. Experimentally, these types of calls can be very slow. The BV is {:?}", bv);
-                    solver.set_opt(BtorOption::ModelGen(ModelGen::All));
+                    solver.set_model_gen(true);
                     while solutions.len() <= n && sat(&solver)? {
                         let val = bv.get_a_solution()?.disambiguate();
                         solutions.insert(val.clone());
@@ -232,7 +263,7 @@ pub fn get_possible_solutions_for_bv<V: BV>(
             },
         }
     };
-    solver.set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+    solver.set_model_gen(false);
     Ok(ps)
 }
 
@@ -246,7 +277,7 @@ fn check_for_common_solutions<V: BV>(
     solver: V::SolverRef,
     bv: &V,
     n: usize,
-    solutions: &mut HashSet<BVSolution>,
+    solutions: &mut IndexSet<BVSolution>,
 ) -> Result<()> {
     let width = bv.get_width();
     if solutions.len() <= n && bvs_can_be_equal(&solver, bv, &BV::zero(solver.clone(), width))? {
@@ -388,6 +419,41 @@ fn check_for_common_solutions<V: BV>(
     Ok(())
 }
 
+/// Get `count` distinct possible solutions for the `BV`, in ascending
+/// (unsigned) order.
+///
+/// Unlike `get_possible_solutions_for_bv()`, which is for finding out whether
+/// there are more than `n` solutions, this is for sampling: it keeps solving
+/// until it has `count` solutions, so it returns fewer than `count` only if
+/// there are fewer than `count` possible solutions. In particular, it returns
+/// an empty `Vec` if the current set of constraints is unsatisfiable.
+///
+/// Only returns `Err` if a solver query itself fails. Panics if the `BV` is
+/// wider than 64 bits.
+pub fn sample_solutions_for_bv_as_u64<V: BV>(
+    solver: V::SolverRef,
+    bv: &V,
+    count: usize,
+) -> Result<Vec<u64>> {
+    if bv.get_width() > 64 {
+        panic!("sample_solutions_for_bv_as_u64 on a BV with width > 64");
+    }
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let solutions = match get_possible_solutions_for_bv(solver, bv, count)? {
+        PossibleSolutions::Exactly(v) => v,
+        PossibleSolutions::AtLeast(v) => v,
+    };
+    let mut samples = solutions
+        .iter()
+        .map(|bvs| bvs.as_u64().unwrap()) // we checked the width above
+        .collect::<Vec<u64>>();
+    samples.sort_unstable();
+    samples.truncate(count);
+    Ok(samples)
+}
+
 /// Get the maximum possible solution for the `BV`: that is, the highest value
 /// for which the current set of constraints is still satisfiable.
 /// "Maximum" will be interpreted in an unsigned fashion.
@@ -647,14 +713,13 @@ pub fn min_possible_solution_for_bv_as_binary_str<V: BV>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::backend::SolverRef;
-    use std::rc::Rc;
+    use crate::backend::{BtorRef, SolverRef};
 
-    type BV = <Rc<Btor> as SolverRef>::BV;
+    type BV = <BtorRef as SolverRef>::BV;
 
     #[test]
     fn basic_sat() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // fresh btor should be sat
         assert_eq!(sat(&btor), Ok(true));
@@ -671,7 +736,7 @@ mod tests {
 
     #[test]
     fn basic_unsat() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // adding False constraint should be unsat
         BV::from_bool(btor.clone(), false).assert();
@@ -680,7 +745,7 @@ mod tests {
 
     #[test]
     fn extra_constraints() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // adding x > 3 constraint should still be sat
         let x: BV = BV::new(btor.clone(), 64, Some("x"));
@@ -700,7 +765,7 @@ mod tests {
 
     #[test]
     fn can_or_must_be_equal() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // create constants 2, 3, 4, 5, and 7, which we'll need
         let two = BV::from_u64(btor.clone(), 2, 64);
@@ -731,7 +796,7 @@ mod tests {
 
     #[test]
     fn possible_solutions() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // add x > 3 constraint
         let x: BV = BV::new(btor.clone(), 64, Some("x"));
@@ -771,9 +836,35 @@ mod tests {
         assert_eq!(solutions, Some(PossibleSolutions::empty()));
     }
 
+    #[test]
+    fn sample_solutions() {
+        let btor = <BtorRef as SolverRef>::new();
+
+        // add x > 3 constraint
+        let x: BV = BV::new(btor.clone(), 64, Some("x"));
+        x.ugt(&BV::from_u64(btor.clone(), 3, 64)).assert();
+
+        // we can get many more samples than `get_possible_solutions_for_bv()`
+        // with a small `n` would give us
+        let samples = sample_solutions_for_bv_as_u64(btor.clone(), &x, 20).unwrap();
+        assert_eq!(samples.len(), 20);
+        assert!(samples.windows(2).all(|w| w[0] < w[1])); // distinct and sorted
+        assert!(samples.iter().all(|&s| s > 3));
+
+        // with only two possible solutions, we get just those two
+        x.ult(&BV::from_u64(btor.clone(), 6, 64)).assert();
+        let samples = sample_solutions_for_bv_as_u64(btor.clone(), &x, 20).unwrap();
+        assert_eq!(samples, vec![4, 5]);
+
+        // with no possible solutions, we get none
+        x.ult(&BV::from_u64(btor.clone(), 3, 64)).assert();
+        let samples = sample_solutions_for_bv_as_u64(btor.clone(), &x, 20).unwrap();
+        assert!(samples.is_empty());
+    }
+
     #[test]
     fn min_possible_solution() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // add x > 3 constraint
         let x: BV = BV::new(btor.clone(), 64, Some("x"));
@@ -806,7 +897,7 @@ mod tests {
 
     #[test]
     fn max_possible_solution() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // add x < 7 constraint
         let x: BV = BV::new(btor.clone(), 64, Some("x"));
@@ -851,7 +942,7 @@ mod tests {
 
     #[test]
     fn min_possible_solution_str() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // add x > 3 constraint (16-bit x)
         let x: BV = BV::new(btor.clone(), 16, Some("x"));
@@ -879,7 +970,7 @@ mod tests {
 
     #[test]
     fn min_possible_solution_overflow() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // Constrain x so that -2 and -1 are the only possible solutions. This
         // means that the min possible _unsigned_ solution will be 0b1111...1110
@@ -899,7 +990,7 @@ mod tests {
 
     #[test]
     fn max_possible_solution_overflow() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // Constrain x so that -2 is a solution but -1 is not. This means that the max possible
         // _unsigned_ solution will be 0b1111...1110 (that is, -2 if we interpreted it as signed).
@@ -1,4 +1,4 @@
-use boolector::option::{BtorOption, ModelGen};
+use boolector::option::BtorOption;
 use boolector::BVSolution;
 use either::Either;
 use itertools::Itertools;
@@ -6,11 +6,11 @@ use llvm_ir::types::{FPType, NamedStructDef, Typed};
 use llvm_ir::*;
 use log::{debug, info, warn};
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 
 // Rust 1.51.0 introduced its own `.reduce()` on the main `Iterator` trait.
 // So, starting with 1.51.0, we don't need `reduce::Reduce`, and in fact it
@@ -20,14 +20,16 @@ use reduce::Reduce;
 
 use crate::alloc::Alloc;
 use crate::backend::*;
-use crate::config::{Config, NullPointerChecking};
-use crate::demangling::Demangling;
+use crate::config::{Config, ExplorationStrategy, NullPointerChecking, SymbolicAddressPolicy};
+use crate::coverage::CoverageReport;
+use crate::demangling::{DemangleScheme, Demangling};
 use crate::error::*;
 use crate::function_hooks::{self, FunctionHooks};
 use crate::global_allocations::*;
 use crate::hooks;
 use crate::project::Project;
-use crate::solver_utils::{self, PossibleSolutions};
+use crate::solver_utils::{self, IndexSet, PossibleSolutions, SolverStats};
+use crate::symex;
 use crate::varmap::{RestoreInfo, VarMap};
 use crate::watchpoints::{Watchpoint, Watchpoints};
 
@@ -49,6 +51,10 @@ pub struct State<'p, B: Backend> {
     mem: RefCell<B::Memory>,
     alloc: Alloc,
     global_allocations: GlobalAllocations<'p, B>,
+    /// All of the global allocations (global variables, functions, and
+    /// function hooks) are made when the `State` is created, so they're all
+    /// below this address, and all other allocations are above it
+    globals_end: u64,
     /// Pointer size in bits.
     /// E.g., this will be `64` if we're analyzing code which was compiled for a
     /// 64-bit platform.
@@ -67,7 +73,21 @@ pub struct State<'p, B: Backend> {
     stack: Vec<StackFrame<'p, B::BV>>,
     /// These backtrack points are places where execution can be resumed later
     /// (efficiently, thanks to the incremental solving capabilities of Boolector).
-    backtrack_points: RefCell<Vec<BacktrackPoint<'p, B>>>,
+    ///
+    /// New backtrack points are pushed onto the back. We resume at the back
+    /// for `ExplorationStrategy::DepthFirst`, or the front for
+    /// `ExplorationStrategy::BreadthFirst`.
+    backtrack_points: RefCell<VecDeque<BacktrackPoint<'p, B>>>,
+    /// Whether we have pushed a solver scope to hold the constraints added
+    /// after a `snapshot()`, or the constraints of a snapshot restored with
+    /// `restore()`. This scope is always underneath the scopes of all the
+    /// backtracking points. This is only used for
+    /// `ExplorationStrategy::DepthFirst`; see notes on `snapshot()`.
+    snapshot_scope: bool,
+    /// Whether a `snapshot()` has ever been taken of this `State`. If so, no
+    /// constraints may be added outside of a solver scope anymore, as
+    /// `restore()` couldn't remove them.
+    snapshot_taken: bool,
     /// Log of the basic blocks which have been executed to get to this point
     path: Vec<PathEntry<'p>>,
     /// Memory watchpoints (segments of memory to log reads/writes of).
@@ -77,6 +97,14 @@ pub struct State<'p, B: Backend> {
     /// backtracking will not touch the set of mem_watchpoints or their
     /// enabled statuses.
     mem_watchpoints: Watchpoints,
+    /// Basic blocks visited so far.
+    ///
+    /// This persists across backtracking (and `restore()`), so that it covers
+    /// all paths explored so far, not just the current one.
+    coverage: CoverageReport<'p>,
+    /// Solver query counts as of the beginning of the current path; see
+    /// `solver_stats()`
+    solver_stats_at_path_start: SolverStats,
     /// Empirically, solving with model-gen enabled can be very slow.
     /// In particular, given a `BV` representing a function pointer, solving for
     /// the concrete function pointer it represents can be slow.
@@ -93,6 +121,59 @@ pub struct State<'p, B: Backend> {
     /// anyway, and function pointers _probably_ resolve to the same value on
     /// multiple paths.
     function_ptr_cache: HashMap<Location<'p>, u64>,
+    /// Nondeterministic values drawn so far on this path, organized by logical
+    /// RNG stream (see `Config.rng_model`). For each stream, the values are in
+    /// the order they were drawn.
+    nondet_values: HashMap<String, Vec<B::BV>>,
+    /// Number of LLVM instructions (including terminators) executed so far on
+    /// this path
+    instructions_executed: u64,
+    /// Pointers which have been passed to `free()` so far on this path, in the
+    /// order they were freed
+    freed_pointers: Vec<B::BV>,
+    /// Name of the RTTI (`type_info`) global for the exception most recently
+    /// thrown with `__cxa_throw()` on this path, if it hasn't been caught and
+    /// finished with yet
+    exception_type_info: Option<String>,
+    /// Calls on this path whose effects were havoc'd (see `havoced_calls()`),
+    /// as (location of the call, name of the called function)
+    havoced_calls: Vec<(Location<'p>, String)>,
+    /// Calls on this path which weren't entered because of
+    /// `Config.max_callstack_depth` (see `truncated_calls()`), as (location of
+    /// the call, name of the called function)
+    truncated_calls: Vec<(Location<'p>, String)>,
+    /// Constraints requested by the currently executing hook via
+    /// `fork_after_hook()`, which haven't been applied yet
+    hook_forks: Vec<B::BV>,
+    /// Memory reads and writes on this path, in order. Only recorded if
+    /// `Config.trace_memory` is enabled.
+    ///
+    /// This is a `RefCell` because reads only have `&self`.
+    memory_trace: RefCell<Vec<MemoryAccess<'p>>>,
+    /// Instructions executed on this path, in order, along with their
+    /// locations. Only recorded if `Config.record_instruction_trace` is
+    /// enabled.
+    instruction_trace: Vec<(Location<'p>, &'p Instruction)>,
+    /// Hits of value-triggered watchpoints on this path, in order.
+    ///
+    /// This is a `RefCell` because `write_without_mut()` only has `&self`.
+    value_watchpoint_hits: RefCell<Vec<ValueWatchpointHit<'p>>>,
+    /// Map from the address of an allocation to the number of bytes of it which
+    /// are valid, for allocations whose valid length is symbolic and may be
+    /// less than their allocated size (see `ParameterVal::Buffer`).
+    ///
+    /// Since allocations are never reused, this persists across backtracking.
+    allocation_valid_bytes: HashMap<u64, B::BV>,
+    /// Map from (function name, block name) to the per-loop bound (from
+    /// `Config.loop_bounds`) of the innermost configured loop containing that
+    /// block. Blocks not in any configured loop don't appear here.
+    loop_bound_by_block: HashMap<(String, Name), usize>,
+    /// Names of the global aliases whose aliasees we are currently in the
+    /// middle of resolving (in `const_to_bv()`). Used to detect cycles of
+    /// aliases, which would otherwise cause infinite recursion.
+    ///
+    /// This is always empty between calls to `const_to_bv()`.
+    aliases_being_resolved: RefCell<HashSet<Name>>,
 }
 
 /// Describes a location in LLVM IR in a format more suitable for printing - for
@@ -149,6 +230,49 @@ impl<'p> LocationDescription<'p> {
     }
 }
 
+/// One memory access recorded in the memory trace.
+/// See [`Config.trace_memory`](config/struct.Config.html#structfield.trace_memory).
+#[derive(Clone, Debug)]
+pub struct MemoryAccess<'p> {
+    /// The instruction which performed the access
+    pub location: Location<'p>,
+    /// The address which was accessed: in hex if it is concrete, or else the
+    /// `Debug` representation of the (symbolic) address
+    pub addr_debug: String,
+    /// Width of the access in bits
+    pub width: u32,
+    /// `true` for a write, `false` for a read
+    pub is_write: bool,
+}
+
+/// Information about an allocation; see
+/// [`State::allocation_info()`](struct.State.html#method.allocation_info).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AllocationInfo {
+    /// Address of the start of the allocation
+    pub base: u64,
+    /// Size of the allocation in bytes, rounded up for allocations which
+    /// aren't a whole number of bytes
+    pub size_bytes: u64,
+    /// `true` if this is the allocation of a global variable or a function (or
+    /// function hook), `false` if it was allocated during execution (e.g., by
+    /// an `alloca` or `malloc()`)
+    pub is_global: bool,
+}
+
+/// One hit of a value-triggered watchpoint.
+/// See [`Watchpoints::watch_for_value()`](watchpoints/struct.Watchpoints.html#method.watch_for_value).
+#[derive(Clone, Debug)]
+pub struct ValueWatchpointHit<'p> {
+    /// Name of the watchpoint which was hit
+    pub name: String,
+    /// The instruction which performed the write
+    pub location: Location<'p>,
+    /// A concrete address at which the write may have stored the watched-for
+    /// value within the watched range
+    pub witness_addr: u64,
+}
+
 /// Describes one segment of a path through the LLVM IR. The "segment" will be
 /// one or more consecutive instructions in a single basic block.
 ///
@@ -349,6 +473,10 @@ struct StackFrame<'p, V: BV> {
     /// This is necessary in the case of (direct or indirect) recursion.
     /// See notes on `VarMap.get_restore_info_for_fn()`.
     restore_info: RestoreInfo<V>,
+    /// If the callee is variadic, these are the arguments which were passed
+    /// beyond the callee's declared (fixed) parameters, in order.
+    /// Empty if the callee is not variadic or no extra arguments were passed.
+    varargs: Vec<V>,
 }
 
 #[derive(Clone)]
@@ -375,6 +503,62 @@ struct BacktrackPoint<'p, B: Backend> {
     /// If we ever revert to this `BacktrackPoint`, we will truncate the `path` to
     /// its first `path_len` entries.
     path_len: usize,
+    /// Nondeterministic values which had been drawn at the `BacktrackPoint`
+    nondet_values: HashMap<String, Vec<B::BV>>,
+    /// Number of instructions which had been executed at the `BacktrackPoint`
+    instructions_executed: u64,
+    /// Pointers which had been freed at the `BacktrackPoint`
+    freed_pointers: Vec<B::BV>,
+    /// `exception_type_info` at the `BacktrackPoint`
+    exception_type_info: Option<String>,
+    /// Length of `havoced_calls` at the `BacktrackPoint`
+    havoced_calls_len: usize,
+    /// Length of `truncated_calls` at the `BacktrackPoint`
+    truncated_calls_len: usize,
+    /// Length of `memory_trace` at the `BacktrackPoint`
+    memory_trace_len: usize,
+    /// Length of `instruction_trace` at the `BacktrackPoint`
+    instruction_trace_len: usize,
+    /// Length of `value_watchpoint_hits` at the `BacktrackPoint`
+    value_watchpoint_hits_len: usize,
+    /// Constraints which had been asserted on the path to the
+    /// `BacktrackPoint` (not including `constraint`). This is only recorded for
+    /// `ExplorationStrategy::BreadthFirst`; see notes on
+    /// `revert_to_backtracking_point()`.
+    path_constraints: Vec<B::BV>,
+}
+
+/// A snapshot of a `State`'s path-specific information, which can be used to
+/// resume execution from that point later; see
+/// [`State::snapshot()`](struct.State.html#method.snapshot).
+#[derive(Clone)]
+pub struct StateSnapshot<'p, B: Backend> {
+    loc: Location<'p>,
+    stack: Vec<StackFrame<'p, B::BV>>,
+    varmap: VarMap<B::BV>,
+    mem: B::Memory,
+    path: Vec<PathEntry<'p>>,
+    nondet_values: HashMap<String, Vec<B::BV>>,
+    instructions_executed: u64,
+    freed_pointers: Vec<B::BV>,
+    exception_type_info: Option<String>,
+    havoced_calls: Vec<(Location<'p>, String)>,
+    truncated_calls: Vec<(Location<'p>, String)>,
+    memory_trace: Vec<MemoryAccess<'p>>,
+    instruction_trace: Vec<(Location<'p>, &'p Instruction)>,
+    value_watchpoint_hits: Vec<ValueWatchpointHit<'p>>,
+    path_constraints: Vec<B::BV>,
+}
+
+impl<'p, B: Backend> fmt::Debug for StateSnapshot<'p, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "<StateSnapshot at {} with {} frames on the callstack>",
+            self.loc.to_string_short_module(),
+            self.stack.len(),
+        )
+    }
 }
 
 impl<'p, B: Backend> fmt::Display for BacktrackPoint<'p, B> {
@@ -407,18 +591,32 @@ where
             pointer_size_bits: project.pointer_size_bits(),
             proj: project,
             varmap: VarMap::new(solver.clone(), config.loop_bound),
-            mem: RefCell::new(Memory::new_uninitialized(
-                solver.clone(),
-                match config.null_pointer_checking {
+            mem: RefCell::new({
+                let null_detection = match config.null_pointer_checking {
+                    _ if config.dry_run => false,
                     NullPointerChecking::Simple => true,
                     NullPointerChecking::SplitPath => true,
                     NullPointerChecking::None => false,
-                },
-                None,
-                project.pointer_size_bits(),
-            )),
+                };
+                match config.memory_cell_bits {
+                    Some(cell_bits) => Memory::new_uninitialized_with_cell_size(
+                        solver.clone(),
+                        null_detection,
+                        None,
+                        project.pointer_size_bits(),
+                        cell_bits,
+                    ),
+                    None => Memory::new_uninitialized(
+                        solver.clone(),
+                        null_detection,
+                        None,
+                        project.pointer_size_bits(),
+                    ),
+                }
+            }),
             alloc: Alloc::new(),
             global_allocations: GlobalAllocations::new(),
+            globals_end: Alloc::ALLOC_START,
             intrinsic_hooks: {
                 let mut intrinsic_hooks = FunctionHooks::new();
                 // we use "function names" that are clearly illegal, as an additional precaution to avoid collisions with actual function names
@@ -430,11 +628,28 @@ where
                 intrinsic_hooks.add("intrinsic: llvm.bswap", &hooks::intrinsics::symex_bswap);
                 intrinsic_hooks.add("intrinsic: llvm.ctlz", &hooks::intrinsics::symex_ctlz);
                 intrinsic_hooks.add("intrinsic: llvm.cttz", &hooks::intrinsics::symex_cttz);
+                intrinsic_hooks.add("intrinsic: llvm.ctpop", &hooks::intrinsics::symex_ctpop);
+                intrinsic_hooks.add(
+                    "intrinsic: llvm.bitreverse",
+                    &hooks::intrinsics::symex_bitreverse,
+                );
+                intrinsic_hooks.add("intrinsic: llvm.fshl", &hooks::intrinsics::symex_fshl);
+                intrinsic_hooks.add("intrinsic: llvm.fshr", &hooks::intrinsics::symex_fshr);
+                intrinsic_hooks.add("intrinsic: llvm.abs", &hooks::intrinsics::symex_abs);
+                intrinsic_hooks.add("intrinsic: llvm.smax", &hooks::intrinsics::symex_smax);
+                intrinsic_hooks.add("intrinsic: llvm.smin", &hooks::intrinsics::symex_smin);
+                intrinsic_hooks.add("intrinsic: llvm.umax", &hooks::intrinsics::symex_umax);
+                intrinsic_hooks.add("intrinsic: llvm.umin", &hooks::intrinsics::symex_umin);
                 intrinsic_hooks.add(
                     "intrinsic: llvm.objectsize",
                     &hooks::intrinsics::symex_objectsize,
                 );
                 intrinsic_hooks.add("intrinsic: llvm.assume", &hooks::intrinsics::symex_assume);
+                intrinsic_hooks.add(
+                    "intrinsic: llvm.va_start",
+                    &hooks::intrinsics::symex_va_start,
+                );
+                intrinsic_hooks.add("intrinsic: llvm.va_copy", &hooks::intrinsics::symex_va_copy);
                 intrinsic_hooks.add(
                     "intrinsic: llvm.uadd.with.overflow",
                     &hooks::intrinsics::symex_uadd_with_overflow,
@@ -480,13 +695,31 @@ where
                     &function_hooks::generic_stub_hook,
                 );
                 intrinsic_hooks.add("intrinsic: abort_hook", &function_hooks::abort_hook);
+                intrinsic_hooks.add("intrinsic: rng_draw", &hooks::nondet::rng_draw_hook);
                 intrinsic_hooks
             },
             stack: Vec::new(),
-            backtrack_points: RefCell::new(Vec::new()),
+            backtrack_points: RefCell::new(VecDeque::new()),
+            snapshot_scope: false,
+            snapshot_taken: false,
             path: Vec::new(),
             mem_watchpoints: config.initial_mem_watchpoints.clone().into_iter().collect(),
+            coverage: CoverageReport::new(project),
+            solver_stats_at_path_start: solver.stats(),
             function_ptr_cache: HashMap::new(),
+            nondet_values: HashMap::new(),
+            instructions_executed: 0,
+            freed_pointers: Vec::new(),
+            exception_type_info: None,
+            havoced_calls: Vec::new(),
+            truncated_calls: Vec::new(),
+            hook_forks: Vec::new(),
+            memory_trace: RefCell::new(Vec::new()),
+            instruction_trace: Vec::new(),
+            value_watchpoint_hits: RefCell::new(Vec::new()),
+            allocation_valid_bytes: HashMap::new(),
+            loop_bound_by_block: Self::loop_bound_by_block(project, &config),
+            aliases_being_resolved: RefCell::new(HashSet::new()),
 
             // listed last (out-of-order) so that they can be used above but moved in now
             solver,
@@ -523,27 +756,31 @@ where
             // exactly once, and the order doesn't matter, so we simply process
             // definitions, since each global variable must have exactly one
             // definition. Hence the `filter()` above.
-            if let Type::PointerType { pointee_type, .. } = var.ty.as_ref() {
-                let size_bits = state.size_in_bits(&pointee_type).expect(
-                    "Global variable has a struct type which is opaque in the entire Project",
+            //
+            // We get the global's size from the type of its initializer, rather
+            // than from the pointee type of `var.ty`, so that we don't depend on
+            // pointers having pointee types (which opaque pointers don't).
+            let initializer = var
+                .initializer
+                .as_ref()
+                .expect("we filtered for globals with initializers above");
+            let size_bits = state
+                .size_in_bits(&module.type_of(initializer.as_ref()))
+                .expect("Global variable has a struct type which is opaque in the entire Project");
+            let size_bits = if size_bits == 0 {
+                debug!(
+                    "Global {:?} has size 0 bits; allocating 8 bits for it anyway",
+                    var.name
                 );
-                let size_bits = if size_bits == 0 {
-                    debug!(
-                        "Global {:?} has size 0 bits; allocating 8 bits for it anyway",
-                        var.name
-                    );
-                    8
-                } else {
-                    size_bits
-                };
-                let addr = state.allocate(size_bits as u64);
-                debug!("Allocated {:?} at {:?}", var.name, addr);
-                state
-                    .global_allocations
-                    .allocate_global_var(var, module, addr);
+                8
             } else {
-                panic!("Global variable has non-pointer type {:?}", &var.ty);
-            }
+                size_bits
+            };
+            let addr = state.allocate(size_bits as u64);
+            debug!("Allocated {:?} at {:?}", var.name, addr);
+            state
+                .global_allocations
+                .allocate_global_var(var, module, addr);
         }
         // We also have to allocate (at least a tiny bit of) memory for each
         // `Function`, just so that we can have pointers to those `Function`s.
@@ -569,7 +806,13 @@ where
                 .global_allocations
                 .allocate_function_hook((*hook).clone(), addr, addr_bv);
         }
+        state.globals_end = state.alloc.next_addr();
         debug!("Done allocating global variables and functions");
+        if state.config.exploration_strategy == ExplorationStrategy::BreadthFirst {
+            // all path-specific constraints will live in this one solver scope;
+            // see notes on `revert_to_backtracking_point()`
+            state.solver.push(1);
+        }
         state
     }
 
@@ -587,6 +830,45 @@ where
         cloned
     }
 
+    /// Add the given constraint on the current path.
+    ///
+    /// This is equivalent to `constraint.assert()`. Either way, the solver
+    /// records the constraint (see
+    /// [`SolverRef::assertions()`](backend/trait.SolverRef.html#tymethod.assertions)),
+    /// so that it can be re-established if we later resume execution at a
+    /// backtracking point on this path with
+    /// `ExplorationStrategy::BreadthFirst`, or at a snapshot.
+    pub fn assert(&self, constraint: &B::BV) -> Result<()> {
+        constraint.assert()
+    }
+
+    /// Serialize all of the constraints on the current path to an SMT-LIB2
+    /// script (declarations and `assert`s), e.g., for loading into an external
+    /// solver to investigate why a path is unsat.
+    ///
+    /// Symbolic variables appear under the names they were created with; in
+    /// particular, the function's parameters appear under their LLVM names.
+    pub fn constraints_to_smtlib(&self) -> Result<String> {
+        // Boolector's dump doesn't include constraints asserted inside a solver
+        // scope, and most of the path's constraints live in scopes (see notes
+        // on `revert_to_backtracking_point()`). So we dump from a duplicate
+        // solver, with all the scopes popped and the path's constraints
+        // asserted at the top level instead.
+        let solver = self.solver.duplicate();
+        let scopes = self.num_path_scopes();
+        if scopes > 0 {
+            solver.pop(scopes as u32);
+        }
+        let in_effect = solver.assertions().len();
+        for constraint in self.solver.assertions().iter().skip(in_effect) {
+            solver
+                .match_bv(constraint)
+                .expect("Failed to find the constraint in the duplicated solver")
+                .assert()?;
+        }
+        Ok(solver.print_constraints())
+    }
+
     /// Returns `true` if current constraints are satisfiable, `false` if not.
     ///
     /// Returns `Error::SolverError` if the query failed (e.g., was interrupted or timed out).
@@ -660,19 +942,79 @@ where
             Some(bstr) => Ok(Some(BVSolution::from_01x_str(bstr))),
             None => {
                 warn!("A call to get_a_solution_for_bv() is resulting in a call to sat() with model generation enabled. Experimentally, these types of calls can be very slow. The BV is {:?}", bv);
-                self.solver.set_opt(BtorOption::ModelGen(ModelGen::All));
+                self.solver.set_model_gen(true);
                 let solution = if self.sat()? {
                     bv.get_a_solution().map(Some)
                 } else {
                     Ok(None)
                 };
-                self.solver
-                    .set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+                self.solver.set_model_gen(false);
                 solution
             },
         }
     }
 
+    /// Get one possible concrete value for each of the `BV`s, all from the same
+    /// solution, so that the values are mutually consistent. (Separate calls to
+    /// `get_a_solution_for_bv()` may each use a different solution.)
+    ///
+    /// The returned values are in the same order as `bvs`.
+    /// Returns `Ok(None)` if no possible solution, or `Error::SolverError` if the solver query failed.
+    pub fn get_a_solution_for_bvs(&self, bvs: &[&B::BV]) -> Result<Option<Vec<BVSolution>>> {
+        // first check if all the `bvs` are constants, if so, we can avoid a solve
+        if let Some(solutions) = bvs
+            .iter()
+            .map(|bv| bv.as_binary_str().map(BVSolution::from_01x_str))
+            .collect::<Option<Vec<_>>>()
+        {
+            return Ok(Some(solutions));
+        }
+        warn!("A call to get_a_solution_for_bvs() is resulting in a call to sat() with model generation enabled. Experimentally, these types of calls can be very slow. The BVs are {:?}", bvs);
+        self.solver.set_model_gen(true);
+        let solutions = if self.sat()? {
+            // all of these come from the model generated by the single `sat()` above
+            bvs.iter()
+                .map(|bv| bv.get_a_solution())
+                .collect::<Result<Vec<_>>>()
+                .map(Some)
+        } else {
+            Ok(None)
+        };
+        self.solver.set_model_gen(false);
+        solutions
+    }
+
+    /// Get one possible concrete value for each of the `num_bytes` bytes of
+    /// memory starting at `addr`, all from the same solution (as with
+    /// `get_a_solution_for_bvs()`), e.g., to reconstruct the exact contents of
+    /// a buffer which triggers a bug. If `addr` is symbolic, the bytes are
+    /// those at the address chosen by that same solution.
+    ///
+    /// Returns `Ok(None)` if no possible solution, or `Error::SolverError` if the solver query failed.
+    ///
+    /// This never adds constraints, and never reports
+    /// `Error::UninitializedRead`: bytes which were never written may have
+    /// any value.
+    pub fn get_solution_for_memory_region(
+        &self,
+        addr: &B::BV,
+        num_bytes: usize,
+    ) -> Result<Option<Vec<u8>>> {
+        let mut bytes = Vec::with_capacity(num_bytes);
+        let mut byte_addr = addr.clone();
+        for _ in 0 .. num_bytes {
+            bytes.push(self.read_maybe_uninitialized(&byte_addr, 8)?);
+            byte_addr = byte_addr.inc();
+        }
+        let bytes: Vec<&B::BV> = bytes.iter().collect();
+        Ok(self.get_a_solution_for_bvs(&bytes)?.map(|solutions| {
+            solutions
+                .iter()
+                .map(|byte| byte.as_u64().unwrap() as u8)
+                .collect()
+        }))
+    }
+
     /// Get one possible concrete value for the given IR `Name` (from the given `Function` name).
     /// Returns `Ok(None)` if no possible solution, or `Error::SolverError` if the solver query failed.
     #[allow(clippy::ptr_arg)] // as of this writing, clippy warns that the &String argument should be &str; but it actually needs to be &String here
@@ -703,6 +1045,22 @@ where
         solver_utils::get_possible_solutions_for_bv(self.solver.clone(), bv, n)
     }
 
+    /// Get `count` distinct possible solutions for the `BV`, in ascending
+    /// (unsigned) order, e.g. for generating many concrete inputs from one
+    /// symbolic value.
+    ///
+    /// Unlike `get_possible_solutions_for_bv()`, which stops once it knows
+    /// there are more than `n` solutions, this keeps solving until it has
+    /// `count` solutions. It returns fewer than `count` only if there are fewer
+    /// than `count` possible solutions (in particular, none if the current set
+    /// of constraints is unsatisfiable).
+    ///
+    /// Only returns `Err` if a solver query itself fails. Panics if the `BV` is
+    /// wider than 64 bits.
+    pub fn sample_solutions(&self, bv: &B::BV, count: usize) -> Result<Vec<u64>> {
+        solver_utils::sample_solutions_for_bv_as_u64(self.solver.clone(), bv, count)
+    }
+
     /// Get a description of the possible solutions for the given IR `Name` (from the given `Function` name).
     ///
     /// `n`: Maximum number of distinct solutions to check for.
@@ -849,8 +1207,54 @@ where
     ///
     /// Also, we assume that no two `Function`s share the same name.
     pub fn new_bv_with_name(&mut self, name: Name, bits: u32) -> Result<B::BV> {
-        self.varmap
-            .new_bv_with_name(self.cur_loc.func.name.clone(), name, bits)
+        let loop_bound = self.current_loop_bound();
+        self.varmap.new_bv_with_name_and_max_versions(
+            self.cur_loc.func.name.clone(),
+            &self.cur_loc.bb.name,
+            name,
+            bits,
+            loop_bound,
+        )
+    }
+
+    /// Create a new (unconstrained) `BV` representing the next value drawn from
+    /// the logical RNG stream named `stream`.
+    ///
+    /// Hooks for nondeterministic functions (e.g., `rand()`, or functions that
+    /// read the environment) can use this instead of `new_bv_with_name()` so
+    /// that the values they produce can later be retrieved with
+    /// [`nth_nondet_value()`](struct.State.html#method.nth_nondet_value).
+    /// Calls to functions named in `Config.rng_model` use this automatically.
+    pub fn new_nondet_value(&mut self, stream: &str, bits: u32) -> B::BV {
+        let values = self.nondet_values.entry(stream.to_owned()).or_default();
+        let bv = B::BV::new(
+            self.solver.clone(),
+            bits,
+            Some(&format!("{}_nondet_{}", stream, values.len())),
+        );
+        debug!(
+            "Drawing nondet value #{} from stream {:?}: {:?}",
+            values.len(),
+            stream,
+            bv
+        );
+        values.push(bv.clone());
+        bv
+    }
+
+    /// Get the `BV` representing the `n`th value drawn from the logical RNG
+    /// stream named `stream` on the current path. `n` is 0-indexed, so `n == 2`
+    /// refers to the third value drawn from the stream.
+    ///
+    /// The returned `BV` can be constrained like any other, e.g., to pin a
+    /// particular draw to a concrete value when constructing a reproducer.
+    ///
+    /// Returns `None` if fewer than `n + 1` values have been drawn from
+    /// `stream` on the current path.
+    pub fn nth_nondet_value(&self, stream: &str, n: usize) -> Option<&B::BV> {
+        self.nondet_values
+            .get(stream)
+            .and_then(|values| values.get(n))
     }
 
     /// Assign the given `BV` to the given `Name` (in the current function).
@@ -866,8 +1270,70 @@ where
     /// of the `BV` would exceed `max_versions_of_name` -- see
     /// [`Config`](struct.Config.html).)
     pub fn assign_bv_to_name(&mut self, name: Name, bv: B::BV) -> Result<()> {
-        self.varmap
-            .assign_bv_to_name(self.cur_loc.func.name.clone(), name, bv)
+        let loop_bound = self.current_loop_bound();
+        self.varmap.assign_bv_to_name_with_max_versions(
+            self.cur_loc.func.name.clone(),
+            &self.cur_loc.bb.name,
+            name,
+            bv,
+            loop_bound,
+        )
+    }
+
+    /// Get the per-loop bound (from `Config.loop_bounds`) which applies at the
+    /// current location, if any. This is the bound of the innermost configured
+    /// loop containing either the current location or one of the callsites on
+    /// the callstack.
+    ///
+    /// If the current function is in `Config.loop_bound_exempt_functions`, the
+    /// bound is effectively unlimited.
+    fn current_loop_bound(&self) -> Option<usize> {
+        if self
+            .config
+            .loop_bound_exempt_functions
+            .contains(&self.cur_loc.func.name)
+        {
+            return Some(usize::MAX);
+        }
+        if self.loop_bound_by_block.is_empty() {
+            return None;
+        }
+        std::iter::once(&self.cur_loc)
+            .chain(self.stack.iter().rev().map(|frame| &frame.callsite.loc))
+            .find_map(|loc| {
+                self.loop_bound_by_block
+                    .get(&(loc.func.name.clone(), loc.bb.name.clone()))
+                    .copied()
+            })
+    }
+
+    /// Compute the `loop_bound_by_block` map for the given `Config`
+    fn loop_bound_by_block(
+        project: &'p Project,
+        config: &Config<'p, B>,
+    ) -> HashMap<(String, Name), usize> {
+        let mut loops = Vec::new();
+        for ((funcname, header), bound) in &config.loop_bounds {
+            match project.get_func_by_name(funcname) {
+                Some((func, _)) => {
+                    loops.push((funcname, natural_loop_blocks(func, header), *bound))
+                },
+                None => warn!(
+                    "Config.loop_bounds refers to function {:?}, which wasn't found in the Project",
+                    funcname
+                ),
+            }
+        }
+        // Process larger loops first, so that for nested loops, the innermost
+        // loop's bound is the one which ends up in the map
+        loops.sort_by_key(|(_, blocks, _)| std::cmp::Reverse(blocks.len()));
+        let mut map = HashMap::new();
+        for (funcname, blocks, bound) in loops {
+            for bbname in blocks {
+                map.insert((funcname.clone(), bbname.clone()), bound);
+            }
+        }
+        map
     }
 
     /// Record the result of `thing` to be `resultval`.
@@ -1021,50 +1487,42 @@ where
                     .iter()
                     .find(|a| &a.name == name)
                 {
-                    self.const_to_bv(&alias.aliasee)
+                    // While initializers which refer to each other's addresses
+                    // are handled by the `.initialized` flag above, a cycle of
+                    // aliases has no well-defined value at all. The LLVM
+                    // verifier rejects such cycles, but we may be given
+                    // unverified (e.g., hand-written) bitcode, so we check for
+                    // them here rather than recursing forever.
+                    if !self
+                        .aliases_being_resolved
+                        .borrow_mut()
+                        .insert(name.clone())
+                    {
+                        return Err(Error::OtherError(format!(
+                            "const_to_bv: global alias {:?} is part of a cycle of aliases",
+                            name
+                        )));
+                    }
+                    let result = self.const_to_bv(&alias.aliasee);
+                    self.aliases_being_resolved.borrow_mut().remove(name);
+                    result
                 } else {
                     Err(Error::OtherError(format!("const_to_bv: GlobalReference to {:?} which was not found (current module is {:?})", name, &self.cur_loc.module.name)))
                 }
             },
-            Constant::Add(a) => Ok(self
-                .const_to_bv(&a.operand0)?
-                .add(&self.const_to_bv(&a.operand1)?)),
-            Constant::Sub(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .sub(&self.const_to_bv(&s.operand1)?)),
-            Constant::Mul(m) => Ok(self
-                .const_to_bv(&m.operand0)?
-                .mul(&self.const_to_bv(&m.operand1)?)),
-            Constant::UDiv(u) => Ok(self
-                .const_to_bv(&u.operand0)?
-                .udiv(&self.const_to_bv(&u.operand1)?)),
-            Constant::SDiv(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .sdiv(&self.const_to_bv(&s.operand1)?)),
-            Constant::URem(u) => Ok(self
-                .const_to_bv(&u.operand0)?
-                .urem(&self.const_to_bv(&u.operand1)?)),
-            Constant::SRem(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .srem(&self.const_to_bv(&s.operand1)?)),
-            Constant::And(a) => Ok(self
-                .const_to_bv(&a.operand0)?
-                .and(&self.const_to_bv(&a.operand1)?)),
-            Constant::Or(o) => Ok(self
-                .const_to_bv(&o.operand0)?
-                .or(&self.const_to_bv(&o.operand1)?)),
-            Constant::Xor(x) => Ok(self
-                .const_to_bv(&x.operand0)?
-                .xor(&self.const_to_bv(&x.operand1)?)),
-            Constant::Shl(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .sll(&self.const_to_bv(&s.operand1)?)),
-            Constant::LShr(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .srl(&self.const_to_bv(&s.operand1)?)),
-            Constant::AShr(s) => Ok(self
-                .const_to_bv(&s.operand0)?
-                .sra(&self.const_to_bv(&s.operand1)?)),
+            Constant::Add(a) => self.const_binop(&a.operand0, &a.operand1, |a, b| a.add(b)),
+            Constant::Sub(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.sub(b)),
+            Constant::Mul(m) => self.const_binop(&m.operand0, &m.operand1, |a, b| a.mul(b)),
+            Constant::UDiv(u) => self.const_binop(&u.operand0, &u.operand1, |a, b| a.udiv(b)),
+            Constant::SDiv(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.sdiv(b)),
+            Constant::URem(u) => self.const_binop(&u.operand0, &u.operand1, |a, b| a.urem(b)),
+            Constant::SRem(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.srem(b)),
+            Constant::And(a) => self.const_binop(&a.operand0, &a.operand1, |a, b| a.and(b)),
+            Constant::Or(o) => self.const_binop(&o.operand0, &o.operand1, |a, b| a.or(b)),
+            Constant::Xor(x) => self.const_binop(&x.operand0, &x.operand1, |a, b| a.xor(b)),
+            Constant::Shl(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.sll(b)),
+            Constant::LShr(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.srl(b)),
+            Constant::AShr(s) => self.const_binop(&s.operand0, &s.operand1, |a, b| a.sra(b)),
             Constant::ExtractElement(ee) => match &ee.index.as_ref() {
                 Constant::Int { value: index, .. } => match &ee.vector.as_ref() {
                     Constant::Vector(els) => {
@@ -1165,25 +1623,27 @@ where
             },
             Constant::PtrToInt(pti) => {
                 let bv = self.const_to_bv(&pti.operand)?;
-                assert_eq!(
-                    bv.get_width(),
-                    self.size_in_bits(&pti.to_type)
-                        .ok_or_else(|| Error::MalformedInstruction(
-                            "PtrToInt result type is opaque struct type".into()
-                        ))?
-                );
-                Ok(bv) // just a cast, it's the same bits underneath
+                let to_size_bits = self.size_in_bits(&pti.to_type).ok_or_else(|| {
+                    Error::MalformedInstruction("PtrToInt result type is opaque struct type".into())
+                })?;
+                let num_elements = match pti.to_type.as_ref() {
+                    Type::VectorType { num_elements, .. } => Some(*num_elements as u32),
+                    _ => None,
+                };
+                // the same bits underneath, except truncated or zero-extended if the integer type isn't pointer-sized
+                symex::ptr_int_cast(&bv, to_size_bits, num_elements)
             },
             Constant::IntToPtr(itp) => {
                 let bv = self.const_to_bv(&itp.operand)?;
-                assert_eq!(
-                    bv.get_width(),
-                    self.size_in_bits(&itp.to_type)
-                        .ok_or_else(|| Error::MalformedInstruction(
-                            "IntToPtr result type is opaque struct type".into()
-                        ))?
-                );
-                Ok(bv) // just a cast, it's the same bits underneath
+                let to_size_bits = self.size_in_bits(&itp.to_type).ok_or_else(|| {
+                    Error::MalformedInstruction("IntToPtr result type is opaque struct type".into())
+                })?;
+                let num_elements = match itp.to_type.as_ref() {
+                    Type::VectorType { num_elements, .. } => Some(*num_elements as u32),
+                    _ => None,
+                };
+                // the same bits underneath, except truncated or zero-extended if the integer type isn't pointer-sized
+                symex::ptr_int_cast(&bv, to_size_bits, num_elements)
             },
             Constant::BitCast(bc) => {
                 let bv = self.const_to_bv(&bc.operand)?;
@@ -1207,33 +1667,77 @@ where
                 );
                 Ok(bv) // just a cast, it's the same bits underneath
             },
-            Constant::ICmp(icmp) => {
-                let bv0 = self.const_to_bv(&icmp.operand0)?;
-                let bv1 = self.const_to_bv(&icmp.operand1)?;
-                Ok(match icmp.predicate {
-                    IntPredicate::EQ => bv0._eq(&bv1),
-                    IntPredicate::NE => bv0._ne(&bv1),
-                    IntPredicate::UGT => bv0.ugt(&bv1),
-                    IntPredicate::UGE => bv0.ugte(&bv1),
-                    IntPredicate::ULT => bv0.ult(&bv1),
-                    IntPredicate::ULE => bv0.ulte(&bv1),
-                    IntPredicate::SGT => bv0.sgt(&bv1),
-                    IntPredicate::SGE => bv0.sgte(&bv1),
-                    IntPredicate::SLT => bv0.slt(&bv1),
-                    IntPredicate::SLE => bv0.slte(&bv1),
-                })
-            },
+            Constant::ICmp(icmp) => self.const_binop(&icmp.operand0, &icmp.operand1, |bv0, bv1| {
+                match icmp.predicate {
+                    IntPredicate::EQ => bv0._eq(bv1),
+                    IntPredicate::NE => bv0._ne(bv1),
+                    IntPredicate::UGT => bv0.ugt(bv1),
+                    IntPredicate::UGE => bv0.ugte(bv1),
+                    IntPredicate::ULT => bv0.ult(bv1),
+                    IntPredicate::ULE => bv0.ulte(bv1),
+                    IntPredicate::SGT => bv0.sgt(bv1),
+                    IntPredicate::SGE => bv0.sgte(bv1),
+                    IntPredicate::SLT => bv0.slt(bv1),
+                    IntPredicate::SLE => bv0.slte(bv1),
+                }
+            }),
             Constant::Select(s) => {
                 let b = self.const_to_bv(&s.condition)?;
-                match b.as_bool() {
-                    None => Err(Error::MalformedInstruction(
-                        "Constant::Select: Expected a constant condition".to_owned(),
-                    )),
-                    Some(true) => self.const_to_bv(&s.true_value),
-                    Some(false) => self.const_to_bv(&s.false_value),
+                match self.type_of(&s.condition).as_ref() {
+                    Type::VectorType { num_elements, .. } => {
+                        // select elementwise
+                        let num_elements = *num_elements as u32;
+                        let true_value = self.const_to_bv(&s.true_value)?;
+                        let false_value = self.const_to_bv(&s.false_value)?;
+                        let el_size = true_value.get_width() / num_elements;
+                        (0 .. num_elements)
+                            .map(|i| {
+                                let (high, low) = ((i + 1) * el_size - 1, i * el_size);
+                                b.slice(i, i).cond_bv(
+                                    &true_value.slice(high, low),
+                                    &false_value.slice(high, low),
+                                )
+                            })
+                            .reduce(|a, b| b.concat(&a))
+                            .ok_or_else(|| {
+                                Error::MalformedInstruction(
+                                    "Constant::Select on a vector with 0 elements".to_owned(),
+                                )
+                            })
+                    },
+                    _ => match b.as_bool() {
+                        Some(true) => self.const_to_bv(&s.true_value),
+                        Some(false) => self.const_to_bv(&s.false_value),
+                        // e.g., a condition comparing addresses which the solver doesn't simplify
+                        None => Ok(b.cond_bv(
+                            &self.const_to_bv(&s.true_value)?,
+                            &self.const_to_bv(&s.false_value)?,
+                        )),
+                    },
                 }
             },
-            _ => unimplemented!("const_to_bv for {:?}", c),
+            _ => Err(Error::UnsupportedInstruction(format!(
+                "const_to_bv for {:?}",
+                c
+            ))),
+        }
+    }
+
+    /// Apply the given binary scalar operation to the `BV`s for the constants
+    /// `op0` and `op1`, elementwise if they are vectors
+    fn const_binop(
+        &self,
+        op0: &Constant,
+        op1: &Constant,
+        op: impl for<'a> Fn(&'a B::BV, &'a B::BV) -> B::BV,
+    ) -> Result<B::BV> {
+        let bv0 = self.const_to_bv(op0)?;
+        let bv1 = self.const_to_bv(op1)?;
+        match self.type_of(op0).as_ref() {
+            Type::VectorType { num_elements, .. } => {
+                symex::binary_on_vector(&bv0, &bv1, *num_elements as u32, op)
+            },
+            _ => Ok(op(&bv0, &bv1)),
         }
     }
 
@@ -1401,7 +1905,8 @@ where
     /// Given a `BV`, interpret it as a function pointer, and return a
     /// description of the possible `Function`s which it would point to.
     ///
-    /// `n`: Maximum number of distinct `Callable`s to check for.
+    /// `n`: Maximum number of distinct `Callable`s to check for (for calls,
+    /// this is `Config.max_fn_ptr_targets`).
     /// If there are more than `n` possible `Callable`s, this returns a
     /// `PossibleSolutions::AtLeast` with `n+1` `Callable`s.
     ///
@@ -1460,7 +1965,7 @@ where
                     .get_func_for_address(addr, self.cur_loc.module)
                     .ok_or_else(|| Error::FailedToResolveFunctionPointer(addr))
             })
-            .collect::<Result<HashSet<_>>>()?;
+            .collect::<Result<IndexSet<_>>>()?;
         if callables.len() > n {
             Ok(PossibleSolutions::AtLeast(callables))
         } else {
@@ -1491,6 +1996,15 @@ where
             .get_function_hook_address(self.config.function_hooks.get_hook_for(funcname)?)
     }
 
+    /// Get a pointer to the given `Callable`, as seen from the current module.
+    ///
+    /// Returns `None` if the `Callable` has not been allocated an address.
+    pub(crate) fn get_pointer_to_callable(&self, callable: &Callable<'p, B>) -> Option<B::BV> {
+        self.global_allocations
+            .get_address_of_callable(callable, self.cur_loc.module)
+            .map(|addr| self.bv_from_u64(addr, self.pointer_size_bits))
+    }
+
     /// Get a `Function` by name. The name must be the fully-mangled function
     /// name, as it appears in the LLVM. The name will be resolved in the current
     /// module; this means that it will first look for a module-private (e.g., C
@@ -1519,7 +2033,59 @@ where
 
     /// Read a value `bits` bits long from memory at `addr`.
     /// Note that `bits` can be arbitrarily large.
+    ///
+    /// If `Config.flag_uninitialized_reads` is enabled, this returns
+    /// `Error::UninitializedRead` if any of the memory being read was never
+    /// written.
     pub fn read(&self, addr: &B::BV, bits: u32) -> Result<B::BV> {
+        self.read_impl(addr, bits, self.config.flag_uninitialized_reads)
+    }
+
+    /// For internal use: like `read()`, but never reports
+    /// `Error::UninitializedRead`. Operations which merely copy memory, such as
+    /// `memcpy()` or `realloc()`, use this, as copying uninitialized bytes is
+    /// not itself a use of them.
+    pub(crate) fn read_maybe_uninitialized(&self, addr: &B::BV, bits: u32) -> Result<B::BV> {
+        self.read_impl(addr, bits, false)
+    }
+
+    /// Read the NUL-terminated C string at `ptr` into a `String`, e.g. for
+    /// logging. Reads bytes until a NUL byte, or until `max_len` bytes have
+    /// been read, in which case the `String` holds just those `max_len`
+    /// bytes. The NUL terminator is not included.
+    ///
+    /// Returns `Ok(None)` if any of the bytes (or the position of the NUL
+    /// terminator) isn't uniquely determined under the current constraints.
+    /// Bytes which aren't valid UTF-8 are replaced with U+FFFD, as in
+    /// `String::from_utf8_lossy()`.
+    ///
+    /// This never adds constraints, and never reports
+    /// `Error::UninitializedRead`.
+    pub fn read_c_string(&self, ptr: &B::BV, max_len: usize) -> Result<Option<String>> {
+        let mut bytes = Vec::new();
+        let mut addr = ptr.clone();
+        for _ in 0 .. max_len {
+            let byte = self.read_maybe_uninitialized(&addr, 8)?;
+            let byte = match byte.as_u64() {
+                Some(byte) => byte as u8,
+                None => match self.get_possible_solutions_for_bv(&byte, 1)? {
+                    PossibleSolutions::Exactly(solutions) if solutions.len() == 1 => {
+                        solutions.iter().next().unwrap().as_u64().unwrap() as u8
+                    },
+                    _ => return Ok(None),
+                },
+            };
+            if byte == 0 {
+                break;
+            }
+            bytes.push(byte);
+            addr = addr.inc();
+        }
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    fn read_impl(&self, addr: &B::BV, bits: u32, check_initialized: bool) -> Result<B::BV> {
+        self.apply_symbolic_address_policy(addr)?;
         let retval = match self.mem.borrow().read(addr, bits) {
             Ok(val) => val,
             e @ Err(Error::NullPointerDereference) => {
@@ -1536,6 +2102,10 @@ where
             },
             e @ Err(_) => return e, // propagate any other kind of error
         };
+        self.record_memory_access(addr, bits, false);
+        if check_initialized && !self.mem.borrow().is_initialized(addr, bits) {
+            return Err(Error::UninitializedRead(self.cur_loc.source_loc.cloned()));
+        }
         for (name, watchpoint) in self.mem_watchpoints.get_triggered_watchpoints(addr, bits)? {
             let pretty_loc = if self.config.print_module_name {
                 self.cur_loc.to_string_with_module()
@@ -1546,10 +2116,55 @@ where
                 "Memory watchpoint {:?} {} read by {{{}}}",
                 name, watchpoint, pretty_loc
             );
+            self.run_watchpoint_callback(name, false, addr, &retval)?;
         }
         Ok(retval)
     }
 
+    /// Before a memory access at `addr`, concretize `addr` as directed by
+    /// `Config.symbolic_address_policy`
+    fn apply_symbolic_address_policy(&self, addr: &B::BV) -> Result<()> {
+        let max_addrs = match self.config.symbolic_address_policy {
+            SymbolicAddressPolicy::FullySymbolic => return Ok(()),
+            SymbolicAddressPolicy::ConcretizeToSingle => 1,
+            SymbolicAddressPolicy::ConcretizeToN(n) => n.max(1),
+        };
+        if addr.as_u64().is_some() {
+            return Ok(()); // already constant
+        }
+        let (mut addrs, exact): (Vec<u64>, bool) = match self
+            .get_possible_solutions_for_bv(addr, max_addrs)?
+            .as_u64_solutions()
+        {
+            Some(PossibleSolutions::Exactly(v)) => (v.into_iter().collect(), true),
+            Some(PossibleSolutions::AtLeast(v)) => (v.into_iter().collect(), false),
+            None => panic!("Address wider than 64 bits"),
+        };
+        match addrs.len() {
+            0 => return Err(Error::Unsat),
+            1 if exact => return Ok(()), // the address is already fully determined
+            _ => {},
+        }
+        // for determinism, prefer the lowest addresses
+        addrs.sort_unstable();
+        addrs.truncate(max_addrs);
+        if addrs.len() > 1 {
+            debug!(
+                "Concretizing a symbolic address to {} possible values, splitting paths",
+                addrs.len()
+            );
+        }
+        // save backtracking points to re-execute the current instruction with
+        // each of the other addresses, then continue with the first
+        for &other_addr in &addrs[1 ..] {
+            self.save_backtracking_point_at_location(
+                self.cur_loc.clone(),
+                addr._eq(&self.bv_from_u64(other_addr, addr.get_width())),
+            );
+        }
+        self.assert(&addr._eq(&self.bv_from_u64(addrs[0], addr.get_width())))
+    }
+
     /// Write a value into memory at `addr`.
     /// Note that `val` can be an arbitrarily large bitvector.
     pub fn write(&mut self, addr: &B::BV, val: B::BV) -> Result<()> {
@@ -1562,8 +2177,9 @@ where
     /// watchpoint checks by calling `self.mem.borrow_mut()` directly, so we
     /// have this
     fn write_without_mut(&self, addr: &B::BV, val: B::BV) -> Result<()> {
+        self.apply_symbolic_address_policy(addr)?;
         let write_width = val.get_width();
-        let result = self.mem.borrow_mut().write(addr, val);
+        let result = self.mem.borrow_mut().write(addr, val.clone());
         // we do this awkward `let result` / `match result` because it forces
         // the mutable borrow of self.mem to end, which is necessary because
         // save_backtracking_point_at_location requires a borrow of self.mem
@@ -1583,6 +2199,7 @@ where
             },
             e @ Err(_) => return e, // propagate any other kind of error
         };
+        self.record_memory_access(addr, write_width, true);
         for (name, watchpoint) in self
             .mem_watchpoints
             .get_triggered_watchpoints(addr, write_width)?
@@ -1606,6 +2223,27 @@ where
                 "Memory watchpoint {:?} {} written by {{{}}}; new value is {:?}",
                 name, watchpoint, pretty_loc, new_value
             );
+            self.run_watchpoint_callback(name, true, addr, &val)?;
+        }
+        for (name, watchpoint, witness_addr) in
+            self.mem_watchpoints.get_value_watchpoint_hits(addr, &val)?
+        {
+            let pretty_loc = if self.config.print_module_name {
+                self.cur_loc.to_string_with_module()
+            } else {
+                self.cur_loc.to_string_no_module()
+            };
+            info!(
+                "Memory watchpoint {:?} {} may be hit by {{{}}}, e.g., by a write to {:#x}",
+                name, watchpoint, pretty_loc, witness_addr
+            );
+            self.value_watchpoint_hits
+                .borrow_mut()
+                .push(ValueWatchpointHit {
+                    name: name.clone(),
+                    location: self.cur_loc.clone(),
+                    witness_addr,
+                });
         }
         Ok(())
     }
@@ -1749,11 +2387,44 @@ where
     /// `false`.
     ///
     /// When any watched memory is read or written to, an INFO-level log message
-    /// will be generated.
+    /// will be generated, and the watchpoint's callback (if any) will be run;
+    /// see [`Callbacks::add_watchpoint_callback()`](callbacks/struct.Callbacks.html#method.add_watchpoint_callback).
     pub fn add_mem_watchpoint(&mut self, name: impl Into<String>, watchpoint: Watchpoint) -> bool {
         self.mem_watchpoints.add(name, watchpoint)
     }
 
+    /// Add a value-triggered memory watchpoint, which fires when a `width`-bit
+    /// value which may be equal to `value` is written anywhere in `addr_range`.
+    /// Hits are logged at INFO level and recorded in
+    /// [`value_watchpoint_hits()`](struct.State.html#method.value_watchpoint_hits).
+    /// See [`Watchpoints::watch_for_value()`](watchpoints/struct.Watchpoints.html#method.watch_for_value)
+    /// for more details.
+    ///
+    /// If a watchpoint (of either kind) with the same name was previously
+    /// added, this will replace that watchpoint and return `true`. Otherwise,
+    /// this will return `false`.
+    pub fn watch_for_value(
+        &mut self,
+        name: impl Into<String>,
+        addr_range: Range<u64>,
+        value: u64,
+        width: u32,
+    ) -> bool {
+        self.mem_watchpoints
+            .watch_for_value(name, addr_range, value, width)
+    }
+
+    /// Get all of the current memory watchpoints (of both kinds).
+    pub fn mem_watchpoints(&self) -> &Watchpoints {
+        &self.mem_watchpoints
+    }
+
+    /// Get the hits of value-triggered watchpoints (see `watch_for_value()`)
+    /// so far on the current path, in order.
+    pub fn value_watchpoint_hits(&self) -> Vec<ValueWatchpointHit<'p>> {
+        self.value_watchpoint_hits.borrow().clone()
+    }
+
     /// Remove the memory watchpoint with the given `name`.
     ///
     /// Returns `true` if the operation was successful, or `false` if no
@@ -1782,12 +2453,71 @@ where
         self.mem_watchpoints.enable(name)
     }
 
+    /// Run the watchpoint callback registered for the memory watchpoint with
+    /// the given `name`, if there is one
+    fn run_watchpoint_callback(
+        &self,
+        name: &str,
+        is_write: bool,
+        addr: &B::BV,
+        value: &B::BV,
+    ) -> Result<()> {
+        match self.config.callbacks.watchpoint_callbacks.get(name) {
+            Some(callback) => callback(self, is_write, addr, value),
+            None => Ok(()),
+        }
+    }
+
     /// Allocate a value of size `bits`; return a pointer to the newly allocated object
     pub fn allocate(&mut self, bits: impl Into<u64>) -> B::BV {
         let raw_ptr = self.alloc.alloc(bits);
         self.bv_from_u64(raw_ptr, self.pointer_size_bits)
     }
 
+    /// Record that the given pointer has been freed on the current path.
+    ///
+    /// Since our allocator never reuses addresses, this doesn't make the memory
+    /// available for reuse; it only records the pointer. If
+    /// `Config.detect_double_free` is enabled, this returns
+    /// `Err(Error::DoubleFree)` if `addr` may be equal to a pointer which was
+    /// already freed on this path.
+    ///
+    /// Freeing a pointer which must be `NULL` is a no-op, as in C.
+    pub fn record_free(&mut self, addr: B::BV) -> Result<()> {
+        if self.bvs_must_be_equal(&addr, &self.zero(addr.get_width()))? {
+            return Ok(());
+        }
+        if self.config.detect_double_free {
+            for freed in &self.freed_pointers {
+                if self.bvs_can_be_equal(&addr, freed)? {
+                    return Err(Error::DoubleFree);
+                }
+            }
+        }
+        self.freed_pointers.push(addr);
+        Ok(())
+    }
+
+    /// Get the pointers which have been freed (with `record_free()`, e.g. by
+    /// the built-in `free()` hook) so far on the current path, in the order
+    /// they were freed.
+    pub fn freed_pointers(&self) -> &[B::BV] {
+        &self.freed_pointers
+    }
+
+    /// Record the name of the RTTI (`type_info`) global for the exception
+    /// being thrown on the current path, or `None` once that exception has been
+    /// handled (or if its `type_info` isn't known)
+    pub(crate) fn set_exception_type_info(&mut self, type_info: Option<String>) {
+        self.exception_type_info = type_info;
+    }
+
+    /// Get the name of the RTTI (`type_info`) global for the exception being
+    /// thrown on the current path, if it was thrown with `__cxa_throw()`
+    pub(crate) fn exception_type_info(&self) -> Option<&str> {
+        self.exception_type_info.as_deref()
+    }
+
     /// Get the size, in bits, of the allocation at the given address, or `None`
     /// if that address is not the result of an `alloc()`.
     pub fn get_allocation_size(&mut self, addr: &B::BV) -> Result<Option<u64>> {
@@ -1819,10 +2549,125 @@ where
         }
     }
 
+    /// Get the address and size (in bits) of the allocation which contains the
+    /// given (concrete) address, or `None` if that address is not within any
+    /// allocation.
+    pub fn get_allocation_containing(&self, addr: u64) -> Option<(u64, u64)> {
+        self.alloc.get_allocation_containing(addr)
+    }
+
+    /// Concretize the given (possibly symbolic) pointer, and identify the
+    /// allocation it points into.
+    ///
+    /// This solves for a value of `ptr` (constraining `ptr` to be that value
+    /// going forward), and returns the start address of the allocation
+    /// containing that value, along with the pointer's offset in bytes from
+    /// the start of that allocation. This is often more meaningful to report
+    /// than a bare address: e.g., "points to offset 4 of the buffer `param0`".
+    ///
+    /// Returns an `Error::OtherError` if the chosen value of `ptr` is not
+    /// within any allocation, or `Error::Unsat` if the current path is unsat.
+    pub fn concretize_pointer(&self, ptr: &B::BV) -> Result<(u64, u64)> {
+        let addr = self
+            .get_a_solution_for_bv(ptr)?
+            .ok_or(Error::Unsat)?
+            .as_u64()
+            .ok_or_else(|| {
+                Error::OtherError(
+                    "concretize_pointer: pointer is more than 64 bits wide".to_owned(),
+                )
+            })?;
+        let (base, _) = self.alloc.get_allocation_containing(addr).ok_or_else(|| {
+            Error::OtherError(format!(
+                "concretize_pointer: pointer {:#x} does not point into any allocation",
+                addr
+            ))
+        })?;
+        self.assert(&ptr._eq(&self.bv_from_u64(addr, ptr.get_width())))?;
+        Ok((base, addr - base))
+    }
+
+    /// Get information about the allocation which the given (possibly
+    /// symbolic) pointer points into, or `None` if it doesn't point into any
+    /// allocation.
+    ///
+    /// If `ptr` is symbolic, this solves for a value of it to identify the
+    /// allocation, but doesn't constrain `ptr`. Returns an `Error::OtherError`
+    /// if `ptr` may point into more than one allocation (or may point either
+    /// into an allocation or outside of all allocations), or `Error::Unsat` if
+    /// the current path is unsat.
+    pub fn allocation_info(&self, ptr: &B::BV) -> Result<Option<AllocationInfo>> {
+        let width = ptr.get_width();
+        let addr = match ptr.as_u64() {
+            Some(addr) => addr,
+            None => self
+                .get_a_solution_for_bv(ptr)?
+                .ok_or(Error::Unsat)?
+                .as_u64()
+                .ok_or_else(|| {
+                    Error::OtherError(
+                        "allocation_info: pointer is more than 64 bits wide".to_owned(),
+                    )
+                })?,
+        };
+        let info = self
+            .alloc
+            .get_allocation_containing(addr)
+            .map(|(base, bits)| AllocationInfo {
+                base,
+                size_bytes: bits / 8 + if bits % 8 == 0 { 0 } else { 1 },
+                is_global: base < self.globals_end,
+            });
+        if ptr.as_u64().is_some() {
+            return Ok(info);
+        }
+        // `ptr` is symbolic, so make sure the allocation we found is the only
+        // possible answer: if we found an allocation, `ptr` must be within it;
+        // if we didn't, `ptr` must be outside all of the allocations
+        let (low, high) = match info {
+            Some(info) => (
+                info.base,
+                info.base + Alloc::reserved_bytes(info.size_bytes * 8),
+            ),
+            None => (Alloc::ALLOC_START, self.alloc.next_addr()),
+        };
+        let in_range = ptr
+            .ugte(&self.bv_from_u64(low, width))
+            .and(&ptr.ult(&self.bv_from_u64(high, width)));
+        let must_be_out = if info.is_some() {
+            in_range.not()
+        } else {
+            in_range
+        };
+        if self.sat_with_extra_constraints(std::iter::once(&must_be_out))? {
+            Err(Error::OtherError(format!(
+                "allocation_info: pointer may point into more than one allocation: {:?}",
+                ptr
+            )))
+        } else {
+            Ok(info)
+        }
+    }
+
+    /// Record that only the first `valid_bytes` bytes of the allocation at
+    /// address `start` are valid to access, even though more may be allocated.
+    /// `Config.check_memory_bounds` will report accesses beyond `valid_bytes`.
+    pub(crate) fn set_allocation_valid_bytes(&mut self, start: u64, valid_bytes: B::BV) {
+        self.allocation_valid_bytes.insert(start, valid_bytes);
+    }
+
+    /// Get the number of valid bytes recorded with
+    /// `set_allocation_valid_bytes()` for the allocation at address `start`,
+    /// if any
+    pub(crate) fn get_allocation_valid_bytes(&self, start: u64) -> Option<&B::BV> {
+        self.allocation_valid_bytes.get(&start)
+    }
+
     /// Record the current location as a `PathEntry` in the current path.
     pub fn record_path_entry(&mut self) {
         let entry = PathEntry(self.cur_loc.clone());
         debug!("Recording a path entry {:?}", entry);
+        self.coverage.record_visit(&self.cur_loc);
         self.path.push(entry);
     }
 
@@ -1831,6 +2676,27 @@ where
         &self.path
     }
 
+    /// Get the basic blocks which have been visited so far, across all paths
+    /// explored so far (not just the current path).
+    pub fn coverage(&self) -> &CoverageReport<'p> {
+        &self.coverage
+    }
+
+    /// Get the number of solver queries which have been performed since the
+    /// beginning of the current path, i.e., since the last backtrack (or
+    /// `restore()`), or since this `State` was created if there hasn't been one.
+    ///
+    /// Queries made on a path before a backtracking point are counted only on
+    /// that path, not again on the paths which resume from the backtracking
+    /// point. For totals across all paths, see
+    /// [`ExecutionManager::solver_stats()`](struct.ExecutionManager.html#method.solver_stats).
+    ///
+    /// Only queries made on this `State`'s solver are counted (see
+    /// [`SolverRef::stats()`](backend/trait.SolverRef.html#tymethod.stats)).
+    pub fn solver_stats(&self) -> SolverStats {
+        self.solver.stats().since(&self.solver_stats_at_path_start)
+    }
+
     /// Record entering a normal `Call` at the current location
     pub fn push_callsite(&mut self, call: &'p instruction::Call) {
         self.push_generic_callsite(Either::Left(call))
@@ -1858,9 +2724,37 @@ where
             restore_info: self
                 .varmap
                 .get_restore_info_for_fn(self.cur_loc.func.name.clone()),
+            varargs: Vec::new(),
         })
     }
 
+    /// Record the variadic arguments (that is, the arguments beyond the
+    /// callee's declared parameters) passed at the most recently pushed
+    /// callsite. These will be available to the callee via `get_varargs()`.
+    ///
+    /// Panics if there is no callsite on the stack.
+    pub(crate) fn set_varargs(&mut self, varargs: Vec<B::BV>) {
+        self.stack
+            .last_mut()
+            .expect("set_varargs: no callsite on the stack")
+            .varargs = varargs;
+    }
+
+    /// Get the variadic arguments which were passed to the current function,
+    /// that is, the arguments beyond its declared (fixed) parameters.
+    ///
+    /// This is what `va_start()` and friends operate on.
+    ///
+    /// Returns an empty slice if the current function is not variadic, if no
+    /// extra arguments were passed, or if the current function is the top-level
+    /// function (in which case we have no callsite to get arguments from).
+    pub fn get_varargs(&self) -> &[B::BV] {
+        self.stack
+            .last()
+            .map(|frame| frame.varargs.as_slice())
+            .unwrap_or(&[])
+    }
+
     /// Record leaving the current function. Returns the `Callsite` at which the
     /// current function was called, or `None` if the current function was the
     /// top-level function.
@@ -1870,6 +2764,7 @@ where
         if let Some(StackFrame {
             callsite,
             restore_info,
+            ..
         }) = self.stack.pop()
         {
             self.varmap.restore_fn_vars(restore_info);
@@ -1886,9 +2781,24 @@ where
         self.stack.len()
     }
 
+    /// Returns the number of activations of the function with the given name
+    /// which are currently on the callstack (including the current function).
+    /// For instance, `0` indicates that calling that function would not be a
+    /// recursive call, and `1` indicates that it would be a recursive call
+    /// with one activation already in progress.
+    pub fn current_recursion_depth(&self, funcname: &str) -> usize {
+        std::iter::once(&self.cur_loc)
+            .chain(self.stack.iter().map(|frame| &frame.callsite.loc))
+            .filter(|loc| loc.func.name == funcname)
+            .count()
+    }
+
     /// Save the current state, about to enter the `BasicBlock` with the given `Name` (which must be
     /// in the same `Module` and `Function` as `state.cur_loc`), as a backtracking point.
     /// The constraint will be added only if we end up backtracking to this point, and only then.
+    ///
+    /// Function hooks which want to split the current path should use
+    /// [`fork_after_hook()`](#method.fork_after_hook) instead.
     pub fn save_backtracking_point(&mut self, bb_to_enter: &Name, constraint: B::BV) {
         debug!(
             "Saving a backtracking point, which would enter bb {:?} with constraint {:?}",
@@ -1919,45 +2829,271 @@ where
     ///
     /// Also it doesn't require `&mut self`. This allows us to save backtracking
     /// points even when we're inside methods that only have `&self`.
-    fn save_backtracking_point_at_location(
+    pub(crate) fn save_backtracking_point_at_location(
         &self,
         loc_to_start_at: Location<'p>,
         constraint: B::BV,
     ) {
-        self.solver.push(1);
-        self.backtrack_points.borrow_mut().push(BacktrackPoint {
-            loc: loc_to_start_at,
-            stack: self.stack.clone(),
-            constraint,
-            varmap: self.varmap.clone(),
-            mem: self.mem.borrow().clone(),
-            path_len: self.path.len(),
-        });
+        if self.config.single_path.is_some() {
+            debug!("Not saving a backtracking point, because we are only exploring a single path");
+            return;
+        }
+        if self.config.exploration_strategy == ExplorationStrategy::DepthFirst {
+            self.solver.push(1);
+        }
+        self.backtrack_points
+            .borrow_mut()
+            .push_back(BacktrackPoint {
+                loc: loc_to_start_at,
+                stack: self.stack.clone(),
+                constraint,
+                varmap: self.varmap.clone(),
+                mem: self.mem.borrow().clone(),
+                path_len: self.path.len(),
+                nondet_values: self.nondet_values.clone(),
+                instructions_executed: self.instructions_executed,
+                freed_pointers: self.freed_pointers.clone(),
+                exception_type_info: self.exception_type_info.clone(),
+                havoced_calls_len: self.havoced_calls.len(),
+                truncated_calls_len: self.truncated_calls.len(),
+                memory_trace_len: self.memory_trace.borrow().len(),
+                instruction_trace_len: self.instruction_trace.len(),
+                value_watchpoint_hits_len: self.value_watchpoint_hits.borrow().len(),
+                path_constraints: match self.config.exploration_strategy {
+                    ExplorationStrategy::DepthFirst => Vec::new(),
+                    ExplorationStrategy::BreadthFirst => self.solver.assertions(),
+                },
+            });
+    }
+
+    /// For use in function hooks: once the hook returns, split the current
+    /// path into one path for each of the given constraints.
+    ///
+    /// After the hook returns normally (and its return value, if any, has been
+    /// assigned), the current path continues with the first feasible
+    /// constraint added, and a backtracking point is saved for each of the
+    /// other feasible constraints, which resumes execution just after the
+    /// hooked call. If none of the constraints is feasible, the current path
+    /// is dead.
+    ///
+    /// For example, a hook for `rand()` which should return each of 1, 2, and
+    /// 3 on different paths can create a fresh `BV` `r`, call this with the
+    /// constraints `r == 1`, `r == 2`, and `r == 3`, and return `r`.
+    ///
+    /// Constraints from multiple calls during the same hook are all used. If
+    /// the hook doesn't return normally (e.g., it throws, aborts, or returns an
+    /// error), the constraints are discarded.
+    ///
+    /// Hooks shouldn't use
+    /// [`save_backtracking_point()`](#method.save_backtracking_point) for
+    /// this, because its backtracking points start over at the beginning of a
+    /// basic block, and wouldn't see the hook's return value.
+    pub fn fork_after_hook(&mut self, constraints: impl IntoIterator<Item = B::BV>) {
+        self.hook_forks.extend(constraints);
+    }
+
+    /// Take the constraints requested with `fork_after_hook()` which haven't
+    /// been applied yet
+    pub(crate) fn take_hook_forks(&mut self) -> Vec<B::BV> {
+        std::mem::take(&mut self.hook_forks)
     }
 
     /// returns `Ok(true)` if the operation was successful, `Ok(false)` if there are
     /// no saved backtracking points, or `Err` for other errors
+    ///
+    /// For `ExplorationStrategy::DepthFirst`, each backtracking point has its
+    /// own solver scope, and since we always revert to the most recent
+    /// backtracking point, we can just pop its scope. For
+    /// `ExplorationStrategy::BreadthFirst`, we revert to the oldest
+    /// backtracking point, whose scope (if it had one) would be buried under
+    /// all the others. So instead, all path-specific constraints live in a
+    /// single scope, which we clear and then repopulate with the backtracking
+    /// point's `path_constraints`.
     pub fn revert_to_backtracking_point(&mut self) -> Result<bool> {
-        if let Some(bp) = self.backtrack_points.borrow_mut().pop() {
+        let bp = match self.config.exploration_strategy {
+            ExplorationStrategy::DepthFirst => self.backtrack_points.borrow_mut().pop_back(),
+            ExplorationStrategy::BreadthFirst => self.backtrack_points.borrow_mut().pop_front(),
+        };
+        if let Some(bp) = bp {
             debug!("Reverting to backtracking point {}", bp);
             self.solver.pop(1);
+            match self.config.exploration_strategy {
+                ExplorationStrategy::DepthFirst => {
+                    if self.snapshot_taken && self.num_path_scopes() == 0 {
+                        // we just popped the last scope; see notes on `snapshot()`
+                        self.solver.push(1);
+                        self.snapshot_scope = true;
+                    }
+                },
+                ExplorationStrategy::BreadthFirst => {
+                    self.solver.push(1);
+                    self.reassert(&bp.path_constraints)?;
+                },
+            }
             self.varmap = bp.varmap;
             self.mem.replace(bp.mem);
             self.stack = bp.stack;
             self.path.truncate(bp.path_len);
+            self.nondet_values = bp.nondet_values;
+            self.instructions_executed = bp.instructions_executed;
+            self.freed_pointers = bp.freed_pointers;
+            self.exception_type_info = bp.exception_type_info;
+            self.havoced_calls.truncate(bp.havoced_calls_len);
+            self.truncated_calls.truncate(bp.truncated_calls_len);
+            self.memory_trace.borrow_mut().truncate(bp.memory_trace_len);
+            self.instruction_trace.truncate(bp.instruction_trace_len);
+            self.value_watchpoint_hits
+                .borrow_mut()
+                .truncate(bp.value_watchpoint_hits_len);
             self.cur_loc = bp.loc;
-            bp.constraint.assert()?;
+            self.solver_stats_at_path_start = self.solver.stats();
+            self.assert(&bp.constraint)?;
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    /// Take a snapshot of the current path-specific information: the current
+    /// location, callstack, variables, memory contents, path, and constraints,
+    /// among others. Execution can later resume from this point with
+    /// [`restore()`](struct.State.html#method.restore).
+    ///
+    /// This is independent of the built-in backtracking points, and is
+    /// intended for users implementing their own exploration scheduler without
+    /// an `ExecutionManager`. A snapshot can be restored any number of times.
+    ///
+    /// Constraints are captured if they were asserted with the `BV` trait's
+    /// `assert()` (or `State::assert()`); see
+    /// [`SolverRef::assertions()`](backend/trait.SolverRef.html#tymethod.assertions).
+    ///
+    /// With `ExplorationStrategy::DepthFirst`, constraints added while there
+    /// are no backtracking points normally go outside of any solver scope,
+    /// where `restore()` couldn't remove them. So from the first snapshot on,
+    /// this `State` keeps such constraints in a solver scope of their own,
+    /// pushing it here if necessary.
+    pub fn snapshot(&mut self) -> StateSnapshot<'p, B> {
+        self.snapshot_taken = true;
+        if self.config.exploration_strategy == ExplorationStrategy::DepthFirst
+            && self.num_path_scopes() == 0
+        {
+            self.solver.push(1);
+            self.snapshot_scope = true;
+        }
+        StateSnapshot {
+            loc: self.cur_loc.clone(),
+            stack: self.stack.clone(),
+            varmap: self.varmap.clone(),
+            mem: self.mem.borrow().clone(),
+            path: self.path.clone(),
+            nondet_values: self.nondet_values.clone(),
+            instructions_executed: self.instructions_executed,
+            freed_pointers: self.freed_pointers.clone(),
+            exception_type_info: self.exception_type_info.clone(),
+            havoced_calls: self.havoced_calls.clone(),
+            truncated_calls: self.truncated_calls.clone(),
+            memory_trace: self.memory_trace.borrow().clone(),
+            instruction_trace: self.instruction_trace.clone(),
+            value_watchpoint_hits: self.value_watchpoint_hits.borrow().clone(),
+            path_constraints: self.solver.assertions(),
+        }
+    }
+
+    /// Restore the path-specific information captured in the given snapshot
+    /// (see [`snapshot()`](struct.State.html#method.snapshot)), abandoning the
+    /// current path. Execution will resume at the snapshot's location.
+    ///
+    /// This discards all saved backtracking points, as they may not be
+    /// compatible with the restored path. As with backtracking, the allocator
+    /// is not rolled back: addresses are never reused, so pointers from
+    /// different paths never alias.
+    pub fn restore(&mut self, snapshot: &StateSnapshot<'p, B>) -> Result<()> {
+        debug!("Restoring snapshot {:?}", snapshot);
+        // Pop all of the solver scopes holding constraints for the current path
+        let scopes = self.num_path_scopes();
+        if scopes > 0 {
+            self.solver.pop(scopes as u32);
+        }
+        self.backtrack_points.borrow_mut().clear();
+        // Re-establish the snapshot's constraints in a fresh scope. Everything
+        // still in effect was asserted before the snapshot was taken (see notes
+        // on `snapshot()`), so `reassert()` can skip it.
+        self.solver.push(1);
+        if self.config.exploration_strategy == ExplorationStrategy::DepthFirst {
+            self.snapshot_scope = true;
+        }
+        self.reassert(&snapshot.path_constraints)?;
+        self.varmap = snapshot.varmap.clone();
+        self.mem.replace(snapshot.mem.clone());
+        self.stack = snapshot.stack.clone();
+        self.path = snapshot.path.clone();
+        self.nondet_values = snapshot.nondet_values.clone();
+        self.instructions_executed = snapshot.instructions_executed;
+        self.freed_pointers = snapshot.freed_pointers.clone();
+        self.exception_type_info = snapshot.exception_type_info.clone();
+        self.havoced_calls = snapshot.havoced_calls.clone();
+        self.truncated_calls = snapshot.truncated_calls.clone();
+        self.memory_trace.replace(snapshot.memory_trace.clone());
+        self.instruction_trace = snapshot.instruction_trace.clone();
+        self.value_watchpoint_hits
+            .replace(snapshot.value_watchpoint_hits.clone());
+        self.cur_loc = snapshot.loc.clone();
+        self.solver_stats_at_path_start = self.solver.stats();
+        Ok(())
+    }
+
+    /// Mark all global variables as not-yet-initialized, so that they will be
+    /// (lazily) initialized again. This is only appropriate if the memory
+    /// contents have also been reset to before any global was initialized.
+    pub(crate) fn reset_global_initialization(&self) {
+        self.global_allocations.mark_all_uninitialized();
+    }
+
+    /// Re-assert the given constraints, which were recorded (with
+    /// `SolverRef::assertions()`) earlier on the current path, after the solver
+    /// scopes holding them have been popped. The constraints from outside of
+    /// any solver scope are still in effect, so those are skipped.
+    fn reassert(&self, constraints: &[B::BV]) -> Result<()> {
+        let in_effect = self.solver.assertions().len();
+        for constraint in constraints.iter().skip(in_effect) {
+            constraint.assert()?;
+        }
+        Ok(())
+    }
+
+    /// Get the number of solver scopes holding constraints for the current
+    /// path. With `BreadthFirst`, that's the single scope set up in `new()`.
+    /// With `DepthFirst`, it's one scope per saved backtracking point, plus the
+    /// scope for a previously-restored snapshot, if any.
+    fn num_path_scopes(&self) -> usize {
+        match self.config.exploration_strategy {
+            ExplorationStrategy::BreadthFirst => 1,
+            ExplorationStrategy::DepthFirst => {
+                self.backtrack_points.borrow().len() + usize::from(self.snapshot_scope)
+            },
+        }
+    }
+
     /// returns the number of saved backtracking points
     pub fn count_backtracking_points(&self) -> usize {
         self.backtrack_points.borrow().len()
     }
 
+    /// Get the locations where each of the saved backtracking points would
+    /// resume execution, in the order in which they will be reverted to (per
+    /// `Config.exploration_strategy`), i.e., the first entry is where
+    /// `revert_to_backtracking_point()` would resume next.
+    pub fn backtracking_point_locations(&self) -> Vec<LocationDescription<'p>> {
+        let backtrack_points = self.backtrack_points.borrow();
+        let locs = backtrack_points
+            .iter()
+            .map(|bp| LocationDescription::from(bp.loc.clone()));
+        match self.config.exploration_strategy {
+            ExplorationStrategy::DepthFirst => locs.rev().collect(),
+            ExplorationStrategy::BreadthFirst => locs.collect(),
+        }
+    }
+
     /// returns a `String` containing a formatted view of the current backtrace
     /// (in terms of LLVM locations, and possibly also source locations depending
     /// on the `Config`)
@@ -2101,6 +3237,23 @@ where
         path_str
     }
 
+    /// Get the source lines executed on the path which led to this point, in
+    /// order, each formatted as `file:line`. Consecutive entries referring to
+    /// the same line are collapsed into one.
+    ///
+    /// If no source locations are available in the path (e.g., because the
+    /// LLVM bitcode was not compiled with debuginfo), this returns an empty
+    /// `Vec`. To compile C/C++ or Rust sources with debuginfo, pass the `-g`
+    /// flag to `clang`, `clang++`, or `rustc`.
+    pub fn source_trace(&self) -> Vec<String> {
+        self.get_path()
+            .iter()
+            .flat_map(|path_entry| path_entry.get_all_source_locs())
+            .map(|source_loc| format!("{}:{}", source_loc.filename, source_loc.line))
+            .dedup()
+            .collect()
+    }
+
     /// returns a `String` containing a formatted view of the full path which led
     /// to this point, in terms of both LLVM and source locations (interleaved
     /// appropriately)
@@ -2130,6 +3283,21 @@ where
         path_str
     }
 
+    /// Get the names of the basic blocks in the current function which are
+    /// reachable from the current basic block (including the current block
+    /// itself), according to the function's control-flow graph.
+    ///
+    /// This is a static query: it considers all of the CFG edges, regardless
+    /// of whether they are feasible under the current path constraints. It
+    /// also doesn't consider returning to the current function's callers, or
+    /// unwinding out of the current function.
+    pub fn reachable_blocks_from_here(&self) -> HashSet<Name> {
+        reachable_blocks(self.cur_loc.func, &self.cur_loc.bb.name, None)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
     /// Returns the number of LLVM instructions in the current path.
     /// The returned value is only accurate if the path under
     /// analysis does not include a panic, exception, exit,
@@ -2138,6 +3306,116 @@ where
         get_path_length(self.get_path())
     }
 
+    /// Returns the number of LLVM instructions (including terminators) which
+    /// have been executed so far on the current path.
+    ///
+    /// Unlike `get_path_length()`, this counts each instruction as it is
+    /// executed, so it is accurate even for paths which end partway through a
+    /// basic block. Instructions executed inside function hooks are not
+    /// counted. This is the count which is compared against
+    /// `Config.max_instructions_per_path`.
+    pub fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
+    /// Get the calls on the current path whose effects were havoc'd, as
+    /// (location of the call, name of the called function), in the order they
+    /// were encountered.
+    ///
+    /// A call is havoc'd when the called function isn't analyzed, either
+    /// because of `Config.max_callstack_depth`, or because it has no LLVM
+    /// definition and so was processed by the default hook (see
+    /// [`FunctionHooks::add_default_hook()`](function_hooks/struct.FunctionHooks.html#method.add_default_hook)).
+    /// Results on paths with havoc'd calls may depend on the havoc'd return
+    /// values.
+    ///
+    /// Function names are demangled as appropriate based on the `Config`.
+    pub fn havoced_calls(&self) -> &[(Location<'p>, String)] {
+        &self.havoced_calls
+    }
+
+    /// Get the calls on the current path which weren't entered because of
+    /// `Config.max_callstack_depth`, as (location of the call, name of the
+    /// called function), in the order they were encountered.
+    ///
+    /// These calls are also included in `havoced_calls()`. Their return values
+    /// are fresh unconstrained `BV`s named `<funcname>_truncated_retval`, so
+    /// any result on a path with truncated calls may be an over-approximation.
+    ///
+    /// Function names are demangled as appropriate based on the `Config`.
+    pub fn truncated_calls(&self) -> &[(Location<'p>, String)] {
+        &self.truncated_calls
+    }
+
+    /// Get the memory reads and writes performed so far on the current path, in
+    /// order. This includes accesses made by hooks (e.g., the reads and writes
+    /// of a `memcpy()`), but not accesses made directly through the `Memory`
+    /// (e.g., when initializing global variables).
+    ///
+    /// This is always empty unless `Config.trace_memory` is enabled.
+    pub fn memory_trace(&self) -> Vec<MemoryAccess<'p>> {
+        self.memory_trace.borrow().clone()
+    }
+
+    /// Get the instructions executed so far on the current path, in order,
+    /// along with the `Location` of each. Instructions in called functions are
+    /// included, interleaved with the caller's instructions in execution order.
+    /// Terminators are not included, but the `Location`s make the basic-block
+    /// transitions apparent.
+    ///
+    /// This is always empty unless `Config.record_instruction_trace` is
+    /// enabled.
+    pub fn instruction_trace(&self) -> &[(Location<'p>, &'p Instruction)] {
+        &self.instruction_trace
+    }
+
+    /// If `Config.record_instruction_trace` is enabled, record that the given
+    /// instruction is being executed at the current location
+    pub(crate) fn record_instruction(&mut self, inst: &'p Instruction) {
+        if self.config.record_instruction_trace {
+            self.instruction_trace.push((self.cur_loc.clone(), inst));
+        }
+    }
+
+    /// If `Config.trace_memory` is enabled, record an access of `bits` bits at
+    /// `addr` at the current location
+    fn record_memory_access(&self, addr: &B::BV, bits: u32, is_write: bool) {
+        if self.config.trace_memory {
+            let addr_debug = match addr.as_u64() {
+                Some(addr) => format!("0x{:x}", addr),
+                None => format!("{:?}", addr),
+            };
+            self.memory_trace.borrow_mut().push(MemoryAccess {
+                location: self.cur_loc.clone(),
+                addr_debug,
+                width: bits,
+                is_write,
+            });
+        }
+    }
+
+    /// Record that the call at the current location, of the function with the
+    /// given name, has been havoc'd
+    pub(crate) fn record_havoced_call(&mut self, funcname: String) {
+        self.havoced_calls.push((self.cur_loc.clone(), funcname));
+    }
+
+    /// Record that the call at the current location, of the function with the
+    /// given name, wasn't entered because of `Config.max_callstack_depth`.
+    /// This also records the call as havoc'd.
+    pub(crate) fn record_truncated_call(&mut self, funcname: String) {
+        self.truncated_calls
+            .push((self.cur_loc.clone(), funcname.clone()));
+        self.record_havoced_call(funcname);
+    }
+
+    /// Record that one more LLVM instruction (or terminator) has been executed
+    /// on the current path, returning the new count
+    pub(crate) fn count_instruction_executed(&mut self) -> u64 {
+        self.instructions_executed += 1;
+        self.instructions_executed
+    }
+
     /// Attempt to demangle the given `funcname` as appropriate based on the
     /// `Config`.
     ///
@@ -2150,6 +3428,18 @@ where
         }
     }
 
+    /// Like `demangle()`, but also reports which demangling scheme was actually
+    /// applied to `funcname`: Rust, C++, or none (in which case the returned
+    /// `String` is just a copy of `funcname`).
+    ///
+    /// This can be useful when a `Project` mixes code from multiple languages.
+    pub fn demangle_with_scheme_info(&self, funcname: &str) -> (String, DemangleScheme) {
+        match self.config.demangling {
+            Some(demangling) => demangling.maybe_demangle_with_scheme_info(funcname),
+            None => panic!("Demangling shouldn't be None here"), // we should resolve it to Some() in the State constructor
+        }
+    }
+
     /// Attempts to demangle the function name in the `LocationDescription`, as
     /// appropriate based on the `Config`.
     fn demangle_locdescr(&self, locdescr: &mut LocationDescription) {
@@ -2166,7 +3456,7 @@ where
 
     /// returns a `String` describing a set of satisfying assignments for all variables
     pub fn current_assignments_as_pretty_string(&self) -> Result<String> {
-        self.solver.set_opt(BtorOption::ModelGen(ModelGen::All));
+        self.solver.set_model_gen(true);
         let string = if self.sat()? {
             let printed = self.solver.print_model();
             let sorted = itertools::sorted(printed.lines());
@@ -2174,8 +3464,7 @@ where
         } else {
             "<state is unsatisfiable>".to_owned()
         };
-        self.solver
-            .set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+        self.solver.set_model_gen(false);
         Ok(string)
     }
 
@@ -2266,6 +3555,71 @@ impl PathDumpType {
     }
 }
 
+/// Get the names of the successors of the given basic block in its function's
+/// CFG
+fn bb_successors(bb: &BasicBlock) -> Vec<&Name> {
+    match &bb.term {
+        Terminator::Br(br) => vec![&br.dest],
+        Terminator::CondBr(condbr) => vec![&condbr.true_dest, &condbr.false_dest],
+        Terminator::Switch(switch) => switch
+            .dests
+            .iter()
+            .map(|(_, dest)| dest)
+            .chain(std::iter::once(&switch.default_dest))
+            .collect(),
+        Terminator::IndirectBr(ibr) => ibr.possible_dests.iter().collect(),
+        Terminator::Invoke(invoke) => vec![&invoke.return_label, &invoke.exception_label],
+        _ => vec![],
+    }
+}
+
+/// Get the names of the basic blocks in `func` which are reachable from the
+/// block named `start` (including `start` itself) without passing through
+/// the block named `avoid`, if any
+fn reachable_blocks<'f>(
+    func: &'f Function,
+    start: &'f Name,
+    avoid: Option<&Name>,
+) -> HashSet<&'f Name> {
+    let mut seen = HashSet::new();
+    let mut worklist = vec![start];
+    while let Some(bbname) = worklist.pop() {
+        if Some(bbname) == avoid || !seen.insert(bbname) {
+            continue;
+        }
+        if let Some(bb) = func.get_bb_by_name(bbname) {
+            worklist.extend(bb_successors(bb));
+        }
+    }
+    seen
+}
+
+/// Get the names of the basic blocks making up the natural loop with the given
+/// header block in the given function: that is, the header itself, plus all
+/// blocks which are dominated by the header and can reach it again.
+///
+/// Returns an empty set if there is no block named `header` in `func`.
+fn natural_loop_blocks<'f>(func: &'f Function, header: &Name) -> HashSet<&'f Name> {
+    let reachable = |start: &'f Name, avoid: Option<&Name>| reachable_blocks(func, start, avoid);
+    let header = match func.get_bb_by_name(header) {
+        Some(bb) => &bb.name,
+        None => return HashSet::new(),
+    };
+    // Blocks which are reachable from the entry without passing through the
+    // header are exactly those which the header doesn't dominate
+    let undominated = match func.basic_blocks.first() {
+        Some(entry) if &entry.name != header => reachable(&entry.name, Some(header)),
+        _ => HashSet::new(),
+    };
+    let mut blocks: HashSet<&'f Name> = reachable(header, None)
+        .into_iter()
+        .filter(|bbname| !undominated.contains(bbname))
+        .filter(|bbname| reachable(bbname, None).contains(header))
+        .collect();
+    blocks.insert(header);
+    blocks
+}
+
 /// Returns the number of LLVM instructions in a passed path.
 /// The returned value is only accurate if the path under
 /// analysis does not include a panic, exception, exit,
@@ -2284,7 +3638,7 @@ pub fn get_path_length<'p>(path: &Vec<PathEntry<'p>>) -> usize {
             BBInstrIndex::Terminator => {
                 // Path with only a terminator: 1 instruction, not counted yet.
                 // With instrs: we already counted the terminator, this is a duplicate bb
-                if location.bb.instrs.len() == 0 {
+                if location.bb.instrs.is_empty() {
                     1
                 } else {
                     0
@@ -2300,6 +3654,8 @@ mod tests {
     use super::*;
     use crate::solver_utils::SolutionCount;
     use crate::test_utils::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
 
     // we don't include tests here for Memory, Alloc, VarMap, or Watchpoints; those are tested in their own modules.
     // Instead, here we just test the nontrivial functionality that `State` has itself.
@@ -2386,6 +3742,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_a_joint_solution() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+
+        // add x + y == 10 and x > 3 constraints
+        let x = state.new_bv_with_name(Name::from("x"), 64)?;
+        let y = state.new_bv_with_name(Name::from("y"), 64)?;
+        x.add(&y)._eq(&state.bv_from_u64(10, 64)).assert();
+        x.ugt(&state.bv_from_u64(3, 64)).assert();
+
+        // the values of x and y should come from the same solution
+        let three = state.bv_from_u64(3, 64);
+        let solutions = state
+            .get_a_solution_for_bvs(&[&x, &y, &three])?
+            .expect("Expected a solution");
+        let values: Vec<u64> = solutions.iter().map(|s| s.as_u64().unwrap()).collect();
+        assert!(values[0] > 3);
+        assert_eq!(values[0].wrapping_add(values[1]), 10);
+        assert_eq!(values[2], 3);
+
+        // no solution if unsat
+        state.bv_from_bool(false).assert();
+        assert_eq!(state.get_a_solution_for_bvs(&[&x, &y])?, None);
+
+        Ok(())
+    }
+
     #[test]
     fn possible_solutions() -> Result<()> {
         let func = blank_function("test_func", vec![Name::from("test_bb")]);
@@ -2531,6 +3916,45 @@ mod tests {
         assert_eq!(state.sat(), Ok(false));
     }
 
+    #[test]
+    fn const_exprs_on_vectors() {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let state = blank_state(&project, "test_func");
+
+        let vector = |bits, values: &[u64]| {
+            ConstantRef::new(Constant::Vector(
+                values
+                    .iter()
+                    .map(|&value| ConstantRef::new(Constant::Int { bits, value }))
+                    .collect(),
+            ))
+        };
+
+        // adds are elementwise, so there's no carry from element 0 into element 1
+        let add = Constant::Add(constant::Add {
+            operand0: vector(8, &[255, 1]),
+            operand1: vector(8, &[1, 1]),
+        });
+        assert_eq!(state.const_to_bv(&add).unwrap().as_u64(), Some(0x0200));
+
+        // comparisons produce one bit per element
+        let icmp = Constant::ICmp(constant::ICmp {
+            predicate: IntPredicate::EQ,
+            operand0: vector(8, &[1, 2]),
+            operand1: vector(8, &[1, 3]),
+        });
+        assert_eq!(state.const_to_bv(&icmp).unwrap().as_u64(), Some(0b01));
+
+        // selects with a vector condition choose elementwise
+        let select = Constant::Select(constant::Select {
+            condition: vector(1, &[1, 0]),
+            true_value: vector(8, &[10, 20]),
+            false_value: vector(8, &[30, 40]),
+        });
+        assert_eq!(state.const_to_bv(&select).unwrap().as_u64(), Some(0x280a));
+    }
+
     #[test]
     fn backtracking() -> Result<()> {
         let func = blank_function(
@@ -2555,6 +3979,10 @@ mod tests {
             .get_bb_by_name(&Name::from("bb_target"))
             .expect("Expected to find bb named 'bb_target'");
         state.save_backtracking_point(&bb.name, constraint);
+        let bp_locs = state.backtracking_point_locations();
+        assert_eq!(bp_locs.len(), 1);
+        assert_eq!(bp_locs[0].funcname, "test_func");
+        assert_eq!(bp_locs[0].bbname, bb.name);
 
         // check that the constraint y > 5 wasn't added: adding y < 4 should keep us sat
         assert_eq!(
@@ -2608,11 +4036,378 @@ mod tests {
         );
 
         // check that trying to backtrack again fails
+        assert!(state.backtracking_point_locations().is_empty());
         assert!(!state.revert_to_backtracking_point().unwrap());
 
         Ok(())
     }
 
+    #[test]
+    fn reachable_blocks_from_here() {
+        let modname = "tests/bcfiles/loop.bc";
+        let project = Project::from_bc_path(modname)
+            .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+        let mut state = blank_state(&project, "while_loop");
+        let expected =
+            |names: &[usize]| -> HashSet<Name> { names.iter().map(|n| Name::from(*n)).collect() };
+
+        // from the entry block, everything is reachable
+        assert_eq!(state.reachable_blocks_from_here(), expected(&[1, 6, 12]));
+
+        // from the loop body, the loop body itself (via the back edge) and the exit
+        state.cur_loc.bb = state.cur_loc.func.get_bb_by_name(&Name::from(6)).unwrap();
+        assert_eq!(state.reachable_blocks_from_here(), expected(&[6, 12]));
+
+        // from the exit block, only the exit block itself
+        state.cur_loc.bb = state.cur_loc.func.get_bb_by_name(&Name::from(12)).unwrap();
+        assert_eq!(state.reachable_blocks_from_here(), expected(&[12]));
+    }
+
+    #[test]
+    fn watchpoint_callbacks() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let addr = state.allocate(64_u64);
+        let raw_addr = addr.as_u64().expect("Expected a constant address");
+
+        // the callback counts reads, and forbids writing anything other than 0
+        type BtorBV = <DefaultBackend as Backend>::BV;
+        let reads = Rc::new(Cell::new(0));
+        let reads_clone = reads.clone();
+        state.config.callbacks.add_watchpoint_callback(
+            "w",
+            move |_state: &State<'_, DefaultBackend>,
+                  is_write: bool,
+                  _addr: &BtorBV,
+                  value: &BtorBV| {
+                if !is_write {
+                    reads_clone.set(reads_clone.get() + 1);
+                    Ok(())
+                } else if value.as_u64() == Some(0) {
+                    Ok(())
+                } else {
+                    Err(Error::OtherError("nonzero write to watched memory".into()))
+                }
+            },
+        );
+        assert!(!state.add_mem_watchpoint("w", Watchpoint::new(raw_addr, 8)));
+
+        state.write(&addr, state.zero(64))?;
+        assert_eq!(reads.get(), 0);
+        state.read(&addr, 32)?;
+        assert_eq!(reads.get(), 1);
+        match state.write(&addr, state.bv_from_u64(5, 64)) {
+            Err(Error::OtherError(_)) => {},
+            r => panic!("Expected the callback's error, got {:?}", r),
+        }
+
+        // a disabled watchpoint doesn't run its callback
+        assert!(state.disable_watchpoint("w"));
+        state.write(&addr, state.bv_from_u64(5, 64))?;
+        state.read(&addr, 32)?;
+        assert_eq!(reads.get(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn value_watchpoints() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let buf = state.allocate(128_u64);
+        let base = buf.as_u64().expect("Expected a constant address");
+        let elem_addr = buf.add(&state.bv_from_u64(4, 64));
+
+        assert!(!state.watch_for_value("sentinel", base .. base + 8, 0xdead, 32));
+        assert!(!state.add_mem_watchpoint("range", Watchpoint::new(base, 8)));
+        let watchpoints: Vec<_> = state.mem_watchpoints().iter().collect();
+        assert_eq!(watchpoints.len(), 2);
+        assert!(watchpoints
+            .iter()
+            .any(|(_, w, enabled)| *enabled && w.get_target_value() == Some((0xdead, 32))));
+
+        // writing some other value doesn't hit, and neither does a read
+        state.write(&buf, state.bv_from_u32(0, 32))?;
+        state.read(&buf, 32)?;
+        assert!(state.value_watchpoint_hits().is_empty());
+
+        // writing a symbolic value which cannot be the target doesn't hit
+        let x = state.new_bv_with_name(Name::from("x"), 32)?;
+        state.assert(&x.ult(&state.bv_from_u32(0x100, 32)))?;
+        state.write(&elem_addr, x)?;
+        assert!(state.value_watchpoint_hits().is_empty());
+
+        // writing the target value with a different width doesn't hit
+        state.write(&elem_addr, state.bv_from_u64(0xdead, 64))?;
+        assert!(state.value_watchpoint_hits().is_empty());
+
+        // writing a symbolic value which may be the target does hit
+        let y = state.new_bv_with_name(Name::from("y"), 32)?;
+        state.write(&elem_addr, y)?;
+        let hits = state.value_watchpoint_hits();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].name, "sentinel");
+        assert_eq!(hits[0].witness_addr, base + 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_c_string() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let buf = state.allocate(64_u64);
+        for (i, byte) in b"hi!\0".iter().enumerate() {
+            let addr = buf.add(&state.bv_from_u64(i as u64, 64));
+            state.write(&addr, state.bv_from_u32(u32::from(*byte), 8))?;
+        }
+        assert_eq!(state.read_c_string(&buf, 8)?, Some("hi!".to_owned()));
+        // stops at `max_len` if there's no NUL before then
+        assert_eq!(state.read_c_string(&buf, 2)?, Some("hi".to_owned()));
+
+        // a symbolic byte is fine as long as it has only one possible value
+        let second_byte = buf.inc();
+        let x = state.new_bv_with_name(Name::from("x"), 8)?;
+        state.assert(&x._eq(&state.bv_from_u32(u32::from(b'o'), 8)))?;
+        state.write(&second_byte, x)?;
+        assert_eq!(state.read_c_string(&buf, 8)?, Some("ho!".to_owned()));
+
+        // but not if it could have multiple values
+        let y = state.new_bv_with_name(Name::from("y"), 8)?;
+        state.write(&second_byte, y)?;
+        assert_eq!(state.read_c_string(&buf, 8)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn solution_for_memory_region() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let buf = state.allocate(32_u64);
+
+        // a concrete byte, then a symbolic 16-bit value, then a copy of that
+        // value's low byte
+        state.write(&buf, state.bv_from_u32(0x12, 8))?;
+        let x = state.new_bv_with_name(Name::from("x"), 16)?;
+        state.assert(&x.ugt(&state.bv_from_u32(0x1000, 16)))?;
+        state.write(&buf.inc(), x.clone())?;
+        let fourth_byte = buf.add(&state.bv_from_u64(3, 64));
+        state.write(&fourth_byte, x.slice(7, 0))?;
+
+        let bytes = state
+            .get_solution_for_memory_region(&buf, 4)?
+            .expect("Expected a solution");
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(bytes[0], 0x12);
+        assert!(u16::from_le_bytes([bytes[1], bytes[2]]) > 0x1000);
+        // the bytes all come from the same solution
+        assert_eq!(bytes[3], bytes[1]);
+
+        // no solution if the constraints are unsat. (Not `x == 0`: the solver
+        // would substitute that into the bytes, making them all constant, and
+        // constants don't need a solve.)
+        state.assert(&x.ult(&state.bv_from_u32(0x1000, 16)))?;
+        assert_eq!(state.get_solution_for_memory_region(&buf, 4)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn concretize_pointer() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let buf = state.allocate(128_u64);
+        let base = buf.as_u64().expect("Expected a constant address");
+
+        // a symbolic pointer constrained to lie in bytes 4 through 7 of the buffer
+        let ptr = state.new_bv_with_name(Name::from("ptr"), 64)?;
+        state.assert(&ptr.ugte(&buf.add(&state.bv_from_u64(4, 64))))?;
+        state.assert(&ptr.ult(&buf.add(&state.bv_from_u64(8, 64))))?;
+        let (ptr_base, offset) = state.concretize_pointer(&ptr)?;
+        assert_eq!(ptr_base, base);
+        assert!((4 .. 8).contains(&offset));
+        // the pointer is now concrete
+        let solutions = state
+            .get_possible_solutions_for_bv(&ptr, 1)?
+            .as_u64_solutions();
+        assert_eq!(
+            solutions,
+            Some(PossibleSolutions::exactly_one(base + offset))
+        );
+
+        // a pointer which doesn't point into any allocation
+        let bad_ptr = state.new_bv_with_name(Name::from("bad_ptr"), 64)?;
+        state.assert(&bad_ptr._eq(&state.bv_from_u64(0x10, 64)))?;
+        match state.concretize_pointer(&bad_ptr) {
+            Err(Error::OtherError(_)) => {},
+            r => panic!("Expected an error, got {:?}", r),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn allocation_info() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+        let buf = state.allocate(128_u64);
+        let base = buf.as_u64().expect("Expected a constant address");
+        let expected = AllocationInfo {
+            base,
+            size_bytes: 16,
+            is_global: false,
+        };
+
+        // a concrete pointer into the buffer
+        let ptr = buf.add(&state.bv_from_u64(5, 64));
+        assert_eq!(state.allocation_info(&ptr)?, Some(expected));
+
+        // a symbolic pointer which must lie within the buffer
+        let ptr = state.new_bv_with_name(Name::from("ptr"), 64)?;
+        state.assert(&ptr.ugte(&buf.add(&state.bv_from_u64(4, 64))))?;
+        state.assert(&ptr.ult(&buf.add(&state.bv_from_u64(8, 64))))?;
+        assert_eq!(state.allocation_info(&ptr)?, Some(expected));
+        // and `allocation_info()` didn't constrain it
+        let num_solutions = state.get_possible_solutions_for_bv(&ptr, 4)?.count();
+        assert_eq!(num_solutions, SolutionCount::Exactly(4));
+
+        // a symbolic pointer which may point into either of two allocations
+        let buf2 = state.allocate(64_u64);
+        let ambiguous = state.new_bv_with_name(Name::from("ambiguous"), 64)?;
+        state.assert(&ambiguous._eq(&buf).or(&ambiguous._eq(&buf2)))?;
+        match state.allocation_info(&ambiguous) {
+            Err(Error::OtherError(_)) => {},
+            r => panic!("Expected an error, got {:?}", r),
+        }
+
+        // a pointer to a function is a global allocation
+        let funcptr = state
+            .get_pointer_to_function("test_func")
+            .expect("Expected a pointer to test_func")
+            .clone();
+        let info = state
+            .allocation_info(&funcptr)?
+            .expect("Expected an allocation for test_func");
+        assert!(info.is_global);
+
+        // a pointer which doesn't point into any allocation
+        let bad_ptr = state.bv_from_u64(0x10, 64);
+        assert_eq!(state.allocation_info(&bad_ptr)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_and_restore() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+
+        // x < 10, and memory at `addr` contains 1
+        let x = state.new_bv_with_name(Name::from("x"), 64)?;
+        state.assert(&x.ult(&state.bv_from_u64(10, 64)))?;
+        let addr = state.allocate(64_u64);
+        state.write(&addr, state.bv_from_u64(1, 64))?;
+        let snapshot = state.snapshot();
+
+        // continue the path: x > 5, `y` is assigned, and memory contains 2
+        state.assert(&x.ugt(&state.bv_from_u64(5, 64)))?;
+        state.assign_bv_to_name(Name::from("y"), state.bv_from_u64(3, 64))?;
+        state.write(&addr, state.bv_from_u64(2, 64))?;
+        assert!(!state
+            .sat_with_extra_constraints(std::iter::once(&x.ult(&state.bv_from_u64(3, 64))))?);
+
+        // restore twice, checking each time that we're back to the snapshot
+        for _ in 0 .. 2 {
+            state.restore(&snapshot)?;
+            assert!(state
+                .sat_with_extra_constraints(std::iter::once(&x.ult(&state.bv_from_u64(3, 64))))?);
+            assert!(!state
+                .sat_with_extra_constraints(std::iter::once(&x.ugt(&state.bv_from_u64(10, 64))))?);
+            let solutions = state
+                .get_possible_solutions_for_bv(&state.read(&addr, 64)?, 1)?
+                .as_u64_solutions();
+            assert_eq!(solutions, Some(PossibleSolutions::exactly_one(1)));
+            // `y` was assigned after the snapshot
+            assert!(!state
+                .varmap
+                .get_all_vars_in_fn("test_func")
+                .any(|(name, _)| name == &Name::from("y")));
+
+            // diverge again before the next restore
+            state.assert(&x._eq(&state.bv_from_u64(7, 64)))?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_after_exhausting_backtracking_points() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb"), Name::from("bb2")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+
+        // branch on x > 5, taking the x <= 5 side first, and snapshot that side
+        let x = state.new_bv_with_name(Name::from("x"), 64)?;
+        state.assert(&x.ult(&state.bv_from_u64(10, 64)))?;
+        let five = state.bv_from_u64(5, 64);
+        state.save_backtracking_point(&Name::from("bb2"), x.ugt(&five));
+        state.assert(&x.ulte(&five))?;
+        let snapshot = state.snapshot();
+
+        // explore the x > 5 side, until there are no backtracking points left
+        assert!(state.revert_to_backtracking_point()?);
+        state.assert(&x._eq(&state.bv_from_u64(7, 64)))?;
+        assert!(state.sat()?);
+        assert!(!state.revert_to_backtracking_point()?);
+
+        // the snapshot's side must still be feasible, without the other side's
+        // constraints
+        state.restore(&snapshot)?;
+        assert!(state.sat()?);
+        assert!(
+            state.sat_with_extra_constraints(std::iter::once(&x.ult(&state.bv_from_u64(3, 64))))?
+        );
+        assert!(!state.sat_with_extra_constraints(std::iter::once(&x.ugt(&five)))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn constraints_to_smtlib() -> Result<()> {
+        let func = blank_function("test_func", vec![Name::from("test_bb"), Name::from("bb2")]);
+        let project = blank_project("test_mod", func);
+        let mut state = blank_state(&project, "test_func");
+
+        // one constraint before the backtracking point, and one after, so that
+        // the second lives in a solver scope
+        let x = state.new_bv_with_name(Name::from("x"), 64)?;
+        state.assert(&x.ult(&state.bv_from_u64(10, 64)))?;
+        state.save_backtracking_point(&Name::from("bb2"), state.bv_from_bool(true));
+        let y = state.new_bv_with_name(Name::from("y"), 64)?;
+        state.assert(&y.ugt(&state.bv_from_u64(5, 64)))?;
+
+        let smtlib = state.constraints_to_smtlib()?;
+        assert!(smtlib.contains("declare-fun"));
+        assert!(smtlib.contains('x'));
+        assert!(smtlib.contains('y'));
+        assert!(smtlib.matches("(assert").count() >= 2);
+
+        // dumping doesn't disturb the solver state of the current path
+        assert!(!state
+            .sat_with_extra_constraints(std::iter::once(&y.ult(&state.bv_from_u64(3, 64))))?);
+        assert_eq!(state.count_backtracking_points(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn fork() {
         let func = blank_function("test_func", vec![Name::from("test_bb")]);
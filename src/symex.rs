@@ -1,11 +1,14 @@
 use either::Either;
 use itertools::Itertools;
-use llvm_ir::instruction::{BinaryOp, InlineAssembly};
+use llvm_ir::instruction::{BinaryOp, HasResult, InlineAssembly};
 use llvm_ir::types::NamedStructDef;
 use llvm_ir::*;
-use log::{debug, info};
+use log::{debug, info, warn};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
 
 // Rust 1.51.0 introduced its own `.reduce()` on the main `Iterator` trait.
 // So, starting with 1.51.0, we don't need `reduce::Reduce`, and in fact it
@@ -13,15 +16,33 @@ use std::fmt;
 #[rustversion::before(1.51)]
 use reduce::Reduce;
 
+use crate::alloc_utils::MAX_ALLOCATION_SIZE_BYTES;
 use crate::backend::*;
 use crate::config::*;
+use crate::coverage::CoverageReport;
 use crate::error::*;
 use crate::function_hooks::*;
+use crate::global_allocations::Callable;
+use crate::hook_utils;
 use crate::parameter_val::ParameterVal;
 use crate::project::Project;
 use crate::return_value::*;
-use crate::solver_utils::PossibleSolutions;
-pub use crate::state::{BBInstrIndex, Location, LocationDescription, PathEntry, State};
+use crate::solver_utils::{IndexSet, PossibleSolutions, SolverStats};
+pub use crate::state::{
+    AllocationInfo,
+    BBInstrIndex,
+    Location,
+    LocationDescription,
+    MemoryAccess,
+    PathEntry,
+    State,
+    StateSnapshot,
+    ValueWatchpointHit,
+};
+
+/// Maximum number of possible targets of a function pointer which are named in
+/// the error message when the function pointer can't be resolved
+const MAX_FUNCTION_PTR_TARGETS_IN_MESSAGE: usize = 5;
 
 /// Begin symbolic execution of the function named `funcname`, obtaining an
 /// `ExecutionManager`.
@@ -59,16 +80,65 @@ pub fn symex_function<'p, B: Backend>(
     };
     let squash_unsats = config.squash_unsats;
     let mut state = State::new(project, start_loc, config);
+    // the parameters' constraints go in a solver scope, so that
+    // `reset_with_params()` can remove them again
+    let initial_snapshot = state.snapshot();
+    let InitializedParams {
+        bvparams,
+        param_array_elements,
+        seed_constraint,
+    } = initialize_params(&mut state, project, func, params)?;
+    Ok(ExecutionManager::new(
+        state,
+        project,
+        initial_snapshot,
+        bvparams,
+        param_array_elements,
+        seed_constraint,
+        squash_unsats,
+    ))
+}
+
+/// The result of `initialize_params()`
+struct InitializedParams<B: Backend> {
+    /// The parameter `BV`s
+    bvparams: Vec<B::BV>,
+    /// The element `BV`s of each `ParameterVal::PointerToUnconstrainedArray`,
+    /// by parameter index
+    param_array_elements: HashMap<usize, Vec<B::BV>>,
+    /// The constraint that the parameters have their `Config.seed_values`, if
+    /// any
+    seed_constraint: Option<B::BV>,
+}
+
+/// Create the `BV`s for the parameters of `func` and set them up according to
+/// the given `ParameterVal`s (see `symex_function()`), also applying the
+/// `Config.initial_constraints`.
+fn initialize_params<'p, B: Backend + 'p>(
+    state: &mut State<'p, B>,
+    project: &'p Project,
+    func: &'p Function,
+    params: Option<Vec<ParameterVal>>,
+) -> Result<InitializedParams<B>> {
     let params = params.unwrap_or_else(|| {
         std::iter::repeat(ParameterVal::Unconstrained)
             .take(func.parameters.len())
             .collect()
     });
+    // for each `ParameterVal::Buffer` with a length parameter: the buffer's
+    // address, its element size in bytes, the index of its length parameter,
+    // and its `max_len`. We can only constrain the length parameters once
+    // all the parameters have been created.
+    let mut buffer_lengths: Vec<(u64, u64, usize, usize)> = Vec::new();
+    // map from parameter index to the elements of its
+    // `ParameterVal::PointerToUnconstrainedArray`
+    let mut param_array_elements: HashMap<usize, Vec<B::BV>> = HashMap::new();
     let bvparams: Vec<_> = func
         .parameters
         .iter()
         .zip_eq(params.into_iter())
-        .map(|(param, paramval)| {
+        .enumerate()
+        .map(|(param_index, (param, paramval))| {
             let param_size = state
                 .size_in_bits(&param.ty)
                 .expect("Parameter type is a struct opaque in the entire Project");
@@ -79,17 +149,17 @@ pub fn symex_function<'p, B: Backend>(
             match paramval {
                 ParameterVal::Unconstrained => {}, // nothing to do
                 ParameterVal::ExactValue(val) => {
-                    bvparam._eq(&state.bv_from_u64(val, param_size)).assert()?;
+                    state.assert(&bvparam._eq(&state.bv_from_u64(val, param_size)))?;
                 },
                 ParameterVal::Range(low, high) => {
                     debug_assert!(low <= high);
-                    bvparam.ugte(&state.bv_from_u64(low, param_size)).assert()?;
-                    bvparam.ulte(&state.bv_from_u64(high, param_size)).assert()?;
+                    state.assert(&bvparam.ugte(&state.bv_from_u64(low, param_size)))?;
+                    state.assert(&bvparam.ulte(&state.bv_from_u64(high, param_size)))?;
                 },
                 ParameterVal::NonNullPointer => {
                     match param.ty.as_ref() {
                         Type::PointerType { .. } => {
-                            bvparam._ne(&state.zero(param_size)).assert()?;
+                            state.assert(&bvparam._ne(&state.zero(param_size)))?;
                         },
                         ty => panic!("ParameterVal::NonNullPointer used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
                     }
@@ -99,21 +169,258 @@ pub fn symex_function<'p, B: Backend>(
                         Type::PointerType { .. } => {
                             let allocbits = allocbytes * 8;
                             let allocated = state.allocate(allocbits);
-                            bvparam._eq(&allocated).assert()?;
+                            state.assert(&bvparam._eq(&allocated))?;
                         },
                         ty => panic!("ParameterVal::PointerToAllocated used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
                     }
                 }
+                ParameterVal::PolymorphicObject { vtable_global } => {
+                    match param.ty.as_ref() {
+                        Type::PointerType { pointee_type, .. } => {
+                            let ptr_size = project.pointer_size_bits();
+                            // the object must at least be large enough to hold the vtable pointer
+                            let objbits = std::cmp::max(state.size_in_bits(pointee_type).unwrap_or(0), ptr_size);
+                            let allocated = state.allocate(objbits);
+                            state.assert(&bvparam._eq(&allocated))?;
+                            let (vtable, _) = project
+                                .all_global_vars()
+                                .find(|(gv, _)| gv.name == Name::from(vtable_global.clone()))
+                                .unwrap_or_else(|| panic!("ParameterVal::PolymorphicObject: failed to find vtable global named {:?}", vtable_global));
+                            // `const_to_bv()` on the `GlobalReference` gets us the vtable's address, initializing the vtable if necessary
+                            let vtable_addr = state.const_to_bv(&Constant::GlobalReference {
+                                name: vtable.name.clone(),
+                                ty: vtable.ty.clone(),
+                            })?;
+                            // the vtable pointer points just past the offset-to-top and RTTI entries
+                            let address_point = vtable_addr.add(&state.bv_from_u32(2 * ptr_size / 8, ptr_size));
+                            state.write(&allocated, address_point)?;
+                        },
+                        ty => panic!("ParameterVal::PolymorphicObject used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
+                    }
+                }
+                ParameterVal::Buffer { max_len, len_param_index } => {
+                    match param.ty.as_ref() {
+                        Type::PointerType { pointee_type, .. } => {
+                            let elem_bits = state
+                                .size_in_bits(pointee_type)
+                                .unwrap_or_else(|| panic!("ParameterVal::Buffer used for parameter {} whose pointee type is opaque", &param.name));
+                            let mut elem_bytes = u64::from(elem_bits / 8);
+                            if elem_bytes * 8 < u64::from(elem_bits) {
+                                elem_bytes += 1; // round up to nearest byte
+                            }
+                            let allocated = state.allocate(elem_bytes * 8 * max_len as u64);
+                            state.assert(&bvparam._eq(&allocated))?;
+                            if let Some(len_param_index) = len_param_index {
+                                buffer_lengths.push((allocated.as_u64().unwrap(), elem_bytes, len_param_index, max_len));
+                            }
+                        },
+                        ty => panic!("ParameterVal::Buffer used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
+                    }
+                }
+                ParameterVal::PointerToUnconstrainedArray { element_bits, num_elements } => {
+                    match param.ty.as_ref() {
+                        Type::PointerType { .. } => {
+                            assert_eq!(element_bits % 8, 0, "ParameterVal::PointerToUnconstrainedArray: element_bits must be a multiple of 8, but got {}", element_bits);
+                            let allocated = state.allocate(u64::from(element_bits) * num_elements as u64);
+                            state.assert(&bvparam._eq(&allocated))?;
+                            let ptr_size = project.pointer_size_bits();
+                            let element_bytes = state.bv_from_u32(element_bits / 8, ptr_size);
+                            let mut addr = allocated;
+                            let mut elements = Vec::with_capacity(num_elements);
+                            for i in 0 .. num_elements {
+                                let element = B::BV::new(state.solver.clone(), element_bits, Some(&format!("{}[{}]", param.name, i)));
+                                state.write(&addr, element.clone())?;
+                                elements.push(element);
+                                addr = addr.add(&element_bytes);
+                            }
+                            param_array_elements.insert(param_index, elements);
+                        },
+                        ty => panic!("ParameterVal::PointerToUnconstrainedArray used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
+                    }
+                }
+                ParameterVal::AlignedPointer { alignment } => {
+                    match param.ty.as_ref() {
+                        Type::PointerType { .. } => {
+                            assert!(alignment.is_power_of_two(), "ParameterVal::AlignedPointer: alignment must be a power of two, but got {}", alignment);
+                            // the low log2(alignment) bits must be zero
+                            let low_bits = alignment.trailing_zeros();
+                            if low_bits > 0 {
+                                state.assert(&bvparam.slice(low_bits - 1, 0)._eq(&state.zero(low_bits)))?;
+                            }
+                        },
+                        ty => panic!("ParameterVal::AlignedPointer used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
+                    }
+                }
+                ParameterVal::PointerToNulTerminatedString { max_len } => {
+                    match param.ty.as_ref() {
+                        Type::PointerType { .. } => {
+                            let allocated = state.allocate((max_len as u64 + 1) * 8);
+                            state.assert(&bvparam._eq(&allocated))?;
+                            // the index of the NUL terminator, i.e., the string's length
+                            let len = B::BV::new(state.solver.clone(), 64, Some(&format!("{}_strlen", param.name)));
+                            state.assert(&len.ulte(&state.bv_from_u64(max_len as u64, 64)))?;
+                            let ptr_size = project.pointer_size_bits();
+                            let one = state.one(ptr_size);
+                            let mut addr = allocated;
+                            for i in 0 ..= max_len {
+                                let byte = B::BV::new(state.solver.clone(), 8, Some(&format!("{}[{}]", param.name, i)));
+                                let i = state.bv_from_u64(i as u64, 64);
+                                let zero = state.zero(8);
+                                state.assert(&len.ugt(&i).implies(&byte._ne(&zero)))?;
+                                state.assert(&len._eq(&i).implies(&byte._eq(&zero)))?;
+                                state.write(&addr, byte)?;
+                                addr = addr.add(&one);
+                            }
+                        },
+                        ty => panic!("ParameterVal::PointerToNulTerminatedString used for non-pointer parameter {} (which has type {:?})", &param.name, ty),
+                    }
+                }
             }
             Ok(bvparam)
         })
         .collect::<Result<Vec<_>>>()?;
-    Ok(ExecutionManager::new(
-        state,
-        project,
+    for (buffer_addr, elem_bytes, len_param_index, max_len) in buffer_lengths {
+        let len_param = func.parameters.get(len_param_index).unwrap_or_else(|| {
+            panic!(
+                "ParameterVal::Buffer: len_param_index {} is out of range for function {:?}, which has {} parameters",
+                len_param_index, func.name, func.parameters.len()
+            )
+        });
+        match len_param.ty.as_ref() {
+            Type::IntegerType { .. } => {},
+            ty => panic!(
+                "ParameterVal::Buffer: length parameter {} has non-integer type {:?}",
+                &len_param.name, ty
+            ),
+        }
+        let len = &bvparams[len_param_index];
+        state.assert(&len.ulte(&state.bv_from_u64(max_len as u64, len.get_width())))?;
+        // the number of valid bytes in the buffer, as a pointer-sized `BV`
+        let ptr_size = project.pointer_size_bits();
+        let len = if len.get_width() > ptr_size {
+            len.slice(ptr_size - 1, 0)
+        } else {
+            len.zero_extend_to_bits(ptr_size)
+        };
+        let valid_bytes = len.mul(&state.bv_from_u64(elem_bytes, ptr_size));
+        state.set_allocation_valid_bytes(buffer_addr, valid_bytes);
+    }
+    if let Some(initial_constraints) = state.config.initial_constraints.clone() {
+        initial_constraints(state)?;
+    }
+    // the constraint that the parameters have their `Config.seed_values`
+    let seed_constraint = state
+        .config
+        .seed_values
+        .iter()
+        .map(|(paramname, &val)| {
+            let param_index = func
+                .parameters
+                .iter()
+                .position(|param| match &param.name {
+                    Name::Name(name) => name.as_str() == paramname.as_str(),
+                    Name::Number(n) => n.to_string() == *paramname,
+                })
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Config.seed_values: function {:?} has no parameter named {:?}",
+                        func.name, paramname
+                    )
+                });
+            let bvparam = &bvparams[param_index];
+            bvparam._eq(&state.bv_from_u64(val, bvparam.get_width()))
+        })
+        .reduce(|a, b| a.and(&b));
+    Ok(InitializedParams {
         bvparams,
-        squash_unsats,
-    ))
+        param_array_elements,
+        seed_constraint,
+    })
+}
+
+/// Begin symbolic execution of `main(int argc, char** argv)`, with symbolic
+/// (but bounded) command-line arguments.
+///
+/// `argc` is constrained to be between 1 and `max_args` (which counts the
+/// program name, `argv[0]`), and each of `argv[0]` through
+/// `argv[max_args - 1]` points to its own NUL-terminated string of up to
+/// `max_arg_len` symbolic bytes (that is, the bytes are unconstrained, and the
+/// string ends at the first NUL byte, or after `max_arg_len` bytes).
+/// `argv[argc]` is `NULL`, as required by the C standard. If `main()` also
+/// takes an `envp` parameter, it points to an empty environment.
+///
+/// Returns the `ExecutionManager`, along with the symbolic bytes of each of the
+/// `max_args` strings (not including the final NUL terminator), so that you
+/// can solve for them at the end of a path. The `BV` for `argc` is available
+/// as the first of the `ExecutionManager`'s
+/// [`param_bvs()`](struct.ExecutionManager.html#method.param_bvs).
+///
+/// Panics if `max_args` is 0, or if the `Project` has no `main()` taking
+/// `argc` and `argv` (and optionally `envp`).
+#[allow(clippy::type_complexity)]
+pub fn symex_main_with_symbolic_args<'p, B: Backend>(
+    project: &'p Project,
+    config: Config<'p, B>,
+    max_args: usize,
+    max_arg_len: usize,
+) -> Result<(ExecutionManager<'p, B>, Vec<Vec<B::BV>>)> {
+    assert!(
+        max_args > 0,
+        "symex_main_with_symbolic_args: max_args must be at least 1"
+    );
+    let (main, _) = project
+        .get_func_by_name("main")
+        .unwrap_or_else(|| panic!("Failed to find function named \"main\""));
+    let ptr_size = project.pointer_size_bits();
+    let ptr_bytes = u64::from(ptr_size / 8);
+    let mut params = vec![
+        ParameterVal::Range(1, max_args as u64),
+        // room for the `max_args` pointers plus the final `NULL`
+        ParameterVal::PointerToAllocated((max_args as u64 + 1) * ptr_bytes),
+    ];
+    match main.parameters.len() {
+        2 => {},
+        3 => params.push(ParameterVal::PointerToAllocated(ptr_bytes)),
+        n => panic!(
+            "symex_main_with_symbolic_args: expected main() to have 2 or 3 parameters, but it has {}",
+            n
+        ),
+    }
+    let mut em = symex_function("main", project, config, Some(params))?;
+    let argc = em.param_bvs()[0].clone();
+    let argv = em.param_bvs()[1].clone();
+    let envp = em.param_bvs().get(2).cloned();
+    // changes to the initial state persist through all paths
+    let state = em.mut_state();
+    let null = state.zero(ptr_size);
+    let mut args = Vec::with_capacity(max_args);
+    let mut argv_entry = argv;
+    for i in 0 .. max_args {
+        let string = state.allocate((max_arg_len as u64 + 1) * 8);
+        let mut addr = string.clone();
+        let mut bytes = Vec::with_capacity(max_arg_len);
+        for j in 0 .. max_arg_len {
+            let byte = B::BV::new(
+                state.solver.clone(),
+                8,
+                Some(&format!("argv[{}][{}]", i, j)),
+            );
+            state.write(&addr, byte.clone())?;
+            bytes.push(byte);
+            addr = addr.inc();
+        }
+        state.write(&addr, state.zero(8))?;
+        args.push(bytes);
+        // `argv[i]` is this string if `i < argc`, or else `NULL`
+        let in_range = argc.ugt(&state.bv_from_u64(i as u64, argc.get_width()));
+        state.write(&argv_entry, in_range.cond_bv(&string, &null))?;
+        argv_entry = argv_entry.add(&state.bv_from_u64(ptr_bytes, ptr_size));
+    }
+    state.write(&argv_entry, null.clone())?;
+    if let Some(envp) = envp {
+        state.write(&envp, null)?;
+    }
+    Ok((em, args))
 }
 
 /// An `ExecutionManager` allows you to symbolically explore executions of a
@@ -137,29 +444,81 @@ pub struct ExecutionManager<'p, B: Backend> {
     project: &'p Project,
     func: &'p Function,
     bvparams: Vec<B::BV>,
+    /// Map from parameter index to the element `BV`s of that parameter's
+    /// `ParameterVal::PointerToUnconstrainedArray`
+    param_array_elements: HashMap<usize, Vec<B::BV>>,
+    /// The constraint that the parameters have their `Config.seed_values`, or
+    /// `None` if there are no seed values
+    seed_constraint: Option<B::BV>,
+    /// Snapshot of the initial `State`, before the parameters were set up;
+    /// see `reset_with_params()`
+    initial_snapshot: StateSnapshot<'p, B>,
     /// Whether the `ExecutionManager` is "fresh". A "fresh" `ExecutionManager`
     /// has not yet produced its first path, i.e., `next()` has not been called
     /// on it yet.
     fresh: bool,
     /// The `squash_unsats` setting from `Config`
     squash_unsats: bool,
+    /// How many paths `next()` has produced so far (whether `Ok` or `Err`)
+    paths_explored: usize,
+    /// When the first path began, for enforcing `Config.total_time_budget`.
+    /// `None` if `next()` has not been called yet.
+    start_time: Option<Instant>,
+    /// Whether we have already reported `Error::TimeBudgetExceeded` or
+    /// `Error::Cancelled`, in which case we won't produce any more paths
+    analysis_stopped: bool,
+    /// State of the pseudorandom number generator used for
+    /// `SinglePathPolicy::Random`
+    single_path_rng: u64,
+    /// If `true`, a path which reaches a dead end stops there (setting
+    /// `reached_dead_end`), rather than immediately backtracking to continue
+    /// with another path. This is only `true` during `next_with_outcome()`.
+    stop_at_dead_ends: bool,
+    /// Whether the current path stopped at a dead end; see `stop_at_dead_ends`
+    reached_dead_end: bool,
+    /// Solver query counts as of when the `ExecutionManager` was created
+    solver_stats_at_start: SolverStats,
+    /// For each function, identified by (module name, function name), a map
+    /// from the `Name` defined by each `getelementptr` or `bitcast` in that
+    /// function to the pointer it was derived from; see `deriving_pointer()`.
+    /// Only populated for functions where we've needed it.
+    derived_pointers: HashMap<(&'p str, &'p str), HashMap<&'p Name, &'p Operand>>,
 }
 
 impl<'p, B: Backend> ExecutionManager<'p, B> {
     fn new(
         state: State<'p, B>,
         project: &'p Project,
+        initial_snapshot: StateSnapshot<'p, B>,
         bvparams: Vec<B::BV>,
+        param_array_elements: HashMap<usize, Vec<B::BV>>,
+        seed_constraint: Option<B::BV>,
         squash_unsats: bool,
     ) -> Self {
         let func = state.cur_loc.func;
+        let solver_stats_at_start = state.solver.stats();
+        let single_path_rng = match state.config.single_path {
+            Some(SinglePathPolicy::Random { seed }) => seed,
+            _ => 0,
+        };
         Self {
             state,
             project,
             func,
             bvparams,
+            param_array_elements,
+            seed_constraint,
+            initial_snapshot,
             fresh: true,
             squash_unsats,
+            paths_explored: 0,
+            start_time: None,
+            analysis_stopped: false,
+            single_path_rng,
+            stop_at_dead_ends: false,
+            reached_dead_end: false,
+            solver_stats_at_start,
+            derived_pointers: HashMap::new(),
         }
     }
 
@@ -186,10 +545,275 @@ impl<'p, B: Backend> ExecutionManager<'p, B> {
         &mut self.state
     }
 
+    /// Provides access to the basic-block coverage accumulated over all the
+    /// paths explored so far by this `ExecutionManager`, including the
+    /// current one.
+    pub fn coverage(&self) -> &CoverageReport<'p> {
+        self.state.coverage()
+    }
+
+    /// Get the number of solver queries performed over all the paths explored
+    /// so far by this `ExecutionManager`, including the current one. For just
+    /// the current path, see
+    /// [`State::solver_stats()`](struct.State.html#method.solver_stats).
+    pub fn solver_stats(&self) -> SolverStats {
+        self.state.solver.stats().since(&self.solver_stats_at_start)
+    }
+
     /// Provides access to the `BV` objects representing each of the function's parameters
     pub fn param_bvs(&self) -> &Vec<B::BV> {
         &self.bvparams
     }
+
+    /// If the parameter with the given (0-indexed) position was set up with
+    /// `ParameterVal::PointerToUnconstrainedArray`, provides access to the
+    /// `BV`s representing the array's initial elements, in order.
+    /// Otherwise, returns `None`.
+    pub fn param_array_elements(&self, param_index: usize) -> Option<&[B::BV]> {
+        self.param_array_elements
+            .get(&param_index)
+            .map(|elements| elements.as_slice())
+    }
+
+    /// Add a constraint relating the function's parameters to the initial
+    /// state, e.g., that the first parameter is less than the second.
+    ///
+    /// `relation` is given the parameter `BV`s (as in
+    /// [`param_bvs()`](#method.param_bvs)) and the initial `State`, and
+    /// should return a 1-bit `BV` which must be true. Like other changes to
+    /// the initial state, this constraint persists through all paths (until
+    /// [`reset_with_params()`](#method.reset_with_params), if it's used).
+    ///
+    /// Panics if called after `next()` has already been called, since the
+    /// initial state is no longer available then.
+    pub fn assert_relation(
+        &mut self,
+        relation: impl FnOnce(&[B::BV], &State<'p, B>) -> B::BV,
+    ) -> Result<()> {
+        assert!(
+            self.fresh,
+            "ExecutionManager::assert_relation() must be called before the first call to next()"
+        );
+        let constraint = relation(&self.bvparams, &self.state);
+        self.state.assert(&constraint)
+    }
+
+    /// How many paths have been produced by `next()` so far, counting both `Ok`
+    /// and `Err` results.
+    ///
+    /// If `Config.max_paths` is `Some`, this can be used to tell whether `next()`
+    /// returned `None` because it reached that limit (in which case this will
+    /// equal the limit) or because there were no more possible paths.
+    pub fn paths_explored(&self) -> usize {
+        self.paths_explored
+    }
+
+    /// Like `next()`, but reports how the path ended as a `PathOutcome`.
+    ///
+    /// In particular, `next()` silently skips paths which are pruned without
+    /// reaching a return or an error (e.g., because they turn out to be
+    /// infeasible), moving on to the next path. This instead reports each such
+    /// path as `PathOutcome::Dead`. As with `next()`, after any call to this
+    /// method, `state()` gives the `State` at the end of the path.
+    ///
+    /// Dead paths don't count towards `paths_explored()` or
+    /// `Config.max_paths`.
+    ///
+    /// Returns `None` when there are no more paths.
+    pub fn next_with_outcome(&mut self) -> Option<PathOutcome<B::BV>> {
+        self.stop_at_dead_ends = true;
+        self.reached_dead_end = false;
+        let result = self.next();
+        self.stop_at_dead_ends = false;
+        if self.reached_dead_end {
+            self.reached_dead_end = false;
+            return Some(PathOutcome::Dead);
+        }
+        result.map(|result| match result {
+            Ok(retval) => retval.into(),
+            Err(e) => PathOutcome::Error(e),
+        })
+    }
+
+    /// Get a set of possible return values of the function, across all of the
+    /// remaining paths: in the same way as
+    /// [`get_possible_return_values_of_func()`](fn.get_possible_return_values_of_func.html),
+    /// but using this `ExecutionManager` (so respecting any changes already
+    /// made to the initial state with `mut_state()`) rather than a new one.
+    ///
+    /// This explores paths using `next()`, so it should usually be called
+    /// before any other calls to `next()`: paths which have already been
+    /// explored aren't considered. Panics if any path results in an error.
+    ///
+    /// `thrown_size` and `n` are as for
+    /// [`get_possible_return_values_of_func()`](fn.get_possible_return_values_of_func.html).
+    pub fn possible_return_values(
+        &mut self,
+        n: usize,
+        thrown_size: Option<u32>,
+    ) -> PossibleSolutions<ReturnValue<u64>> {
+        self.possible_return_values_with_budget(thrown_size, ReturnValueBudget::Combined(n))
+    }
+
+    /// Like [`possible_return_values()`](#method.possible_return_values), but
+    /// with separate budgets for distinct returned values and distinct thrown
+    /// values, so that a function with many possible return values doesn't
+    /// crowd out the discovery of thrown values (or vice versa); see
+    /// [`get_possible_returns_and_throws_of_func()`](fn.get_possible_returns_and_throws_of_func.html).
+    pub fn possible_returns_and_throws(
+        &mut self,
+        thrown_size: Option<u32>,
+        max_returns: usize,
+        max_throws: usize,
+    ) -> PossibleSolutions<ReturnValue<u64>> {
+        self.possible_return_values_with_budget(
+            thrown_size,
+            ReturnValueBudget::Separate {
+                returns: max_returns,
+                throws: max_throws,
+            },
+        )
+    }
+
+    fn possible_return_values_with_budget(
+        &mut self,
+        thrown_size: Option<u32>,
+        budget: ReturnValueBudget,
+    ) -> PossibleSolutions<ReturnValue<u64>> {
+        let return_width = self
+            .state
+            .size_in_bits(&self.func.return_type)
+            .expect("Function return type shouldn't be opaque struct type");
+        let mut candidate_values = IndexSet::<ReturnValue<u64>>::new();
+        let mut have_throw = false; // is there at least one `ReturnValue::Throw` in the `candidate_values`
+        while let Some(bvretval) = self.next() {
+            let bvretval = bvretval
+                .unwrap_or_else(|e| panic!("{}", self.state.full_error_message_with_context(e)));
+            let is_throw = matches!(bvretval, ReturnValue::Throw(_));
+            if budget.is_exceeded(&candidate_values, is_throw) {
+                // we already know there are too many values of this kind
+                continue;
+            }
+            match bvretval {
+                ReturnValue::ReturnVoid => {
+                    candidate_values.insert(ReturnValue::ReturnVoid);
+                },
+                ReturnValue::Abort => {
+                    candidate_values.insert(ReturnValue::Abort);
+                },
+                ReturnValue::Return(bvretval) => {
+                    assert_eq!(bvretval.get_width(), return_width);
+                    let state = &mut self.state;
+                    // rule out all the returned values we already have - we're interested in new values
+                    for candidate in candidate_values.iter() {
+                        if let ReturnValue::Return(candidate) = candidate {
+                            bvretval
+                                ._ne(&state.bv_from_u64(*candidate, return_width))
+                                .assert()
+                                .unwrap();
+                        }
+                    }
+                    match state
+                        .get_possible_solutions_for_bv(&bvretval, budget.limit(false))
+                        .unwrap()
+                    {
+                        PossibleSolutions::Exactly(v) | PossibleSolutions::AtLeast(v) => {
+                            candidate_values.extend(
+                                v.iter()
+                                    .map(|bvsol| ReturnValue::Return(bvsol.as_u64().unwrap())),
+                            );
+                        },
+                    };
+                },
+                ReturnValue::Throw(bvptr) => {
+                    let state = &mut self.state;
+                    match thrown_size {
+                        None => {
+                            if !have_throw {
+                                candidate_values
+                                    .insert(ReturnValue::Throw(bvptr.as_u64().unwrap()));
+                                have_throw = true;
+                            }
+                        },
+                        Some(thrown_size) => {
+                            let thrown_value = state.read(&bvptr, thrown_size).unwrap();
+                            // rule out all the thrown values we already have - we're interested in new values
+                            for candidate in candidate_values.iter() {
+                                if let ReturnValue::Throw(candidate) = candidate {
+                                    thrown_value
+                                        ._ne(&state.bv_from_u64(*candidate, thrown_size))
+                                        .assert()
+                                        .unwrap();
+                                }
+                            }
+                            match state
+                                .get_possible_solutions_for_bv(&thrown_value, budget.limit(true))
+                                .unwrap()
+                            {
+                                PossibleSolutions::Exactly(v) | PossibleSolutions::AtLeast(v) => {
+                                    candidate_values.extend(
+                                        v.iter().map(|bvsol| {
+                                            ReturnValue::Throw(bvsol.as_u64().unwrap())
+                                        }),
+                                    );
+                                },
+                            }
+                        },
+                    }
+                },
+            }
+            if budget.is_exceeded(&candidate_values, false)
+                && budget.is_exceeded(&candidate_values, true)
+            {
+                break;
+            }
+        }
+        if budget.is_exceeded(&candidate_values, false)
+            || budget.is_exceeded(&candidate_values, true)
+        {
+            PossibleSolutions::AtLeast(candidate_values)
+        } else {
+            PossibleSolutions::Exactly(candidate_values)
+        }
+    }
+
+    /// Start over with new values for the function's parameters, as if with a
+    /// fresh call to [`symex_function()`](fn.symex_function.html) for the same
+    /// function, `Project`, and `Config`. Unlike that, this reuses the solver
+    /// instance and the allocations of global variables, so it's much cheaper
+    /// when trying many different sets of `params` in a loop.
+    ///
+    /// This abandons the current path and all saved backtracking points. The
+    /// parameter `BV`s (see [`param_bvs()`](#method.param_bvs)) are created
+    /// anew, and `Config.initial_constraints` is applied again. Global
+    /// variables are re-initialized from their initializers, so changes made to
+    /// them on previous paths don't carry over. Any changes made to the initial
+    /// state with `mut_state()` are discarded.
+    ///
+    /// `paths_explored()` and `Config.total_time_budget` start over as well;
+    /// `coverage()` and `solver_stats()` keep accumulating.
+    pub fn reset_with_params(&mut self, params: Option<Vec<ParameterVal>>) -> Result<()> {
+        debug!("ExecutionManager: resetting with params {:?}", params);
+        self.state.restore(&self.initial_snapshot)?;
+        self.state.reset_global_initialization();
+        let InitializedParams {
+            bvparams,
+            param_array_elements,
+            seed_constraint,
+        } = initialize_params(&mut self.state, self.project, self.func, params)?;
+        self.bvparams = bvparams;
+        self.param_array_elements = param_array_elements;
+        self.seed_constraint = seed_constraint;
+        self.fresh = true;
+        self.paths_explored = 0;
+        self.start_time = None;
+        self.analysis_stopped = false;
+        self.single_path_rng = match self.state.config.single_path {
+            Some(SinglePathPolicy::Random { seed }) => seed,
+            _ => 0,
+        };
+        Ok(())
+    }
 }
 
 impl<'p, B: Backend> Iterator for ExecutionManager<'p, B>
@@ -199,8 +823,21 @@ where
     type Item = Result<ReturnValue<B::BV>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let retval = if self.fresh {
+        if let Some(max_paths) = self.state.config.max_paths {
+            if self.paths_explored >= max_paths {
+                info!(
+                    "ExecutionManager: reached max_paths ({}), stopping",
+                    max_paths
+                );
+                return None;
+            }
+        }
+        if self.analysis_stopped {
+            return None;
+        }
+        let mut retval = if self.fresh {
             self.fresh = false;
+            self.start_time = Some(Instant::now());
             info!(
                 "Beginning symex in function {:?}",
                 self.state.cur_loc.func.name
@@ -208,9 +845,29 @@ where
             self.symex_from_cur_loc_through_end_of_function()
         } else {
             debug!("ExecutionManager: requesting next path");
-            self.backtrack_and_continue()
+            self.check_time_budget()
+                .and_then(|()| self.backtrack_and_continue())
         };
-        retval.transpose()
+        if self.state.config.skip_throwing_paths {
+            while let Ok(Some(ReturnValue::Throw(_))) = retval {
+                info!("ExecutionManager: skipping a path which ended in an uncaught throw");
+                retval = self
+                    .check_time_budget()
+                    .and_then(|()| self.backtrack_and_continue());
+            }
+        }
+        let retval = match (retval, self.state.config.return_value_transform.clone()) {
+            (Ok(Some(retval)), Some(transform)) => transform(&mut self.state, retval).map(Some),
+            (retval, _) => retval,
+        };
+        if let Err(Error::TimeBudgetExceeded) | Err(Error::Cancelled) = retval {
+            self.analysis_stopped = true;
+        }
+        let retval = retval.transpose();
+        if retval.is_some() {
+            self.paths_explored += 1;
+        }
+        retval
     }
 }
 
@@ -218,6 +875,45 @@ impl<'p, B: Backend> ExecutionManager<'p, B>
 where
     B: 'p,
 {
+    /// Returns `Error::TimeBudgetExceeded` if more than `Config.total_time_budget`
+    /// has elapsed since the first path began
+    fn check_time_budget(&self) -> Result<()> {
+        match (self.state.config.total_time_budget, self.start_time) {
+            (Some(budget), Some(start_time)) if start_time.elapsed() > budget => {
+                info!(
+                    "ExecutionManager: exceeded total_time_budget of {:?}",
+                    budget
+                );
+                Err(Error::TimeBudgetExceeded)
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns `Error::Cancelled` if the `Config.cancellation` flag has been set
+    fn check_cancellation(&self) -> Result<()> {
+        match &self.state.config.cancellation {
+            Some(flag) if flag.load(Ordering::Relaxed) => {
+                info!("ExecutionManager: cancelled");
+                Err(Error::Cancelled)
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts one more instruction (or terminator) executed on the current path,
+    /// returning `Error::InstructionBudgetExceeded` if that exceeds
+    /// `Config.max_instructions_per_path`, or `Error::Cancelled` if the
+    /// `Config.cancellation` flag has been set
+    fn count_instruction(&mut self) -> Result<()> {
+        self.check_cancellation()?;
+        let count = self.state.count_instruction_executed();
+        match self.state.config.max_instructions_per_path {
+            Some(budget) if count > budget => Err(Error::InstructionBudgetExceeded(budget)),
+            _ => Ok(()),
+        }
+    }
+
     /// Symex from the current `Location` through the rest of the function.
     /// Returns the `ReturnValue` representing the return value of the function,
     /// or `Ok(None)` if no possible paths were found.
@@ -228,6 +924,7 @@ where
     /// `BBInstrIndex::Instr(0)` will still be considered valid, and be treated
     /// equivalently to `BBInstrIndex::Terminator`.
     fn symex_from_cur_loc_through_end_of_function(&mut self) -> Result<Option<ReturnValue<B::BV>>> {
+        self.check_time_budget()?;
         debug!(
             "Symexing basic block {:?} in function {}",
             self.state.cur_loc.bb.name, self.state.cur_loc.func.name
@@ -264,6 +961,8 @@ where
                 first_iter = false;
                 self.state.record_path_entry(); // do this only on the first iteration
             }
+            self.count_instruction()?;
+            self.state.record_instruction(inst);
             for callback in &self.state.config.callbacks.instruction_callbacks {
                 callback(inst, &self.state)?;
             }
@@ -284,13 +983,14 @@ where
                     Instruction::ZExt(zext) => self.symex_zext(zext),
                     Instruction::SExt(sext) => self.symex_sext(sext),
                     Instruction::Trunc(trunc) => self.symex_trunc(trunc),
-                    Instruction::PtrToInt(pti) => self.symex_cast_op(pti),
-                    Instruction::IntToPtr(itp) => self.symex_cast_op(itp),
+                    Instruction::PtrToInt(pti) => self.symex_ptr_int_cast(pti),
+                    Instruction::IntToPtr(itp) => self.symex_ptr_int_cast(itp),
                     Instruction::BitCast(bitcast) => self.symex_cast_op(bitcast),
                     #[cfg(feature = "llvm-10-or-greater")]
-                    Instruction::Freeze(freeze) => self.symex_cast_op(freeze), // since our BVs are never undef or poison, freeze is the identity operation for us
+                    Instruction::Freeze(freeze) => self.symex_freeze(freeze),
                     Instruction::Phi(phi) => self.symex_phi(phi),
                     Instruction::Select(select) => self.symex_select(select),
+                    Instruction::VAArg(vaarg) => self.symex_vaarg(vaarg),
                     Instruction::CmpXchg(cmpxchg) => self.symex_cmpxchg(cmpxchg),
                     #[cfg(feature = "llvm-9-or-lower")]
                     Instruction::AtomicRMW(_) => return Err(Error::UnsupportedInstruction("LLVM `AtomicRMW` instruction is not supported for the LLVM 9 version of Haybale; see Haybale issue #12".into())),
@@ -310,7 +1010,7 @@ where
                 Err(Error::Unsat) if self.squash_unsats => {
                     // we can't continue down this path anymore; try another
                     info!("Path is unsat");
-                    return self.backtrack_and_continue();
+                    return self.dead_end();
                 },
                 Err(e) => return Err(e), // propagate any other errors
             };
@@ -322,6 +1022,7 @@ where
             // in this case, we did 0 iterations of the for loop, and still need to record the path entry
             self.state.record_path_entry();
         }
+        self.count_instruction()?;
         for callback in &self.state.config.callbacks.terminator_callbacks {
             callback(term, &self.state)?;
         }
@@ -331,6 +1032,7 @@ where
             Terminator::CondBr(condbr) => self.symex_condbr(condbr),
             Terminator::Switch(switch) => self.symex_switch(switch),
             Terminator::Invoke(invoke) => self.symex_invoke(invoke),
+            Terminator::CallBr(callbr) => self.symex_callbr(callbr),
             Terminator::Resume(resume) => self.symex_resume(resume),
             Terminator::Unreachable(_) => Err(Error::UnreachableInstruction),
             _ => Err(Error::UnsupportedInstruction(format!(
@@ -347,6 +1049,7 @@ where
     /// Returns the `ReturnValue` representing the final return value, or
     /// `Ok(None)` if no possible paths were found.
     fn backtrack_and_continue(&mut self) -> Result<Option<ReturnValue<B::BV>>> {
+        self.check_cancellation()?;
         if self.state.revert_to_backtracking_point()? {
             info!(
                 "Reverted to backtrack point; {} more backtrack points available",
@@ -369,6 +1072,20 @@ where
         }
     }
 
+    /// Called when the current path reaches a dead end (e.g., it turns out to
+    /// be infeasible). Usually this backtracks and continues with another path,
+    /// like `backtrack_and_continue()`; but during `next_with_outcome()`, this
+    /// instead ends the path, so that it can be reported as
+    /// `PathOutcome::Dead`.
+    fn dead_end(&mut self) -> Result<Option<ReturnValue<B::BV>>> {
+        if self.stop_at_dead_ends {
+            self.reached_dead_end = true;
+            Ok(None)
+        } else {
+            self.backtrack_and_continue()
+        }
+    }
+
     /// Symex starting from the current location, returning (using the saved
     /// callstack) all the way back to the end of the top-level function.
     ///
@@ -384,10 +1101,13 @@ where
         match self.symex_from_cur_loc_through_end_of_function()? {
             Some(ReturnValue::Throw(bvptr)) => {
                 // pop callsites until we find an `invoke` instruction that can direct us to a catch block
+                let mut leaving_func = self.state.cur_loc.func;
                 loop {
                     match self.state.pop_callsite() {
                         Some(callsite) => match callsite.instr {
                             Either::Left(_call) => {
+                                self.run_return_callbacks(leaving_func)?;
+                                leaving_func = callsite.loc.func;
                                 // a normal callsite, not an `invoke` instruction
                                 info!("Caller {:?} (bb {}){} is not prepared to catch the exception, rethrowing",
                                     callsite.loc.func.name,
@@ -401,6 +1121,7 @@ where
                                 continue;
                             },
                             Either::Right(invoke) => {
+                                self.run_return_callbacks(leaving_func)?;
                                 // catch the thrown value
                                 info!(
                                     "Caller {:?} (bb {}){} catching the thrown value at bb {}",
@@ -429,6 +1150,7 @@ where
             Some(symexresult) => match self.state.pop_callsite() {
                 Some(callsite) => match callsite.instr {
                     Either::Left(call) => {
+                        self.run_return_callbacks(self.state.cur_loc.func)?;
                         // Return to normal callsite
                         info!(
                             "Leaving function {:?}, continuing in caller {:?} (bb {}){}",
@@ -451,7 +1173,7 @@ where
                                     .is_err()
                                 {
                                     // This path is dead, try backtracking again
-                                    return self.backtrack_and_continue();
+                                    return self.dead_end();
                                 };
                             },
                             ReturnValue::ReturnVoid => {},
@@ -467,6 +1189,7 @@ where
                         self.symex_from_cur_loc()
                     },
                     Either::Right(invoke) => {
+                        self.run_return_callbacks(self.state.cur_loc.func)?;
                         // Normal return to an `Invoke` instruction
                         info!("Leaving function {:?}, continuing in caller {:?}{} (finished invoke in bb {}, now in bb {})",
                             self.state.cur_loc.func.name,
@@ -489,7 +1212,7 @@ where
                                     .is_err()
                                 {
                                     // This path is dead, try backtracking again
-                                    return self.backtrack_and_continue();
+                                    return self.dead_end();
                                 };
                             },
                             ReturnValue::ReturnVoid => {},
@@ -514,7 +1237,7 @@ where
             },
             None => {
                 // This path is dead, try backtracking again
-                self.backtrack_and_continue()
+                self.dead_end()
             },
         }
     }
@@ -572,24 +1295,252 @@ where
         let bvop0 = self.state.operand_to_bv(op0)?;
         let bvop1 = self.state.operand_to_bv(op1)?;
         let bvoperation = Self::binop_to_bvbinop(bop)?;
-        match op_type.as_ref() {
-            Type::IntegerType { .. } => {
-                self.state.record_bv_result(bop, bvoperation(&bvop0, &bvop1))
-            },
+        let num_elements = match op_type.as_ref() {
             #[cfg(feature = "llvm-11-or-greater")]
             Type::VectorType { scalable: true, .. } => {
-                return Err(Error::UnsupportedInstruction("operation on scalable vectors".into()));
+                return Err(Error::UnsupportedInstruction(
+                    "operation on scalable vectors".into(),
+                ));
+            },
+            Type::VectorType { num_elements, .. } => Some(*num_elements as u32),
+            _ => None,
+        };
+        let config = &self.state.config;
+        if (config.check_arithmetic_overflow || config.check_divide_by_zero) && !config.dry_run {
+            if self.state.config.check_arithmetic_overflow {
+                self.check_arithmetic_overflow(bop, &bvop0, &bvop1, num_elements)?;
             }
+            if self.state.config.check_divide_by_zero {
+                self.check_divide_by_zero(bop, &bvop0, &bvop1, num_elements)?;
+            }
+        }
+        let (bvop1, poison_mask) = match bop {
+            instruction::groups::BinaryOp::Shl(_)
+            | instruction::groups::BinaryOp::LShr(_)
+            | instruction::groups::BinaryOp::AShr(_) => {
+                self.apply_shift_overflow(bvop1, num_elements)?
+            },
+            _ => (bvop1, None),
+        };
+        let result = match op_type.as_ref() {
+            Type::IntegerType { .. } => bvoperation(&bvop0, &bvop1),
             Type::VectorType { element_type, num_elements, .. } => {
                 match element_type.as_ref() {
                     Type::IntegerType { .. } => {
-                        self.state.record_bv_result(bop, binary_on_vector(&bvop0, &bvop1, *num_elements as u32, bvoperation)?)
+                        binary_on_vector(&bvop0, &bvop1, *num_elements as u32, bvoperation)?
                     },
-                    ty => Err(Error::MalformedInstruction(format!("Expected binary operation's vector operands to have integer elements, but elements are type {:?}", ty))),
+                    ty => return Err(Error::MalformedInstruction(format!("Expected binary operation's vector operands to have integer elements, but elements are type {:?}", ty))),
                 }
             }
-            ty => Err(Error::MalformedInstruction(format!("Expected binary operation to have operands of type integer or vector of integers, but got type {:?}", ty))),
+            ty => return Err(Error::MalformedInstruction(format!("Expected binary operation to have operands of type integer or vector of integers, but got type {:?}", ty))),
+        };
+        let result = match poison_mask {
+            Some(mask) => {
+                self.havoc_masked_bits(&result, &mask, format!("{}_poison", bop.get_result()))?
+            },
+            None => result,
+        };
+        self.state.record_bv_result(bop, result)
+    }
+
+    /// Apply `Config.shift_overflow` to a shift whose shift amount is `amount`.
+    ///
+    /// Returns the shift amount to actually use, and, for
+    /// `ShiftOverflow::Poison`, a mask which is all ones in each element of the
+    /// result which is poison, and all zeroes elsewhere. For
+    /// `ShiftOverflow::Error`, this returns `Error::PossibleOversizedShift` if
+    /// the shift amount may be too large, saving a backtracking point for the
+    /// case where it isn't.
+    ///
+    /// `num_elements` should be `Some` if the operands are vectors.
+    fn apply_shift_overflow(
+        &mut self,
+        amount: B::BV,
+        num_elements: Option<u32>,
+    ) -> Result<(B::BV, Option<B::BV>)> {
+        match self.state.config.shift_overflow {
+            ShiftOverflow::ShiftOutAllBits => Ok((amount, None)),
+            ShiftOverflow::MaskLikeX86 => {
+                let num_elements = num_elements.unwrap_or(1);
+                let el_bits = amount.get_width() / num_elements;
+                let wrapped = if el_bits < 32 {
+                    // x86 masks the amount of an 8- or 16-bit shift to 5 bits, as for
+                    // a 32-bit shift; amounts which are then still at least the
+                    // bitwidth shift out every bit, as in `ShiftOutAllBits`
+                    let mask = if el_bits < 5 { (1 << el_bits) - 1 } else { 31 };
+                    let low_bits = self.state.bv_from_u32(mask, el_bits);
+                    unary_on_vector(&amount, num_elements, |el| Ok(el.and(&low_bits)))?
+                } else if el_bits.is_power_of_two() {
+                    let low_bits = self.state.bv_from_u32(el_bits - 1, el_bits);
+                    unary_on_vector(&amount, num_elements, |el| Ok(el.and(&low_bits)))?
+                } else {
+                    let el_bitwidth = self.state.bv_from_u32(el_bits, el_bits);
+                    unary_on_vector(&amount, num_elements, |el| Ok(el.urem(&el_bitwidth)))?
+                };
+                Ok((wrapped, None))
+            },
+            ShiftOverflow::Poison => {
+                let mask = self.oversized_shift_mask(&amount, num_elements)?;
+                Ok((amount, Some(mask)))
+            },
+            ShiftOverflow::Error => {
+                if !self.state.config.dry_run {
+                    let oversized = self
+                        .oversized_shift_mask(&amount, num_elements)?
+                        ._ne(&self.state.zero(amount.get_width()));
+                    if self
+                        .state
+                        .sat_with_extra_constraints(std::iter::once(&oversized))?
+                    {
+                        info!("Shift amount {:?} may be at least the bitwidth", amount);
+                        self.state.save_backtracking_point_at_location(
+                            self.state.cur_loc.clone(),
+                            oversized.not(),
+                        );
+                        self.state.assert(&oversized)?;
+                        return Err(Error::PossibleOversizedShift(
+                            self.state.cur_loc.source_loc.cloned(),
+                        ));
+                    }
+                }
+                Ok((amount, None))
+            },
+        }
+    }
+
+    /// Get a mask which is all ones in each element of the shift amount
+    /// `amount` which is at least the bitwidth, and all zeroes in the other
+    /// elements.
+    ///
+    /// `num_elements` should be `Some` if `amount` is a vector.
+    fn oversized_shift_mask(&self, amount: &B::BV, num_elements: Option<u32>) -> Result<B::BV> {
+        let num_elements = num_elements.unwrap_or(1);
+        let el_bits = amount.get_width() / num_elements;
+        let el_bitwidth = self.state.bv_from_u32(el_bits, el_bits);
+        let el_zero = self.state.zero(el_bits);
+        let el_ones = self.state.ones(el_bits);
+        unary_on_vector(amount, num_elements, |el| {
+            Ok(el.ugte(&el_bitwidth).cond_bv(&el_ones, &el_zero))
+        })
+    }
+
+    /// Replace the bits of `bv` which are set in `mask` with fresh
+    /// unconstrained bits, which are named after `name`
+    fn havoc_masked_bits(&mut self, bv: &B::BV, mask: &B::BV, name: String) -> Result<B::BV> {
+        let arbitrary = self
+            .state
+            .new_bv_with_name(Name::from(name), bv.get_width())?;
+        Ok(arbitrary.and(mask).or(&bv.and(&mask.not())))
+    }
+
+    /// If `bop` is an `Add`, `Sub`, or `Mul` which may overflow (see
+    /// `Config.check_arithmetic_overflow`), save a backtracking point for the
+    /// non-overflowing case, constrain the current path to overflow, and return
+    /// `Error::PossibleIntegerOverflow`.
+    ///
+    /// `num_elements` should be `Some` if the operands are vectors.
+    fn check_arithmetic_overflow(
+        &mut self,
+        bop: &instruction::groups::BinaryOp,
+        bvop0: &B::BV,
+        bvop1: &B::BV,
+        num_elements: Option<u32>,
+    ) -> Result<()> {
+        let overflow_op: fn(&B::BV, &B::BV) -> B::BV = match bop {
+            instruction::groups::BinaryOp::Add(_) => B::BV::saddo,
+            instruction::groups::BinaryOp::Sub(_) => B::BV::ssubo,
+            instruction::groups::BinaryOp::Mul(_) => B::BV::smulo,
+            _ => return Ok(()),
+        };
+        let overflows = match num_elements {
+            None => overflow_op(bvop0, bvop1),
+            Some(num_elements) => binary_on_vector(bvop0, bvop1, num_elements, overflow_op)?
+                ._ne(&self.state.zero(num_elements)),
+        };
+        if self
+            .state
+            .sat_with_extra_constraints(std::iter::once(&overflows))?
+        {
+            info!("Binop {:?} may overflow", bop);
+            // explore the non-overflowing case later, if there is one
+            let no_overflow = overflows.not();
+            if self
+                .state
+                .sat_with_extra_constraints(std::iter::once(&no_overflow))?
+            {
+                self.state
+                    .save_backtracking_point_at_location(self.state.cur_loc.clone(), no_overflow);
+            }
+            self.state.assert(&overflows)?;
+            return Err(Error::PossibleIntegerOverflow(
+                self.state.cur_loc.source_loc.cloned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// If `bop` is a `UDiv`, `SDiv`, `URem`, or `SRem` whose divisor may be zero
+    /// (see `Config.check_divide_by_zero`), save a backtracking point for the
+    /// nonzero case, constrain the current path to divide by zero, and return
+    /// `Error::PossibleDivideByZero`.
+    ///
+    /// `num_elements` should be `Some` if the operands are vectors; then we
+    /// check whether any element of the divisor may be zero.
+    fn check_divide_by_zero(
+        &mut self,
+        bop: &instruction::groups::BinaryOp,
+        dividend: &B::BV,
+        divisor: &B::BV,
+        num_elements: Option<u32>,
+    ) -> Result<()> {
+        match bop {
+            instruction::groups::BinaryOp::UDiv(_)
+            | instruction::groups::BinaryOp::SDiv(_)
+            | instruction::groups::BinaryOp::URem(_)
+            | instruction::groups::BinaryOp::SRem(_) => {},
+            _ => return Ok(()),
+        };
+        let divides_by_zero = match num_elements {
+            None => divisor._eq(&self.state.zero(divisor.get_width())),
+            Some(num_elements) => {
+                let el_zero = self.state.zero(divisor.get_width() / num_elements);
+                let one = self.state.one(1);
+                let zero = self.state.zero(1);
+                unary_on_vector(divisor, num_elements, |el| {
+                    Ok(el._eq(&el_zero).cond_bv(&one, &zero))
+                })?
+                ._ne(&self.state.zero(num_elements))
+            },
+        };
+        if self
+            .state
+            .sat_with_extra_constraints(std::iter::once(&divides_by_zero))?
+        {
+            info!("Binop {:?} may divide by zero", bop);
+            // explore the nonzero case later, if there is one
+            let nonzero = divides_by_zero.not();
+            if self
+                .state
+                .sat_with_extra_constraints(std::iter::once(&nonzero))?
+            {
+                self.state
+                    .save_backtracking_point_at_location(self.state.cur_loc.clone(), nonzero);
+            }
+            self.state.assert(&divides_by_zero)?;
+            let witness = |bv: &B::BV| -> Result<String> {
+                let solution = self.state.get_a_solution_for_bv(bv)?.ok_or(Error::Unsat)?;
+                Ok(match solution.as_u64() {
+                    Some(u) => u.to_string(),
+                    None => format!("0b{}", solution.as_01x_str()),
+                })
+            };
+            return Err(Error::PossibleDivideByZero {
+                source_loc: self.state.cur_loc.source_loc.cloned(),
+                dividend: witness(dividend)?,
+                divisor: witness(divisor)?,
+            });
         }
+        Ok(())
     }
 
     fn symex_icmp(&mut self, icmp: &'p instruction::ICmp) -> Result<()> {
@@ -832,6 +1783,144 @@ where
         self.state.record_bv_result(cast, bvop) // from Boolector's perspective a cast is simply a no-op; the bit patterns are equal
     }
 
+    /// `ptrtoint` and `inttoptr` are also just reinterpretations of the bits,
+    /// except that the integer type may be narrower or wider than a pointer,
+    /// in which case (per the LLVM LangRef) the value is truncated or
+    /// zero-extended.
+    ///
+    /// Nothing more is needed for pointers which round-trip through integers
+    /// (in registers or in memory): memory holds only bits, and allocations
+    /// are always looked up by raw address, so there's no per-pointer metadata
+    /// which could be lost along the way.
+    fn symex_ptr_int_cast(&mut self, cast: &'p impl instruction::UnaryOp) -> Result<()> {
+        debug!("Symexing ptrtoint or inttoptr {:?}", cast);
+        let bvop = self.state.operand_to_bv(cast.get_operand())?;
+        let result_type = self.state.type_of(cast);
+        let result_bits = self.state.size_in_bits(&result_type).ok_or_else(|| {
+            Error::MalformedInstruction(
+                "PtrToInt or IntToPtr result type is an opaque struct type".into(),
+            )
+        })?;
+        let num_elements = match result_type.as_ref() {
+            #[cfg(feature = "llvm-11-or-greater")]
+            Type::VectorType { scalable: true, .. } => {
+                return Err(Error::UnsupportedInstruction(
+                    "ptrtoint or inttoptr on a scalable vector".into(),
+                ));
+            },
+            Type::VectorType { num_elements, .. } => Some(*num_elements as u32),
+            _ => None,
+        };
+        self.state
+            .record_bv_result(cast, ptr_int_cast(&bvop, result_bits, num_elements)?)
+    }
+
+    #[cfg(feature = "llvm-10-or-greater")]
+    fn symex_freeze(&mut self, freeze: &'p instruction::Freeze) -> Result<()> {
+        debug!("Symexing freeze {:?}", freeze);
+        let op_type = self.state.type_of(&freeze.operand);
+        let result_type = self.state.type_of(freeze);
+        if op_type != result_type {
+            return Err(Error::MalformedInstruction(format!(
+                "Freeze of a {:?} should produce the same type, but produces a {:?}",
+                op_type, result_type
+            )));
+        }
+        match result_type.as_ref() {
+            #[cfg(feature = "llvm-11-or-greater")]
+            Type::VectorType { scalable: true, .. } => {
+                return Err(Error::UnsupportedInstruction(
+                    "freeze on a scalable vector".into(),
+                ));
+            },
+            _ => {},
+        }
+        let size = self
+            .state
+            .size_in_bits(&result_type)
+            .ok_or_else(|| Error::MalformedInstruction("Freeze of an opaque struct type".into()))?;
+        let bvop = self.state.operand_to_bv(&freeze.operand)?;
+        if bvop.get_width() != size {
+            return Err(Error::MalformedInstruction(format!(
+                "Freeze of a {}-bit type, but the operand is {} bits",
+                size,
+                bvop.get_width()
+            )));
+        }
+        // Our BVs are never undef or poison: `undef` constants, including
+        // undef elements of vectors and undef fields of structs, are already
+        // given concrete values by `const_to_bv()`. So once we know the sizes
+        // agree, freeze is the identity operation for us, for scalars and
+        // aggregates alike -- unless we're modeling the poison which the
+        // operand would have been (see `Config.model_poison`).
+        if self.state.config.model_poison {
+            let num_elements = match result_type.as_ref() {
+                Type::VectorType { num_elements, .. } => Some(*num_elements as u32),
+                _ => None,
+            };
+            if let Some(mask) = self.poison_mask(&freeze.operand, num_elements)? {
+                let frozen =
+                    self.havoc_masked_bits(&bvop, &mask, format!("{}_frozen", freeze.dest))?;
+                return self.state.record_bv_result(freeze, frozen);
+            }
+        }
+        self.state.record_bv_result(freeze, bvop)
+    }
+
+    /// If `operand` is the result of an instruction in the current function
+    /// which produces poison for some operands -- a `udiv`, `sdiv`, `urem`, or
+    /// `srem` by zero, or a `shl`, `lshr`, or `ashr` by at least the bitwidth --
+    /// get a mask which is all ones in each element of `operand` which is
+    /// poison, and all zeroes in the other elements. Otherwise, returns `None`.
+    ///
+    /// `num_elements` should be `Some` if `operand` is a vector.
+    #[cfg(feature = "llvm-10-or-greater")]
+    fn poison_mask(
+        &self,
+        operand: &'p Operand,
+        num_elements: Option<u32>,
+    ) -> Result<Option<B::BV>> {
+        let name = match operand {
+            Operand::LocalOperand { name, .. } => name,
+            _ => return Ok(None),
+        };
+        // the divisor or shift amount of the instruction defining `name`, and
+        // whether that instruction is a division
+        let source = self
+            .state
+            .cur_loc
+            .func
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .find_map(|instr| match instr {
+                Instruction::UDiv(i) if &i.dest == name => Some((&i.operand1, true)),
+                Instruction::SDiv(i) if &i.dest == name => Some((&i.operand1, true)),
+                Instruction::URem(i) if &i.dest == name => Some((&i.operand1, true)),
+                Instruction::SRem(i) if &i.dest == name => Some((&i.operand1, true)),
+                Instruction::Shl(i) if &i.dest == name => Some((&i.operand1, false)),
+                Instruction::LShr(i) if &i.dest == name => Some((&i.operand1, false)),
+                Instruction::AShr(i) if &i.dest == name => Some((&i.operand1, false)),
+                _ => None,
+            });
+        let (rhs, is_division) = match source {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+        let rhs = self.state.operand_to_bv(rhs)?;
+        if !is_division {
+            return self.oversized_shift_mask(&rhs, num_elements).map(Some);
+        }
+        let num_elements = num_elements.unwrap_or(1);
+        let el_bits = rhs.get_width() / num_elements;
+        let el_zero = self.state.zero(el_bits);
+        let el_ones = self.state.ones(el_bits);
+        let mask = unary_on_vector(&rhs, num_elements, |el| {
+            Ok(el._eq(&el_zero).cond_bv(&el_ones, &el_zero))
+        })?;
+        Ok(Some(mask))
+    }
+
     fn symex_load(&mut self, load: &'p instruction::Load) -> Result<()> {
         debug!("Symexing load {:?}", load);
         let bvaddr = self.state.operand_to_bv(&load.address)?;
@@ -846,6 +1935,9 @@ where
                 "Shouldn't be loading a value of size 0 bits".into(),
             ));
         }
+        if self.state.config.check_memory_bounds && !self.state.config.dry_run {
+            self.check_memory_bounds(&load.address, &bvaddr, dest_size)?;
+        }
         self.state
             .record_bv_result(load, self.state.read(&bvaddr, dest_size)?)
     }
@@ -854,11 +1946,159 @@ where
         debug!("Symexing store {:?}", store);
         let bvval = self.state.operand_to_bv(&store.value)?;
         let bvaddr = self.state.operand_to_bv(&store.address)?;
+        if self.state.config.check_memory_bounds && !self.state.config.dry_run {
+            self.check_memory_bounds(&store.address, &bvaddr, bvval.get_width())?;
+        }
         self.state.write(&bvaddr, bvval)
     }
 
+    /// If an access of `bits` bits at `bvaddr` may fall outside the allocation
+    /// which `address` was derived from (see `Config.check_memory_bounds`), save
+    /// a backtracking point for the in-bounds case, constrain the current path
+    /// to access out of bounds, and return `Error::PossibleOutOfBoundsAccess`.
+    ///
+    /// `bvaddr` should be the `BV` for `address`.
+    fn check_memory_bounds(
+        &mut self,
+        address: &'p Operand,
+        bvaddr: &B::BV,
+        bits: u32,
+    ) -> Result<()> {
+        let bvbase = self.deriving_pointer(address)?;
+        let base = match bvbase.as_u64() {
+            Some(base) => base,
+            None => match self.state.get_a_solution_for_bv(&bvbase)? {
+                Some(solution) => match solution.as_u64() {
+                    Some(base) => base,
+                    None => return Ok(()), // pointers wider than 64 bits aren't in any allocation
+                },
+                None => return Err(Error::Unsat),
+            },
+        };
+        let (start, size_bits) = match self.state.get_allocation_containing(base) {
+            Some(allocation) => allocation,
+            None => return Ok(()), // we don't know which allocation this was derived from
+        };
+        let width = bvaddr.get_width();
+        let mut size_bytes = size_bits / 8;
+        if size_bytes * 8 < size_bits {
+            size_bytes += 1; // round up to nearest byte
+        }
+        let mut access_bytes = u64::from(bits / 8);
+        if access_bytes * 8 < u64::from(bits) {
+            access_bytes += 1; // round up to nearest byte
+        }
+        let bvstart = self.state.bv_from_u64(start, width);
+        // if the base pointer is symbolic, we only consider the cases where it
+        // points into the allocation we found
+        let bvend = self
+            .state
+            .bv_from_u64(start + std::cmp::max(size_bytes, 1), width);
+        let base_in_allocation = bvbase.ugte(&bvstart).and(&bvbase.ult(&bvend));
+        let out_of_bounds = if access_bytes > size_bytes {
+            self.state.bv_from_bool(true)
+        } else {
+            let last_valid_addr = self
+                .state
+                .bv_from_u64(start + size_bytes - access_bytes, width);
+            bvaddr.ult(&bvstart).or(&bvaddr.ugt(&last_valid_addr))
+        };
+        let out_of_bounds = match self.state.get_allocation_valid_bytes(start) {
+            // accesses past the symbolic valid length are out of bounds too
+            Some(valid_bytes) => {
+                let access_end = bvaddr.add(&self.state.bv_from_u64(access_bytes, width));
+                out_of_bounds.or(&access_end.ugt(&bvstart.add(valid_bytes)))
+            },
+            None => out_of_bounds,
+        };
+        let out_of_bounds = base_in_allocation.and(&out_of_bounds);
+        if self
+            .state
+            .sat_with_extra_constraints(std::iter::once(&out_of_bounds))?
+        {
+            info!(
+                "Access of {} bits may be outside the {}-byte allocation at 0x{:x}",
+                bits, size_bytes, start
+            );
+            // explore the in-bounds case later, if there is one
+            let in_bounds = out_of_bounds.not();
+            if self
+                .state
+                .sat_with_extra_constraints(std::iter::once(&in_bounds))?
+            {
+                self.state
+                    .save_backtracking_point_at_location(self.state.cur_loc.clone(), in_bounds);
+            }
+            self.state.assert(&out_of_bounds)?;
+            let address = self
+                .state
+                .get_a_solution_for_bv(bvaddr)?
+                .ok_or(Error::Unsat)?
+                .as_u64()
+                .ok_or_else(|| {
+                    Error::OtherError(
+                        "check_memory_bounds: address is more than 64 bits wide".into(),
+                    )
+                })?;
+            return Err(Error::PossibleOutOfBoundsAccess {
+                source_loc: self.state.cur_loc.source_loc.cloned(),
+                address,
+                allocation_start: start,
+                allocation_size: size_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Get the `BV` for the base pointer which `address` was derived from, by
+    /// following `getelementptr`s and `bitcast`s (whether instructions in the
+    /// current function, or constant expressions) back to their base.
+    fn deriving_pointer(&mut self, address: &'p Operand) -> Result<B::BV> {
+        let func = self.state.cur_loc.func;
+        let derived_pointers = self
+            .derived_pointers
+            .entry((self.state.cur_loc.module.name.as_str(), func.name.as_str()))
+            .or_insert_with(|| {
+                func.basic_blocks
+                    .iter()
+                    .flat_map(|bb| bb.instrs.iter())
+                    .filter_map(|instr| match instr {
+                        Instruction::GetElementPtr(gep) => Some((&gep.dest, &gep.address)),
+                        Instruction::BitCast(bitcast) => Some((&bitcast.dest, &bitcast.operand)),
+                        _ => None,
+                    })
+                    .collect()
+            });
+        let mut address = address;
+        loop {
+            match address {
+                Operand::LocalOperand { name, .. } => {
+                    // the instruction defining `name`, if it's one we can see through
+                    match derived_pointers.get(name) {
+                        Some(op) => address = op,
+                        None => return self.state.operand_to_bv(address),
+                    }
+                },
+                Operand::ConstantOperand(cref) => {
+                    let mut constant = cref.as_ref();
+                    loop {
+                        match constant {
+                            Constant::GetElementPtr(gep) => constant = gep.address.as_ref(),
+                            Constant::BitCast(bitcast) => constant = bitcast.operand.as_ref(),
+                            _ => return self.state.const_to_bv(constant),
+                        }
+                    }
+                },
+                Operand::MetadataOperand => return self.state.operand_to_bv(address),
+            }
+        }
+    }
+
     fn symex_gep(&mut self, gep: &'p instruction::GetElementPtr) -> Result<()> {
         debug!("Symexing gep {:?}", gep);
+        if let Some(num_lanes) = self.gep_num_lanes(gep)? {
+            return self.symex_vector_gep(gep, num_lanes);
+        }
         match self.state.type_of(gep).as_ref() {
             Type::PointerType { .. } => {
                 let bvbase = self.state.operand_to_bv(&gep.address)?;
@@ -867,12 +2107,10 @@ where
                     gep.indices.iter(),
                     &self.state.type_of(&gep.address),
                     bvbase.get_width(),
+                    None,
                 )?;
                 self.state.record_bv_result(gep, bvbase.add(&offset))
             },
-            Type::VectorType { .. } => Err(Error::UnsupportedInstruction(
-                "GEP calculating a vector of pointers".to_owned(),
-            )),
             ty => Err(Error::MalformedInstruction(format!(
                 "Expected GEP result type to be pointer or vector of pointers; got {:?}",
                 ty
@@ -880,14 +2118,123 @@ where
         }
     }
 
+    /// Symex a GEP calculating a vector of `num_lanes` pointers (e.g., for the
+    /// addresses of a SIMD gather or scatter).
+    ///
+    /// The base address and each of the indices may be either a scalar, which
+    /// is used for every lane, or a vector with one element per lane. Each
+    /// lane's pointer is calculated just as for a scalar GEP, and the results
+    /// are packed into a vector.
+    fn symex_vector_gep(
+        &mut self,
+        gep: &'p instruction::GetElementPtr,
+        num_lanes: u32,
+    ) -> Result<()> {
+        let base_type = self.gep_base_type(gep)?;
+        let lane_ptrs = (0 .. num_lanes)
+            .map(|lane| {
+                let bvbase = Self::gep_operand_for_lane(&self.state, &gep.address, Some(lane))?;
+                let offset = Self::get_offset_recursive(
+                    &self.state,
+                    gep.indices.iter(),
+                    &base_type,
+                    bvbase.get_width(),
+                    Some(lane),
+                )?;
+                Ok(bvbase.add(&offset))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let final_bv = lane_ptrs
+            .into_iter()
+            .reduce(|a, b| b.concat(&a))
+            .ok_or_else(|| {
+                Error::MalformedInstruction("GEP calculating a vector of 0 pointers".to_owned())
+            })?; // LLVM disallows vectors of size 0: https://releases.llvm.org/9.0.0/docs/LangRef.html#vector-type
+
+        // `llvm-ir` computes the type of any GEP as a single pointer, so
+        // `record_bv_result()` would reject our vector of pointers as the
+        // wrong size
+        self.state.assign_bv_to_name(gep.dest.clone(), final_bv)
+    }
+
+    /// Get the number of lanes in the vector of pointers calculated by the GEP,
+    /// or `None` if the GEP calculates a single pointer.
+    ///
+    /// A GEP calculates a vector of pointers if its base address or any of its
+    /// indices is a vector.
+    fn gep_num_lanes(&self, gep: &'p instruction::GetElementPtr) -> Result<Option<u32>> {
+        for op in std::iter::once(&gep.address).chain(gep.indices.iter()) {
+            match self.state.type_of(op).as_ref() {
+                #[cfg(feature = "llvm-11-or-greater")]
+                Type::VectorType { scalable: true, .. } => {
+                    return Err(Error::UnsupportedInstruction(
+                        "GEP calculating a scalable vector of pointers".to_owned(),
+                    ));
+                },
+                Type::VectorType { num_elements, .. } => {
+                    return Ok(Some((*num_elements).try_into().unwrap()));
+                },
+                _ => {},
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the value of the given GEP operand (base address or index) in the
+    /// given lane of a GEP calculating a vector of pointers; or, if `lane` is
+    /// `None`, the value of the operand in a GEP calculating a single pointer.
+    ///
+    /// Scalar operands have the same value in every lane.
+    fn gep_operand_for_lane(
+        state: &State<'p, B>,
+        op: &Operand,
+        lane: Option<u32>,
+    ) -> Result<B::BV> {
+        let bv = state.operand_to_bv(op)?;
+        match (state.type_of(op).as_ref(), lane) {
+            (Type::VectorType { num_elements, .. }, Some(lane)) => {
+                let num_elements: u32 = (*num_elements).try_into().unwrap();
+                let el_size = bv.get_width() / num_elements;
+                Ok(bv.slice((lane + 1) * el_size - 1, lane * el_size))
+            },
+            (Type::VectorType { .. }, None) => Err(Error::MalformedInstruction(format!(
+                "GEP calculating a single pointer has a vector operand {:?}",
+                op
+            ))),
+            _ => Ok(bv),
+        }
+    }
+
+    /// Get the type which each lane of a GEP calculating a vector of pointers
+    /// steps through with its indices, starting with the first index. This is
+    /// the type of the GEP's base address, or of one element of it if the base
+    /// address is itself a vector of pointers.
+    fn gep_base_type(&self, gep: &'p instruction::GetElementPtr) -> Result<TypeRef> {
+        let address_ty = self.state.type_of(&gep.address);
+        match address_ty.as_ref() {
+            Type::PointerType { .. } => Ok(address_ty),
+            // for a vector of pointers, each lane steps through the indices
+            // starting from the pointer type
+            Type::VectorType { element_type, .. } => Ok(element_type.clone()),
+            ty => Err(Error::MalformedInstruction(format!(
+                "Expected GEP base address to be a pointer; got {:?}",
+                ty
+            ))),
+        }
+    }
+
     /// Get the offset of the element (in bytes, as a `BV` of `result_bits` bits)
     ///
     /// If `base_type` is a `NamedStructType`, the struct should be defined in the `state`'s current module.
+    ///
+    /// For a GEP calculating a vector of pointers, `lane` indicates which lane
+    /// to get the offset for; see `gep_operand_for_lane()`.
     fn get_offset_recursive(
         state: &State<'p, B>,
         mut indices: impl Iterator<Item = &'p Operand>,
         base_type: &Type,
         result_bits: u32,
+        lane: Option<u32>,
     ) -> Result<B::BV> {
         if let Type::NamedStructType { name } = base_type {
             match state.cur_loc.module.types.named_struct_def(name) {
@@ -904,7 +2251,7 @@ where
                     )));
                 },
                 Some(NamedStructDef::Defined(ty)) => {
-                    return Self::get_offset_recursive(state, indices, &ty, result_bits);
+                    return Self::get_offset_recursive(state, indices, &ty, result_bits, lane);
                 },
             }
         }
@@ -912,29 +2259,42 @@ where
             None => Ok(state.zero(result_bits)),
             Some(index) => match base_type {
                 Type::PointerType { .. } | Type::ArrayType { .. } | Type::VectorType { .. } => {
-                    let index = state.operand_to_bv(index)?.zero_extend_to_bits(result_bits);
+                    let index = Self::gep_operand_for_lane(state, index, lane)?
+                        .zero_extend_to_bits(result_bits);
                     let (offset, nested_ty) =
                         state.get_offset_bv_index(base_type, &index, state.solver.clone())?;
-                    Self::get_offset_recursive(state, indices, nested_ty, result_bits)
+                    Self::get_offset_recursive(state, indices, nested_ty, result_bits, lane)
                         .map(|bv| bv.add(&offset))
                 },
-                Type::StructType { .. } => match index {
-                    Operand::ConstantOperand(cref) => match cref.as_ref() {
-                        Constant::Int { value: index, .. } => {
+                Type::StructType { .. } => {
+                    let const_index = match index {
+                        Operand::ConstantOperand(cref) => match (cref.as_ref(), lane) {
+                            (Constant::Int { value, .. }, _) => Some(*value),
+                            // in a GEP calculating a vector of pointers, an
+                            // index into a struct type may also be a vector,
+                            // whose elements must all be the same constant int
+                            (Constant::Vector(elements), Some(lane)) => {
+                                match elements.get(lane as usize).map(|el| el.as_ref()) {
+                                    Some(Constant::Int { value, .. }) => Some(*value),
+                                    _ => None,
+                                }
+                            },
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match const_index {
+                        Some(const_index) => {
                             let (offset, nested_ty) =
-                                state.get_offset_constant_index(base_type, *index as usize)?;
-                            Self::get_offset_recursive(state, indices, &nested_ty, result_bits)
+                                state.get_offset_constant_index(base_type, const_index as usize)?;
+                            Self::get_offset_recursive(state, indices, &nested_ty, result_bits, lane)
                                 .map(|bv| bv.add(&state.bv_from_u32(offset, result_bits)))
                         },
-                        c => Err(Error::MalformedInstruction(format!(
+                        None => Err(Error::MalformedInstruction(format!(
                             "Expected index into struct type to be constant int, but got index {:?}",
-                            c
-                        )))
-                    },
-                    _ => Err(Error::MalformedInstruction(format!(
-                        "Expected index into struct type to be constant int, but got index {:?}",
-                        index
-                    ))),
+                            index
+                        ))),
+                    }
                 },
                 Type::NamedStructType { .. } => {
                     panic!("NamedStructType case should have been handled above")
@@ -946,37 +2306,72 @@ where
 
     fn symex_alloca(&mut self, alloca: &'p instruction::Alloca) -> Result<()> {
         debug!("Symexing alloca {:?}", alloca);
-        match &alloca.num_elements {
+        let element_size_bits = self
+            .state
+            .size_in_bits(&alloca.allocated_type)
+            .ok_or_else(|| Error::MalformedInstruction("Alloca with opaque struct type".into()))?;
+        let num_elements = match &alloca.num_elements {
             Operand::ConstantOperand(cref) => match cref.as_ref() {
-                Constant::Int { value: num_elements, .. } => {
-                    let allocation_size_bits = {
-                        let element_size_bits = self
-                            .state
-                            .size_in_bits(&alloca.allocated_type)
-                            .ok_or_else(|| {
-                                Error::MalformedInstruction("Alloca with opaque struct type".into())
-                            })?;
-                        element_size_bits as u64 * *num_elements
-                    };
-                    let allocation_size_bits = if allocation_size_bits == 0 {
-                        debug!("Alloca of 0 bits; we'll give it 8 bits anyway");
-                        8
-                    } else {
-                        allocation_size_bits
-                    };
-                    let allocated = self.state.allocate(allocation_size_bits);
-                    self.state.record_bv_result(alloca, allocated)
-                },
-                c => Err(Error::UnsupportedInstruction(format!(
-                    "Alloca with num_elements not a constant int: {:?}",
-                    c
-                ))),
+                Constant::Int { value, .. } => *value,
+                _ => self.dynamic_alloca_num_elements(alloca, element_size_bits)?,
             },
-            op => Err(Error::UnsupportedInstruction(format!(
-                "Alloca with num_elements not a constant int: {:?}",
-                op
-            ))),
+            _ => self.dynamic_alloca_num_elements(alloca, element_size_bits)?,
+        };
+        let allocation_size_bits = element_size_bits as u64 * num_elements;
+        let allocation_size_bits = if allocation_size_bits == 0 {
+            debug!("Alloca of 0 bits; we'll give it 8 bits anyway");
+            8
+        } else {
+            allocation_size_bits
+        };
+        let allocated = self.state.allocate(allocation_size_bits);
+        self.state.record_bv_result(alloca, allocated)
+    }
+
+    /// For an `Alloca` whose `num_elements` isn't a constant int (e.g., for a
+    /// C99 variable-length array), get the number of elements to actually
+    /// allocate: the maximum possible value of `num_elements`, after applying
+    /// `Config.max_dynamic_alloc_elements`.
+    fn dynamic_alloca_num_elements(
+        &mut self,
+        alloca: &'p instruction::Alloca,
+        element_size_bits: u32,
+    ) -> Result<u64> {
+        let num_elements = self.state.operand_to_bv(&alloca.num_elements)?;
+        if let Some(max_elements) = self.state.config.max_dynamic_alloc_elements {
+            let max_elements_bv = self
+                .state
+                .bv_from_u64(max_elements, num_elements.get_width());
+            if !self
+                .state
+                .sat_with_extra_constraints(std::iter::once(&num_elements.ulte(&max_elements_bv)))?
+            {
+                return Err(Error::OtherError(format!("Encountered an alloca whose number of elements is always larger than max_dynamic_alloc_elements ({})", max_elements)));
+            }
+            if self
+                .state
+                .sat_with_extra_constraints(std::iter::once(&num_elements.ugt(&max_elements_bv)))?
+            {
+                warn!("Encountered an alloca whose number of elements may be larger than max_dynamic_alloc_elements ({}). Constraining the number of elements to be at most {}.", max_elements, max_elements);
+                self.state.assert(&num_elements.ulte(&max_elements_bv))?;
+            }
         }
+        let max_possible = self
+            .state
+            .max_possible_solution_for_bv_as_u64(&num_elements)?
+            .ok_or(Error::Unsat)?;
+        if self.state.config.max_dynamic_alloc_elements.is_none() {
+            // Without an explicit bound, insist on a reasonable allocation size
+            let too_large = match max_possible.checked_mul(u64::from(element_size_bits)) {
+                Some(bits) => bits > MAX_ALLOCATION_SIZE_BYTES * 8,
+                None => true,
+            };
+            if too_large {
+                return Err(Error::UnsupportedInstruction(format!("Alloca with a symbolic number of elements, which may be as large as {} (of {} bits each). Consider bounding it with `Config.max_dynamic_alloc_elements`", max_possible, element_size_bits)));
+            }
+        }
+        debug!("Alloca with a symbolic number of elements; allocating space for the maximum possible number, {}", max_possible);
+        Ok(max_possible)
     }
 
     fn symex_extractelement(&mut self, ee: &'p instruction::ExtractElement) -> Result<()> {
@@ -1298,6 +2693,7 @@ where
     /// _current function_ (the function containing the call instruction), because either:
     ///     - we had backtracking and finished on a different path, and this is the final return value of the top-level function
     ///     - the called function threw an exception which the current function isn't set up to catch, so this is a `ReturnValue::Throw` which should be thrown from the current function
+    ///     - the call was a tail call which reused the current frame (see `Config.reuse_frames_for_tail_calls`), so the callee's return value is the current function's return value
     ///
     /// If the returned value is `Ok(None)`, then we finished the call normally, and execution should continue from here.
     fn symex_call(&mut self, call: &'p instruction::Call) -> Result<Option<ReturnValue<B::BV>>> {
@@ -1324,6 +2720,18 @@ where
                     },
                     ReturnValue::Abort => return Ok(Some(ReturnValue::Abort)),
                 }
+                // any other paths requested by the hook resume just after the call
+                let mut resume_loc = self.state.cur_loc.clone();
+                resume_loc.instr = match resume_loc.instr {
+                    BBInstrIndex::Instr(i) if i + 1 < resume_loc.bb.instrs.len() => {
+                        BBInstrIndex::Instr(i + 1)
+                    },
+                    _ => BBInstrIndex::Terminator,
+                };
+                resume_loc.source_loc = None;
+                if !self.apply_hook_forks(resume_loc)? {
+                    return Err(Error::Unsat);
+                }
                 let log_level = if quiet {
                     log::Level::Debug
                 } else {
@@ -1344,12 +2752,24 @@ where
                 Ok(None)
             },
             ResolvedFunction::NoHookActive { called_funcname } => {
+                let reuses_frame = match self.state.get_func_by_name(called_funcname) {
+                    Some((callee, _)) => self.tail_call_reuses_frame(call, callee),
+                    None => false,
+                };
                 let at_max_callstack_depth = match self.state.config.max_callstack_depth {
+                    Some(_) if reuses_frame => false, // the callee will be at the current depth
                     Some(max_depth) => self.state.current_callstack_depth() >= max_depth,
                     None => false,
                 };
-                if at_max_callstack_depth {
-                    info!("Ignoring a call to function {:?} due to max_callstack_len setting (current callstack depth is {}, max is {})", called_funcname, self.state.current_callstack_depth(), self.state.config.max_callstack_depth.unwrap());
+                let trusted_pure = self.is_trusted_pure(&call.function_attributes, called_funcname);
+                if at_max_callstack_depth || trusted_pure {
+                    if at_max_callstack_depth {
+                        info!("Ignoring a call to function {:?} due to max_callstack_len setting (current callstack depth is {}, max is {})", called_funcname, self.state.current_callstack_depth(), self.state.config.max_callstack_depth.unwrap());
+                        let pretty_funcname = self.state.demangle(called_funcname);
+                        self.state.record_truncated_call(pretty_funcname);
+                    } else {
+                        info!("Summarizing a call to function {:?} as pure, due to its function attributes and the pure_function_attributes setting", called_funcname);
+                    }
                     match self.state.type_of(call).as_ref() {
                         Type::VoidType => {},
                         ty => {
@@ -1359,10 +2779,14 @@ where
                                 )
                             })?;
                             assert_ne!(width, 0, "Function return type has size 0 bits but isn't void type"); // void type was handled above
-                            let bv = self.state.new_bv_with_name(
-                                Name::from(format!("{}_retval", called_funcname)),
-                                width,
-                            )?;
+                            // tag truncated calls' return values, so that
+                            // they're recognizable as over-approximations
+                            let name = if at_max_callstack_depth {
+                                format!("{}_truncated_retval", called_funcname)
+                            } else {
+                                format!("{}_retval", called_funcname)
+                            };
+                            let bv = self.state.new_bv_with_name(Name::from(name), width)?;
                             self.state
                                 .assign_bv_to_name(call.dest.as_ref().unwrap().clone(), bv)?;
                         },
@@ -1371,23 +2795,55 @@ where
                 } else if let Some((callee, callee_mod)) =
                     self.state.get_func_by_name(called_funcname)
                 {
-                    if call.arguments.len() != callee.parameters.len() {
-                        if callee.is_var_arg {
-                            return Err(Error::UnsupportedInstruction(format!(
-                                "Call of a function named {:?} which is variadic",
-                                callee.name
-                            )));
-                        } else {
-                            return Err(Error::MalformedInstruction(format!("Call of a function named {:?} which has {} parameters, but {} arguments were given", callee.name, callee.parameters.len(), call.arguments.len())));
-                        }
+                    // variadic functions may be given extra arguments beyond their declared parameters, but never fewer
+                    if call.arguments.len() < callee.parameters.len()
+                        || (call.arguments.len() > callee.parameters.len() && !callee.is_var_arg)
+                    {
+                        return Err(Error::MalformedInstruction(format!("Call of a function named {:?} which has {} parameters, but {} arguments were given", callee.name, callee.parameters.len(), call.arguments.len())));
                     }
-                    let bvargs: Vec<B::BV> = call
+                    let mut bvargs: Vec<B::BV> = call
                         .arguments
                         .iter()
                         .map(|arg| self.state.operand_to_bv(&arg.0)) // have to do this before changing state.cur_loc, so that the lookups happen in the caller function
                         .collect::<Result<Vec<B::BV>>>()?;
+                    // any arguments beyond the declared parameters are variadic arguments, which the callee can access via `va_start()` etc
+                    let varargs = bvargs.split_off(callee.parameters.len());
+                    if reuses_frame {
+                        // reuse the current frame: move to the callee without pushing a callsite.
+                        // When the callee returns, it returns directly to our caller.
+                        // We skip `check_recursion_depth()`: with no callsite pushed, the
+                        // recursion depth doesn't grow, and `loop_bound` bounds the tail
+                        // recursion instead (see `Config.reuse_frames_for_tail_calls`).
+                        // The callee isn't variadic (see `tail_call_reuses_frame()`), so
+                        // `varargs` is empty and there's nothing to `set_varargs()`.
+                        debug_assert!(varargs.is_empty());
+                        self.state.cur_loc = Location {
+                            module: callee_mod,
+                            func: callee,
+                            bb: callee
+                                .basic_blocks
+                                .first()
+                                .expect("Failed to get entry basic block"),
+                            instr: BBInstrIndex::Instr(0),
+                            source_loc: None, // this will be updated once we get there and begin symex of the instruction
+                        };
+                        for (bvarg, param) in bvargs.into_iter().zip_eq(callee.parameters.iter()) {
+                            self.state.assign_bv_to_name(param.name.clone(), bvarg)?;
+                        }
+                        self.run_call_callbacks(callee)?;
+                        info!(
+                            "Tail-calling function {:?}, reusing the current frame",
+                            called_funcname
+                        );
+                        return self
+                            .symex_from_cur_loc_through_end_of_function()?
+                            .ok_or(Error::Unsat)
+                            .map(Some); // if symex_from_cur_loc_through_end_of_function() returns `None`, this path is unsat
+                    }
+                    self.check_recursion_depth(called_funcname)?;
                     let saved_loc = self.state.cur_loc.clone();
                     self.state.push_callsite(call);
+                    self.state.set_varargs(varargs);
                     self.state.cur_loc = Location {
                         module: callee_mod,
                         func: callee,
@@ -1402,6 +2858,7 @@ where
                         self.state.assign_bv_to_name(param.name.clone(), bvarg)?;
                         // have to do the assign_bv_to_name calls after changing state.cur_loc, so that the variables are created in the callee function
                     }
+                    self.run_call_callbacks(callee)?;
                     info!(
                         "Entering function {:?}{}",
                         called_funcname,
@@ -1419,6 +2876,7 @@ where
                         Some(ref callsite)
                             if callsite.loc == saved_loc && callsite.instr.is_left() =>
                         {
+                            self.run_return_callbacks(callee)?;
                             self.state.cur_loc = saved_loc;
                             self.state.cur_loc.inc(); // advance past the call instruction itself before recording the path entry. `saved_loc` must have been a call instruction, so can't be a terminator, so the call to `inc()` is safe.
                             self.state.record_path_entry();
@@ -1454,17 +2912,17 @@ where
                         Some(callsite) => panic!("Received unexpected callsite {:?}", callsite),
                     }
                 } else {
-                    match self.state.config.function_hooks.get_default_hook() {
+                    match self.hook_for_missing_function() {
                         None => Err(Error::FunctionNotFound(
                             self.state.demangle(called_funcname),
                         )),
                         Some(hook) => {
-                            let hook = hook.clone(); // end the implicit borrow of `self` that arose from `get_default_hook()`. The `clone` is just an `Rc` and a `usize`, as of this writing
                             let pretty_funcname = self.state.demangle(called_funcname);
                             info!(
                                 "Using default hook for a function named {:?}",
                                 pretty_funcname
                             );
+                            self.state.record_havoced_call(pretty_funcname.clone());
                             match self.symex_hook(call, &hook.clone(), &pretty_funcname, true)? {
                                 // Assume that `symex_hook()` has taken care of validating the hook return value as necessary
                                 ReturnValue::Return(retval) => {
@@ -1489,12 +2947,161 @@ where
         }
     }
 
+    /// Get the hook to use for a call to a function which isn't defined in the
+    /// `Project` and has no hook of its own: the default hook if there is one,
+    /// or else the hook implementing `Config.on_missing_function`, if any
+    fn hook_for_missing_function(&self) -> Option<FunctionHook<'p, B>> {
+        if let Some(hook) = self.state.config.function_hooks.get_default_hook() {
+            return Some(hook.clone());
+        }
+        let hookname = match self.state.config.on_missing_function {
+            MissingFunctionBehavior::Error => return None,
+            MissingFunctionBehavior::ReturnUnconstrained => "intrinsic: generic_stub_hook",
+            MissingFunctionBehavior::Abort => "intrinsic: abort_hook",
+        };
+        Some(
+            self.state
+                .intrinsic_hooks
+                .get_hook_for(hookname)
+                .cloned()
+                .unwrap_or_else(|| panic!("Failed to find {:?}", hookname)),
+        )
+    }
+
+    /// Run all of the call callbacks for entering the given `callee`
+    fn run_call_callbacks(&self, callee: &'p Function) -> Result<()> {
+        for callback in &self.state.config.callbacks.call_callbacks {
+            callback(callee, &self.state)?;
+        }
+        Ok(())
+    }
+
+    /// Run all of the return callbacks for leaving the given `func`
+    fn run_return_callbacks(&self, func: &'p Function) -> Result<()> {
+        for callback in &self.state.config.callbacks.return_callbacks {
+            callback(func, &self.state)?;
+        }
+        Ok(())
+    }
+
+    /// Should the given call (of the given callee) reuse the current frame, per
+    /// `Config.reuse_frames_for_tail_calls`? That is: is the setting enabled,
+    /// and is the call marked `tail` (or `musttail`), to a non-variadic
+    /// function, and immediately followed by a `ret` of its result (or a `ret
+    /// void`, if the call is void)?
+    ///
+    /// Variadic callees are excluded because their varargs live on the
+    /// callsite, and a reused frame has no callsite of its own to put them on.
+    fn tail_call_reuses_frame(&self, call: &'p instruction::Call, callee: &Function) -> bool {
+        if !self.state.config.reuse_frames_for_tail_calls || !call.is_tail_call || callee.is_var_arg
+        {
+            return false;
+        }
+        let bb = self.state.cur_loc.bb;
+        match self.state.cur_loc.instr {
+            BBInstrIndex::Instr(i) if i + 1 == bb.instrs.len() => {},
+            _ => return false, // the call isn't the last instruction in the bb
+        }
+        match (&bb.term, &call.dest) {
+            (Terminator::Ret(ret), None) => ret.return_operand.is_none(),
+            (Terminator::Ret(ret), Some(dest)) => match &ret.return_operand {
+                Some(Operand::LocalOperand { name, .. }) => name == dest,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Check that calling the function named `called_funcname` wouldn't exceed
+    /// the `max_recursion_depth` setting in `Config`, returning
+    /// `Error::RecursionBoundExceeded` if it would.
+    fn check_recursion_depth(&self, called_funcname: &str) -> Result<()> {
+        match self.state.config.max_recursion_depth {
+            Some(max_depth) if self.state.current_recursion_depth(called_funcname) > max_depth => {
+                info!(
+                    "Recursive call to function {:?} would exceed max_recursion_depth of {}",
+                    called_funcname, max_depth
+                );
+                Err(Error::RecursionBoundExceeded(max_depth))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Should a call to the function named `called_funcname` be summarized as a
+    /// pure function, based on the `pure_function_attributes` setting in `Config`?
+    ///
+    /// `call_attrs`: the function attributes present at the call site
+    fn is_trusted_pure(
+        &self,
+        call_attrs: &[function::FunctionAttribute],
+        called_funcname: &str,
+    ) -> bool {
+        let pure_attrs = &self.state.config.pure_function_attributes;
+        if pure_attrs.is_empty() {
+            return false;
+        }
+        let func_attrs: &[function::FunctionAttribute] =
+            match self.state.get_func_by_name(called_funcname) {
+                Some((func, _)) => &func.function_attributes,
+                None => &[], // function is only declared, not defined; we only have the call-site attributes
+            };
+        pure_attrs
+            .iter()
+            .all(|attr| attr.is_satisfied_by(call_attrs) || attr.is_satisfied_by(func_attrs))
+    }
+
+    /// Determine which `Callable` the given function pointer should be
+    /// considered to point to, consulting the `function_ptr_resolution_hook`
+    /// in the `Config` if the function pointer has multiple possible targets
+    fn resolve_function_ptr(&mut self, fptr: B::BV) -> Result<Callable<'p, B>> {
+        let n = self.state.config.max_fn_ptr_targets;
+        let (targets, over_budget): (Vec<Callable<'p, B>>, bool) =
+            match self.state.interpret_as_function_ptr(fptr.clone(), n)? {
+                PossibleSolutions::Exactly(v) => (v.into_iter().collect(), false),
+                PossibleSolutions::AtLeast(v) => (v.into_iter().collect(), true),
+            };
+        match targets.len() {
+            0 => return Err(Error::Unsat), // no valid solutions for the function pointer
+            1 => return Ok(targets.into_iter().next().unwrap()),
+            _ => {},
+        }
+        let chosen = match &self.state.config.function_ptr_resolution_hook {
+            Some(hook) => hook(&self.state, &targets),
+            None => None,
+        };
+        match chosen {
+            None if over_budget => Err(Error::OtherError(format!(
+                "calling a function pointer which has multiple possible targets (more than the `max_fn_ptr_targets` budget of {}), including {}",
+                n,
+                describe_function_ptr_targets(&targets),
+            ))),
+            None => Err(Error::OtherError(format!(
+                "calling a function pointer which has multiple possible targets: {}",
+                describe_function_ptr_targets(&targets),
+            ))),
+            Some(callable) => {
+                if !targets.contains(&callable) {
+                    return Err(Error::OtherError(format!(
+                        "function_ptr_resolution_hook chose {:?}, which is not one of the possible targets of the function pointer: {:?}",
+                        callable, targets
+                    )));
+                }
+                let addr = self
+                    .state
+                    .get_pointer_to_callable(&callable)
+                    .expect("Possible target of a function pointer should have an address");
+                self.state.assert(&fptr._eq(&addr))?;
+                Ok(callable)
+            },
+        }
+    }
+
     #[allow(clippy::if_same_then_else)] // in this case, having some identical `if` blocks actually improves readability, I think
     fn resolve_function(
         &mut self,
         function: &'p Either<InlineAssembly, Operand>,
     ) -> Result<ResolvedFunction<'p, B>> {
-        use crate::global_allocations::Callable;
         let funcname_or_hook: Either<&str, FunctionHook<B>> = match function {
             // the first case is really just an optimization for the second case; things should still work if the first case was omitted
             Either::Right(Operand::ConstantOperand(cref)) if is_global_reference(cref) => match cref.as_ref() {
@@ -1502,15 +3109,9 @@ where
                 Constant::GlobalReference { name, .. } => panic!("Function with a numbered name: {:?}", name),
                 _ => panic!("Expected only a GlobalReference here because of earlier check"),
             },
-            Either::Right(operand) => {
-                match self.state.interpret_as_function_ptr(self.state.operand_to_bv(&operand)?, 1)? {
-                    PossibleSolutions::AtLeast(_) => return Err(Error::OtherError("calling a function pointer which has multiple possible targets".to_owned())),  // there must be at least 2 targets since we passed n==1 to `interpret_as_function_ptr`
-                    PossibleSolutions::Exactly(v) => match v.iter().next() {
-                        None => return Err(Error::Unsat),  // no valid solutions for the function pointer
-                        Some(Callable::LLVMFunction(f)) => Either::Left(&f.name),
-                        Some(Callable::FunctionHook(h)) => Either::Right(h.clone()),
-                    }
-                }
+            Either::Right(operand) => match self.resolve_function_ptr(self.state.operand_to_bv(&operand)?)? {
+                Callable::LLVMFunction(f) => Either::Left(&f.name),
+                Callable::FunctionHook(h) => Either::Right(h),
             },
             Either::Left(_) => match self.state.config.function_hooks.get_inline_asm_hook() {
                 Some(hook) => return Ok(ResolvedFunction::HookActive {
@@ -1530,7 +3131,17 @@ where
                 None => {
                     // No hook currently defined for this function, check if any intrinsic hooks apply
                     // (see notes on function resolution in function_hooks.rs)
-                    if funcname.starts_with("llvm.memset") || funcname.starts_with("__memset") {
+                    if self.state.config.rng_model.contains_key(funcname) {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: rng_draw")
+                                .cloned()
+                                .expect("Failed to find intrinsic rng_draw hook"),
+                            hooked_thing: HookedThing::Function(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.memset") || funcname.starts_with("__memset") {
                         Ok(ResolvedFunction::HookActive {
                             hook: self
                                 .state
@@ -1564,6 +3175,86 @@ where
                                 .expect("Failed to find LLVM intrinsic bswap hook"),
                             hooked_thing: HookedThing::Intrinsic(funcname),
                         })
+                    } else if funcname.starts_with("llvm.bitreverse") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.bitreverse")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic bitreverse hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.fshl") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.fshl")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic fshl hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.fshr") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.fshr")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic fshr hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.abs") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.abs")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic abs hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.smax") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.smax")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic smax hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.smin") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.smin")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic smin hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.umax") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.umax")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic umax hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.umin") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.umin")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic umin hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
                     } else if funcname.starts_with("llvm.ctlz") {
                         Ok(ResolvedFunction::HookActive {
                             hook: self
@@ -1584,6 +3275,16 @@ where
                                 .expect("Failed to find LLVM intrinsic cttz hook"),
                             hooked_thing: HookedThing::Intrinsic(funcname),
                         })
+                    } else if funcname.starts_with("llvm.ctpop") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.ctpop")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic ctpop hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
                     } else if funcname.starts_with("llvm.objectsize") {
                         Ok(ResolvedFunction::HookActive {
                             hook: self
@@ -1604,6 +3305,26 @@ where
                                 .expect("Failed to find LLVM intrinsic assume hook"),
                             hooked_thing: HookedThing::Intrinsic(funcname),
                         })
+                    } else if funcname.starts_with("llvm.va_start") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.va_start")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic va_start hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
+                    } else if funcname.starts_with("llvm.va_copy") {
+                        Ok(ResolvedFunction::HookActive {
+                            hook: self
+                                .state
+                                .intrinsic_hooks
+                                .get_hook_for("intrinsic: llvm.va_copy")
+                                .cloned()
+                                .expect("Failed to find LLVM intrinsic va_copy hook"),
+                            hooked_thing: HookedThing::Intrinsic(funcname),
+                        })
                     } else if funcname.starts_with("llvm.uadd.with.overflow") {
                         Ok(ResolvedFunction::HookActive {
                             hook: self
@@ -1724,6 +3445,7 @@ where
                         || funcname.starts_with("llvm.dbg")
                         || funcname.starts_with("llvm.expect")
                         || funcname.starts_with("llvm.experimental.noalias.scope.decl")
+                        || funcname.starts_with("llvm.va_end")
                     {
                         // these are all safe to ignore
                         Ok(ResolvedFunction::HookActive {
@@ -1735,6 +3457,16 @@ where
                                 .expect("Failed to find intrinsic generic stub hook"),
                             hooked_thing: HookedThing::Intrinsic(funcname),
                         })
+                    } else if funcname.starts_with("llvm.experimental.constrained.") {
+                        // The constrained (strict-FP) intrinsics are all floating-point
+                        // operations, which we don't support yet. Once we do, these
+                        // should be routed to the same operations as the corresponding
+                        // (unconstrained) instructions, ignoring the rounding-mode and
+                        // exception-behavior metadata operands.
+                        Err(Error::UnsupportedInstruction(format!(
+                            "constrained floating-point intrinsic {:?}",
+                            funcname
+                        )))
                     } else {
                         // No hook currently defined for this function, and none of our intrinsic hooks apply
                         Ok(ResolvedFunction::NoHookActive {
@@ -1771,9 +3503,12 @@ where
             log::Level::Info
         };
         log::log!(log_level, "Processing hook for {}", hooked_funcname);
+        // discard any forks left over from a previous hook which didn't return normally
+        self.state.take_hook_forks();
         match hook.call_hook(&mut self.state, call)? {
             ReturnValue::ReturnVoid => {
-                if self.state.type_of(call).as_ref() == &Type::VoidType {
+                let ret_type = call.get_return_type(&self.state.cur_loc.module.types)?;
+                if ret_type.as_ref() == &Type::VoidType {
                     Ok(ReturnValue::ReturnVoid)
                 } else {
                     Err(Error::HookReturnValueMismatch(format!(
@@ -1783,7 +3518,7 @@ where
                 }
             },
             ReturnValue::Return(retval) => {
-                let ret_type = self.state.type_of(call);
+                let ret_type = call.get_return_type(&self.state.cur_loc.module.types)?;
                 if ret_type.as_ref() == &Type::VoidType {
                     Err(Error::HookReturnValueMismatch(format!(
                         "Hook for {:?} returned a value but call is void-typed",
@@ -1807,6 +3542,44 @@ where
         }
     }
 
+    /// Apply the forks requested by a hook with `State::fork_after_hook()`, if
+    /// any. This should be called after the hook has returned normally and its
+    /// return value (if any) has been assigned.
+    ///
+    /// Backtracking points for the other paths will resume at `resume_loc`.
+    ///
+    /// Returns `Ok(false)` if none of the requested paths is feasible, in which
+    /// case the current path is dead.
+    fn apply_hook_forks(&mut self, resume_loc: Location<'p>) -> Result<bool> {
+        let constraints = self.state.take_hook_forks();
+        if constraints.is_empty() {
+            return Ok(true);
+        }
+        let mut feasible = Vec::with_capacity(constraints.len());
+        for constraint in constraints {
+            if self.state.config.dry_run
+                || self
+                    .state
+                    .sat_with_extra_constraints(std::iter::once(&constraint))?
+            {
+                feasible.push(constraint);
+            }
+        }
+        debug!("Hook requested {} feasible paths", feasible.len());
+        let mut feasible = feasible.into_iter();
+        match feasible.next() {
+            None => Ok(false),
+            Some(constraint) => {
+                for other_constraint in feasible {
+                    self.state
+                        .save_backtracking_point_at_location(resume_loc.clone(), other_constraint);
+                }
+                self.state.assert(&constraint)?;
+                Ok(true)
+            },
+        }
+    }
+
     /// Returns the `ReturnValue` representing the return value
     fn symex_return(&self, ret: &'p terminator::Ret) -> Result<ReturnValue<B::BV>> {
         debug!("Symexing return {:?}", ret);
@@ -1828,6 +3601,47 @@ where
         self.symex_from_cur_loc_through_end_of_function()
     }
 
+    /// For `Config.single_path`: of the `num_successors` feasible ways execution
+    /// could go, choose the index of the one to follow
+    fn choose_single_path_successor(
+        &mut self,
+        policy: SinglePathPolicy,
+        num_successors: usize,
+    ) -> usize {
+        match policy {
+            SinglePathPolicy::FirstFeasible => 0,
+            SinglePathPolicy::Random { .. } => {
+                // SplitMix64
+                self.single_path_rng = self.single_path_rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = self.single_path_rng;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                z ^= z >> 31;
+                (z % num_successors as u64) as usize
+            },
+        }
+    }
+
+    /// Of the feasible ways execution could go, as (constraint, destination)
+    /// pairs, get the index of the first one which is consistent with
+    /// `Config.seed_values`. Returns 0 if there are no seed values, or if none
+    /// of the ways are consistent with them.
+    fn seeded_successor(&self, successors: &[(B::BV, &Name)]) -> Result<usize> {
+        let seed_constraint = match &self.seed_constraint {
+            Some(seed_constraint) if !self.state.config.dry_run => seed_constraint,
+            _ => return Ok(0),
+        };
+        for (i, (constraint, _)) in successors.iter().enumerate() {
+            if self
+                .state
+                .sat_with_extra_constraints(vec![constraint, seed_constraint])?
+            {
+                return Ok(i);
+            }
+        }
+        Ok(0)
+    }
+
     /// Continues to the target(s) of the `CondBr` (saving a backtracking point if
     /// necessary) and eventually returns the new `ReturnValue` representing the
     /// return value of the function (when it reaches the end of the function), or
@@ -1838,39 +3652,59 @@ where
     ) -> Result<Option<ReturnValue<B::BV>>> {
         debug!("Symexing condbr {:?}", condbr);
         let bvcond = self.state.operand_to_bv(&condbr.condition)?;
-        let true_feasible = self
-            .state
-            .sat_with_extra_constraints(std::iter::once(&bvcond))?;
-        let false_feasible = self
-            .state
-            .sat_with_extra_constraints(std::iter::once(&bvcond.not()))?;
+        let (true_feasible, false_feasible) = if self.state.config.dry_run {
+            (true, true)
+        } else {
+            (
+                self.state
+                    .sat_with_extra_constraints(std::iter::once(&bvcond))?,
+                self.state
+                    .sat_with_extra_constraints(std::iter::once(&bvcond.not()))?,
+            )
+        };
         if true_feasible && false_feasible {
             debug!("both true and false branches are feasible");
-            // for now we choose to explore true first, and backtrack to false if necessary
-            self.state
-                .save_backtracking_point(&condbr.false_dest, bvcond.not());
-            bvcond.assert()?;
+            if let Some(policy) = self.state.config.single_path {
+                // follow only one of the branches, without a backtracking point for the other
+                let (constraint, dest) = match self.choose_single_path_successor(policy, 2) {
+                    0 => (bvcond, &condbr.true_dest),
+                    _ => (bvcond.not(), &condbr.false_dest),
+                };
+                self.state.assert(&constraint)?;
+                self.state.cur_loc.move_to_start_of_bb_by_name(dest);
+                return self.symex_from_cur_loc_through_end_of_function();
+            }
+            // we explore true first (unless only false is consistent with the
+            // seed values), and backtrack to the other direction if necessary
+            let successors = [
+                (bvcond.clone(), &condbr.true_dest),
+                (bvcond.not(), &condbr.false_dest),
+            ];
+            let chosen = self.seeded_successor(&successors)?;
+            let (other_constraint, other_dest) = &successors[1 - chosen];
             self.state
-                .cur_loc
-                .move_to_start_of_bb_by_name(&condbr.true_dest);
+                .save_backtracking_point(other_dest, other_constraint.clone());
+            let (constraint, dest) = &successors[chosen];
+            self.state.assert(constraint)?;
+            self.state.cur_loc.move_to_start_of_bb_by_name(dest);
             self.symex_from_cur_loc_through_end_of_function()
         } else if true_feasible {
             debug!("only the true branch is feasible");
-            bvcond.assert()?; // unnecessary, but may help Boolector more than it hurts?
+            self.state.assert(&bvcond)?; // unnecessary, but may help Boolector more than it hurts?
             self.state
                 .cur_loc
                 .move_to_start_of_bb_by_name(&condbr.true_dest);
             self.symex_from_cur_loc_through_end_of_function()
         } else if false_feasible {
             debug!("only the false branch is feasible");
-            bvcond.not().assert()?; // unnecessary, but may help Boolector more than it hurts?
+            self.state.assert(&bvcond.not())?; // unnecessary, but may help Boolector more than it hurts?
             self.state
                 .cur_loc
                 .move_to_start_of_bb_by_name(&condbr.false_dest);
             self.symex_from_cur_loc_through_end_of_function()
         } else {
             debug!("neither branch is feasible");
-            self.backtrack_and_continue()
+            self.dead_end()
         }
     }
 
@@ -1889,12 +3723,17 @@ where
             .iter()
             .map(|(c, n)| self.state.const_to_bv(c).map(|c| (c, n)))
             .collect::<Result<Vec<(B::BV, &Name)>>>()?;
+        let dry_run = self.state.config.dry_run;
         let feasible_dests: Vec<_> = dests
             .iter()
             .map(|(c, n)| {
-                self.state
-                    .bvs_can_be_equal(&c, &switchval)
-                    .map(|b| (c, *n, b))
+                if dry_run {
+                    Ok((c, *n, true))
+                } else {
+                    self.state
+                        .bvs_can_be_equal(&c, &switchval)
+                        .map(|b| (c, *n, b))
+                }
             })
             .collect::<Result<Vec<(&B::BV, &Name, bool)>>>()?
             .into_iter()
@@ -1908,32 +3747,111 @@ where
                 .move_to_start_of_bb_by_name(&switch.default_dest);
             self.symex_from_cur_loc_through_end_of_function()
         } else {
-            // make backtracking points for all but the first destination
-            for (val, name) in feasible_dests.iter().skip(1) {
-                self.state
-                    .save_backtracking_point(name, val._eq(&switchval));
-            }
-            // if the default dest is feasible, make a backtracking point for it
+            // all the feasible successors: each feasible destination, in order,
+            // followed by the default dest if it is feasible
+            let mut successors: Vec<(B::BV, &Name)> = feasible_dests
+                .iter()
+                .map(|(val, name)| (val._eq(&switchval), *name))
+                .collect();
             let default_dest_constraint = dests
                 .iter()
                 .map(|(c, _)| c._eq(&switchval).not())
                 .reduce(|a, b| a.and(&b))
                 .unwrap_or_else(|| self.state.bv_from_bool(true)); // if `dests` was empty, that's weird, but the default dest is definitely feasible
-            if self
-                .state
-                .sat_with_extra_constraints(std::iter::once(&default_dest_constraint))?
+            if dry_run
+                || self
+                    .state
+                    .sat_with_extra_constraints(std::iter::once(&default_dest_constraint))?
             {
-                self.state
-                    .save_backtracking_point(&switch.default_dest, default_dest_constraint);
+                successors.push((default_dest_constraint, &switch.default_dest));
             }
-            // follow the first destination
-            let (val, name) = &feasible_dests[0];
-            val._eq(&switchval).assert()?; // unnecessary, but may help Boolector more than it hurts?
+            let chosen = match self.state.config.single_path {
+                Some(policy) => self.choose_single_path_successor(policy, successors.len()),
+                None => {
+                    // follow the first successor (unless another is the first
+                    // consistent with the seed values), and make backtracking
+                    // points for all the others
+                    let chosen = self.seeded_successor(&successors)?;
+                    for (i, (constraint, name)) in successors.iter().enumerate() {
+                        if i != chosen {
+                            self.state.save_backtracking_point(name, constraint.clone());
+                        }
+                    }
+                    chosen
+                },
+            };
+            // follow the chosen successor
+            let (constraint, name) = &successors[chosen];
+            self.state.assert(constraint)?;
             self.state.cur_loc.move_to_start_of_bb_by_name(name);
             self.symex_from_cur_loc_through_end_of_function()
         }
     }
 
+    /// Processes the `CallBr` (e.g., from C's `asm goto`) using the inline
+    /// assembly hook, then continues to the `CallBr`'s fallthrough destination
+    /// and eventually returns the new `ReturnValue` representing the return
+    /// value of the function (when it reaches the end of the function), or
+    /// `Ok(None)` if no possible paths were found.
+    ///
+    /// `CallBr`s with indirect destinations (labels which the asm may jump to)
+    /// are reported as `Error::UnsupportedInstruction`, because `llvm-ir`
+    /// doesn't give us those destinations (its `CallBr.other_labels` is `()`,
+    /// and the `blockaddress` operands passed to the asm are opaque
+    /// `Constant::BlockAddress`es), so we can't explore them. (`llvm-ir` also
+    /// can't currently parse a `CallBr` without any indirect destinations.)
+    fn symex_callbr(
+        &mut self,
+        callbr: &'p terminator::CallBr,
+    ) -> Result<Option<ReturnValue<B::BV>>> {
+        debug!("Symexing callbr {:?}", callbr);
+        // LLVM requires each indirect destination to also be passed to the asm
+        // as a `blockaddress` operand, so this finds all `CallBr`s which have
+        // indirect destinations
+        let has_indirect_dests = callbr.arguments.iter().any(|(arg, _)| match arg {
+            Operand::ConstantOperand(c) => matches!(c.as_ref(), Constant::BlockAddress),
+            _ => false,
+        });
+        if has_indirect_dests {
+            return Err(Error::UnsupportedInstruction(
+                "callbr with indirect destinations".into(),
+            ));
+        }
+        let hook = match self.resolve_function(&callbr.function)? {
+            ResolvedFunction::HookActive {
+                hook,
+                hooked_thing: HookedThing::InlineAsm,
+            } => hook,
+            _ => {
+                return Err(Error::UnsupportedInstruction(
+                    "callbr of something other than inline assembly".into(),
+                ))
+            },
+        };
+        match self.symex_hook(callbr, &hook, "inline assembly", true)? {
+            // Assume that `symex_hook()` has taken care of validating the hook return value as necessary
+            ReturnValue::Return(retval) => {
+                self.state
+                    .assign_bv_to_name(callbr.result.clone(), retval)?;
+            },
+            ReturnValue::ReturnVoid => {},
+            ReturnValue::Throw(_) => {
+                return Err(Error::OtherError(
+                    "Inline assembly hook for a callbr threw an exception".into(),
+                ))
+            },
+            ReturnValue::Abort => return Ok(Some(ReturnValue::Abort)),
+        };
+        self.state
+            .cur_loc
+            .move_to_start_of_bb_by_name(&callbr.return_label);
+        // any other paths requested by the hook resume at the `return_label` too
+        if !self.apply_hook_forks(self.state.cur_loc.clone())? {
+            return self.dead_end();
+        }
+        self.symex_from_cur_loc_through_end_of_function()
+    }
+
     /// Continues to the target of the `Invoke` and eventually returns the new
     /// `ReturnValue` representing the return value of the function (when it
     /// reaches the end of the function), or `Ok(None)` if no possible paths were
@@ -1992,6 +3910,10 @@ where
                     old_bb_name,
                     self.state.cur_loc.bb.name,
                 );
+                // any other paths requested by the hook resume at the `return_label` too
+                if !self.apply_hook_forks(self.state.cur_loc.clone())? {
+                    return self.dead_end();
+                }
                 self.symex_from_cur_loc_through_end_of_function()
             },
             ResolvedFunction::NoHookActive { called_funcname } => {
@@ -1999,8 +3921,16 @@ where
                     Some(max_depth) => self.state.current_callstack_depth() >= max_depth,
                     None => false,
                 };
-                if at_max_callstack_depth {
-                    info!("Ignoring a call to function {:?} due to max_callstack_len setting (current callstack depth is {}, max is {})", called_funcname, self.state.current_callstack_depth(), self.state.config.max_callstack_depth.unwrap());
+                let trusted_pure =
+                    self.is_trusted_pure(&invoke.function_attributes, called_funcname);
+                if at_max_callstack_depth || trusted_pure {
+                    if at_max_callstack_depth {
+                        info!("Ignoring a call to function {:?} due to max_callstack_len setting (current callstack depth is {}, max is {})", called_funcname, self.state.current_callstack_depth(), self.state.config.max_callstack_depth.unwrap());
+                        let pretty_funcname = self.state.demangle(called_funcname);
+                        self.state.record_truncated_call(pretty_funcname);
+                    } else {
+                        info!("Summarizing a call to function {:?} as pure, due to its function attributes and the pure_function_attributes setting", called_funcname);
+                    }
                     match self.state.type_of(invoke).as_ref() {
                         Type::VoidType => {},
                         ty => {
@@ -2010,10 +3940,14 @@ where
                                 )
                             })?;
                             assert_ne!(width, 0, "Invoke return type has size 0 bits but isn't void type"); // void type was handled above
-                            let bv = self.state.new_bv_with_name(
-                                Name::from(format!("{}_retval", called_funcname)),
-                                width,
-                            )?;
+                            // tag truncated calls' return values, so that
+                            // they're recognizable as over-approximations
+                            let name = if at_max_callstack_depth {
+                                format!("{}_truncated_retval", called_funcname)
+                            } else {
+                                format!("{}_retval", called_funcname)
+                            };
+                            let bv = self.state.new_bv_with_name(Name::from(name), width)?;
                             self.state.assign_bv_to_name(invoke.result.clone(), bv)?;
                         },
                     }
@@ -2024,23 +3958,23 @@ where
                 } else if let Some((callee, callee_mod)) =
                     self.state.get_func_by_name(called_funcname)
                 {
-                    if invoke.arguments.len() != callee.parameters.len() {
-                        if callee.is_var_arg {
-                            return Err(Error::UnsupportedInstruction(format!(
-                                "Call of a function named {:?} which is variadic",
-                                callee.name
-                            )));
-                        } else {
-                            return Err(Error::MalformedInstruction(format!("Call of a function named {:?} which has {} parameters, but {} arguments were given", callee.name, callee.parameters.len(), invoke.arguments.len())));
-                        }
+                    // variadic functions may be given extra arguments beyond their declared parameters, but never fewer
+                    if invoke.arguments.len() < callee.parameters.len()
+                        || (invoke.arguments.len() > callee.parameters.len() && !callee.is_var_arg)
+                    {
+                        return Err(Error::MalformedInstruction(format!("Call of a function named {:?} which has {} parameters, but {} arguments were given", callee.name, callee.parameters.len(), invoke.arguments.len())));
                     }
-                    let bvargs: Vec<B::BV> = invoke
+                    let mut bvargs: Vec<B::BV> = invoke
                         .arguments
                         .iter()
                         .map(|arg| self.state.operand_to_bv(&arg.0)) // have to do this before changing state.cur_loc, so that the lookups happen in the caller function
                         .collect::<Result<Vec<B::BV>>>()?;
+                    // any arguments beyond the declared parameters are variadic arguments, which the callee can access via `va_start()` etc
+                    let varargs = bvargs.split_off(callee.parameters.len());
+                    self.check_recursion_depth(called_funcname)?;
                     let saved_loc = self.state.cur_loc.clone();
                     self.state.push_invokesite(invoke);
+                    self.state.set_varargs(varargs);
                     self.state.cur_loc = Location {
                         module: callee_mod,
                         func: callee,
@@ -2055,6 +3989,7 @@ where
                         self.state.assign_bv_to_name(param.name.clone(), bvarg)?;
                         // have to do the assign_bv_to_name calls after changing state.cur_loc, so that the variables are created in the callee function
                     }
+                    self.run_call_callbacks(callee)?;
                     info!(
                         "Entering function {:?} in module {:?}",
                         called_funcname, &callee_mod.name
@@ -2067,6 +4002,7 @@ where
                         Some(ref callsite)
                             if callsite.loc == saved_loc && callsite.instr.is_right() =>
                         {
+                            self.run_return_callbacks(callee)?;
                             let old_bb_name = &self.state.cur_loc.bb.name;
                             self.state.cur_loc = saved_loc;
                             match returned_bv {
@@ -2110,17 +4046,17 @@ where
                         Some(callsite) => panic!("Received unexpected callsite {:?}", callsite),
                     }
                 } else {
-                    match self.state.config.function_hooks.get_default_hook() {
+                    match self.hook_for_missing_function() {
                         None => Err(Error::FunctionNotFound(
                             self.state.demangle(called_funcname),
                         )),
                         Some(hook) => {
-                            let hook = hook.clone(); // end the implicit borrow of `self` that arose from `get_default_hook()`. The `clone` is just an `Rc` and a `usize`, as of this writing
                             let pretty_funcname = self.state.demangle(called_funcname);
                             info!(
                                 "Using default hook for a function named {:?}",
                                 pretty_funcname
                             );
+                            self.state.record_havoced_call(pretty_funcname.clone());
                             match self.symex_hook(invoke, &hook.clone(), &pretty_funcname, true)? {
                                 // Assume that `symex_hook()` has taken care of validating the hook return value as necessary
                                 ReturnValue::Return(retval) => {
@@ -2177,10 +4113,20 @@ where
         thrown_ptr: &B::BV,
         bbname: &Name,
     ) -> Result<Option<ReturnValue<B::BV>>> {
-        // For now we just add an unconstrained type index
-        let type_index = self
-            .state
-            .new_bv_with_name(Name::from("unconstrained_type_index_for_thrown_value"), 32)?;
+        // If we know the type of the thrown value and have a mapping from types
+        // to type indices, use it; otherwise, add an unconstrained type index
+        let type_index = match (
+            self.state.config.exception_type_index_hook.clone(),
+            self.state.exception_type_info().map(str::to_owned),
+        ) {
+            (Some(hook), Some(type_info)) => {
+                let type_index = hook(&self.state, &type_info)?;
+                self.state.bv_from_u32(type_index, 32)
+            },
+            _ => self
+                .state
+                .new_bv_with_name(Name::from("unconstrained_type_index_for_thrown_value"), 32)?,
+        };
         self.catch_with_type_index(thrown_ptr, &type_index, bbname)
     }
 
@@ -2215,6 +4161,7 @@ where
                 first_iter = false;
                 self.state.record_path_entry(); // do this only on the first iteration
             }
+            self.count_instruction()?;
             let result = match inst {
                 Instruction::Phi(phi) => self.symex_phi(phi),  // phi instructions are allowed before the landingpad
                 Instruction::LandingPad(lp) => { found_landingpad = true; self.symex_landing_pad(lp, thrown_ptr, type_index) },
@@ -2231,10 +4178,10 @@ where
                         continue;
                     }
                 },
-                Err(Error::Unsat) | Err(Error::LoopBoundExceeded(_)) => {
+                Err(Error::Unsat) | Err(Error::LoopBoundExceeded { .. }) => {
                     // we can't continue down this path anymore
                     info!("Path is either unsat or exceeds the loop bound");
-                    return self.backtrack_and_continue();
+                    return self.dead_end();
                 },
                 Err(e) => return Err(e), // propagate any other errors
             }
@@ -2285,7 +4232,10 @@ where
         }
         // Partly due to current restrictions in `llvm-ir` (not enough info
         // available on landingpad clauses - see `llvm-ir` docs), for now we
-        // assume that the landingpad always catches
+        // assume that the landingpad always catches. If the `type_index` is
+        // concrete (see `Config.exception_type_index_hook`), the code following
+        // the landingpad will check it and `resume` if it doesn't match any of
+        // the types handled here
         self.state
             .record_bv_result(lp, type_index.concat(thrown_ptr))
     }
@@ -2332,10 +4282,10 @@ where
                         self.state
                             .record_bv_result(select, bvcond.cond_bv(&bvtrueval, &bvfalseval))
                     } else if true_feasible {
-                        bvcond.assert()?; // unnecessary, but may help Boolector more than it hurts?
+                        self.state.assert(&bvcond)?; // unnecessary, but may help Boolector more than it hurts?
                         self.state.record_bv_result(select, bvtrueval)
                     } else if false_feasible {
-                        bvcond.not().assert()?; // unnecessary, but may help Boolector more than it hurts?
+                        self.state.assert(&bvcond.not())?; // unnecessary, but may help Boolector more than it hurts?
                         self.state.record_bv_result(select, bvfalseval)
                     } else {
                         // this path is unsat
@@ -2396,6 +4346,16 @@ where
         }
     }
 
+    fn symex_vaarg(&mut self, vaarg: &'p instruction::VAArg) -> Result<()> {
+        debug!("Symexing va_arg {:?}", vaarg);
+        let va_list = self.state.operand_to_bv(&vaarg.arg_list)?;
+        let bits = self.state.size_in_bits(&vaarg.cur_type).ok_or_else(|| {
+            Error::MalformedInstruction("VAArg result type is an opaque struct type".into())
+        })?;
+        let arg = hook_utils::va_arg(&mut self.state, &va_list, bits)?;
+        self.state.record_bv_result(vaarg, arg)
+    }
+
     fn symex_cmpxchg(&mut self, cmpxchg: &'p instruction::CmpXchg) -> Result<()> {
         debug!("Symexing cmpxchg {:?}", cmpxchg);
         let main_ty = {
@@ -2466,9 +4426,14 @@ where
             RMWBinOp::UMax => read_val.ugt(&val).cond_bv(&read_val, &val),
             RMWBinOp::UMin => read_val.ult(&val).cond_bv(&read_val, &val),
             RMWBinOp::FAdd | RMWBinOp::FSub => {
-                return Err(Error::UnsupportedInstruction(
-                    "Floating-point operation in an AtomicRMW".into(),
-                ))
+                // We don't support floating-point operations yet (neither the
+                // instructions nor the backend operations). Once we do, these
+                // should follow the same read-modify-write pattern as above,
+                // using floating-point add/sub on `read_val` and `val`.
+                return Err(Error::UnsupportedInstruction(format!(
+                    "floating-point operation {:?} in an AtomicRMW",
+                    armw.operation
+                )));
             },
         };
         self.state.write(&addr, modified_val)?;
@@ -2476,6 +4441,65 @@ where
     }
 }
 
+/// How many distinct values `ExecutionManager::possible_return_values()` and
+/// friends should look for
+enum ReturnValueBudget {
+    /// At most this many distinct values in total
+    Combined(usize),
+    /// At most `returns` distinct non-throw values (`Return`, `ReturnVoid`,
+    /// and `Abort`), and separately, at most `throws` distinct `Throw`s
+    Separate { returns: usize, throws: usize },
+}
+
+impl ReturnValueBudget {
+    /// Maximum number of distinct values to solve for when we find a returned
+    /// (if `throw` is `false`) or thrown (if `throw` is `true`) value
+    fn limit(&self, throw: bool) -> usize {
+        match self {
+            ReturnValueBudget::Combined(n) => *n,
+            ReturnValueBudget::Separate { returns, .. } if !throw => *returns,
+            ReturnValueBudget::Separate { throws, .. } => *throws,
+        }
+    }
+
+    /// Have we already found more than the budgeted number of values of the
+    /// given kind (for a `Combined` budget, of any kind)
+    fn is_exceeded(&self, values: &IndexSet<ReturnValue<u64>>, throw: bool) -> bool {
+        match self {
+            ReturnValueBudget::Combined(n) => values.len() > *n,
+            ReturnValueBudget::Separate { .. } => {
+                let count = values
+                    .iter()
+                    .filter(|val| match val {
+                        ReturnValue::Throw(_) => throw,
+                        _ => !throw,
+                    })
+                    .count();
+                count > self.limit(throw)
+            },
+        }
+    }
+}
+
+// Describe (up to `MAX_FUNCTION_PTR_TARGETS_IN_MESSAGE` of) the given possible
+// targets of a function pointer, for use in error messages. Function names are
+// sorted so that the message is deterministic.
+fn describe_function_ptr_targets<B: Backend>(targets: &[Callable<B>]) -> String {
+    let mut names: Vec<String> = targets
+        .iter()
+        .map(|target| match target {
+            Callable::LLVMFunction(f) => f.name.clone(),
+            Callable::FunctionHook(_) => "<FunctionHook>".to_owned(),
+        })
+        .collect();
+    names.sort();
+    if names.len() > MAX_FUNCTION_PTR_TARGETS_IN_MESSAGE {
+        names.truncate(MAX_FUNCTION_PTR_TARGETS_IN_MESSAGE);
+        names.push("...".to_owned());
+    }
+    names.join(", ")
+}
+
 // Is the given `Constant` a `GlobalReference`
 fn is_global_reference(c: &Constant) -> bool {
     match c {
@@ -2484,6 +4508,30 @@ fn is_global_reference(c: &Constant) -> bool {
     }
 }
 
+// Convert the operand of a `ptrtoint` or `inttoptr` to the result width
+// `result_bits`, truncating or zero-extending as necessary. For vectors,
+// `num_elements` is the number of elements, and each element is converted
+// separately.
+pub(crate) fn ptr_int_cast<V: BV>(
+    bv: &V,
+    result_bits: u32,
+    num_elements: Option<u32>,
+) -> Result<V> {
+    let convert = |bv: &V, bits: u32| {
+        if bv.get_width() > bits {
+            bv.slice(bits - 1, 0)
+        } else {
+            bv.zero_extend_to_bits(bits)
+        }
+    };
+    match num_elements {
+        None => Ok(convert(bv, result_bits)),
+        Some(num_elements) => unary_on_vector(bv, num_elements, |el| {
+            Ok(convert(el, result_bits / num_elements))
+        }),
+    }
+}
+
 // Apply the given unary scalar operation to a vector
 pub(crate) fn unary_on_vector<F: FnMut(&V) -> Result<V>, V: BV>(
     in_vector: &V,
@@ -2758,7 +4806,7 @@ mod tests {
         fn next(&mut self) -> Option<Self::Item> {
             loop {
                 match self.em.next() {
-                    Some(Err(Error::LoopBoundExceeded(_))) => {
+                    Some(Err(Error::LoopBoundExceeded { .. })) => {
                         // for the purposes of the PathIterator for these tests,
                         // we silently ignore paths which exceeded the loop bound
                         continue;
@@ -3135,6 +5183,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[rustfmt::skip]
+    fn simple_call_trusted_pure() -> Result<()> {
+        let modname = "tests/bcfiles/call.bc";
+        let funcname = "simple_caller";
+        init_logging();
+        let proj = Project::from_bc_path(modname)
+            .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+
+        // `simple_callee` is `readnone` and `nounwind`, so we shouldn't enter the call
+        let config = Config {
+            loop_bound: 5,
+            pure_function_attributes: vec![PureFunctionAttribute::ReadNone, PureFunctionAttribute::NoUnwind].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut paths: Vec<Path> = PathIterator::<DefaultBackend>::new(funcname, &proj, config, None)
+            .collect::<Result<Vec<Path>>>()
+            .unwrap_or_else(|r| panic!("{}", r));
+        paths.sort();
+        assert_eq!(paths[0], path_from_tuples_with_bbnums(modname, vec![
+            ("simple_caller", 1, Instr(0)),
+            // shouldn't enter the call, due to `pure_function_attributes` setting
+        ]));
+        assert_eq!(paths.len(), 1); // ensure there are no more paths
+
+        // `simple_callee` isn't `willreturn`, so we should enter the call as normal
+        let config = Config {
+            loop_bound: 5,
+            pure_function_attributes: vec![PureFunctionAttribute::ReadOnly, PureFunctionAttribute::WillReturn].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut paths: Vec<Path> = PathIterator::<DefaultBackend>::new(funcname, &proj, config, None)
+            .collect::<Result<Vec<Path>>>()
+            .unwrap_or_else(|r| panic!("{}", r));
+        paths.sort();
+        assert_eq!(paths[0], path_from_tuples_with_bbnums(modname, vec![
+            ("simple_caller", 1, Instr(0)),
+            ("simple_callee", 2, Instr(0)),
+            ("simple_caller", 1, Terminator),
+        ]));
+        assert_eq!(paths.len(), 1); // ensure there are no more paths
+
+        Ok(())
+    }
+
     #[test]
     #[rustfmt::skip]
     fn cross_module_simple_call() -> Result<()> {
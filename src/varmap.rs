@@ -52,7 +52,7 @@ impl<V: BV> VarMap<V> {
             solver,
             active_version: DoubleKeyedMap::new(),
             version_num: DoubleKeyedMap::new(),
-            max_version_num: max_versions_of_name - 1, // because 0 is a version
+            max_version_num: max_versions_of_name.saturating_sub(1), // because 0 is a version
         }
     }
 
@@ -67,8 +67,33 @@ impl<V: BV> VarMap<V> {
     /// `Error::LoopBoundExceeded`, which is returned if creating the new `BV`
     /// would exceed `max_versions_of_name` -- see
     /// [`VarMap::new()`](struct.VarMap.html#method.new).)
-    pub fn new_bv_with_name(&mut self, funcname: String, name: Name, bits: u32) -> Result<V> {
-        let new_version = self.new_version_of_name(&funcname, &name)?;
+    ///
+    /// `bbname` is the name of the basic block (in the function `funcname`)
+    /// which is creating the `BV`. It's used only to report which block
+    /// exceeded the loop bound, if one did.
+    pub fn new_bv_with_name(
+        &mut self,
+        funcname: String,
+        bbname: &Name,
+        name: Name,
+        bits: u32,
+    ) -> Result<V> {
+        self.new_bv_with_name_and_max_versions(funcname, bbname, name, bits, None)
+    }
+
+    /// Like `new_bv_with_name()`, but if `max_versions_of_name` is `Some`, it
+    /// is used in place of the `max_versions_of_name` the `VarMap` was created
+    /// with, for the purposes of this creation only.
+    pub fn new_bv_with_name_and_max_versions(
+        &mut self,
+        funcname: String,
+        bbname: &Name,
+        name: Name,
+        bits: u32,
+        max_versions_of_name: Option<usize>,
+    ) -> Result<V> {
+        let new_version =
+            self.new_version_of_name(&funcname, bbname, &name, max_versions_of_name)?;
         let bv = V::new(self.solver.clone(), bits, Some(&new_version));
         debug!("Adding var {:?} = {:?}", name, bv);
         self.active_version.insert(funcname, name, bv.clone());
@@ -87,14 +112,42 @@ impl<V: BV> VarMap<V> {
     /// `Error::LoopBoundExceeded`, which is returned if creating the new version
     /// of the `BV` would exceed `max_versions_of_name` -- see
     /// [`VarMap::new()`](struct.VarMap.html#method.new).)
-    pub fn assign_bv_to_name(&mut self, funcname: String, name: Name, bv: V) -> Result<()> {
+    ///
+    /// `bbname` is as for `new_bv_with_name()`.
+    pub fn assign_bv_to_name(
+        &mut self,
+        funcname: String,
+        bbname: &Name,
+        name: Name,
+        bv: V,
+    ) -> Result<()> {
+        self.assign_bv_to_name_with_max_versions(funcname, bbname, name, bv, None)
+    }
+
+    /// Like `assign_bv_to_name()`, but if `max_versions_of_name` is `Some`, it
+    /// is used in place of the `max_versions_of_name` the `VarMap` was created
+    /// with, for the purposes of this assignment only.
+    pub fn assign_bv_to_name_with_max_versions(
+        &mut self,
+        funcname: String,
+        bbname: &Name,
+        name: Name,
+        bv: V,
+        max_versions_of_name: Option<usize>,
+    ) -> Result<()> {
+        let max_version_num = self.max_version_num(max_versions_of_name);
         let new_version_num = self
             .version_num
             .entry(funcname.clone(), name.clone())
             .and_modify(|v| *v += 1) // increment if it already exists in map
             .or_insert(0); // insert a 0 if it didn't exist in map
-        if *new_version_num > self.max_version_num {
-            Err(Error::LoopBoundExceeded(self.max_version_num))
+        if *new_version_num > max_version_num {
+            Err(Error::LoopBoundExceeded {
+                bound: max_version_num,
+                funcname,
+                bbname: bbname.clone(),
+                varname: name,
+            })
         } else {
             // We don't actually use the new_version_num except for the above check,
             // since we aren't creating a new BV that needs a versioned name
@@ -146,20 +199,43 @@ impl<V: BV> VarMap<V> {
 
     /// Given a `Name` (from a particular function), creates a new version of it
     /// and returns the corresponding versioned name
-    /// (or `Error::LoopBoundExceeded` if it would exceed the `max_version_num`)
-    fn new_version_of_name(&mut self, funcname: &str, name: &Name) -> Result<String> {
+    /// (or `Error::LoopBoundExceeded` if it would exceed the `max_version_num`,
+    /// or the override given by `max_versions_of_name`)
+    fn new_version_of_name(
+        &mut self,
+        funcname: &str,
+        bbname: &Name,
+        name: &Name,
+        max_versions_of_name: Option<usize>,
+    ) -> Result<String> {
+        let max_version_num = self.max_version_num(max_versions_of_name);
         let new_version_num = self
             .version_num
             .entry(funcname.to_owned(), name.clone())
             .and_modify(|v| *v += 1) // increment if it already exists in map
             .or_insert(0); // insert a 0 if it didn't exist in map
-        if *new_version_num > self.max_version_num {
-            Err(Error::LoopBoundExceeded(self.max_version_num))
+        if *new_version_num > max_version_num {
+            Err(Error::LoopBoundExceeded {
+                bound: max_version_num,
+                funcname: funcname.to_owned(),
+                bbname: bbname.clone(),
+                varname: name.clone(),
+            })
         } else {
             Ok(Self::build_versioned_name(funcname, name, *new_version_num))
         }
     }
 
+    /// The maximum version number to enforce, given an optional override of
+    /// `max_versions_of_name`. An override of 0 is treated like 1, since the
+    /// first version of a `Name` is always allowed.
+    fn max_version_num(&self, max_versions_of_name: Option<usize>) -> usize {
+        match max_versions_of_name {
+            Some(max_versions) => max_versions.saturating_sub(1), // because 0 is a version
+            None => self.max_version_num,
+        }
+    }
+
     /// Given a `Name` (from a particular function) and a version number, build
     /// the corresponding versioned name.
     ///
@@ -232,17 +308,17 @@ pub struct RestoreInfo<V: BV> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::BtorRef;
     use crate::solver_utils;
-    use boolector::Btor;
-    use std::rc::Rc;
 
-    type BV = boolector::BV<Rc<Btor>>;
+    type BV = boolector::BV<BtorRef>;
 
     #[test]
     fn lookup_vars() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut varmap: VarMap<BV> = VarMap::new(btor, 20);
         let funcname = "foo".to_owned();
+        let bbname = Name::from("bb");
 
         // create llvm-ir names
         let name1 = Name::from("val");
@@ -250,10 +326,10 @@ mod tests {
 
         // create corresponding BV values
         let var1 = varmap
-            .new_bv_with_name(funcname.clone(), name1.clone(), 64)
+            .new_bv_with_name(funcname.clone(), &bbname, name1.clone(), 64)
             .unwrap();
         let var2 = varmap
-            .new_bv_with_name(funcname.clone(), name2.clone(), 1)
+            .new_bv_with_name(funcname.clone(), &bbname, name2.clone(), 1)
             .unwrap(); // these clone()s wouldn't normally be necessary but we want to compare against the original values later
 
         // check that looking up the llvm-ir values gives the correct BV ones
@@ -263,20 +339,23 @@ mod tests {
 
     #[test]
     fn vars_are_uniqued() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut varmap: VarMap<BV> = VarMap::new(btor.clone(), 20);
         let funcname = "foo".to_owned();
+        let bbname = Name::from("bb");
 
         // create two vars with the same name
         let name = Name::from("x");
         let x1 = varmap
-            .new_bv_with_name(funcname.clone(), name.clone(), 64)
+            .new_bv_with_name(funcname.clone(), &bbname, name.clone(), 64)
+            .unwrap();
+        let x2 = varmap
+            .new_bv_with_name(funcname.clone(), &bbname, name, 64)
             .unwrap();
-        let x2 = varmap.new_bv_with_name(funcname.clone(), name, 64).unwrap();
 
         // constrain with incompatible constraints
-        x1.ugt(&BV::from_u64(btor.clone().into(), 2, 64)).assert();
-        x2.ult(&BV::from_u64(btor.clone().into(), 1, 64)).assert();
+        x1.ugt(&BV::from_u64(btor.clone(), 2, 64)).assert();
+        x2.ult(&BV::from_u64(btor.clone(), 1, 64)).assert();
 
         // check that we're still sat
         assert_eq!(solver_utils::sat(&btor), Ok(true));
@@ -284,63 +363,156 @@ mod tests {
         // now repeat with integer names
         let name = Name::from(3);
         let x1 = varmap
-            .new_bv_with_name(funcname.clone(), name.clone(), 64)
+            .new_bv_with_name(funcname.clone(), &bbname, name.clone(), 64)
             .unwrap();
-        let x2 = varmap.new_bv_with_name(funcname.clone(), name, 64).unwrap();
-        x1.ugt(&BV::from_u64(btor.clone().into(), 2, 64)).assert();
-        x2.ult(&BV::from_u64(btor.clone().into(), 1, 64)).assert();
+        let x2 = varmap
+            .new_bv_with_name(funcname.clone(), &bbname, name, 64)
+            .unwrap();
+        x1.ugt(&BV::from_u64(btor.clone(), 2, 64)).assert();
+        x2.ult(&BV::from_u64(btor.clone(), 1, 64)).assert();
         assert_eq!(solver_utils::sat(&btor), Ok(true));
 
         // now repeat with the same name but different functions
         let name = Name::from(10);
         let otherfuncname = "bar".to_owned();
         let x1 = varmap
-            .new_bv_with_name(funcname.clone(), name.clone(), 64)
+            .new_bv_with_name(funcname.clone(), &bbname, name.clone(), 64)
             .unwrap();
         let x2 = varmap
-            .new_bv_with_name(otherfuncname.clone(), name.clone(), 64)
+            .new_bv_with_name(otherfuncname.clone(), &bbname, name.clone(), 64)
             .unwrap();
-        x1.ugt(&BV::from_u64(btor.clone().into(), 2, 64)).assert();
-        x2.ult(&BV::from_u64(btor.clone().into(), 1, 64)).assert();
+        x1.ugt(&BV::from_u64(btor.clone(), 2, 64)).assert();
+        x2.ult(&BV::from_u64(btor.clone(), 1, 64)).assert();
         assert_eq!(solver_utils::sat(&btor), Ok(true));
     }
 
     #[test]
     fn enforces_max_version() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
 
         // Create a `VarMap` with `max_version_num = 10`
         let mut varmap: VarMap<BV> = VarMap::new(btor, 10);
 
         // Check that we can create 10 versions of the same `Name`
         let funcname = "foo".to_owned();
+        let bbname = Name::from("bb");
         let name = Name::from(7);
         for _ in 0 .. 10 {
-            let bv = varmap.new_bv_with_name(funcname.clone(), name.clone(), 64);
+            let bv = varmap.new_bv_with_name(funcname.clone(), &bbname, name.clone(), 64);
             assert!(bv.is_ok());
         }
 
         // Check that we can create another 10 versions of that `Name` in a different function
         let funcname2 = "bar".to_owned();
         for _ in 0 .. 10 {
-            let bv = varmap.new_bv_with_name(funcname2.clone(), name.clone(), 64);
+            let bv = varmap.new_bv_with_name(funcname2.clone(), &bbname, name.clone(), 64);
             assert!(bv.is_ok());
         }
 
         // Check that we can't create an 11th version of that `Name`
-        let bv = varmap.new_bv_with_name(funcname, name, 64);
+        let bv = varmap.new_bv_with_name(funcname, &bbname, name, 64);
         assert!(bv.is_err());
     }
 
+    #[test]
+    fn max_versions_override() {
+        let btor = <BtorRef as SolverRef>::new();
+        let mut varmap: VarMap<BV> = VarMap::new(btor.clone(), 3);
+        let funcname = "foo".to_owned();
+        let bbname = Name::from("bb");
+        let name = Name::from(7);
+
+        // With an override, we can create more versions than the default allows
+        for _ in 0 .. 5 {
+            let bv = varmap.new_bv_with_name_and_max_versions(
+                funcname.clone(),
+                &bbname,
+                name.clone(),
+                64,
+                Some(5),
+            );
+            assert!(bv.is_ok());
+        }
+
+        // but not more than the override allows
+        let bv = varmap.new_bv_with_name_and_max_versions(
+            funcname.clone(),
+            &bbname,
+            name.clone(),
+            64,
+            Some(5),
+        );
+        assert_eq!(
+            bv.err(),
+            Some(Error::LoopBoundExceeded {
+                bound: 4,
+                funcname: funcname.clone(),
+                bbname: bbname.clone(),
+                varname: name.clone(),
+            })
+        );
+
+        // and without the override, the default applies again
+        let x = BV::from_u64(btor, 0, 64);
+        let res = varmap.assign_bv_to_name(funcname.clone(), &bbname, name.clone(), x);
+        assert_eq!(
+            res,
+            Err(Error::LoopBoundExceeded {
+                bound: 2,
+                funcname,
+                bbname,
+                varname: name,
+            })
+        );
+    }
+
+    #[test]
+    fn max_versions_override_zero() {
+        let btor = <BtorRef as SolverRef>::new();
+        let mut varmap: VarMap<BV> = VarMap::new(btor, 3);
+        let funcname = "foo".to_owned();
+        let bbname = Name::from("bb");
+        let name = Name::from(7);
+
+        // An override of 0 behaves like 1: the first version is allowed
+        let bv = varmap.new_bv_with_name_and_max_versions(
+            funcname.clone(),
+            &bbname,
+            name.clone(),
+            64,
+            Some(0),
+        );
+        assert!(bv.is_ok());
+
+        // but no more
+        let bv = varmap.new_bv_with_name_and_max_versions(
+            funcname.clone(),
+            &bbname,
+            name.clone(),
+            64,
+            Some(0),
+        );
+        assert_eq!(
+            bv.err(),
+            Some(Error::LoopBoundExceeded {
+                bound: 0,
+                funcname,
+                bbname,
+                varname: name,
+            })
+        );
+    }
+
     #[test]
     fn restore_info() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut varmap: VarMap<BV> = VarMap::new(btor, 10);
 
         // create a var named "foo" in function "func"
         let fooname = Name::from("foo");
+        let bbname = Name::from("bb");
         let foo1 = varmap
-            .new_bv_with_name("func".to_owned(), fooname.clone(), 64)
+            .new_bv_with_name("func".to_owned(), &bbname, fooname.clone(), 64)
             .unwrap();
 
         // save restore info for "func"
@@ -348,7 +520,7 @@ mod tests {
 
         // create another var named "foo" in function "func"
         let foo2 = varmap
-            .new_bv_with_name("func".to_owned(), fooname.clone(), 64)
+            .new_bv_with_name("func".to_owned(), &bbname, fooname.clone(), 64)
             .unwrap();
 
         // check that a lookup gives the most recent var
@@ -361,13 +533,14 @@ mod tests {
 
     #[test]
     fn restore_different_function() {
-        let btor = <Rc<Btor> as SolverRef>::new();
+        let btor = <BtorRef as SolverRef>::new();
         let mut varmap: VarMap<BV> = VarMap::new(btor, 10);
 
         // create a var named "foo" in function "func"
         let fooname = Name::from("foo");
+        let bbname = Name::from("bb");
         let _foo1 = varmap
-            .new_bv_with_name("func".to_owned(), fooname.clone(), 64)
+            .new_bv_with_name("func".to_owned(), &bbname, fooname.clone(), 64)
             .unwrap();
 
         // save restore info for function "blah"
@@ -375,7 +548,7 @@ mod tests {
 
         // create another var named "foo" in function "func"
         let foo2 = varmap
-            .new_bv_with_name("func".to_owned(), fooname.clone(), 64)
+            .new_bv_with_name("func".to_owned(), &bbname, fooname.clone(), 64)
             .unwrap();
 
         // restore function "blah", and check that lookups in function "func" are unaffected
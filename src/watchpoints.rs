@@ -1,19 +1,28 @@
 //! Structures for defining and processing memory watchpoints
 
-use crate::backend::BV;
+use crate::backend::{SolverRef, BV};
 use crate::error::Result;
 use crate::solver_utils;
 use std::collections::HashMap;
 use std::fmt;
 use std::iter::FromIterator;
+use std::ops::Range;
 
 /// A `Watchpoint` describes a segment of memory to watch.
+///
+/// To run custom logic whenever a watchpoint is triggered, see
+/// [`Callbacks::add_watchpoint_callback()`](../callbacks/struct.Callbacks.html#method.add_watchpoint_callback).
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct Watchpoint {
     /// Lower bound of the memory segment to watch (inclusive).
     low: u64,
     /// Upper bound of the memory segment to watch (inclusive).
     high: u64,
+    /// If this is `Some((value, width))`, this is a value-triggered watchpoint
+    /// (see [`Watchpoints::watch_for_value()`](struct.Watchpoints.html#method.watch_for_value)),
+    /// which only fires on writes of a `width`-bit value which may be equal to
+    /// `value`.
+    target_value: Option<(u64, u32)>,
 }
 
 impl Watchpoint {
@@ -26,6 +35,7 @@ impl Watchpoint {
         Self {
             low: addr,
             high: addr + bytes - 1,
+            target_value: None,
         }
     }
 
@@ -38,11 +48,22 @@ impl Watchpoint {
     pub fn get_upper_bound(&self) -> u64 {
         self.high
     }
+
+    /// For a value-triggered watchpoint, get the value being watched for and
+    /// its width in bits. Returns `None` for ordinary watchpoints, which fire on
+    /// any access to the watched memory.
+    pub fn get_target_value(&self) -> Option<(u64, u32)> {
+        self.target_value
+    }
 }
 
 impl fmt::Display for Watchpoint {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[{:#x}, {:#x}]", self.low, self.high)
+        write!(f, "[{:#x}, {:#x}]", self.low, self.high)?;
+        if let Some((value, width)) = self.target_value {
+            write!(f, " == {:#x} ({} bits)", value, width)?;
+        }
+        Ok(())
     }
 }
 
@@ -88,6 +109,33 @@ impl Watchpoints {
         self.0.insert(name.into(), (watchpoint, true)).is_some()
     }
 
+    /// Add a value-triggered memory watchpoint, which fires when a `width`-bit
+    /// value which may be equal to `value` is written anywhere in `addr_range`.
+    /// Unlike ordinary watchpoints, it does not fire on reads, or on writes
+    /// which cannot write `value` (including writes of other widths).
+    /// It will be enabled unless/until `disable()` is called on it.
+    ///
+    /// Value-triggered watchpoints share a namespace with ordinary watchpoints:
+    /// if a watchpoint (of either kind) with the same name was previously
+    /// added, this will replace that watchpoint and return `true`. Otherwise,
+    /// this will return `false`.
+    pub fn watch_for_value(
+        &mut self,
+        name: impl Into<String>,
+        addr_range: Range<u64>,
+        value: u64,
+        width: u32,
+    ) -> bool {
+        if addr_range.start >= addr_range.end {
+            panic!("Watchpoints::watch_for_value: `addr_range` cannot be empty");
+        }
+        let watchpoint = Watchpoint {
+            target_value: Some((value, width)),
+            ..Watchpoint::new(addr_range.start, addr_range.end - addr_range.start)
+        };
+        self.add(name, watchpoint)
+    }
+
     /// Remove the memory watchpoint with the given `name`.
     ///
     /// Returns `true` if the operation was successful, or `false` if no
@@ -126,9 +174,20 @@ impl Watchpoints {
         }
     }
 
+    /// Iterate over all the watchpoints (of both kinds), giving the name of
+    /// each, the `Watchpoint` itself, and whether it is currently enabled.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Watchpoint, bool)> {
+        self.0
+            .iter()
+            .map(|(name, (watchpoint, enabled))| (name, watchpoint, *enabled))
+    }
+
     /// For a memory operation on the given address with the given bitwidth, get
     /// `(name, watchpoint)` pairs corresponding to the active watchpoints which
     /// are triggered by the operation.
+    ///
+    /// Value-triggered watchpoints are not included; see
+    /// `get_value_watchpoint_hits()`.
     pub(crate) fn get_triggered_watchpoints<V: BV>(
         &self,
         addr: &V,
@@ -143,7 +202,7 @@ impl Watchpoints {
             .0
             .iter()
             .map(|(name, (watchpoint, enabled))| {
-                if *enabled {
+                if *enabled && watchpoint.target_value.is_none() {
                     if self.is_watchpoint_triggered(watchpoint, op_lower, &op_upper)? {
                         Ok(Some((name, watchpoint)))
                     } else {
@@ -157,6 +216,54 @@ impl Watchpoints {
         Ok(results?.into_iter().filter_map(|opt| opt))
     }
 
+    /// For a write of `val` to the given address, get the active
+    /// value-triggered watchpoints which may be triggered by the write, as
+    /// `(name, watchpoint, witness)` triples. `witness` is a concrete address
+    /// at which the write can store the watched-for value within the watched
+    /// range.
+    pub(crate) fn get_value_watchpoint_hits<V: BV>(
+        &self,
+        addr: &V,
+        val: &V,
+    ) -> Result<Vec<(&String, &Watchpoint, u64)>> {
+        let btor = addr.get_solver();
+        let addr_width = addr.get_width();
+        let bytes = if val.get_width() < 8 {
+            1
+        } else {
+            val.get_width() / 8
+        };
+        let op_upper = addr.add(&V::from_u32(btor.clone(), bytes - 1, addr_width));
+        let mut hits = Vec::new();
+        for (name, (watchpoint, enabled)) in self.0.iter() {
+            let (value, width) = match watchpoint.target_value {
+                Some(target) if *enabled => target,
+                _ => continue,
+            };
+            if width != val.get_width() {
+                continue;
+            }
+            let triggered = self.trigger_condition(watchpoint, addr, &op_upper);
+            let writes_value = val._eq(&V::from_u64(btor.clone(), value, width));
+            // temporarily constrain that the write triggers the watchpoint, and
+            // get a witness address if that's possible
+            btor.push(1);
+            triggered.and(&writes_value).assert()?;
+            btor.set_model_gen(true);
+            let witness = if solver_utils::sat(&btor)? {
+                addr.get_a_solution()?.as_u64()
+            } else {
+                None
+            };
+            btor.set_model_gen(false);
+            btor.pop(1);
+            if let Some(witness) = witness {
+                hits.push((name, watchpoint, witness));
+            }
+        }
+        Ok(hits)
+    }
+
     /// Is the given watchpoint triggered on any address in the given interval (with both endpoints inclusive)?
     pub(crate) fn is_watchpoint_triggered<V: BV>(
         &self,
@@ -164,6 +271,20 @@ impl Watchpoints {
         interval_lower: &V,
         interval_upper: &V,
     ) -> Result<bool> {
+        solver_utils::sat_with_extra_constraints(
+            &interval_lower.get_solver(),
+            std::iter::once(&self.trigger_condition(watchpoint, interval_lower, interval_upper)),
+        )
+    }
+
+    /// Get a condition which is true if the given watchpoint is triggered by an
+    /// operation on the given interval (with both endpoints inclusive)
+    fn trigger_condition<V: BV>(
+        &self,
+        watchpoint: &Watchpoint,
+        interval_lower: &V,
+        interval_upper: &V,
+    ) -> V {
         let btor = interval_lower.get_solver();
         let width = interval_lower.get_width();
         assert_eq!(width, interval_upper.get_width());
@@ -197,14 +318,9 @@ impl Watchpoints {
             .ulte(&watchpoint_lower)
             .and(&interval_upper.ugte(&watchpoint_upper));
 
-        solver_utils::sat_with_extra_constraints(
-            &btor,
-            std::iter::once(
-                &interval_lower_contained
-                    .or(&interval_upper_contained)
-                    .or(&contains_entire_watchpoint),
-            ),
-        )
+        interval_lower_contained
+            .or(&interval_upper_contained)
+            .or(&contains_entire_watchpoint)
     }
 }
 
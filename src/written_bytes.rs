@@ -0,0 +1,127 @@
+//! Tracking of which bytes of a `Memory` have been written, so that reads of
+//! uninitialized memory can be detected.
+//! See [`Config.flag_uninitialized_reads`](../config/struct.Config.html#structfield.flag_uninitialized_reads).
+
+use std::collections::BTreeMap;
+
+/// The set of bytes which have been written, as disjoint, non-adjacent ranges
+/// of concrete addresses.
+///
+/// Writes to symbolic addresses can't be recorded precisely, so after any such
+/// write, we conservatively consider all memory written. Likewise, reads from
+/// symbolic addresses are always considered initialized.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct WrittenBytes {
+    /// Map from the first address of each range to one past its last address
+    ranges: BTreeMap<u64, u64>,
+    /// If `true`, all memory is considered written, e.g. because the memory was
+    /// zero-initialized, or because there has been a write to a symbolic address
+    all: bool,
+}
+
+impl WrittenBytes {
+    /// No bytes have been written
+    pub fn none() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+            all: false,
+        }
+    }
+
+    /// All bytes are considered written
+    pub fn all() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+            all: true,
+        }
+    }
+
+    /// Record a write of `bytes` bytes at `addr`, which is `None` if the
+    /// address is symbolic
+    pub fn record_write(&mut self, addr: Option<u64>, bytes: u64) {
+        if self.all || bytes == 0 {
+            return;
+        }
+        let mut start = match addr {
+            Some(addr) => addr,
+            None => {
+                self.all = true;
+                self.ranges.clear();
+                return;
+            },
+        };
+        let mut end = start.saturating_add(bytes);
+        // merge with any ranges which overlap or are adjacent to this one
+        let merged: Vec<(u64, u64)> = self
+            .ranges
+            .range(0 ..= end)
+            .rev()
+            .take_while(|(_, &range_end)| range_end >= start)
+            .map(|(&range_start, &range_end)| (range_start, range_end))
+            .collect();
+        for (range_start, range_end) in merged {
+            self.ranges.remove(&range_start);
+            start = std::cmp::min(start, range_start);
+            end = std::cmp::max(end, range_end);
+        }
+        self.ranges.insert(start, end);
+    }
+
+    /// Have all of the `bytes` bytes at `addr` been written? `addr` is `None`
+    /// if the address is symbolic.
+    pub fn is_written(&self, addr: Option<u64>, bytes: u64) -> bool {
+        if self.all || bytes == 0 {
+            return true;
+        }
+        match addr {
+            None => true,
+            Some(addr) => match self.ranges.range(0 ..= addr).next_back() {
+                Some((_, &range_end)) => range_end >= addr.saturating_add(bytes),
+                None => false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_write() {
+        let mut written = WrittenBytes::none();
+        assert!(!written.is_written(Some(0x100), 1));
+        written.record_write(Some(0x100), 8);
+        assert!(written.is_written(Some(0x100), 8));
+        assert!(written.is_written(Some(0x104), 4));
+        assert!(!written.is_written(Some(0x104), 8));
+        assert!(!written.is_written(Some(0xff), 2));
+        assert!(!written.is_written(Some(0x108), 1));
+    }
+
+    #[test]
+    fn merge_writes() {
+        let mut written = WrittenBytes::none();
+        written.record_write(Some(0x100), 4);
+        written.record_write(Some(0x108), 4);
+        assert!(!written.is_written(Some(0x100), 12));
+        // fill in the gap, which should merge all three into one range
+        written.record_write(Some(0x104), 4);
+        assert!(written.is_written(Some(0x100), 12));
+        // overlapping writes
+        written.record_write(Some(0xfc), 8);
+        written.record_write(Some(0x10a), 8);
+        assert!(written.is_written(Some(0xfc), 22));
+        assert_eq!(written.ranges.len(), 1);
+    }
+
+    #[test]
+    fn symbolic_addresses() {
+        let mut written = WrittenBytes::none();
+        assert!(written.is_written(None, 4));
+        assert!(!written.is_written(Some(0x100), 4));
+        written.record_write(None, 4);
+        assert!(written.is_written(Some(0x100), 4));
+        assert!(WrittenBytes::all().is_written(Some(0x100), 4));
+    }
+}
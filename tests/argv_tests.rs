@@ -0,0 +1,52 @@
+use haybale::backend::DefaultBackend;
+use haybale::*;
+use std::collections::HashSet;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/argv.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn symbolic_args() {
+    init_logging();
+    let proj = get_project();
+    let (mut em, args) =
+        symex_main_with_symbolic_args::<DefaultBackend>(&proj, Config::default(), 3, 4).unwrap();
+    assert_eq!(args.len(), 3);
+    assert!(args.iter().all(|bytes| bytes.len() == 4));
+    let argc = em.param_bvs()[0].clone();
+
+    let mut rvals = HashSet::new();
+    while let Some(result) = em.next() {
+        let rval = match result {
+            Ok(ReturnValue::Return(rval)) => {
+                rval.as_u64().expect("Expected a concrete return value")
+            },
+            Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        };
+        if rval == 1 {
+            // we can solve for an input which makes the first argument start with 'x'
+            let state = em.state();
+            let first_char = state.get_a_solution_for_bv(&args[1][0]).unwrap().unwrap();
+            assert_eq!(first_char.as_u64(), Some(u64::from(b'x')));
+            assert!(
+                state
+                    .min_possible_solution_for_bv_as_u64(&argc)
+                    .unwrap()
+                    .unwrap()
+                    >= 2
+            );
+        }
+        rvals.insert(rval);
+    }
+    // `argv[argc]` is always `NULL`, so we never return 3
+    assert_eq!(rvals, vec![0, 1, 2].into_iter().collect());
+}
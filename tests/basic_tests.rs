@@ -1,6 +1,10 @@
-use haybale::solver_utils::PossibleSolutions;
+use haybale::backend::DefaultBackend;
+use haybale::solver_utils::{PossibleSolutions, SolverStats};
 use haybale::*;
+use llvm_ir::Name;
+use std::collections::HashSet;
 use std::num::Wrapping;
+use std::rc::Rc;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -93,8 +97,8 @@ fn three_args() {
         .unwrap_or_else(|r| panic!("{}", r))
         .expect("Failed to find zero of the function");
     assert_eq!(args.len(), 3);
-    let sum: i32 = args.iter().map(|a| a.unwrap_to_i32()).sum();
-    assert_eq!(sum, 3);
+    let sum: Wrapping<i32> = args.iter().map(|a| Wrapping(a.unwrap_to_i32())).sum();
+    assert_eq!(sum.0, 3);
 }
 
 #[test]
@@ -370,3 +374,418 @@ fn issue_10() {
         PossibleSolutions::exactly_two(ReturnValue::ReturnVoid, ReturnValue::Abort)
     );
 }
+
+#[test]
+fn arithmetic_overflow() {
+    let funcname = "one_arg";
+    init_logging();
+    let proj = get_project();
+
+    // by default, overflow isn't checked
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.next().is_none());
+
+    // with `check_arithmetic_overflow`, `a - 3` overflows for the three smallest `a`
+    let mut config = Config::default();
+    config.check_arithmetic_overflow = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleIntegerOverflow(_)) => {},
+        Ok(retval) => panic!("Expected an overflow error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    let param = em.param_bvs()[0].clone();
+    let a = em
+        .state()
+        .get_a_solution_for_bv(&param)
+        .unwrap()
+        .expect("Expected a solution")
+        .as_u64()
+        .unwrap() as u32 as i32;
+    assert!(
+        a <= i32::MIN + 2,
+        "expected an overflowing input, got {}",
+        a
+    );
+    // and the non-overflowing path still runs
+    em.next()
+        .expect("Expected a second path")
+        .unwrap_or_else(|e| panic!("{}", em.state().full_error_message_with_context(e)));
+    assert!(em.next().is_none());
+
+    // if `a - 3` always overflows, there is no non-overflowing path to explore
+    let mut config = Config::default();
+    config.check_arithmetic_overflow = true;
+    let params = vec![ParameterVal::ExactValue(i32::MIN as u32 as u64)];
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, Some(params)).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleIntegerOverflow(_)) => {},
+        Ok(retval) => panic!("Expected an overflow error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn divide_by_zero() {
+    let funcname = "binops";
+    init_logging();
+    let proj = get_project();
+
+    // the `sdiv` divisor `a | 99` can't be zero, but the `srem` divisor `c << 3` can
+    let mut config = Config::default();
+    config.check_divide_by_zero = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleDivideByZero { divisor, .. }) => assert_eq!(divisor, "0"),
+        Ok(retval) => panic!("Expected a divide-by-zero error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    // and the path with a nonzero divisor still runs
+    em.next()
+        .expect("Expected a second path")
+        .unwrap_or_else(|e| panic!("{}", em.state().full_error_message_with_context(e)));
+    assert!(em.next().is_none());
+
+    // with `a == 0` and `b == -1`, `c` is 0, so the `srem` always divides by
+    // zero, and there is no successful path
+    let mut config = Config::default();
+    config.check_divide_by_zero = true;
+    let params = vec![
+        ParameterVal::ExactValue(0),
+        ParameterVal::ExactValue(-1i32 as u32 as u64),
+    ];
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, Some(params)).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleDivideByZero { divisor, .. }) => assert_eq!(divisor, "0"),
+        Ok(retval) => panic!("Expected a divide-by-zero error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn find_zero_errors() {
+    init_logging();
+
+    // a function with void return type can't return zero
+    let proj = get_issue_4_project();
+    let err = find_zero_of_func("issue_4::main", &proj, Config::default(), None)
+        .expect_err("Expected an error for a void function");
+    assert_eq!(err, FindZeroError::VoidReturnType);
+    assert_eq!(err.to_string(), "find_zero_of_func: function has void type");
+
+    // errors on paths are returned along with their full messages
+    let proj = get_project();
+    let mut config = Config::default();
+    config.check_divide_by_zero = true;
+    match find_zero_of_func("binops", &proj, config, None) {
+        Err(FindZeroError::Path { error, message }) => {
+            assert!(matches!(*error, Error::PossibleDivideByZero { .. }));
+            assert!(message.contains("PossibleDivideByZero"));
+        },
+        res => panic!("Expected a PossibleDivideByZero error, got {:?}", res),
+    }
+}
+
+#[test]
+fn path_outcomes() {
+    let funcname = "conditional_true";
+    init_logging();
+    let proj = get_project();
+
+    // both paths return
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    let mut outcomes = vec![];
+    while let Some(outcome) = em.next_with_outcome() {
+        outcomes.push(outcome);
+    }
+    assert_eq!(outcomes.len(), 2);
+    for outcome in outcomes {
+        match outcome {
+            PathOutcome::Return(_) => {},
+            PathOutcome::Error(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+            outcome => panic!("Expected a return, got {:?}", outcome),
+        }
+    }
+
+    // with contradictory initial constraints, the only path is dead, since
+    // neither direction of the first branch is feasible. `next()` silently
+    // skips it, but `next_with_outcome()` reports it
+    let mut config = Config::default();
+    config.initial_constraints = Some(Rc::new(|state: &mut State<DefaultBackend>| -> Result<()> {
+        state.assert(&state.bv_from_bool(false))
+    }));
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), None).unwrap();
+    assert!(em.next().is_none());
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    assert_eq!(em.next_with_outcome(), Some(PathOutcome::Dead));
+    assert_eq!(em.next_with_outcome(), None);
+    assert_eq!(em.paths_explored(), 0);
+}
+
+#[test]
+fn coverage() {
+    let funcname = "conditional_true";
+    init_logging();
+    let proj = get_project();
+    let modname = &proj.get_func_by_name(funcname).unwrap().1.name;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    assert_eq!(em.coverage().num_visited_blocks(modname, funcname), 0);
+    assert_eq!(em.coverage().total_blocks(modname, funcname), Some(4));
+
+    // after the first path, we should have visited 3 of the 4 blocks
+    em.next().unwrap().unwrap();
+    let coverage = em.coverage();
+    assert_eq!(coverage.num_visited_blocks(modname, funcname), 3);
+    assert!(coverage.is_visited(modname, funcname, &Name::Number(2)));
+    assert!(coverage.is_visited(modname, funcname, &Name::Number(12)));
+
+    // after both paths, we should have visited all 4, even though neither path visits all 4
+    em.next().unwrap().unwrap();
+    assert!(em.next().is_none());
+    assert_eq!(em.coverage().num_visited_blocks(modname, funcname), 4);
+    assert_eq!(em.coverage().visited_blocks().count(), 4);
+
+    // other functions in the module weren't visited at all
+    let conditional_false = em
+        .coverage()
+        .functions()
+        .find(|f| f.funcname == "conditional_false")
+        .expect("Expected coverage statistics for conditional_false");
+    assert_eq!(conditional_false.visited_blocks, 0);
+    assert_eq!(conditional_false.total_blocks, 4);
+}
+
+#[test]
+fn solver_stats() {
+    let funcname = "conditional_true";
+    init_logging();
+    let proj = get_project();
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    assert_eq!(em.solver_stats(), SolverStats::default());
+
+    // the branch requires checking satisfiability, but no models
+    em.next().unwrap().unwrap();
+    let first_path = em.state().solver_stats();
+    assert!(first_path.sat_checks > 0);
+    assert_eq!(first_path.model_gens, 0);
+
+    // asking for a solution counts as a model generation, on both the path and the total
+    let param = em.param_bvs()[0].clone();
+    em.state().get_a_solution_for_bv(&param).unwrap().unwrap();
+    assert_eq!(em.state().solver_stats().model_gens, 1);
+    assert_eq!(em.solver_stats().model_gens, 1);
+
+    // the second path's counts start over, but the total keeps accumulating
+    em.next().unwrap().unwrap();
+    let second_path = em.state().solver_stats();
+    assert_eq!(second_path.model_gens, 0);
+    assert_eq!(em.solver_stats().model_gens, 1);
+    assert!(em.solver_stats().sat_checks > second_path.sat_checks);
+
+    // queries made by another `ExecutionManager` aren't counted
+    let total = em.solver_stats();
+    let mut other_em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    other_em.next().unwrap().unwrap();
+    let param = other_em.param_bvs()[0].clone();
+    other_em
+        .state()
+        .get_a_solution_for_bv(&param)
+        .unwrap()
+        .unwrap();
+    assert_eq!(other_em.solver_stats().model_gens, 1);
+    assert_eq!(em.solver_stats(), total);
+    assert_eq!(em.state().solver_stats(), second_path);
+}
+
+#[test]
+fn seed_values() {
+    init_logging();
+    let proj = get_project();
+    let seeded_config = |a: u64, b: u64| {
+        let mut config = Config::default();
+        config.seed_values = vec![("0".to_owned(), a), ("1".to_owned(), b)]
+            .into_iter()
+            .collect();
+        config
+    };
+    // is the current path of the `ExecutionManager` one which is taken with the given parameters
+    let path_consistent_with = |em: &ExecutionManager<DefaultBackend>, a: u64, b: u64| {
+        let params = em.param_bvs();
+        let a = params[0]._eq(&em.state().bv_from_u64(a, 32));
+        let b = params[1]._eq(&em.state().bv_from_u64(b, 32));
+        em.state().sat_with_extra_constraints(vec![&a, &b]).unwrap()
+    };
+
+    // without seed values, the first path is the true branch of the `if`
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("conditional_true", &proj, Config::default(), None).unwrap();
+    em.next().unwrap().unwrap();
+    assert!(!path_consistent_with(&em, 0, 5));
+
+    // with them, it's the branch taken by the seed input; the other is still explored
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("conditional_true", &proj, seeded_config(0, 5), None).unwrap();
+    em.next().unwrap().unwrap();
+    assert!(path_consistent_with(&em, 0, 5));
+    em.next().unwrap().unwrap();
+    assert!(em.next().is_none());
+
+    // likewise for the cases of a switch
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("has_switch", &proj, seeded_config(5, 3), None).unwrap();
+    em.next().unwrap().unwrap();
+    assert!(path_consistent_with(&em, 5, 3));
+    assert_eq!(em.count(), 6);
+}
+
+#[test]
+fn possible_return_values_of_execution_manager() -> Result<()> {
+    init_logging();
+    let proj = get_project();
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("one_arg", &proj, Config::default(), None).unwrap();
+
+    // constrain the parameter to 10..=12 in the initial state; this is sticky
+    let a = em.param_bvs()[0].clone();
+    let state = em.mut_state();
+    state.assert(&a.ugte(&state.bv_from_u32(10, 32)))?;
+    state.assert(&a.ulte(&state.bv_from_u32(12, 32)))?;
+
+    assert_eq!(
+        em.possible_return_values(5, None),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(7),
+                ReturnValue::Return(8),
+                ReturnValue::Return(9),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+    Ok(())
+}
+
+#[test]
+fn possible_return_values_order_is_stable() {
+    init_logging();
+    let proj = get_project();
+    let get_rvals = || -> Vec<ReturnValue<u64>> {
+        match get_possible_return_values_of_func(
+            "conditional_true",
+            &proj,
+            Config::default(),
+            None,
+            None,
+            10,
+        ) {
+            PossibleSolutions::Exactly(v) => v.into_iter().collect(),
+            PossibleSolutions::AtLeast(v) => v.into_iter().collect(),
+        }
+    };
+    let first = get_rvals();
+    assert!(first.len() > 1);
+    for _ in 0 .. 3 {
+        assert_eq!(get_rvals(), first);
+    }
+}
+
+#[test]
+fn assert_relation() {
+    init_logging();
+    let proj = get_project();
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("two_args", &proj, Config::default(), None).unwrap();
+
+    // 0 < a < b <= 3
+    em.assert_relation(|params, state| {
+        let (a, b) = (&params[0], &params[1]);
+        a.sgt(&state.zero(32))
+            .and(&a.slt(b))
+            .and(&b.slte(&state.bv_from_u32(3, 32)))
+    })
+    .unwrap();
+
+    // (a, b) is (1, 2), (1, 3), or (2, 3)
+    assert_eq!(
+        em.possible_return_values(5, None),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(0),
+                ReturnValue::Return(1),
+                ReturnValue::Return(2),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+}
+
+#[test]
+fn instruction_trace() {
+    let funcname = "conditional_true";
+    init_logging();
+    let proj = get_project();
+
+    // by default, nothing is traced
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.state().instruction_trace().is_empty());
+
+    // with `record_instruction_trace`, each path gets its own trace, in order
+    let mut config = Config::default();
+    config.record_instruction_trace = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let mut traces = HashSet::new();
+    while let Some(res) = em.next() {
+        res.unwrap_or_else(|e| panic!("{}", e));
+        let trace: Vec<(Name, Name)> = em
+            .state()
+            .instruction_trace()
+            .iter()
+            .map(|(loc, inst)| (loc.bb.name.clone(), inst.try_get_result().unwrap().clone()))
+            .collect();
+        traces.insert(trace);
+    }
+    assert_eq!(
+        traces,
+        vec![
+            vec![
+                (Name::from(2), Name::from(3)),
+                (Name::from(4), Name::from(5)),
+                (Name::from(4), Name::from(6)),
+                (Name::from(4), Name::from(7)),
+                (Name::from(12), Name::from(13)),
+            ],
+            vec![
+                (Name::from(2), Name::from(3)),
+                (Name::from(8), Name::from(9)),
+                (Name::from(8), Name::from(10)),
+                (Name::from(8), Name::from(11)),
+                (Name::from(12), Name::from(13)),
+            ],
+        ]
+        .into_iter()
+        .collect()
+    );
+}
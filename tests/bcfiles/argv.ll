@@ -0,0 +1,39 @@
+; This file is written by hand, to get a `main()` which inspects its `argv`.
+source_filename = "argv.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Returns 3 if `argv[argc]` isn't NULL (which should never happen); otherwise
+; 2 if there are no arguments after the program name; otherwise 1 if the first
+; argument starts with 'x', or 0 if it doesn't
+define i32 @main(i32 %argc, i8** %argv) {
+entry:
+  %argc_idx = sext i32 %argc to i64
+  %last_ptr = getelementptr inbounds i8*, i8** %argv, i64 %argc_idx
+  %last = load i8*, i8** %last_ptr, align 8
+  %last_is_null = icmp eq i8* %last, null
+  br i1 %last_is_null, label %count_args, label %bad_argv
+
+bad_argv:
+  ret i32 3
+
+count_args:
+  %no_args = icmp slt i32 %argc, 2
+  br i1 %no_args, label %none, label %check_first
+
+none:
+  ret i32 2
+
+check_first:
+  %first_ptr = getelementptr inbounds i8*, i8** %argv, i64 1
+  %first = load i8*, i8** %first_ptr, align 8
+  %first_char = load i8, i8* %first, align 1
+  %is_x = icmp eq i8 %first_char, 120
+  br i1 %is_x, label %starts_with_x, label %other
+
+starts_with_x:
+  ret i32 1
+
+other:
+  ret i32 0
+}
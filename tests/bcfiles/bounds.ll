@@ -0,0 +1,47 @@
+; This file is written by hand, since it needs out-of-bounds accesses which a
+; C compiler would warn about, or optimize based on the undefined behavior.
+source_filename = "bounds.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+@array = global [2 x i32] [i32 1, i32 2], align 4
+
+; Stores to and loads from index `i` of a local 4-element array, which is out
+; of bounds unless 0 <= i < 4
+define i32 @array_index(i32 %0) {
+  %2 = alloca [4 x i32], align 16
+  %3 = sext i32 %0 to i64
+  %4 = getelementptr inbounds [4 x i32], [4 x i32]* %2, i64 0, i64 %3
+  store i32 7, i32* %4, align 4
+  %5 = load i32, i32* %4, align 4
+  ret i32 %5
+}
+
+; Writes both fields of a local struct in bounds, but then reads 8 bytes
+; starting at the second (4-byte) field
+define i32 @struct_overread() {
+  %1 = alloca { i32, i32 }, align 8
+  %2 = getelementptr inbounds { i32, i32 }, { i32, i32 }* %1, i32 0, i32 0
+  store i32 1, i32* %2, align 8
+  %3 = getelementptr inbounds { i32, i32 }, { i32, i32 }* %1, i32 0, i32 1
+  store i32 2, i32* %3, align 4
+  %4 = bitcast i32* %3 to i64*
+  %5 = load i64, i64* %4, align 4
+  %6 = trunc i64 %5 to i32
+  ret i32 %6
+}
+
+; Reads in bounds of a global array, then one past its end
+define i32 @global_past_end() {
+  %1 = load i32, i32* getelementptr inbounds ([2 x i32], [2 x i32]* @array, i64 0, i64 1), align 4
+  %2 = load i32, i32* getelementptr ([2 x i32], [2 x i32]* @array, i64 0, i64 2), align 4
+  %3 = add i32 %1, %2
+  ret i32 %3
+}
+
+; Reads the third element of a buffer with the given length
+define i32 @buffer_third(i32* %0, i64 %1) {
+  %3 = getelementptr inbounds i32, i32* %0, i64 2
+  %4 = load i32, i32* %3, align 4
+  ret i32 %4
+}
@@ -0,0 +1,15 @@
+; This file is written by hand, and deliberately contains a cycle of aliases,
+; which the LLVM verifier would reject. So it must be assembled with
+; `llvm-as -disable-verify`.
+source_filename = "circular_alias.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+@alias_a = alias i32, i32* @alias_b
+@alias_b = alias i32, bitcast (i8* @alias_c to i32*)
+@alias_c = alias i8, bitcast (i32* @alias_a to i8*)
+
+define i32 @read_circular_alias() {
+  %1 = load i32, i32* @alias_a, align 4
+  ret i32 %1
+}
@@ -0,0 +1,34 @@
+; This file is written by hand, to get a small function with debug locations
+; (source locations) attached to its instructions.
+source_filename = "debuginfo.c"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Returns `x + 1` if `x` is positive, else `0`. The final `ret` deliberately has
+; no debug location.
+define i32 @debuginfo(i32 %0) !dbg !7 {
+  %2 = icmp sgt i32 %0, 0, !dbg !10
+  br i1 %2, label %3, label %5, !dbg !10
+
+3:
+  %4 = add nsw i32 %0, 1, !dbg !11
+  ret i32 %4, !dbg !12
+
+5:
+  ret i32 0
+}
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "hand-written", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "debuginfo.c", directory: "/haybale/tests/bcfiles")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!7 = distinct !DISubprogram(name: "debuginfo", scope: !1, file: !1, line: 1, type: !8, scopeLine: 1, flags: DIFlagPrototyped, spFlags: DISPFlagDefinition, unit: !0, retainedNodes: !2)
+!8 = !DISubroutineType(types: !9)
+!9 = !{null}
+!10 = !DILocation(line: 2, column: 9, scope: !7)
+!11 = !DILocation(line: 3, column: 18, scope: !7)
+!12 = !DILocation(line: 3, column: 9, scope: !7)
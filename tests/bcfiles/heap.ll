@@ -0,0 +1,76 @@
+; This file is written by hand, since it exercises corner cases of the libc
+; allocator functions (such as `malloc(0)`) which a C compiler may optimize
+; differently depending on its version and flags.
+source_filename = "heap.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Two 0-byte allocations should get distinct addresses.
+; Returns 0 iff x == 3 (if the addresses were equal, it would be x == 2)
+define i32 @malloc_zero_distinct(i32 %0) {
+  %2 = call i8* @malloc(i64 0)
+  %3 = call i8* @malloc(i64 0)
+  %4 = icmp eq i8* %2, %3
+  %5 = zext i1 %4 to i32
+  %6 = add i32 %5, %0
+  %7 = sub i32 %6, 3
+  ret i32 %7
+}
+
+; Returns 0 iff x == 3, as long as calloc() zero-initializes
+define i32 @calloc_zeroed(i32 %0) {
+  %2 = call i8* @calloc(i64 4, i64 4)
+  %3 = bitcast i8* %2 to i32*
+  %4 = getelementptr inbounds i32, i32* %3, i64 2
+  %5 = load i32, i32* %4, align 4
+  %6 = add i32 %5, %0
+  %7 = sub i32 %6, 3
+  ret i32 %7
+}
+
+; Returns 0 iff x == 3, as long as realloc() copies the old contents
+define i32 @realloc_copies(i32 %0) {
+  %2 = call i8* @malloc(i64 8)
+  %3 = bitcast i8* %2 to i32*
+  %4 = getelementptr inbounds i32, i32* %3, i64 1
+  store i32 %0, i32* %4, align 4
+  %5 = call i8* @realloc(i8* %2, i64 64)
+  %6 = bitcast i8* %5 to i32*
+  %7 = getelementptr inbounds i32, i32* %6, i64 1
+  %8 = load i32, i32* %7, align 4
+  %9 = sub i32 %8, 3
+  ret i32 %9
+}
+
+; Frees the same pointer twice iff x > 10. Always returns 0
+define i32 @double_free(i32 %0) {
+  %2 = call i8* @malloc(i64 4)
+  call void @free(i8* %2)
+  %3 = icmp sgt i32 %0, 10
+  br i1 %3, label %4, label %5
+
+4:
+  call void @free(i8* %2)
+  br label %5
+
+5:
+  ret i32 0
+}
+
+; Writes only the first half of an 8-byte allocation, then reads both halves.
+; The read of the second half reads uninitialized memory
+define i32 @malloc_partial_init(i32 %0) {
+  %2 = call i8* @malloc(i64 8)
+  %3 = bitcast i8* %2 to i32*
+  store i32 %0, i32* %3, align 4
+  %4 = load i32, i32* %3, align 4
+  %5 = getelementptr inbounds i32, i32* %3, i64 1
+  %6 = load i32, i32* %5, align 4
+  %7 = add i32 %4, %6
+  ret i32 %7
+}
+
+declare i8* @malloc(i64)
+declare i8* @calloc(i64, i64)
+declare i8* @realloc(i8*, i64)
+declare void @free(i8*)
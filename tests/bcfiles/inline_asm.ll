@@ -0,0 +1,28 @@
+; This file is written by hand, since what matters for the tests is the shape
+; of the inline assembly calls (their operands and output types), not what the
+; assembly itself does.
+source_filename = "inline_asm.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Inline assembly with two outputs, which LLVM returns as a struct
+define i32 @asm_two_outputs(i32 %0) {
+  %2 = call { i32, i32 } asm "movl $2, $0\0A\09movl $$3, $1", "=r,=r,r"(i32 %0)
+  %3 = extractvalue { i32, i32 } %2, 0
+  %4 = extractvalue { i32, i32 } %2, 1
+  %5 = sub i32 %3, %4
+  ret i32 %5
+}
+
+; `asm goto`: the asm may jump to `%error` rather than falling through to `%ok`
+define i32 @asm_goto(i32 %0) {
+  %2 = callbr i32 asm "movl $1, $0\0A\09testl $0, $0\0A\09jz ${2:l}", "=r,r,X,~{dirflag},~{fpsr},~{flags}"(i32 %0, i8* blockaddress(@asm_goto, %error))
+          to label %ok [label %error]
+
+ok:
+  %3 = add i32 %2, 1
+  ret i32 %3
+
+error:
+  ret i32 -1
+}
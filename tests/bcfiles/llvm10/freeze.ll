@@ -0,0 +1,35 @@
+; llvm10/freeze.ll is written by hand, to exercise `freeze` of vectors and structs
+; which contain undef elements, and of results which may be poison.
+source_filename = "freeze.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+define i32 @freeze_vector(i32 %x) {
+  %v0 = insertelement <4 x i32> undef, i32 %x, i32 0
+  %v1 = insertelement <4 x i32> %v0, i32 3, i32 2
+  %f = freeze <4 x i32> %v1
+  %a = extractelement <4 x i32> %f, i32 0
+  %b = extractelement <4 x i32> %f, i32 2
+  %r = add i32 %a, %b
+  ret i32 %r
+}
+
+define i32 @freeze_struct(i32 %x) {
+  %s0 = insertvalue { i32, i64 } undef, i32 %x, 0
+  %f = freeze { i32, i64 } %s0
+  %a = extractvalue { i32, i64 } %f, 0
+  %r = sub i32 %a, 7
+  ret i32 %r
+}
+
+define i32 @freeze_udiv(i32 %x, i32 %y) {
+  %q = udiv i32 %x, %y
+  %f = freeze i32 %q
+  ret i32 %f
+}
+
+define i32 @freeze_shl(i32 %x, i32 %y) {
+  %s = shl i32 %x, %y
+  %f = freeze i32 %s
+  ret i32 %f
+}
@@ -0,0 +1,19 @@
+; This file is written by hand, to get a `memset` with a symbolic length
+; without depending on how a C compiler lowers it.
+source_filename = "memset.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Fills a local 8-byte buffer with 0xFF, then zeroes the first `n` bytes of it,
+; and returns byte `i` of the buffer
+define i8 @memset_prefix(i64 %0, i64 %1) {
+  %3 = alloca [8 x i8], align 1
+  %4 = getelementptr inbounds [8 x i8], [8 x i8]* %3, i64 0, i64 0
+  call void @llvm.memset.p0i8.i64(i8* align 1 %4, i8 -1, i64 8, i1 false)
+  call void @llvm.memset.p0i8.i64(i8* align 1 %4, i8 0, i64 %0, i1 false)
+  %5 = getelementptr inbounds [8 x i8], [8 x i8]* %3, i64 0, i64 %1
+  %6 = load i8, i8* %5, align 1
+  ret i8 %6
+}
+
+declare void @llvm.memset.p0i8.i64(i8* nocapture writeonly, i8, i64, i1 immarg)
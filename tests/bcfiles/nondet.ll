@@ -0,0 +1,15 @@
+; This file is written by hand, to get a function which makes two calls to
+; `rand()` and depends on both results, as in
+;   int two_draws(void) { int a = rand(); int b = rand(); return b - a; }
+source_filename = "nondet.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+define i32 @two_draws() {
+  %a = call i32 @rand()
+  %b = call i32 @rand()
+  %r = sub nsw i32 %b, %a
+  ret i32 %r
+}
+
+declare i32 @rand()
@@ -0,0 +1,29 @@
+; This file is written by hand, to get functions whose parameters have integer
+; widths other than the standard 8/16/32/64 bits.
+source_filename = "odd_widths.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Returns 0 if and only if `flag` is true
+define i32 @bool_param(i1 zeroext %0) {
+  %2 = select i1 %0, i32 0, i32 1
+  ret i32 %2
+}
+
+; Returns 0 if and only if `x` is 2^64 + 5. (2^64 + 5 is computed rather than
+; written as a constant, because llvm-ir only keeps the low 64 bits of integer
+; constants.)
+define i32 @i128_param(i128 %0) {
+  %2 = shl i128 1, 64
+  %3 = add i128 %2, 5
+  %4 = icmp eq i128 %0, %3
+  %5 = select i1 %4, i32 0, i32 1
+  ret i32 %5
+}
+
+; Returns 0 if and only if `x` is 123456
+define i32 @i24_param(i24 %0) {
+  %2 = icmp eq i24 %0, 123456
+  %3 = select i1 %2, i32 0, i32 1
+  ret i32 %3
+}
@@ -0,0 +1,32 @@
+; This file is written by hand, since it's small and exercises the ways of
+; setting up pointer parameters (`ParameterVal`), which don't depend on any
+; particular C compiler output.
+source_filename = "params.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Returns the sum of the first three elements of the array
+define i32 @sum_array3(i32* %0) {
+  %2 = load i32, i32* %0, align 4
+  %3 = getelementptr inbounds i32, i32* %0, i64 1
+  %4 = load i32, i32* %3, align 4
+  %5 = getelementptr inbounds i32, i32* %0, i64 2
+  %6 = load i32, i32* %5, align 4
+  %7 = add i32 %2, %4
+  %8 = add i32 %7, %6
+  ret i32 %8
+}
+
+; Returns 1 if the pointer is 16-byte aligned, or 0 otherwise
+define i32 @is_aligned16(i8* %0) {
+  %2 = ptrtoint i8* %0 to i64
+  %3 = and i64 %2, 15
+  %4 = icmp eq i64 %3, 0
+  br i1 %4, label %5, label %6
+
+5:
+  ret i32 1
+
+6:
+  ret i32 0
+}
@@ -0,0 +1,19 @@
+struct Shape {
+  virtual int sides() const = 0;
+  int sides_plus(int x) const;
+};
+
+struct Triangle : Shape {
+  int sides() const override;
+};
+
+struct Square : Shape {
+  int sides() const override;
+};
+
+int Triangle::sides() const { return 3; }
+int Square::sides() const { return 4; }
+
+int Shape::sides_plus(int x) const {
+  return sides() + x;
+}
@@ -0,0 +1,37 @@
+; ModuleID = 'polymorphic.cpp'
+source_filename = "polymorphic.cpp"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+%struct.Triangle = type { %struct.Shape }
+%struct.Shape = type { i32 (...)** }
+%struct.Square = type { %struct.Shape }
+
+@_ZTV8Triangle = unnamed_addr constant { [3 x i8*] } { [3 x i8*] [i8* null, i8* null, i8* bitcast (i32 (%struct.Triangle*)* @_ZNK8Triangle5sidesEv to i8*)] }, align 8
+@_ZTV6Square = unnamed_addr constant { [3 x i8*] } { [3 x i8*] [i8* null, i8* null, i8* bitcast (i32 (%struct.Square*)* @_ZNK6Square5sidesEv to i8*)] }, align 8
+
+; Function Attrs: norecurse nounwind readnone ssp uwtable
+define i32 @_ZNK8Triangle5sidesEv(%struct.Triangle* nocapture %this) unnamed_addr #0 align 2 {
+entry:
+  ret i32 3
+}
+
+; Function Attrs: norecurse nounwind readnone ssp uwtable
+define i32 @_ZNK6Square5sidesEv(%struct.Square* nocapture %this) unnamed_addr #0 align 2 {
+entry:
+  ret i32 4
+}
+
+; Function Attrs: ssp uwtable
+define i32 @_ZNK5Shape10sides_plusEi(%struct.Shape* %this, i32 %x) local_unnamed_addr #1 align 2 {
+entry:
+  %0 = bitcast %struct.Shape* %this to i32 (%struct.Shape*)***
+  %vtable = load i32 (%struct.Shape*)**, i32 (%struct.Shape*)*** %0, align 8
+  %1 = load i32 (%struct.Shape*)*, i32 (%struct.Shape*)** %vtable, align 8
+  %call = tail call i32 %1(%struct.Shape* %this)
+  %add = add nsw i32 %call, %x
+  ret i32 %add
+}
+
+attributes #0 = { norecurse nounwind readnone ssp uwtable }
+attributes #1 = { ssp uwtable }
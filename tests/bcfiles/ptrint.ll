@@ -0,0 +1,96 @@
+; This file is written by hand, since it needs specific combinations of
+; `ptrtoint`/`inttoptr` and integer widths which a C compiler wouldn't
+; reliably produce.
+source_filename = "ptrint.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+@global = global i32 3, align 4
+
+; Stores a pointer to memory as a pointer-sized integer, loads it back, and
+; dereferences it. Returns 0 iff x == 3
+define i32 @roundtrip_through_memory(i32 %0) {
+  %2 = alloca i32, align 4
+  store i32 %0, i32* %2, align 4
+  %3 = alloca i64, align 8
+  %4 = ptrtoint i32* %2 to i64
+  store i64 %4, i64* %3, align 8
+  %5 = load i64, i64* %3, align 8
+  %6 = inttoptr i64 %5 to i32*
+  %7 = load i32, i32* %6, align 4
+  %8 = sub i32 %7, 3
+  ret i32 %8
+}
+
+; Like the above, but with a tag in the low bit of the stored integer, as in
+; a tagged union. Returns 0 iff x == 3
+define i32 @roundtrip_low_tag(i32 %0) {
+  %2 = alloca i32, align 4
+  store i32 %0, i32* %2, align 4
+  %3 = alloca i64, align 8
+  %4 = ptrtoint i32* %2 to i64
+  %5 = or i64 %4, 1
+  store i64 %5, i64* %3, align 8
+  %6 = load i64, i64* %3, align 8
+  %7 = and i64 %6, -2
+  %8 = inttoptr i64 %7 to i32*
+  %9 = load i32, i32* %8, align 4
+  %10 = sub i32 %9, 3
+  ret i32 %10
+}
+
+; Stores the pointer in an integer wider than a pointer, with a tag in the
+; upper bits; `inttoptr` truncates the tag away. Returns 0 iff x == 3
+define i32 @roundtrip_wide(i32 %0) {
+  %2 = alloca i32, align 4
+  store i32 %0, i32* %2, align 4
+  %3 = alloca i128, align 16
+  %4 = ptrtoint i32* %2 to i128
+  %5 = or i128 %4, 18446744073709551616
+  store i128 %5, i128* %3, align 16
+  %6 = load i128, i128* %3, align 16
+  %7 = inttoptr i128 %6 to i32*
+  %8 = load i32, i32* %7, align 4
+  %9 = sub i32 %8, 3
+  ret i32 %9
+}
+
+; Stores the pointer in an integer narrower than a pointer. This is only a
+; faithful round-trip if the address fits in 32 bits. Returns 0 iff x == 3
+define i32 @roundtrip_narrow(i32 %0) {
+  %2 = alloca i32, align 4
+  store i32 %0, i32* %2, align 4
+  %3 = alloca i32, align 4
+  %4 = ptrtoint i32* %2 to i32
+  store i32 %4, i32* %3, align 4
+  %5 = load i32, i32* %3, align 4
+  %6 = inttoptr i32 %5 to i32*
+  %7 = load i32, i32* %6, align 4
+  %8 = sub i32 %7, 3
+  ret i32 %8
+}
+
+; Round-trips a vector of pointers through a vector of wider integers.
+; Returns 0 iff x == 3
+define i32 @roundtrip_vector(i32 %0) {
+  %2 = alloca i32, align 4
+  store i32 %0, i32* %2, align 4
+  %3 = alloca i32, align 4
+  store i32 7, i32* %3, align 4
+  %4 = insertelement <2 x i32*> undef, i32* %3, i32 0
+  %5 = insertelement <2 x i32*> %4, i32* %2, i32 1
+  %6 = ptrtoint <2 x i32*> %5 to <2 x i128>
+  %7 = inttoptr <2 x i128> %6 to <2 x i32*>
+  %8 = extractelement <2 x i32*> %7, i32 1
+  %9 = load i32, i32* %8, align 4
+  %10 = sub i32 %9, 3
+  ret i32 %10
+}
+
+; Like `roundtrip_narrow`, but with constant expressions.
+; Returns 0 iff x == 3
+define i32 @roundtrip_constant(i32 %0) {
+  %2 = load i32, i32* inttoptr (i32 ptrtoint (i32* @global to i32) to i32*), align 4
+  %3 = sub i32 %2, %0
+  ret i32 %3
+}
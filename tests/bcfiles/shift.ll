@@ -0,0 +1,38 @@
+; This file is written by hand, to get shifts whose shift amounts may be at
+; least the bitwidth.
+source_filename = "shift.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+define i32 @shl_by(i32 %x, i32 %n) {
+  %r = shl i32 %x, %n
+  ret i32 %r
+}
+
+define i32 @ashr_by(i32 %x, i32 %n) {
+  %r = ashr i32 %x, %n
+  ret i32 %r
+}
+
+; shifts `x` left by `n` in one element and by 4 in the other, and returns the
+; sum of the elements
+define i32 @shl_vector(i32 %x, i32 %n) {
+  %x0 = insertelement <2 x i32> undef, i32 %x, i32 0
+  %x1 = insertelement <2 x i32> %x0, i32 %x, i32 1
+  %n0 = insertelement <2 x i32> undef, i32 %n, i32 0
+  %n1 = insertelement <2 x i32> %n0, i32 4, i32 1
+  %s = shl <2 x i32> %x1, %n1
+  %a = extractelement <2 x i32> %s, i32 0
+  %b = extractelement <2 x i32> %s, i32 1
+  %r = add i32 %a, %b
+  ret i32 %r
+}
+
+; shifts the low byte of `x` left by `n` as an i8, and zero-extends the result
+define i32 @shl_i8_by(i32 %x, i32 %n) {
+  %x8 = trunc i32 %x to i8
+  %n8 = trunc i32 %n to i8
+  %s = shl i8 %x8, %n8
+  %r = zext i8 %s to i32
+  ret i32 %r
+}
@@ -0,0 +1,149 @@
+; This file is written by hand, so that the string functions stay actual calls
+; rather than being optimized away for strings with known contents.
+source_filename = "strings.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; The string is "a", c, "b", so its length is 1 if c is NUL, or 3 otherwise.
+; Returns 0 iff c == 0
+define i64 @strlen_local(i8 %0) {
+  %2 = alloca [4 x i8], align 1
+  %3 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 0
+  store i8 97, i8* %3, align 1
+  %4 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 1
+  store i8 %0, i8* %4, align 1
+  %5 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 2
+  store i8 98, i8* %5, align 1
+  %6 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 3
+  store i8 0, i8* %6, align 1
+  %7 = call i64 @strlen(i8* %3)
+  %8 = sub i64 %7, 1
+  ret i64 %8
+}
+
+; Returns the length of the given string
+define i64 @strlen_param(i8* %0) {
+  %2 = call i64 @strlen(i8* %0)
+  ret i64 %2
+}
+
+; Compares [1, c, 3] with [1, 5, 3]. Returns 1 if the sign of the result
+; matches the comparison of c with 5 (as unsigned), or 0 if not
+define i32 @memcmp_sign(i8 %0) {
+  %2 = alloca [3 x i8], align 1
+  %3 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 0
+  store i8 1, i8* %3, align 1
+  %4 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 1
+  store i8 %0, i8* %4, align 1
+  %5 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 2
+  store i8 3, i8* %5, align 1
+  %6 = alloca [3 x i8], align 1
+  %7 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 0
+  store i8 1, i8* %7, align 1
+  %8 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 1
+  store i8 5, i8* %8, align 1
+  %9 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 2
+  store i8 3, i8* %9, align 1
+  %10 = call i32 @memcmp(i8* %3, i8* %7, i64 3)
+  %11 = icmp slt i32 %10, 0
+  %12 = icmp ult i8 %0, 5
+  %13 = icmp eq i1 %11, %12
+  %14 = icmp sgt i32 %10, 0
+  %15 = icmp ugt i8 %0, 5
+  %16 = icmp eq i1 %14, %15
+  %17 = and i1 %13, %16
+  %18 = zext i1 %17 to i32
+  ret i32 %18
+}
+
+; Compares "ab" with "a", c. Returns 1 if the sign of the result matches the
+; comparison of 'b' with c (as unsigned), or 0 if not. (In particular, if c is
+; NUL, the second string is a prefix of the first, so the result is positive)
+define i32 @strcmp_sign(i8 %0) {
+  %2 = alloca [3 x i8], align 1
+  %3 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 0
+  store i8 97, i8* %3, align 1
+  %4 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 1
+  store i8 98, i8* %4, align 1
+  %5 = getelementptr inbounds [3 x i8], [3 x i8]* %2, i64 0, i64 2
+  store i8 0, i8* %5, align 1
+  %6 = alloca [3 x i8], align 1
+  %7 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 0
+  store i8 97, i8* %7, align 1
+  %8 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 1
+  store i8 %0, i8* %8, align 1
+  %9 = getelementptr inbounds [3 x i8], [3 x i8]* %6, i64 0, i64 2
+  store i8 0, i8* %9, align 1
+  %10 = call i32 @strcmp(i8* %3, i8* %7)
+  %11 = icmp slt i32 %10, 0
+  %12 = icmp ult i8 98, %0
+  %13 = icmp eq i1 %11, %12
+  %14 = icmp sgt i32 %10, 0
+  %15 = icmp ugt i8 98, %0
+  %16 = icmp eq i1 %14, %15
+  %17 = and i1 %13, %16
+  %18 = zext i1 %17 to i32
+  ret i32 %18
+}
+
+; Compares the given strings
+define i32 @strcmp_params(i8* %0, i8* %1) {
+  %3 = call i32 @strcmp(i8* %0, i8* %1)
+  ret i32 %3
+}
+
+; Compares the first n bytes of "abc" and "abd". Returns 0 iff n <= 2
+define i32 @strncmp_len(i64 %0) {
+  %2 = alloca [4 x i8], align 1
+  %3 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 0
+  store i8 97, i8* %3, align 1
+  %4 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 1
+  store i8 98, i8* %4, align 1
+  %5 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 2
+  store i8 99, i8* %5, align 1
+  %6 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 3
+  store i8 0, i8* %6, align 1
+  %7 = alloca [4 x i8], align 1
+  %8 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 0
+  store i8 97, i8* %8, align 1
+  %9 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 1
+  store i8 98, i8* %9, align 1
+  %10 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 2
+  store i8 100, i8* %10, align 1
+  %11 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 3
+  store i8 0, i8* %11, align 1
+  %12 = call i32 @strncmp(i8* %3, i8* %8, i64 %0)
+  ret i32 %12
+}
+
+; Compares the first n bytes of [1, 2, 3, 4] and [1, 2, 3, 5]. Returns 0 iff
+; the result is zero, i.e., iff n <= 3
+define i32 @memcmp_len(i64 %0) {
+  %2 = alloca [4 x i8], align 1
+  %3 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 0
+  store i8 1, i8* %3, align 1
+  %4 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 1
+  store i8 2, i8* %4, align 1
+  %5 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 2
+  store i8 3, i8* %5, align 1
+  %6 = getelementptr inbounds [4 x i8], [4 x i8]* %2, i64 0, i64 3
+  store i8 4, i8* %6, align 1
+  %7 = alloca [4 x i8], align 1
+  %8 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 0
+  store i8 1, i8* %8, align 1
+  %9 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 1
+  store i8 2, i8* %9, align 1
+  %10 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 2
+  store i8 3, i8* %10, align 1
+  %11 = getelementptr inbounds [4 x i8], [4 x i8]* %7, i64 0, i64 3
+  store i8 5, i8* %11, align 1
+  %12 = call i32 @memcmp(i8* %3, i8* %8, i64 %0)
+  %13 = icmp ne i32 %12, 0
+  %14 = zext i1 %13 to i32
+  ret i32 %14
+}
+
+declare i64 @strlen(i8*)
+declare i32 @memcmp(i8*, i8*, i64)
+declare i32 @strcmp(i8*, i8*)
+declare i32 @strncmp(i8*, i8*, i64)
@@ -0,0 +1,15 @@
+; This file is written by hand, to get a load from a symbolic address whose
+; possible values are easy to reason about.
+source_filename = "symbolic_address.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+@table = global [4 x i32] [i32 10, i32 20, i32 30, i32 40], align 4
+
+; Returns element `i % 4` of a global table
+define i32 @table_lookup(i64 %0) {
+  %2 = and i64 %0, 3
+  %3 = getelementptr inbounds [4 x i32], [4 x i32]* @table, i64 0, i64 %2
+  %4 = load i32, i32* %3, align 4
+  ret i32 %4
+}
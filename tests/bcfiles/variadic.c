@@ -0,0 +1,16 @@
+#include <stdarg.h>
+
+__attribute__((noinline)) int sum_ints(int count, ...) {
+  va_list ap;
+  va_start(ap, count);
+  int sum = 0;
+  for (int i = 0; i < count; i++) {
+    sum += va_arg(ap, int);
+  }
+  va_end(ap);
+  return sum;
+}
+
+int variadic_caller(int x) {
+  return sum_ints(3, x, x + 1, 10) - 23;
+}
@@ -0,0 +1,77 @@
+; ModuleID = 'variadic.c'
+source_filename = "variadic.c"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+%struct.__va_list_tag = type { i32, i32, i8*, i8* }
+
+; Function Attrs: noinline nounwind ssp uwtable
+define i32 @sum_ints(i32 %count, ...) local_unnamed_addr #0 {
+entry:
+  %ap = alloca [1 x %struct.__va_list_tag], align 16
+  %ap.i8 = bitcast [1 x %struct.__va_list_tag]* %ap to i8*
+  call void @llvm.va_start(i8* nonnull %ap.i8)
+  %cmp = icmp sgt i32 %count, 0
+  br i1 %cmp, label %for.body.lr.ph, label %for.end
+
+for.body.lr.ph:                                   ; preds = %entry
+  %gp_offset_p = getelementptr inbounds [1 x %struct.__va_list_tag], [1 x %struct.__va_list_tag]* %ap, i64 0, i64 0, i32 0
+  %reg_save_area_p = getelementptr inbounds [1 x %struct.__va_list_tag], [1 x %struct.__va_list_tag]* %ap, i64 0, i64 0, i32 3
+  %overflow_arg_area_p = getelementptr inbounds [1 x %struct.__va_list_tag], [1 x %struct.__va_list_tag]* %ap, i64 0, i64 0, i32 2
+  br label %for.body
+
+for.body:                                         ; preds = %vaarg.end, %for.body.lr.ph
+  %i = phi i32 [ 0, %for.body.lr.ph ], [ %inc, %vaarg.end ]
+  %sum = phi i32 [ 0, %for.body.lr.ph ], [ %add, %vaarg.end ]
+  %gp_offset = load i32, i32* %gp_offset_p, align 16
+  %fits_in_gp = icmp ult i32 %gp_offset, 41
+  br i1 %fits_in_gp, label %vaarg.in_reg, label %vaarg.in_mem
+
+vaarg.in_reg:                                     ; preds = %for.body
+  %reg_save_area = load i8*, i8** %reg_save_area_p, align 16
+  %gp_offset.ext = zext i32 %gp_offset to i64
+  %reg_addr = getelementptr i8, i8* %reg_save_area, i64 %gp_offset.ext
+  %reg_addr.i32 = bitcast i8* %reg_addr to i32*
+  %gp_offset.next = add nuw nsw i32 %gp_offset, 8
+  store i32 %gp_offset.next, i32* %gp_offset_p, align 16
+  br label %vaarg.end
+
+vaarg.in_mem:                                     ; preds = %for.body
+  %overflow_arg_area = load i8*, i8** %overflow_arg_area_p, align 8
+  %mem_addr.i32 = bitcast i8* %overflow_arg_area to i32*
+  %overflow_arg_area.next = getelementptr i8, i8* %overflow_arg_area, i64 8
+  store i8* %overflow_arg_area.next, i8** %overflow_arg_area_p, align 8
+  br label %vaarg.end
+
+vaarg.end:                                        ; preds = %vaarg.in_mem, %vaarg.in_reg
+  %vaarg.addr = phi i32* [ %reg_addr.i32, %vaarg.in_reg ], [ %mem_addr.i32, %vaarg.in_mem ]
+  %arg = load i32, i32* %vaarg.addr, align 4
+  %add = add nsw i32 %arg, %sum
+  %inc = add nuw nsw i32 %i, 1
+  %exitcond = icmp eq i32 %inc, %count
+  br i1 %exitcond, label %for.end, label %for.body
+
+for.end:                                          ; preds = %vaarg.end, %entry
+  %sum.final = phi i32 [ 0, %entry ], [ %add, %vaarg.end ]
+  call void @llvm.va_end(i8* nonnull %ap.i8)
+  ret i32 %sum.final
+}
+
+; Function Attrs: nounwind
+declare void @llvm.va_start(i8*) #1
+
+; Function Attrs: nounwind
+declare void @llvm.va_end(i8*) #1
+
+; Function Attrs: nounwind ssp uwtable
+define i32 @variadic_caller(i32 %x) local_unnamed_addr #2 {
+entry:
+  %add = add nsw i32 %x, 1
+  %call = tail call i32 (i32, ...) @sum_ints(i32 3, i32 %x, i32 %add, i32 10)
+  %sub = add nsw i32 %call, -23
+  ret i32 %sub
+}
+
+attributes #0 = { noinline nounwind ssp uwtable }
+attributes #1 = { nounwind }
+attributes #2 = { nounwind ssp uwtable }
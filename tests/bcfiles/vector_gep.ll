@@ -0,0 +1,52 @@
+; This file is written by hand, to get GEPs calculating vectors of pointers,
+; like those in autovectorized gather/scatter loops.
+source_filename = "vector_gep.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+%struct.Pair = type { i32, i64 }
+
+; Stores 10, 20, 30, 40 to an array, then uses a scalar base address with a
+; vector of indices <3, 2, 1, 0> to get the addresses of its elements in
+; reverse order. Returns (element at lane 0) - (element at lane 3), i.e. 30.
+define i32 @vector_indices() {
+  %1 = alloca [4 x i32], align 16
+  %2 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i64 0, i64 0
+  store i32 10, i32* %2, align 16
+  %3 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i64 0, i64 1
+  store i32 20, i32* %3, align 4
+  %4 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i64 0, i64 2
+  store i32 30, i32* %4, align 8
+  %5 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i64 0, i64 3
+  store i32 40, i32* %5, align 4
+  %6 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i64 0, <4 x i64> <i64 3, i64 2, i64 1, i64 0>
+  %7 = extractelement <4 x i32*> %6, i32 0
+  %8 = load i32, i32* %7, align 4
+  %9 = extractelement <4 x i32*> %6, i32 3
+  %10 = load i32, i32* %9, align 4
+  %11 = sub i32 %8, %10
+  ret i32 %11
+}
+
+; Uses a vector of two pointers to structs as the base address, with a
+; (splat) vector index into the struct type, to get the addresses of the
+; second field of each struct. Stores 7 and 5 through those addresses, and
+; returns (first struct's field) * 10 + (second struct's field), i.e. 75.
+define i64 @vector_base() {
+  %1 = alloca %struct.Pair, align 8
+  %2 = alloca %struct.Pair, align 8
+  %3 = insertelement <2 x %struct.Pair*> undef, %struct.Pair* %1, i32 0
+  %4 = insertelement <2 x %struct.Pair*> %3, %struct.Pair* %2, i32 1
+  %5 = getelementptr inbounds %struct.Pair, <2 x %struct.Pair*> %4, i64 0, <2 x i32> <i32 1, i32 1>
+  %6 = extractelement <2 x i64*> %5, i32 0
+  store i64 7, i64* %6, align 8
+  %7 = extractelement <2 x i64*> %5, i32 1
+  store i64 5, i64* %7, align 8
+  %8 = getelementptr inbounds %struct.Pair, %struct.Pair* %1, i64 0, i32 1
+  %9 = load i64, i64* %8, align 8
+  %10 = getelementptr inbounds %struct.Pair, %struct.Pair* %2, i64 0, i32 1
+  %11 = load i64, i64* %10, align 8
+  %12 = mul i64 %9, 10
+  %13 = add i64 %12, %11
+  ret i64 %13
+}
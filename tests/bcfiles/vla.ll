@@ -0,0 +1,27 @@
+; This file is written by hand, to get variable-length `alloca`s without
+; depending on how a C compiler lowers VLAs.
+source_filename = "vla.ll"
+target datalayout = "e-m:o-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-apple-macosx10.15.0"
+
+; Allocates a variable-length array of `n % 8` i32s, stores `x` to its last
+; element, and loads it back
+define i32 @vla_bounded(i32 %0, i32 %1) {
+  %3 = and i32 %0, 7
+  %4 = alloca i32, i32 %3, align 16
+  %5 = add i32 %3, -1
+  %6 = sext i32 %5 to i64
+  %7 = getelementptr inbounds i32, i32* %4, i64 %6
+  store i32 %1, i32* %7, align 4
+  %8 = load i32, i32* %7, align 4
+  ret i32 %8
+}
+
+; Allocates a variable-length array of `n` i32s, where `n` is unbounded,
+; stores `x` to its first element, and loads it back
+define i32 @vla_unbounded(i64 %0, i32 %1) {
+  %3 = alloca i32, i64 %0, align 16
+  store i32 %1, i32* %3, align 4
+  %4 = load i32, i32* %3, align 4
+  ret i32 %4
+}
@@ -0,0 +1,171 @@
+use haybale::backend::DefaultBackend;
+use haybale::config::NullPointerChecking;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/bounds.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+/// Returns the results of all paths through the function, ignoring return values
+fn all_results<'p>(
+    funcname: &str,
+    proj: &'p Project,
+    config: Config<'p, DefaultBackend>,
+) -> Vec<Result<()>> {
+    let em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, proj, config, None).unwrap();
+    em.map(|res| res.map(|_| ())).collect()
+}
+
+#[test]
+fn array_index() {
+    let funcname = "array_index";
+    init_logging();
+    let proj = get_project();
+
+    // by default, any index is fine, other than those which make the address
+    // null
+    let mut config = Config::default();
+    config.null_pointer_checking = NullPointerChecking::None;
+    assert_eq!(all_results(funcname, &proj, config), vec![Ok(())]);
+
+    // with bounds checks, the store may be out of bounds; the in-bounds case
+    // is explored afterwards
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.check_memory_bounds = true;
+    let results = all_results(funcname, &proj, config);
+    assert_eq!(results.len(), 2);
+    match &results[0] {
+        Err(Error::PossibleOutOfBoundsAccess {
+            address,
+            allocation_start,
+            allocation_size,
+            ..
+        }) => {
+            assert_eq!(*allocation_size, 16);
+            assert!(
+                *address < *allocation_start || *address > *allocation_start + 12,
+                "expected an out-of-bounds address, got 0x{:x} for the allocation at 0x{:x}",
+                address,
+                allocation_start
+            );
+        },
+        res => panic!("Expected an out-of-bounds error, got {:?}", res),
+    }
+    assert_eq!(results[1], Ok(()));
+}
+
+#[test]
+fn struct_overread() {
+    let funcname = "struct_overread";
+    init_logging();
+    let proj = get_project();
+    assert_eq!(all_results(funcname, &proj, Config::default()).len(), 1);
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.check_memory_bounds = true;
+    let results = all_results(funcname, &proj, config);
+    match results.as_slice() {
+        [Err(Error::PossibleOutOfBoundsAccess {
+            address,
+            allocation_start,
+            allocation_size,
+            ..
+        })] => {
+            assert_eq!(*allocation_size, 8);
+            assert_eq!(*address, *allocation_start + 4);
+        },
+        res => panic!("Expected exactly one out-of-bounds error, got {:?}", res),
+    }
+}
+
+#[test]
+fn global_past_end() {
+    let funcname = "global_past_end";
+    init_logging();
+    let proj = get_project();
+    assert_eq!(all_results(funcname, &proj, Config::default()).len(), 1);
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.check_memory_bounds = true;
+    let results = all_results(funcname, &proj, config);
+    match results.as_slice() {
+        [Err(Error::PossibleOutOfBoundsAccess {
+            address,
+            allocation_start,
+            allocation_size,
+            ..
+        })] => {
+            assert_eq!(*allocation_size, 8);
+            assert_eq!(*address, *allocation_start + 8);
+        },
+        res => panic!("Expected exactly one out-of-bounds error, got {:?}", res),
+    }
+}
+
+#[test]
+fn buffer_third() {
+    let funcname = "buffer_third";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.check_memory_bounds = true;
+
+    // without a length parameter, all 4 allocated elements are in bounds
+    let params = vec![
+        ParameterVal::Buffer {
+            max_len: 4,
+            len_param_index: None,
+        },
+        ParameterVal::Unconstrained,
+    ];
+    let em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), Some(params)).unwrap();
+    let results: Vec<Result<()>> = em.map(|res| res.map(|_| ())).collect();
+    assert_eq!(results, vec![Ok(())]);
+
+    // with a length parameter, the third element is only in bounds if the
+    // length is at least 3
+    let params = vec![
+        ParameterVal::Buffer {
+            max_len: 4,
+            len_param_index: Some(1),
+        },
+        ParameterVal::Unconstrained,
+    ];
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, Some(params)).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleOutOfBoundsAccess {
+            address,
+            allocation_start,
+            allocation_size,
+            ..
+        }) => {
+            assert_eq!(allocation_size, 16);
+            assert_eq!(address, allocation_start + 8);
+        },
+        res => panic!("Expected an out-of-bounds error, got {:?}", res),
+    }
+    match em.next().expect("Expected a second path") {
+        Ok(_) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    let len = em.param_bvs()[1].clone();
+    let state = em.state();
+    let len_can_be = |n| {
+        state
+            .bvs_can_be_equal(&len, &state.bv_from_u64(n, 64))
+            .unwrap()
+    };
+    assert!(!len_can_be(2));
+    assert!(len_can_be(3));
+    assert!(len_can_be(4));
+    assert!(!len_can_be(5)); // the length is at most `max_len`
+    assert!(em.next().is_none());
+}
@@ -1,5 +1,9 @@
+use haybale::backend::{Backend, DefaultBackend, BV};
+use haybale::config::MissingFunctionBehavior;
 use haybale::*;
+use std::cell::RefCell;
 use std::num::Wrapping;
+use std::rc::Rc;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -39,6 +43,146 @@ fn cross_module_simple_call() {
     assert_eq!(args[0], SolutionValue::I32(3));
 }
 
+#[test]
+fn missing_callee_is_not_recoverable() {
+    // without call.bc, the callee is not defined anywhere
+    let modname = "tests/bcfiles/crossmod.bc";
+    let funcname = "cross_module_simple_caller";
+    init_logging();
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(e @ Error::FunctionNotFound(_)) => assert!(!e.is_recoverable()),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        Ok(retval) => panic!("Expected an error, got {:?}", retval),
+    }
+}
+
+#[test]
+fn on_missing_function() {
+    // without call.bc, the callee is not defined anywhere
+    let modname = "tests/bcfiles/crossmod.bc";
+    let funcname = "cross_module_simple_caller";
+    init_logging();
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+
+    // the call can return an unconstrained value, and is havoc'd
+    let mut config = Config::default();
+    config.on_missing_function = MissingFunctionBehavior::ReturnUnconstrained;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(_)) => {},
+        Ok(retval) => panic!("Expected a return value, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert_eq!(em.state().havoced_calls().len(), 1);
+
+    // or can abort
+    let mut config = Config::default();
+    config.on_missing_function = MissingFunctionBehavior::Abort;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Abort) => {},
+        Ok(retval) => panic!("Expected an abort, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+}
+
+#[test]
+fn havoced_calls() {
+    let funcname = "simple_caller";
+    init_logging();
+    let proj = get_project();
+
+    // normally, the callee is analyzed, so nothing is havoc'd
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.state().havoced_calls().is_empty());
+
+    // with `max_callstack_depth` of 0, the call to the callee is havoc'd
+    let mut config = Config::default();
+    config.max_callstack_depth = Some(0);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let havoced = em.state().havoced_calls();
+    assert_eq!(havoced.len(), 1);
+    assert_eq!(havoced[0].0.func.name, "simple_caller");
+    assert_eq!(havoced[0].1, "simple_callee");
+
+    // without call.bc, the callee has no definition, so the call is havoc'd by the default hook
+    let modname = "tests/bcfiles/crossmod.bc";
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add_default_hook(&function_hooks::generic_stub_hook);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("cross_module_simple_caller", &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let havoced = em.state().havoced_calls();
+    assert_eq!(havoced.len(), 1);
+    assert_eq!(havoced[0].0.func.name, "cross_module_simple_caller");
+    assert_eq!(havoced[0].1, "simple_callee");
+}
+
+#[test]
+fn truncated_calls() {
+    let funcname = "simple_caller";
+    init_logging();
+    let proj = get_project();
+
+    // normally, the callee is entered
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.state().truncated_calls().is_empty());
+
+    // with `max_callstack_depth` of 0, the call to the callee is truncated
+    let mut config = Config::default();
+    config.max_callstack_depth = Some(0);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let truncated = em.state().truncated_calls();
+    assert_eq!(truncated.len(), 1);
+    assert_eq!(truncated[0].0.func.name, "simple_caller");
+    assert_eq!(truncated[0].1, "simple_callee");
+
+    // calls havoc'd by the default hook aren't truncated
+    let modname = "tests/bcfiles/crossmod.bc";
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add_default_hook(&function_hooks::generic_stub_hook);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("cross_module_simple_caller", &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(em.state().havoced_calls().len(), 1);
+    assert!(em.state().truncated_calls().is_empty());
+}
+
 #[test]
 fn conditional_call() {
     let funcname = "conditional_caller";
@@ -94,6 +238,84 @@ fn nested_call() {
     assert_eq!((x + y).0, 3);
 }
 
+#[test]
+fn call_and_return_callbacks() {
+    let funcname = "nested_caller";
+    init_logging();
+    let proj = get_project();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut config = Config::default();
+    let call_events = events.clone();
+    config.callbacks.add_call_callback(move |func, _state| {
+        call_events
+            .borrow_mut()
+            .push(format!("enter {}", func.name));
+        Ok(())
+    });
+    let return_events = events.clone();
+    config.callbacks.add_return_callback(move |func, _state| {
+        return_events
+            .borrow_mut()
+            .push(format!("leave {}", func.name));
+        Ok(())
+    });
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", em.state().full_error_message_with_context(e)));
+    assert_eq!(
+        *events.borrow(),
+        vec![
+            "enter simple_caller",
+            "enter simple_callee",
+            "leave simple_callee",
+            "leave simple_caller",
+        ],
+    );
+}
+
+#[test]
+fn tail_calls_reuse_frame() {
+    let funcname = "nested_caller";
+    init_logging();
+    let proj = get_project();
+
+    // `nested_caller()` tail-calls `simple_caller()`, which tail-calls
+    // `simple_callee()`. With frame reuse, neither call grows the callstack, so
+    // even with `max_callstack_depth` of 0, nothing is havoc'd
+    let mut config = Config::default();
+    config.max_callstack_depth = Some(0);
+    config.reuse_frames_for_tail_calls = true;
+    let depths = Rc::new(RefCell::new(Vec::new()));
+    let depths_clone = depths.clone();
+    config.callbacks.add_call_callback(move |_func, state| {
+        depths_clone
+            .borrow_mut()
+            .push(state.current_callstack_depth());
+        Ok(())
+    });
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", em.state().full_error_message_with_context(e)));
+    assert!(em.state().havoced_calls().is_empty());
+    assert_eq!(*depths.borrow(), vec![0, 0]);
+
+    // and the callee's return value is correctly returned from `nested_caller()`
+    let mut config = Config::default();
+    config.max_callstack_depth = Some(0);
+    config.reuse_frames_for_tail_calls = true;
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 2);
+    let x = Wrapping(args[0].unwrap_to_i32());
+    let y = Wrapping(args[1].unwrap_to_i32());
+    assert_eq!((x + y).0, 3);
+}
+
 #[test]
 fn cross_module_nested_near_call() {
     let callee_modname = "tests/bcfiles/call.bc";
@@ -178,6 +400,35 @@ fn recursive_simple_dummy(x: Wrapping<i32>) -> Wrapping<i32> {
     }
 }
 
+#[test]
+fn recursion_bound() {
+    let funcname = "recursive_simple";
+    init_logging();
+    let proj = get_project();
+
+    // finding the zero requires one level of recursion
+    let mut config = Config::default();
+    config.max_recursion_depth = Some(1);
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args, vec![SolutionValue::I32(11)]);
+
+    // so disallowing recursion entirely should mean there is no zero
+    let mut config = Config::default();
+    config.max_recursion_depth = Some(0);
+    let args = find_zero_of_func(funcname, &proj, config, None).unwrap_or_else(|r| panic!("{}", r));
+    assert_eq!(args, None);
+
+    // non-recursive calls should be unaffected
+    let mut config = Config::default();
+    config.max_recursion_depth = Some(0);
+    let args = find_zero_of_func("nested_caller", &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 2);
+}
+
 #[test]
 fn recursive_double() {
     let funcname = "recursive_double";
@@ -241,6 +492,20 @@ fn mutually_recursive_functions() {
     //assert_eq!(args[0], SolutionValue::I32(3))
 }
 
+#[test]
+fn variadic_call() {
+    let modname = "tests/bcfiles/variadic.bc";
+    let funcname = "variadic_caller";
+    init_logging();
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(6)); // x + (x + 1) + 10 == 23
+}
+
 #[test]
 fn test_pretty_path_llvm_instructions() {
     let funcname = "nested_caller";
@@ -264,3 +529,36 @@ fn test_pretty_path_llvm_instructions() {
     assert_eq!(instrs, actual_instrs,);
     assert!(em.next().is_none(), "Expected only one path");
 }
+
+/// Turns any return value which could be 0 into an `Abort`
+fn abort_if_maybe_zero<B: Backend>(
+    state: &mut State<B>,
+    retval: ReturnValue<B::BV>,
+) -> Result<ReturnValue<B::BV>> {
+    match retval {
+        ReturnValue::Return(bv) => {
+            if state.bvs_can_be_equal(&bv, &state.zero(bv.get_width()))? {
+                Ok(ReturnValue::Abort)
+            } else {
+                Ok(ReturnValue::Return(bv))
+            }
+        },
+        retval => Ok(retval),
+    }
+}
+
+#[test]
+fn return_value_transform() {
+    let funcname = "simple_caller";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.return_value_transform = Some(Rc::new(abort_if_maybe_zero));
+    let mut em = symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Abort) => {},
+        Ok(retval) => panic!("Expected an Abort, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.next().is_none(), "Expected only one path");
+}
@@ -0,0 +1,64 @@
+use haybale::backend::DefaultBackend;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/debuginfo.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+/// Run `debuginfo` with the given concrete argument, and return the
+/// `source_trace()` of the single path
+fn source_trace_for_arg(arg: u64) -> Vec<String> {
+    let proj = get_project();
+    let mut em: ExecutionManager<DefaultBackend> = symex_function(
+        "debuginfo",
+        &proj,
+        Config::default(),
+        Some(vec![ParameterVal::ExactValue(arg)]),
+    )
+    .unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(_)) => {},
+        Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    let trace = em.state().source_trace();
+    assert!(em.next().is_none(), "Expected only one path");
+    trace
+}
+
+#[test]
+fn source_trace() {
+    init_logging();
+
+    // positive argument: both the comparison (line 2) and the add (line 3)
+    // are executed, and repeated locations on the same line are collapsed
+    assert_eq!(
+        source_trace_for_arg(5),
+        vec!["debuginfo.c:2".to_owned(), "debuginfo.c:3".to_owned()]
+    );
+
+    // nonpositive argument: the final `ret` has no debug location, so only
+    // the comparison shows up
+    assert_eq!(source_trace_for_arg(0), vec!["debuginfo.c:2".to_owned()]);
+}
+
+#[test]
+fn source_trace_without_debuginfo() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/basic.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("no_args_zero", &proj, Config::default(), None).unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(_) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.state().source_trace().is_empty());
+}
@@ -0,0 +1,90 @@
+#![cfg(not(feature = "llvm-9"))] // The `freeze` instruction was introduced in LLVM 10
+
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/llvm10/freeze.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn freeze_vector() {
+    let funcname = "freeze_vector";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(-3));
+}
+
+#[test]
+fn freeze_struct() {
+    let funcname = "freeze_struct";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(7));
+}
+
+#[test]
+fn freeze_poison() {
+    init_logging();
+    let proj = get_project();
+    let possible_rets = |funcname: &str, x: u64, y: u64, model_poison: bool| {
+        let mut config = Config::default();
+        config.model_poison = model_poison;
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            config,
+            Some(vec![
+                ParameterVal::ExactValue(x),
+                ParameterVal::ExactValue(y),
+            ]),
+            None,
+            2,
+        )
+    };
+
+    // operands which don't produce poison are unaffected
+    assert_eq!(
+        possible_rets("freeze_udiv", 10, 2, true),
+        PossibleSolutions::exactly_one(ReturnValue::Return(5)),
+    );
+    assert_eq!(
+        possible_rets("freeze_shl", 1, 4, true),
+        PossibleSolutions::exactly_one(ReturnValue::Return(16)),
+    );
+
+    // by default, `freeze` of poison is just whatever value we computed for it
+    assert_eq!(
+        possible_rets("freeze_udiv", 10, 0, false),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0xFFFF_FFFF)),
+    );
+    assert_eq!(
+        possible_rets("freeze_shl", 1, 40, false),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0)),
+    );
+
+    // but with `model_poison`, it's an arbitrary value
+    match possible_rets("freeze_udiv", 10, 0, true) {
+        PossibleSolutions::AtLeast(_) => {},
+        rets => panic!("Expected an arbitrary return value, got {:?}", rets),
+    }
+    match possible_rets("freeze_shl", 1, 40, true) {
+        PossibleSolutions::AtLeast(_) => {},
+        rets => panic!("Expected an arbitrary return value, got {:?}", rets),
+    }
+}
@@ -1,5 +1,7 @@
+use haybale::backend::DefaultBackend;
 use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
+use std::rc::Rc;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -32,3 +34,150 @@ fn call_through_function_ptr_struct() {
         PossibleSolutions::exactly_one(ReturnValue::Return(15)),
     );
 }
+
+#[test]
+fn virtual_call_on_polymorphic_object() {
+    let modname = "tests/bcfiles/polymorphic.bc";
+    let funcname = "_ZNK5Shape10sides_plusEi";
+    init_logging();
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let triangle = vec![
+        ParameterVal::PolymorphicObject {
+            vtable_global: "_ZTV8Triangle".into(),
+        },
+        ParameterVal::ExactValue(10),
+    ];
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            Config::default(),
+            Some(triangle),
+            None,
+            5
+        ),
+        PossibleSolutions::exactly_one(ReturnValue::Return(13)),
+    );
+    let square = vec![
+        ParameterVal::PolymorphicObject {
+            vtable_global: "_ZTV6Square".into(),
+        },
+        ParameterVal::ExactValue(10),
+    ];
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            Config::default(),
+            Some(square),
+            None,
+            5
+        ),
+        PossibleSolutions::exactly_one(ReturnValue::Return(14)),
+    );
+}
+
+/// Start executing `calls_fptr`, with the function pointer constrained to
+/// point to either `foo` or `bar`
+fn symex_calls_fptr<'p>(
+    proj: &'p Project,
+    config: Config<'p, DefaultBackend>,
+) -> ExecutionManager<'p, DefaultBackend> {
+    let params = vec![ParameterVal::Unconstrained, ParameterVal::ExactValue(10)];
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("calls_fptr", proj, config, Some(params)).unwrap();
+    let fptr = em.param_bvs()[0].clone();
+    let state = em.mut_state();
+    let foo_ptr = state.get_pointer_to_function("foo").unwrap().clone();
+    let bar_ptr = state.get_pointer_to_function("bar").unwrap().clone();
+    state
+        .assert(&fptr._eq(&foo_ptr).or(&fptr._eq(&bar_ptr)))
+        .unwrap();
+    em
+}
+
+fn choose_bar<'p>(
+    _state: &State<'p, DefaultBackend>,
+    targets: &[Callable<'p, DefaultBackend>],
+) -> Option<Callable<'p, DefaultBackend>> {
+    targets
+        .iter()
+        .find(|target| match target {
+            Callable::LLVMFunction(f) => f.name == "bar",
+            Callable::FunctionHook(_) => false,
+        })
+        .cloned()
+}
+
+#[test]
+fn function_ptr_resolution_hook() {
+    init_logging();
+    let proj = get_project();
+
+    // by default, calling a function pointer with multiple targets is an error
+    let mut em = symex_calls_fptr(&proj, Config::default());
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(msg.contains("multiple possible targets")),
+        res => panic!("Expected an error, got {:?}", res),
+    }
+
+    // same if the hook declines to choose a target
+    let mut config = Config::default();
+    config.function_ptr_resolution_hook = Some(Rc::new(
+        |_: &State<DefaultBackend>, _: &[Callable<DefaultBackend>]| None,
+    ));
+    let mut em = symex_calls_fptr(&proj, config);
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(msg.contains("multiple possible targets")),
+        res => panic!("Expected an error, got {:?}", res),
+    }
+
+    // if the hook chooses `bar`, that's the only target we call
+    let mut config = Config::default();
+    config.function_ptr_resolution_hook = Some(Rc::new(choose_bar));
+    let mut em = symex_calls_fptr(&proj, config);
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(rval)) => {
+            let rval = em.state().get_a_solution_for_bv(&rval).unwrap().unwrap();
+            assert_eq!(rval.as_u64(), Some(9));
+        },
+        Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn function_ptr_target_budget() {
+    init_logging();
+    let proj = get_project();
+
+    // the error names the possible targets
+    let mut em = symex_calls_fptr(&proj, Config::default());
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(
+            msg.ends_with("multiple possible targets: bar, foo"),
+            "unexpected message: {}",
+            msg
+        ),
+        res => panic!("Expected an error, got {:?}", res),
+    }
+
+    // with a budget of 1, the error says we're over budget, but still names
+    // the targets we found
+    let mut config = Config::default();
+    config.max_fn_ptr_targets = 1;
+    let mut em = symex_calls_fptr(&proj, config);
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => {
+            assert!(msg.contains("budget of 1"), "unexpected message: {}", msg);
+            assert!(
+                msg.ends_with("including bar, foo"),
+                "unexpected message: {}",
+                msg
+            );
+        },
+        res => panic!("Expected an error, got {:?}", res),
+    }
+}
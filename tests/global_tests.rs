@@ -1,5 +1,8 @@
+use haybale::backend::DefaultBackend;
 use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
+use llvm_ir::{Constant, Name};
+use std::rc::Rc;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -35,6 +38,37 @@ fn read_global() {
     );
 }
 
+#[test]
+fn initial_constraints() {
+    let funcname = "read_global";
+    init_logging();
+    let proj = get_project();
+    // replace the initial contents of `global1` with a constrained symbolic value
+    let (global1, _) = proj
+        .all_global_vars()
+        .find(|(var, _)| var.name == Name::from("global1"))
+        .expect("Failed to find global1");
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.initial_constraints = Some(Rc::new(
+        move |state: &mut State<DefaultBackend>| -> Result<()> {
+            // this initializes `global1`, so our write replaces its initializer
+            let addr = state.const_to_bv(&Constant::GlobalReference {
+                name: global1.name.clone(),
+                ty: global1.ty.clone(),
+            })?;
+            let val = state.new_bv_with_name(Name::from("initial_global1"), 32)?;
+            state.write(&addr, val.clone())?;
+            state.assert(&val.ugte(&state.bv_from_u32(10, 32)))?;
+            state.assert(&val.ulte(&state.bv_from_u32(11, 32)))?;
+            Ok(())
+        },
+    ));
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, Some(vec![]), None, 5),
+        PossibleSolutions::exactly_two(ReturnValue::Return(10), ReturnValue::Return(11)),
+    );
+}
+
 #[test]
 fn modify_global() {
     let funcname = "modify_global";
@@ -89,6 +123,81 @@ fn dont_confuse_globals() {
     )
 }
 
+#[test]
+fn reset_with_params() {
+    init_logging();
+    let proj = get_project();
+
+    let mut em: ExecutionManager<DefaultBackend> = symex_function(
+        "modify_global",
+        &proj,
+        Config::default(),
+        Some(vec![ParameterVal::ExactValue(3)]),
+    )
+    .unwrap();
+    assert_eq!(
+        em.possible_return_values(5, None),
+        PossibleSolutions::exactly_one(ReturnValue::Return(3)),
+    );
+    for &x in &[7, 10] {
+        em.reset_with_params(Some(vec![ParameterVal::ExactValue(x)]))
+            .unwrap();
+        assert_eq!(
+            em.possible_return_values(5, None),
+            PossibleSolutions::exactly_one(ReturnValue::Return(x)),
+        );
+        assert_eq!(em.paths_explored(), 1);
+    }
+
+    // globals are re-initialized after a reset
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("read_global", &proj, Config::default(), Some(vec![])).unwrap();
+    for _ in 0 .. 2 {
+        assert_eq!(
+            em.possible_return_values(5, None),
+            PossibleSolutions::exactly_one(ReturnValue::Return(3)),
+        );
+        em.reset_with_params(Some(vec![])).unwrap();
+    }
+}
+
+#[test]
+fn reset_twice_with_same_params() {
+    init_logging();
+    let proj = get_project();
+    let (global3, _) = proj
+        .all_global_vars()
+        .find(|(var, _)| var.name == Name::from("global3"))
+        .expect("Failed to find global3");
+    let global3 = Constant::GlobalReference {
+        name: global3.name.clone(),
+        ty: global3.ty.clone(),
+    };
+    let params = Some(vec![ParameterVal::Range(1, 3)]);
+    let expected = PossibleSolutions::Exactly(
+        vec![
+            ReturnValue::Return(1),
+            ReturnValue::Return(2),
+            ReturnValue::Return(3),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("modify_global", &proj, Config::default(), params.clone()).unwrap();
+    assert_eq!(em.possible_return_values(5, None), expected);
+    let addr = em.state().const_to_bv(&global3).unwrap().as_u64();
+    assert!(addr.is_some());
+
+    // each reset starts over the same way, with the same global allocations
+    for _ in 0 .. 2 {
+        em.reset_with_params(params.clone()).unwrap();
+        assert_eq!(em.possible_return_values(5, None), expected);
+        assert_eq!(em.state().const_to_bv(&global3).unwrap().as_u64(), addr);
+    }
+}
+
 // The following tests essentially assume that the simple cross-module call tests are passing
 
 #[test]
@@ -185,3 +294,20 @@ fn globals_initialization() {
         PossibleSolutions::exactly_one(ReturnValue::Return(1052)),
     )
 }
+
+#[test]
+fn circular_alias() {
+    let modname = "tests/bcfiles/circular_alias/circular_alias.bc";
+    let funcname = "read_circular_alias";
+    init_logging();
+    let proj = Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e));
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    // we should get an error rather than recursing forever
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(msg.contains("cycle of aliases"), "{}", msg),
+        Err(e) => panic!("Expected an OtherError, got {}", e),
+        Ok(retval) => panic!("Expected an error, got {:?}", retval),
+    }
+}
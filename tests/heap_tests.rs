@@ -0,0 +1,131 @@
+use haybale::backend::DefaultBackend;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/heap.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn malloc_zero_distinct() {
+    let funcname = "malloc_zero_distinct";
+    init_logging();
+    let proj = get_project();
+    // `Config::new()` has no function hooks, so this has just the allocator hooks
+    let mut config: Config<DefaultBackend> = Config::new();
+    config.add_libc_allocator_hooks();
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn calloc_zeroed() {
+    let funcname = "calloc_zeroed";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::new();
+    config.add_libc_allocator_hooks();
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn realloc_copies() {
+    let funcname = "realloc_copies";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::new();
+    config.add_libc_allocator_hooks();
+    let args = find_zero_of_func(funcname, &proj, config.clone(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+
+    // `realloc()` copies the uninitialized first half too, but copying isn't
+    // a use of the uninitialized bytes, so this shouldn't be flagged
+    config.flag_uninitialized_reads = true;
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn double_free() {
+    let funcname = "double_free";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::new();
+    config.add_libc_allocator_hooks();
+
+    // by default, double frees aren't errors, but freed pointers are still recorded
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), None).unwrap();
+    let mut num_freed = vec![];
+    while let Some(res) = em.next() {
+        match res {
+            Ok(_) => num_freed.push(em.state().freed_pointers().len()),
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    num_freed.sort();
+    assert_eq!(num_freed, vec![1, 2]);
+
+    // with `detect_double_free`, the path which frees twice is an error
+    config.detect_double_free = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let mut num_ok = 0;
+    let mut num_double_free = 0;
+    while let Some(res) = em.next() {
+        match res {
+            Ok(_) => num_ok += 1,
+            Err(Error::DoubleFree) => num_double_free += 1,
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    assert_eq!(num_ok, 1);
+    assert_eq!(num_double_free, 1);
+}
+
+#[test]
+fn malloc_partial_init() {
+    let funcname = "malloc_partial_init";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::new();
+    config.add_libc_allocator_hooks();
+
+    // by default, reading uninitialized memory just gives an unconstrained value
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.next().is_none());
+
+    // with `flag_uninitialized_reads`, the read of the second half is an error
+    config.flag_uninitialized_reads = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::UninitializedRead(_)) => {},
+        Ok(retval) => panic!("Expected an UninitializedRead error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert!(em.next().is_none());
+}
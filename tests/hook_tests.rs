@@ -1,4 +1,4 @@
-use haybale::backend::Backend;
+use haybale::backend::{Backend, DefaultBackend, BV};
 use haybale::function_hooks::IsCall;
 use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
@@ -37,6 +37,112 @@ fn hook_a_function() {
     );
 }
 
+// Hook call.c's "simple_callee" to return 1, 2, or 3, each on a different path
+fn forking_hook_for_simple_callee<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    let ret_size = state.size_in_bits(&state.type_of(call)).ok_or_else(|| {
+        Error::OtherError("simple_callee shouldn't return opaque struct type".into())
+    })?;
+    let retval = state.new_bv_with_name("forking_hook_retval".into(), ret_size)?;
+    let constraints: Vec<_> = (1 ..= 3)
+        .map(|i| retval._eq(&state.bv_from_u32(i, ret_size)))
+        .collect();
+    state.fork_after_hook(constraints);
+    Ok(ReturnValue::Return(retval))
+}
+
+#[test]
+fn hook_forks_paths() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/call.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module call.bc: {}", e));
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add("simple_callee", &forking_hook_for_simple_callee);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("simple_caller", &proj, config, None).unwrap();
+    let mut retvals = Vec::new();
+    while let Some(res) = em.next() {
+        match res {
+            Ok(ReturnValue::Return(retval)) => {
+                // on each path, the return value is fully determined
+                let solutions = em
+                    .state()
+                    .get_possible_solutions_for_bv(&retval, 1)
+                    .unwrap()
+                    .as_u64_solutions()
+                    .unwrap();
+                match solutions {
+                    PossibleSolutions::Exactly(hs) if hs.len() == 1 => {
+                        retvals.push(hs.into_iter().next().unwrap())
+                    },
+                    _ => panic!("Expected exactly one solution, got {:?}", solutions),
+                }
+            },
+            Ok(retval) => panic!("Expected a return value, got {:?}", retval),
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    retvals.sort();
+    assert_eq!(retvals, vec![1, 2, 3]);
+}
+
+// Hook call.c's "simple_callee" to return a fresh value which it constrains
+// to be 7, using the BV's own `assert()` rather than `State::assert()`
+fn constraining_hook_for_simple_callee<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    _call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    let retval = state.new_bv_with_name("constraining_hook_retval".into(), 32)?;
+    BV::assert(&retval._eq(&state.bv_from_u32(7, 32)))?;
+    Ok(ReturnValue::Return(retval))
+}
+
+#[test]
+fn hook_constraints_survive_bfs() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/call.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module call.bc: {}", e));
+    let mut config = Config::default();
+    config.loop_bound = 3;
+    config.exploration_strategy = config::ExplorationStrategy::BreadthFirst;
+    config
+        .function_hooks
+        .add("simple_callee", &constraining_hook_for_simple_callee);
+    // each trip through caller_with_loop's loop adds 7, so it returns 7k - 14.
+    // BFS switches paths between loop iterations, and must keep the hook's
+    // constraints on each path
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("caller_with_loop", &proj, config, None).unwrap();
+    let mut retvals = Vec::new();
+    while let Some(res) = em.next() {
+        match res {
+            Ok(ReturnValue::Return(retval)) => {
+                let solutions = em
+                    .state()
+                    .get_possible_solutions_for_bv(&retval, 1)
+                    .unwrap()
+                    .as_u64_solutions()
+                    .unwrap();
+                match solutions {
+                    PossibleSolutions::Exactly(hs) if hs.len() == 1 => {
+                        retvals.push(hs.into_iter().next().unwrap() as u32 as i32)
+                    },
+                    _ => panic!("Expected exactly one solution, got {:?}", solutions),
+                }
+            },
+            Ok(retval) => panic!("Expected a return value, got {:?}", retval),
+            Err(Error::LoopBoundExceeded { .. }) => {},
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    retvals.sort();
+    assert_eq!(retvals, vec![-14, -7, 0, 7]);
+}
+
 // Hook functionptr.c's "get_function_ptr" to return a pointer to our hook "target_hook" instead of "foo" or "bar" like it normally does
 fn hook_for_get_function_ptr<'p, B: Backend>(
     state: &mut State<'p, B>,
@@ -82,3 +188,194 @@ fn hook_a_function_ptr() {
         PossibleSolutions::exactly_one(ReturnValue::Return(15)),
     );
 }
+
+#[test]
+fn rng_model() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/nondet.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module nondet.bc: {}", e));
+    let mut config: Config<DefaultBackend> = Config::default();
+    config
+        .rng_model
+        .insert("rand".to_owned(), "rand".to_owned());
+    let mut em = symex_function("two_draws", &proj, config, None).unwrap();
+    let retval = match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(retval)) => retval,
+        Ok(retval) => panic!("Expected a return value, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    };
+    let state = em.mut_state();
+    assert!(state.nth_nondet_value("rand", 2).is_none());
+    // pin the two draws, and check that the return value follows
+    let first = state
+        .nth_nondet_value("rand", 0)
+        .expect("Expected a first draw")
+        .clone();
+    let second = state
+        .nth_nondet_value("rand", 1)
+        .expect("Expected a second draw")
+        .clone();
+    BV::assert(&first._eq(&state.bv_from_u32(10, 32))).unwrap();
+    BV::assert(&second._eq(&state.bv_from_u32(15, 32))).unwrap();
+    let solution = state
+        .get_a_solution_for_bv(&retval)
+        .unwrap()
+        .expect("Expected a solution")
+        .as_u64()
+        .unwrap();
+    assert_eq!(solution, 5);
+    assert!(em.next().is_none(), "Expected only one path");
+}
+
+// Model inline_asm.ll's two-output asm: the first output is a copy of the
+// input, and the second output is the constant 3
+fn hook_for_two_output_asm<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<AsmReturnValue<B::BV>> {
+    assert_eq!(call.get_arguments().len(), 1);
+    let input = state.operand_to_bv(&call.get_arguments()[0].0)?;
+    Ok(AsmReturnValue::Outputs(vec![
+        input,
+        state.bv_from_u32(3, 32),
+    ]))
+}
+
+// Incorrectly models the same asm as having just one output
+fn hook_with_too_few_outputs<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    _call: &'p dyn IsCall,
+) -> Result<AsmReturnValue<B::BV>> {
+    Ok(AsmReturnValue::Outputs(vec![state.bv_from_u32(3, 32)]))
+}
+
+#[test]
+fn inline_asm_outputs() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/inline_asm.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module inline_asm.bc: {}", e));
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add_inline_asm_hook(&hook_for_two_output_asm);
+    assert_eq!(
+        find_zero_of_func("asm_two_outputs", &proj, config, None),
+        Ok(Some(vec![SolutionValue::I32(3)]))
+    );
+
+    let mut config: Config<DefaultBackend> = Config::default();
+    config
+        .function_hooks
+        .add_inline_asm_hook(&hook_with_too_few_outputs);
+    let mut em = symex_function("asm_two_outputs", &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::HookReturnValueMismatch(_)) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        Ok(retval) => panic!("Expected an error, got {:?}", retval),
+    }
+}
+
+// Model inline_asm.ll's `asm goto`: the output is a copy of the input
+fn hook_for_asm_goto<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    call: &'p dyn IsCall,
+) -> Result<AsmReturnValue<B::BV>> {
+    let input = state.operand_to_bv(&call.get_arguments()[0].0)?;
+    Ok(AsmReturnValue::Outputs(vec![input]))
+}
+
+#[test]
+fn asm_goto() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/inline_asm.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module inline_asm.bc: {}", e));
+    let mut config: Config<DefaultBackend> = Config::default();
+    config
+        .function_hooks
+        .add_inline_asm_hook(&hook_for_asm_goto);
+    // we can't explore the indirect destination of `asm_goto`, so we
+    // shouldn't pretend that the asm always falls through
+    let mut em = symex_function("asm_goto", &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::UnsupportedInstruction(_)) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        Ok(retval) => panic!("Expected an error, got {:?}", retval),
+    }
+}
+
+// Hook call.c's "simple_callee" to return an arbitrary value in 3 ..= 5
+fn range_hook_for_simple_callee<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    _call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    hook_utils::return_symbolic_in_range(state, 32, 3, 5)
+}
+
+// Hook call.c's "simple_callee" to return an arbitrary value in -2 ..= 1
+fn signed_range_hook_for_simple_callee<'p, B: Backend>(
+    state: &mut State<'p, B>,
+    _call: &'p dyn IsCall,
+) -> Result<ReturnValue<B::BV>> {
+    hook_utils::return_symbolic_in_signed_range(state, 32, -2, 1)
+}
+
+#[test]
+fn symbolic_return_value_helpers() {
+    init_logging();
+    let proj = Project::from_bc_path("tests/bcfiles/call.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module call.bc: {}", e));
+
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add("simple_callee", &range_hook_for_simple_callee);
+    assert_eq!(
+        get_possible_return_values_of_func("simple_caller", &proj, config, None, None, 5),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(3),
+                ReturnValue::Return(4),
+                ReturnValue::Return(5),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+
+    let mut config = Config::default();
+    config
+        .function_hooks
+        .add("simple_callee", &signed_range_hook_for_simple_callee);
+    assert_eq!(
+        get_possible_return_values_of_func("simple_caller", &proj, config, None, None, 5),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return((-2i32) as u32 as u64),
+                ReturnValue::Return((-1i32) as u32 as u64),
+                ReturnValue::Return(0),
+                ReturnValue::Return(1),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+
+    // the helpers reject ranges which are empty or don't fit in the width
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function("simple_caller", &proj, Config::default(), None).unwrap();
+    let state = em.mut_state();
+    assert!(hook_utils::return_symbolic_in_range(state, 32, 5, 3).is_err());
+    assert!(hook_utils::return_symbolic_in_range(state, 8, 0, 256).is_err());
+    assert!(hook_utils::return_symbolic_in_signed_range(state, 8, -129, 0).is_err());
+
+    // a symbolic non-null pointer is pointer-sized and can't be null
+    match hook_utils::return_symbolic_nonnull_ptr(state).unwrap() {
+        ReturnValue::Return(ptr) => {
+            assert_eq!(ptr.get_width(), proj.pointer_size_bits());
+            assert!(!state
+                .bvs_can_be_equal(&ptr, &state.zero(ptr.get_width()))
+                .unwrap());
+        },
+        rval => panic!("Expected a return value, got {:?}", rval),
+    }
+}
@@ -1,4 +1,10 @@
+use haybale::backend::DefaultBackend;
+use haybale::config::{ExplorationStrategy, SinglePathPolicy};
 use haybale::*;
+use llvm_ir::Name;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -120,3 +126,286 @@ fn nested_loop() {
     assert_eq!(args.len(), 1);
     assert_eq!(args[0], SolutionValue::I32(3));
 }
+
+#[test]
+fn per_loop_bound() {
+    let funcname = "for_loop";
+    init_logging();
+    let proj = get_project();
+    // finding the zero requires 3 iterations of the loop, so the global
+    // `loop_bound` is too small, but the per-loop bound should take precedence
+    let mut config = Config::default();
+    config.loop_bound = 2;
+    config
+        .loop_bounds
+        .insert((funcname.to_owned(), Name::from(9)), 5);
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn loop_bound_exempt_function() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+    // finding the zero requires 3 iterations of the loop, so the `loop_bound`
+    // is too small, but the function is exempt from it
+    let mut config = Config::default();
+    config.loop_bound = 2;
+    config
+        .loop_bound_exempt_functions
+        .insert(funcname.to_owned());
+    // with no loop bound, the per-path instruction budget is what stops the
+    // analysis from going around the loop forever
+    config.max_instructions_per_path = Some(200);
+    let args = find_zero_of_func(funcname, &proj, config, None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn loop_bound_exceeded_location() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+
+    // always taking the true branch means staying in the loop until we exceed the loop bound
+    let mut config = Config::default();
+    config.loop_bound = 5;
+    config.single_path = Some(SinglePathPolicy::FirstFeasible);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let e = match em.next().expect("Expected a path") {
+        Err(e) => e,
+        Ok(retval) => panic!("Expected to exceed the loop bound, got {:?}", retval),
+    };
+    match &e {
+        Error::LoopBoundExceeded {
+            funcname: errfuncname,
+            bbname,
+            ..
+        } => {
+            assert_eq!(errfuncname, funcname);
+            assert_eq!(bbname, &Name::from(6));
+        },
+        _ => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    let msg = em.state().full_error_message_with_context(e);
+    assert!(msg.contains("in function \"while_loop\" at basic block %6"));
+}
+
+#[test]
+fn max_paths() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+
+    // without a limit, we get one path per possible number of loop iterations
+    // (plus paths which exceed the loop bound)
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), None).unwrap();
+    let num_paths = em.by_ref().count();
+    assert!(num_paths > 3);
+    assert_eq!(em.paths_explored(), num_paths);
+
+    // with a limit, we stop early
+    let mut config = Config::default();
+    config.max_paths = Some(3);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    assert_eq!(em.by_ref().count(), 3);
+    assert_eq!(em.paths_explored(), 3);
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn max_instructions_per_path() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+    // each path executes 9 instructions before the loop, 8 per loop
+    // iteration, and 5 after the loop; so with this budget, only paths with
+    // one or two loop iterations complete
+    let mut config = Config::default();
+    config.max_instructions_per_path = Some(30);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let mut completed_path_lengths = vec![];
+    let mut num_exceeded = 0;
+    while let Some(res) = em.next() {
+        match res {
+            Ok(_) => completed_path_lengths.push(em.state().instructions_executed()),
+            Err(Error::InstructionBudgetExceeded(30)) => {
+                assert_eq!(em.state().instructions_executed(), 31);
+                num_exceeded += 1;
+            },
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    // the count should reflect only the current path, not any previous ones
+    completed_path_lengths.sort();
+    assert_eq!(completed_path_lengths, vec![22, 30]);
+    assert_eq!(num_exceeded, 1);
+}
+
+#[test]
+fn breadth_first() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+
+    // returns the length of each path which completes, in the order they are found
+    fn path_lengths<'p>(
+        funcname: &str,
+        proj: &'p Project,
+        config: Config<'p, DefaultBackend>,
+    ) -> Vec<u64> {
+        let mut em: ExecutionManager<DefaultBackend> =
+            symex_function(funcname, proj, config, None).unwrap();
+        let mut lengths = vec![];
+        while let Some(res) = em.next() {
+            match res {
+                Ok(_) => lengths.push(em.state().instructions_executed()),
+                Err(Error::LoopBoundExceeded { .. }) => {},
+                Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+            }
+        }
+        lengths
+    }
+
+    // depth-first, we first find the paths which exit the loop latest
+    let dfs_lengths = path_lengths(funcname, &proj, Config::default());
+    assert!(dfs_lengths.len() > 3);
+    assert!(dfs_lengths.windows(2).all(|w| w[0] > w[1]));
+
+    // breadth-first, we find the same paths in the opposite order
+    let mut config = Config::default();
+    config.exploration_strategy = ExplorationStrategy::BreadthFirst;
+    let mut bfs_lengths = path_lengths(funcname, &proj, config);
+    bfs_lengths.reverse();
+    assert_eq!(bfs_lengths, dfs_lengths);
+}
+
+#[test]
+fn single_path() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+
+    // always taking the true branch means staying in the loop until we exceed the loop bound
+    let mut config = Config::default();
+    config.single_path = Some(SinglePathPolicy::FirstFeasible);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::LoopBoundExceeded { .. }) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        Ok(retval) => panic!("Expected to exceed the loop bound, got {:?}", retval),
+    }
+    assert!(em.next().is_none(), "Expected only one path");
+
+    // choosing randomly, we get exactly one path, and the same one for the same seed
+    let path_length = |seed: u64| -> Option<u64> {
+        let mut config = Config::default();
+        config.single_path = Some(SinglePathPolicy::Random { seed });
+        let mut em: ExecutionManager<DefaultBackend> =
+            symex_function(funcname, &proj, config, None).unwrap();
+        let length = match em.next().expect("Expected a path") {
+            Ok(_) => Some(em.state().instructions_executed()),
+            Err(Error::LoopBoundExceeded { .. }) => None,
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        };
+        assert!(em.next().is_none(), "Expected only one path");
+        length
+    };
+    let lengths: Vec<Option<u64>> = (0 .. 10).map(path_length).collect();
+    assert!(lengths.iter().any(Option::is_some));
+    assert_eq!(path_length(3), lengths[3]);
+}
+
+#[test]
+fn total_time_budget() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+    let mut config = Config::default();
+    config.total_time_budget = Some(Duration::from_millis(500));
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    // the first path should finish well within the budget (depending on the
+    // order paths are explored in, it may end by exceeding the loop bound)
+    match em.next().expect("Expected a path") {
+        Ok(_) | Err(Error::LoopBoundExceeded { .. }) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    // but after the budget has passed, we should get the error, and then no more paths
+    std::thread::sleep(Duration::from_millis(600));
+    match em.next().expect("Expected a path") {
+        Err(Error::TimeBudgetExceeded) => {},
+        res => panic!("Expected TimeBudgetExceeded, got {:?}", res),
+    }
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn cancellation() {
+    let funcname = "while_loop";
+    init_logging();
+    let proj = get_project();
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut config = Config::default();
+    config.cancellation = Some(flag.clone());
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    // with the flag unset, the first path should finish normally (depending
+    // on the order paths are explored in, it may end by exceeding the loop bound)
+    match em.next().expect("Expected a path") {
+        Ok(_) | Err(Error::LoopBoundExceeded { .. }) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    // once the flag is set, we should get the error, and then no more paths
+    flag.store(true, Ordering::Relaxed);
+    match em.next().expect("Expected a path") {
+        Err(Error::Cancelled) => {},
+        res => panic!("Expected Cancelled, got {:?}", res),
+    }
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn dry_run() {
+    let funcname = "loop_over_array";
+    init_logging();
+    let proj = get_project();
+
+    // the loop always runs exactly 10 times, so there is only one feasible path
+    let mut config = Config::default();
+    config.loop_bound = 20;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    assert_eq!(em.by_ref().count(), 1);
+
+    // but a dry run doesn't check feasibility, so it can exit the loop after
+    // any number of iterations up to the loop bound
+    let mut config = Config::default();
+    config.loop_bound = 20;
+    config.dry_run = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let mut num_ok = 0;
+    let mut num_exceeded = 0;
+    while let Some(res) = em.next() {
+        match res {
+            Ok(_) => num_ok += 1,
+            Err(Error::LoopBoundExceeded { .. }) => num_exceeded += 1,
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    assert!(num_ok > 10, "expected more than 10 paths, got {}", num_ok);
+    assert_eq!(num_exceeded, 1);
+}
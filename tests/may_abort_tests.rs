@@ -1,3 +1,6 @@
+use haybale::backend::DefaultBackend;
+use haybale::config::Demangling;
+use haybale::function_hooks::FunctionHooks;
 use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
 
@@ -53,3 +56,34 @@ fn may_panic() {
         PossibleSolutions::exactly_two(ReturnValue::Return(1), ReturnValue::Abort),
     );
 }
+
+#[test]
+fn explicit_demangling_in_errors() {
+    let funcname = "panic::may_panic";
+    init_logging();
+    let proj = get_panic_project();
+    // Returns the function name reported in the `FunctionNotFound` error
+    let missing_function = |demangling| {
+        let mut config = Config::default();
+        // without the default `begin_panic` hook, `begin_panic` is analyzed,
+        // and ends up calling a function which isn't defined in the `Project`
+        config.function_hooks = FunctionHooks::new();
+        config.demangling = Some(demangling);
+        let mut em: ExecutionManager<DefaultBackend> =
+            symex_function(funcname, &proj, config, None).unwrap();
+        loop {
+            match em.next().expect("Expected a FunctionNotFound error") {
+                Ok(_) => continue,
+                Err(Error::FunctionNotFound(name)) => return name,
+                Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+            }
+        }
+    };
+    assert_eq!(
+        missing_function(Demangling::Rust),
+        "core::panic::Location::caller"
+    );
+    assert!(
+        missing_function(Demangling::NoDemangling).starts_with("_ZN4core5panic8Location6caller")
+    );
+}
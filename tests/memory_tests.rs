@@ -1,5 +1,6 @@
-use haybale::backend::DefaultBackend;
+use haybale::backend::{CellMemoryBackend, DefaultBackend};
 use haybale::config::NullPointerChecking;
+use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
 
 fn init_logging() {
@@ -33,6 +34,34 @@ fn load_and_store() {
     assert_eq!(args[1], SolutionValue::I32(3));
 }
 
+#[test]
+fn load_and_store_with_cell_size() {
+    let funcname = "load_and_store";
+    init_logging();
+    let proj = get_project();
+    for &cell_bits in &[16, 32, 128] {
+        let mut config: Config<CellMemoryBackend> = Config::default();
+        config.memory_cell_bits = Some(cell_bits);
+        let params = vec![ParameterVal::NonNullPointer, ParameterVal::ExactValue(10)];
+        let mut em = symex_function(funcname, &proj, config, Some(params)).unwrap();
+        match em.next().expect("Expected a path") {
+            Ok(ReturnValue::Return(rval)) => {
+                // the loaded value isn't a constant `BV`, but it has only one possible value
+                let solutions = em
+                    .state()
+                    .get_possible_solutions_for_bv(&rval, 1)
+                    .unwrap()
+                    .as_u64_solutions()
+                    .unwrap();
+                assert_eq!(solutions, PossibleSolutions::exactly_one(7));
+            },
+            Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+        assert!(em.next().is_none());
+    }
+}
+
 #[test]
 fn local_ptr() {
     let funcname = "local_ptr";
@@ -158,3 +187,49 @@ fn pointer_compare() {
     assert_eq!(args.len(), 1);
     assert_eq!(args[0], SolutionValue::I32(3));
 }
+
+#[test]
+fn trace_memory() {
+    let funcname = "overwrite";
+    init_logging();
+    let proj = get_project();
+    let params = Some(vec![
+        ParameterVal::PointerToAllocated(4),
+        ParameterVal::Unconstrained,
+    ]);
+
+    // by default, nothing is traced
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), params.clone()).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.state().memory_trace().is_empty());
+
+    // with `trace_memory`, we get the three stores and then the load, in order
+    let mut config = Config::default();
+    config.trace_memory = true;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, params).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let trace = em.state().memory_trace();
+    let summary: Vec<(BBInstrIndex, u32, bool)> = trace
+        .iter()
+        .map(|access| (access.location.instr, access.width, access.is_write))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (BBInstrIndex::Instr(0), 32, true),
+            (BBInstrIndex::Instr(1), 32, true),
+            (BBInstrIndex::Instr(3), 32, true),
+            (BBInstrIndex::Instr(4), 32, false),
+        ]
+    );
+    // all four accesses are through the same pointer
+    assert!(trace
+        .iter()
+        .all(|access| access.addr_debug == trace[0].addr_debug));
+}
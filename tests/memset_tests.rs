@@ -0,0 +1,72 @@
+use haybale::backend::DefaultBackend;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/memset.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn symbolic_length_memset() {
+    let funcname = "memset_prefix";
+    init_logging();
+    let proj = get_project();
+    let rvals = |n: ParameterVal| {
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            Config::default(),
+            Some(vec![n, ParameterVal::ExactValue(3)]),
+            None,
+            3,
+        )
+    };
+    // byte 3 is zeroed if and only if the length is more than 3
+    assert_eq!(
+        rvals(ParameterVal::Range(0, 8)),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(0xFF)),
+    );
+    assert_eq!(
+        rvals(ParameterVal::Range(0, 3)),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0xFF)),
+    );
+    assert_eq!(
+        rvals(ParameterVal::Range(4, 8)),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0)),
+    );
+}
+
+#[test]
+fn unbounded_symbolic_length_memset() {
+    let funcname = "memset_prefix";
+    init_logging();
+    let proj = get_project();
+    let params = Some(vec![
+        ParameterVal::Unconstrained,
+        ParameterVal::ExactValue(3),
+    ]);
+
+    // without `max_memcpy_length`, the length could be huge
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), params.clone()).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(msg.contains("max_memcpy_length")),
+        res => panic!("Expected OtherError, got {:?}", res),
+    }
+
+    // with it, the length gets bounded, and byte 3 may or may not be zeroed
+    let mut config = Config::default();
+    config.max_memcpy_length = Some(8);
+    let rvals = get_possible_return_values_of_func(funcname, &proj, config, params, None, 3);
+    assert_eq!(
+        rvals,
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(0xFF)),
+    );
+}
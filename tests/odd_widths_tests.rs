@@ -0,0 +1,52 @@
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/odd_widths.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn bool_param() {
+    let funcname = "bool_param";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args, vec![SolutionValue::Bool(true)]);
+}
+
+#[test]
+fn i128_param() {
+    let funcname = "i128_param";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(
+        args,
+        vec![SolutionValue::Int {
+            bits: 128,
+            value: (1 << 64) + 5,
+        }]
+    );
+}
+
+#[test]
+fn i24_param() {
+    let funcname = "i24_param";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0].unwrap_to_int(), 123_456);
+}
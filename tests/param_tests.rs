@@ -0,0 +1,99 @@
+use haybale::backend::DefaultBackend;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/params.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn unconstrained_array() {
+    let funcname = "sum_array3";
+    init_logging();
+    let proj = get_project();
+    let params = vec![ParameterVal::PointerToUnconstrainedArray {
+        element_bits: 32,
+        num_elements: 10,
+    }];
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), Some(params)).unwrap();
+    let elements = em
+        .param_array_elements(0)
+        .expect("Expected array elements for parameter 0")
+        .to_vec();
+    assert_eq!(elements.len(), 10);
+    assert!(elements.iter().all(|element| element.get_width() == 32));
+    assert!(em.param_array_elements(1).is_none());
+
+    // constrain the first two elements, then solve for the third
+    let state = em.mut_state();
+    state
+        .assert(&elements[0]._eq(&state.bv_from_u32(1, 32)))
+        .unwrap();
+    state
+        .assert(&elements[1]._eq(&state.bv_from_u32(2, 32)))
+        .unwrap();
+    let retval = match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(retval)) => retval,
+        Ok(retval) => panic!("Unexpected return value {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    };
+    let state = em.state();
+    state
+        .assert(&retval._eq(&state.bv_from_u32(10, 32)))
+        .unwrap();
+    let third = state
+        .get_a_solution_for_bv(&elements[2])
+        .unwrap()
+        .expect("Expected a solution")
+        .as_u64()
+        .unwrap();
+    assert_eq!(third, 7);
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn aligned_pointer() {
+    let funcname = "is_aligned16";
+    init_logging();
+    let proj = get_project();
+
+    // an unconstrained pointer may or may not be aligned
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, Config::default(), None, None, 5),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(1)),
+    );
+
+    // with 16-byte alignment, the misaligned path isn't feasible
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            Config::default(),
+            Some(vec![ParameterVal::AlignedPointer { alignment: 16 }]),
+            None,
+            5
+        ),
+        PossibleSolutions::exactly_one(ReturnValue::Return(1)),
+    );
+
+    // 8-byte alignment isn't enough to guarantee 16-byte alignment
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            Config::default(),
+            Some(vec![ParameterVal::AlignedPointer { alignment: 8 }]),
+            None,
+            5
+        ),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(1)),
+    );
+}
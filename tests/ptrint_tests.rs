@@ -0,0 +1,84 @@
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/ptrint.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn roundtrip_through_memory() {
+    let funcname = "roundtrip_through_memory";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn roundtrip_low_tag() {
+    let funcname = "roundtrip_low_tag";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn roundtrip_wide() {
+    let funcname = "roundtrip_wide";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn roundtrip_narrow() {
+    let funcname = "roundtrip_narrow";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn roundtrip_vector() {
+    let funcname = "roundtrip_vector";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
+
+#[test]
+fn roundtrip_constant() {
+    let funcname = "roundtrip_constant";
+    init_logging();
+    let proj = get_project();
+    let args = find_zero_of_func(funcname, &proj, Config::default(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I32(3));
+}
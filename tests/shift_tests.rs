@@ -0,0 +1,160 @@
+use haybale::backend::DefaultBackend;
+use haybale::config::ShiftOverflow;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/shift.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+fn possible_rets(
+    funcname: &str,
+    x: u64,
+    n: u64,
+    shift_overflow: ShiftOverflow,
+) -> PossibleSolutions<ReturnValue<u64>> {
+    let proj = get_project();
+    let mut config = Config::default();
+    config.shift_overflow = shift_overflow;
+    get_possible_return_values_of_func(
+        funcname,
+        &proj,
+        config,
+        Some(vec![
+            ParameterVal::ExactValue(x),
+            ParameterVal::ExactValue(n),
+        ]),
+        None,
+        2,
+    )
+}
+
+#[test]
+fn shift_out_all_bits() {
+    init_logging();
+    assert_eq!(
+        possible_rets("shl_by", 1, 3, ShiftOverflow::ShiftOutAllBits),
+        PossibleSolutions::exactly_one(ReturnValue::Return(8)),
+    );
+    assert_eq!(
+        possible_rets("shl_by", 1, 33, ShiftOverflow::ShiftOutAllBits),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0)),
+    );
+    assert_eq!(
+        possible_rets("ashr_by", 0xFFFF_FFF8, 34, ShiftOverflow::ShiftOutAllBits),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0xFFFF_FFFF)),
+    );
+    // (1 << 3) + (1 << 4)
+    assert_eq!(
+        possible_rets("shl_vector", 1, 3, ShiftOverflow::ShiftOutAllBits),
+        PossibleSolutions::exactly_one(ReturnValue::Return(24)),
+    );
+    // 0 + (1 << 4)
+    assert_eq!(
+        possible_rets("shl_vector", 1, 33, ShiftOverflow::ShiftOutAllBits),
+        PossibleSolutions::exactly_one(ReturnValue::Return(16)),
+    );
+}
+
+#[test]
+fn mask_like_x86() {
+    init_logging();
+    assert_eq!(
+        possible_rets("shl_by", 1, 33, ShiftOverflow::MaskLikeX86),
+        PossibleSolutions::exactly_one(ReturnValue::Return(2)),
+    );
+    // -8 >> 34 is -8 >> 2
+    assert_eq!(
+        possible_rets("ashr_by", 0xFFFF_FFF8, 34, ShiftOverflow::MaskLikeX86),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0xFFFF_FFFE)),
+    );
+    // (1 << 33) + (1 << 4)
+    assert_eq!(
+        possible_rets("shl_vector", 1, 33, ShiftOverflow::MaskLikeX86),
+        PossibleSolutions::exactly_one(ReturnValue::Return(18)),
+    );
+    // an 8-bit shift amount is taken modulo 32, not modulo 8
+    assert_eq!(
+        possible_rets("shl_i8_by", 1, 9, ShiftOverflow::MaskLikeX86),
+        PossibleSolutions::exactly_one(ReturnValue::Return(0)),
+    );
+    assert_eq!(
+        possible_rets("shl_i8_by", 1, 33, ShiftOverflow::MaskLikeX86),
+        PossibleSolutions::exactly_one(ReturnValue::Return(2)),
+    );
+}
+
+#[test]
+fn poison() {
+    init_logging();
+    assert_eq!(
+        possible_rets("shl_by", 1, 3, ShiftOverflow::Poison),
+        PossibleSolutions::exactly_one(ReturnValue::Return(8)),
+    );
+    match possible_rets("shl_by", 1, 33, ShiftOverflow::Poison) {
+        PossibleSolutions::AtLeast(_) => {},
+        rets => panic!("Expected an arbitrary return value, got {:?}", rets),
+    }
+    // only the element shifted by `n` is arbitrary
+    assert_eq!(
+        possible_rets("shl_vector", 1, 3, ShiftOverflow::Poison),
+        PossibleSolutions::exactly_one(ReturnValue::Return(24)),
+    );
+    match possible_rets("shl_vector", 1, 33, ShiftOverflow::Poison) {
+        PossibleSolutions::AtLeast(_) => {},
+        rets => panic!("Expected an arbitrary return value, got {:?}", rets),
+    }
+}
+
+#[test]
+fn error() {
+    init_logging();
+    let proj = get_project();
+    let mut config = Config::default();
+    config.shift_overflow = ShiftOverflow::Error;
+    let mut em: ExecutionManager<DefaultBackend> = symex_function(
+        "shl_by",
+        &proj,
+        config,
+        Some(vec![
+            ParameterVal::ExactValue(1),
+            ParameterVal::Unconstrained,
+        ]),
+    )
+    .unwrap();
+
+    // first, the path where the shift amount is too large
+    match em.next().expect("Expected a path") {
+        Err(Error::PossibleOversizedShift(_)) => {},
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        Ok(retval) => panic!("Expected an oversized shift, got {:?}", retval),
+    }
+    let n = em.param_bvs()[1].clone();
+    let n_val = em
+        .state()
+        .get_a_solution_for_bv(&n)
+        .unwrap()
+        .expect("Expected a solution")
+        .as_u64()
+        .unwrap();
+    assert!(n_val >= 32);
+
+    // then, the path where it isn't
+    match em.next().expect("Expected a second path") {
+        Ok(ReturnValue::Return(_)) => {},
+        Ok(retval) => panic!("Expected a return value, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    assert_eq!(
+        em.state().max_possible_solution_for_bv_as_u64(&n).unwrap(),
+        Some(31)
+    );
+    assert!(em.next().is_none(), "Expected only two paths");
+}
@@ -0,0 +1,207 @@
+use haybale::backend::DefaultBackend;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/strings.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn strlen_local() {
+    let funcname = "strlen_local";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    let args = find_zero_of_func(funcname, &proj, config.clone(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0], SolutionValue::I8(0));
+
+    // both lengths are possible, on a single path
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), None).unwrap();
+    em.next()
+        .expect("Expected a path")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(em.next().is_none());
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, None, None, 5),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(2)),
+    );
+}
+
+#[test]
+fn strlen_param() {
+    let funcname = "strlen_param";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the string's contents are unconstrained, so its length can be anything
+    // up to `max_string_len`. (`strlen` reads `max_string_len + 1` bytes, so
+    // the pointer has to point to at least that many.)
+    config.max_string_len = 3;
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            config,
+            Some(vec![ParameterVal::PointerToAllocated(4)]),
+            None,
+            10
+        ),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(0),
+                ReturnValue::Return(1),
+                ReturnValue::Return(2),
+                ReturnValue::Return(3),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+}
+
+#[test]
+fn strlen_nul_terminated_param() {
+    let funcname = "strlen_param";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the string's length can be anything up to its `max_len`, regardless of
+    // the (larger) `max_string_len`
+    assert_eq!(
+        get_possible_return_values_of_func(
+            funcname,
+            &proj,
+            config,
+            Some(vec![ParameterVal::PointerToNulTerminatedString {
+                max_len: 2
+            }]),
+            None,
+            10
+        ),
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(0),
+                ReturnValue::Return(1),
+                ReturnValue::Return(2),
+            ]
+            .into_iter()
+            .collect()
+        ),
+    );
+}
+
+#[test]
+fn memcmp_sign() {
+    let funcname = "memcmp_sign";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the sign of the result always matches the comparison of the differing byte
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, None, None, 5),
+        PossibleSolutions::exactly_one(ReturnValue::Return(1)),
+    );
+}
+
+#[test]
+fn memcmp_len() {
+    let funcname = "memcmp_len";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the length is unconstrained, so without a bound it may be huge
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config.clone(), None).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::OtherError(msg)) => assert!(msg.contains("max_memcpy_length"), "{}", msg),
+        Ok(retval) => panic!("Expected an error, got {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    // with `max_memcpy_length`, the buffers are equal iff the length is at most 3
+    config.max_memcpy_length = Some(4);
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, None, None, 5),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(1)),
+    );
+}
+
+#[test]
+fn strcmp_sign() {
+    let funcname = "strcmp_sign";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the sign of the result always matches the comparison of the differing byte
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, None, None, 5),
+        PossibleSolutions::exactly_one(ReturnValue::Return(1)),
+    );
+}
+
+#[test]
+fn strcmp_params() {
+    let funcname = "strcmp_params";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    // the strings are unconstrained, but must differ or end within `max_string_len`
+    config.max_string_len = 3;
+    let mut em: ExecutionManager<DefaultBackend> = symex_function(
+        funcname,
+        &proj,
+        config,
+        Some(vec![
+            ParameterVal::PointerToAllocated(4),
+            ParameterVal::PointerToAllocated(4),
+        ]),
+    )
+    .unwrap();
+    let retval = match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(retval)) => retval,
+        Ok(retval) => panic!("Unexpected return value {:?}", retval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    };
+    // the strings may or may not be equal
+    let state = em.state();
+    assert!(state.bvs_can_be_equal(&retval, &state.zero(32)).unwrap());
+    assert!(!state.bvs_must_be_equal(&retval, &state.zero(32)).unwrap());
+    assert!(em.next().is_none());
+}
+
+#[test]
+fn strncmp_len() {
+    let funcname = "strncmp_len";
+    init_logging();
+    let proj = get_project();
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.add_libc_string_hooks();
+    let args = find_zero_of_func(funcname, &proj, config.clone(), None)
+        .unwrap_or_else(|r| panic!("{}", r))
+        .expect("Failed to find zero of the function");
+    assert_eq!(args.len(), 1);
+    let n = args[0].unwrap_to_i64();
+    assert!((0 ..= 2).contains(&n), "expected 0 <= n <= 2, got {}", n);
+    // 'c' - 'd' is -1
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, config, None, None, 5),
+        PossibleSolutions::exactly_two(ReturnValue::Return(0), ReturnValue::Return(0xFFFF_FFFF)),
+    );
+}
@@ -0,0 +1,86 @@
+use haybale::backend::DefaultBackend;
+use haybale::config::SymbolicAddressPolicy;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/symbolic_address.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+/// Returns the possible return values of each path through `table_lookup`
+/// under the given policy, in the order the paths were explored
+fn return_values_per_path(policy: SymbolicAddressPolicy) -> Vec<PossibleSolutions<u64>> {
+    let funcname = "table_lookup";
+    init_logging();
+    let proj = get_project();
+    let mut config = Config::default();
+    config.symbolic_address_policy = policy;
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, None).unwrap();
+    let mut retvals = vec![];
+    while let Some(res) = em.next() {
+        match res {
+            Ok(ReturnValue::Return(bv)) => retvals.push(
+                em.state()
+                    .get_possible_solutions_for_bv(&bv, 4)
+                    .unwrap()
+                    .as_u64_solutions()
+                    .unwrap(),
+            ),
+            Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+            Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+        }
+    }
+    retvals
+}
+
+#[test]
+fn fully_symbolic() {
+    // one path, on which any of the table's elements may be returned
+    let retvals = return_values_per_path(SymbolicAddressPolicy::FullySymbolic);
+    assert_eq!(
+        retvals,
+        vec![PossibleSolutions::Exactly(
+            vec![10, 20, 30, 40].into_iter().collect()
+        )],
+    );
+}
+
+#[test]
+fn concretize_to_single() {
+    // one path, on which the address was concretized to the lowest one
+    let retvals = return_values_per_path(SymbolicAddressPolicy::ConcretizeToSingle);
+    assert_eq!(retvals, vec![PossibleSolutions::exactly_one(10)]);
+}
+
+#[test]
+fn concretize_to_n() {
+    // one path per address, each returning a single element
+    let retvals = return_values_per_path(SymbolicAddressPolicy::ConcretizeToN(4));
+    assert_eq!(retvals.len(), 4);
+    assert_eq!(retvals[0], PossibleSolutions::exactly_one(10));
+    for elem in &[20, 30, 40] {
+        assert!(retvals.contains(&PossibleSolutions::exactly_one(*elem)));
+    }
+
+    // with fewer paths than addresses, the higher addresses are dropped
+    let mut retvals = return_values_per_path(SymbolicAddressPolicy::ConcretizeToN(2));
+    retvals.sort_by_key(|sols| match sols {
+        PossibleSolutions::Exactly(v) => v.iter().copied().min(),
+        PossibleSolutions::AtLeast(_) => None,
+    });
+    assert_eq!(
+        retvals,
+        vec![
+            PossibleSolutions::exactly_one(10),
+            PossibleSolutions::exactly_one(20),
+        ],
+    );
+}
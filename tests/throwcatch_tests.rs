@@ -1,5 +1,7 @@
+use haybale::backend::DefaultBackend;
 use haybale::solver_utils::PossibleSolutions;
 use haybale::*;
+use std::rc::Rc;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -86,6 +88,126 @@ fn throw_multiple_values() {
     );
 }
 
+#[test]
+fn separate_return_and_throw_budgets() {
+    let funcname = "throw_multiple_values";
+    init_logging();
+    let proj = get_project();
+
+    // with enough budget for both, we get the same values as above
+    let rvals = get_possible_returns_and_throws_of_func(
+        funcname,
+        &proj,
+        Config::default(),
+        None,
+        Some(32),
+        2,
+        2,
+    );
+    assert_eq!(
+        rvals,
+        PossibleSolutions::Exactly(
+            vec![
+                ReturnValue::Return(1),
+                ReturnValue::Return(2),
+                ReturnValue::Throw(3),
+                ReturnValue::Throw(4),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+
+    // running out of budget for returns doesn't stop us from finding all the throws
+    let rvals = get_possible_returns_and_throws_of_func(
+        funcname,
+        &proj,
+        Config::default(),
+        None,
+        Some(32),
+        1,
+        5,
+    );
+    match rvals {
+        PossibleSolutions::AtLeast(v) => {
+            let throws: Vec<ReturnValue<u64>> = v
+                .into_iter()
+                .filter(|rval| matches!(rval, ReturnValue::Throw(_)))
+                .collect();
+            assert_eq!(throws.len(), 2);
+            assert!(throws.contains(&ReturnValue::Throw(3)));
+            assert!(throws.contains(&ReturnValue::Throw(4)));
+        },
+        PossibleSolutions::Exactly(v) => panic!("Expected AtLeast, got Exactly({:?})", v),
+    }
+
+    // and vice versa
+    let rvals = get_possible_returns_and_throws_of_func(
+        funcname,
+        &proj,
+        Config::default(),
+        None,
+        Some(32),
+        5,
+        1,
+    );
+    match rvals {
+        PossibleSolutions::AtLeast(v) => {
+            assert!(v.contains(&ReturnValue::Return(1)));
+            assert!(v.contains(&ReturnValue::Return(2)));
+        },
+        PossibleSolutions::Exactly(v) => panic!("Expected AtLeast, got Exactly({:?})", v),
+    }
+}
+
+#[test]
+fn skip_throwing_paths() {
+    let funcname = "throw_multiple_values";
+    init_logging();
+    let mut config = Config::default();
+    config.skip_throwing_paths = true;
+    let rvals =
+        get_possible_return_values_of_func(funcname, &get_project(), config, None, Some(32), 5);
+    assert_eq!(
+        rvals,
+        PossibleSolutions::exactly_two(ReturnValue::Return(1), ReturnValue::Return(2)),
+    );
+}
+
+#[test]
+fn assert_possible_returns_helper() {
+    let funcname = "throw_multiple_values";
+    init_logging();
+    assert_possible_returns(
+        funcname,
+        &get_project(),
+        Config::default(),
+        None,
+        Some(32),
+        &[
+            ReturnValue::Throw(4),
+            ReturnValue::Return(1),
+            ReturnValue::Throw(3),
+            ReturnValue::Return(2),
+        ],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected but not possible: [Return(5)]")]
+fn assert_possible_returns_helper_mismatch() {
+    let funcname = "throw_uncaught";
+    init_logging();
+    assert_possible_returns(
+        funcname,
+        &get_project(),
+        Config::default(),
+        None,
+        Some(32),
+        &[ReturnValue::Return(5), ReturnValue::Throw(20)],
+    );
+}
+
 #[test]
 fn throw_uncaught_wrongtype() {
     let funcname = "throw_uncaught_wrongtype";
@@ -228,3 +350,48 @@ fn throw_and_rethrow_in_caller() {
         PossibleSolutions::exactly_two(ReturnValue::Return(2), ReturnValue::Throw(20)),
     );
 }
+
+#[test]
+fn typed_catches_with_type_indices() {
+    init_logging();
+    let proj = get_project();
+    // give each exception type used in throwcatch.bc its own type index, so
+    // that catch blocks only catch the types they handle
+    let mut config: Config<DefaultBackend> = Config::default();
+    config.exception_type_index_hook = Some(Rc::new(
+        |_state: &State<DefaultBackend>, type_info: &str| -> Result<u32> {
+            match type_info {
+                "_ZTIi" => Ok(1),
+                "_ZTIh" => Ok(2),
+                _ => panic!("Unexpected type_info {}", type_info),
+            }
+        },
+    ));
+
+    // the exception isn't caught by the catch block for a different type
+    let rvals = get_possible_return_values_of_func(
+        "throw_uncaught_wrongtype",
+        &proj,
+        config.clone(),
+        None,
+        Some(32),
+        3,
+    );
+    assert_eq!(
+        rvals,
+        PossibleSolutions::exactly_two(ReturnValue::Return(2), ReturnValue::Throw(20)),
+    );
+
+    // and is always caught by the catch block for its own type, whether it
+    // was thrown in the same function or in a callee
+    for funcname in &["throw_and_catch_val", "throw_and_catch_in_caller"] {
+        let rvals =
+            get_possible_return_values_of_func(funcname, &proj, config.clone(), None, Some(32), 3);
+        assert_eq!(
+            rvals,
+            PossibleSolutions::exactly_two(ReturnValue::Return(2), ReturnValue::Return(20)),
+            "for function {}",
+            funcname,
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/vector_gep.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn vector_indices() {
+    let funcname = "vector_indices";
+    init_logging();
+    let proj = get_project();
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, Config::default(), None, None, 3),
+        PossibleSolutions::exactly_one(ReturnValue::Return(30)),
+    );
+}
+
+#[test]
+fn vector_base() {
+    let funcname = "vector_base";
+    init_logging();
+    let proj = get_project();
+    assert_eq!(
+        get_possible_return_values_of_func(funcname, &proj, Config::default(), None, None, 3),
+        PossibleSolutions::exactly_one(ReturnValue::Return(75)),
+    );
+}
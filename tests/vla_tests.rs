@@ -0,0 +1,78 @@
+use haybale::backend::DefaultBackend;
+use haybale::solver_utils::PossibleSolutions;
+use haybale::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn get_project() -> Project {
+    let modname = "tests/bcfiles/vla.bc";
+    Project::from_bc_path(modname)
+        .unwrap_or_else(|e| panic!("Failed to parse module {:?}: {}", modname, e))
+}
+
+#[test]
+fn bounded_vla() {
+    let funcname = "vla_bounded";
+    init_logging();
+    let proj = get_project();
+    let rvals = get_possible_return_values_of_func(
+        funcname,
+        &proj,
+        Config::default(),
+        Some(vec![
+            ParameterVal::Unconstrained,
+            ParameterVal::ExactValue(42),
+        ]),
+        None,
+        3,
+    );
+    assert_eq!(
+        rvals,
+        PossibleSolutions::exactly_one(ReturnValue::Return(42))
+    );
+}
+
+#[test]
+fn unbounded_vla() {
+    let funcname = "vla_unbounded";
+    init_logging();
+    let proj = get_project();
+    let params = Some(vec![
+        ParameterVal::Unconstrained,
+        ParameterVal::ExactValue(42),
+    ]);
+
+    // without `max_dynamic_alloc_elements`, we can't allocate the VLA
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, Config::default(), params.clone()).unwrap();
+    match em.next().expect("Expected a path") {
+        Err(Error::UnsupportedInstruction(msg)) => {
+            assert!(msg.contains("max_dynamic_alloc_elements"))
+        },
+        res => panic!("Expected UnsupportedInstruction, got {:?}", res),
+    }
+
+    // with it, the number of elements gets constrained to the bound
+    let mut config = Config::default();
+    config.max_dynamic_alloc_elements = Some(16);
+    let mut em: ExecutionManager<DefaultBackend> =
+        symex_function(funcname, &proj, config, params).unwrap();
+    match em.next().expect("Expected a path") {
+        Ok(ReturnValue::Return(rval)) => {
+            let state = em.state();
+            assert!(state
+                .bvs_must_be_equal(&rval, &state.bv_from_u64(42, rval.get_width()))
+                .unwrap());
+        },
+        Ok(rval) => panic!("Expected a return value, got {:?}", rval),
+        Err(e) => panic!("{}", em.state().full_error_message_with_context(e)),
+    }
+    let n = em.param_bvs()[0].clone();
+    assert_eq!(
+        em.state().max_possible_solution_for_bv_as_u64(&n).unwrap(),
+        Some(16)
+    );
+}